@@ -28,7 +28,7 @@ async def test_list_apis_shows_both_sources(mcp_mixed_session: ClientSession):
 async def test_list_functions_includes_both(mcp_mixed_session: ClientSession):
     """list_functions returns both OpenAPI functions and MCP tools."""
     result = await mcp_mixed_session.call_tool("list_functions", {})
-    functions = json.loads(result.content[0].text)
+    functions = json.loads(result.content[0].text)["functions"]
     names = {f["name"] for f in functions}
     assert "list_pets" in names, f"Missing OpenAPI function. Got: {names}"
     assert "echo" in names, f"Missing MCP tool. Got: {names}"
@@ -38,7 +38,7 @@ async def test_list_apis_shows_mcp_server(mcp_only_session: ClientSession):
 async def test_list_functions_shows_all_mcp_tools(mcp_only_session: ClientSession):
     """list_functions returns all 6 mock MCP tools."""
     result = await mcp_only_session.call_tool("list_functions", {})
-    functions = json.loads(result.content[0].text)
+    functions = json.loads(result.content[0].text)["functions"]
     names = {f["name"] for f in functions}
     expected = {"echo", "add", "get_user", "list_items", "failing_tool", "no_params"}
     assert expected.issubset(names), f"Missing tools. Got: {names}"
@@ -48,7 +48,7 @@ async def test_list_functions_shows_all_mcp_tools(mcp_only_session: ClientSessio
 async def test_list_functions_mcp_source_field(mcp_only_session: ClientSession):
     """Each MCP function has source: 'mcp' and api: 'mock'."""
     result = await mcp_only_session.call_tool("list_functions", {"api": "mock"})
-    functions = json.loads(result.content[0].text)
+    functions = json.loads(result.content[0].text)["functions"]
     assert len(functions) > 0, "Expected at least one MCP function"
     for fn in functions:
         assert fn["source"] == "mcp", f"{fn['name']} missing source=mcp"
@@ -59,7 +59,7 @@ async def test_list_functions_mcp_source_field(mcp_only_session: ClientSession):
 async def test_list_functions_filter_by_api(mcp_only_session: ClientSession):
     """list_functions with api='mock' returns only mock tools."""
     result = await mcp_only_session.call_tool("list_functions", {"api": "mock"})
-    functions = json.loads(result.content[0].text)
+    functions = json.loads(result.content[0].text)["functions"]
     assert len(functions) == 6
     assert all(f["api"] == "mock" for f in functions)
 
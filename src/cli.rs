@@ -1,4 +1,7 @@
-use clap::{Parser, Subcommand};
+use crate::codegen::generate::{DuplicateNamePolicy, FunctionNamespace};
+use crate::codegen::parser::FunctionNaming;
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -6,19 +9,52 @@ use std::path::PathBuf;
 pub struct Cli {
     #[command(subcommand)]
     pub command: Command,
+    /// Log output format, written to stderr
+    #[arg(long, global = true, value_enum, default_value = "pretty")]
+    pub log_format: LogFormat,
+    /// Minimum log level or `tracing` filter directive (`RUST_LOG` wins when set)
+    #[arg(long, global = true, default_value = "info")]
+    pub log_level: String,
+}
+
+/// How log events are rendered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable lines
+    Pretty,
+    /// One JSON object per line
+    Json,
 }
 
 #[derive(Subcommand)]
 pub enum Command {
     /// Generate manifest and SDK annotations from `OpenAPI` specs
     Generate {
-        /// Spec sources: `path`, `url`, or `name=path`/`name=url`
+        /// Spec sources: `path`, `url`, `-` (stdin), or `name=path`/`name=url`/`name=-`
         specs: Vec<String>,
         #[arg(short, long, default_value = "./output")]
         output: PathBuf,
         /// Path to TOML config file
         #[arg(long)]
         config: Option<PathBuf>,
+        /// How to handle functions that derive the same name
+        #[arg(long, value_enum, default_value = "suffix")]
+        duplicate_names: DuplicateNamePolicy,
+        /// Write manifest.json here instead of into the output directory
+        #[arg(long)]
+        manifest_out: Option<PathBuf>,
+        /// Skip specs that fail to load, write output from the rest, then exit nonzero
+        #[arg(long)]
+        continue_on_error: bool,
+        /// Scope function names per API
+        #[arg(long, value_enum, default_value = "none")]
+        function_prefix: FunctionNamespace,
+        /// Function name casing
+        #[arg(long, value_enum, default_value = "snake")]
+        naming: FunctionNaming,
+        /// Fail when a spec has a `$ref` that does not resolve, instead of emitting `unknown`
+        #[arg(long)]
+        strict_schema: bool,
     },
     /// Start MCP server from a generated directory
     Serve {
@@ -34,32 +70,206 @@ pub enum Command {
         auth_audience: Option<String>,
         #[arg(long, env = "MCP_AUTH_JWKS_URI")]
         auth_jwks_uri: Option<String>,
+        /// Scopes an MCP client token must carry (comma-separated); otherwise 403
+        #[arg(long, env = "MCP_AUTH_REQUIRED_SCOPES", value_delimiter = ',')]
+        auth_required_scopes: Vec<String>,
+        /// Claim an MCP client token must carry, as `name=value` (repeatable); otherwise 403
+        #[arg(long = "auth-required-claim", num_args = 1)]
+        auth_required_claims: Vec<String>,
         /// Upstream API auth: `name:ENV_VAR` or `ENV_VAR` (for single-spec)
         #[arg(long = "auth")]
         api_auth: Vec<String>,
+        /// Load environment variables from a dotenv file (existing vars win)
+        #[arg(long)]
+        env_file: Option<PathBuf>,
         #[arg(long, default_value = "30")]
         timeout: u64,
+        /// Seconds a single upstream API call may take before it raises a catchable error
+        #[arg(long)]
+        timeout_per_call: Option<u64>,
         #[arg(long, default_value = "64")]
         memory_limit: usize,
         #[arg(long, default_value = "100")]
         max_api_calls: usize,
+        /// Approximate cap on the script result's JSON size in bytes; larger results are truncated (0 disables)
+        #[arg(long, default_value = "262144")]
+        max_result_bytes: usize,
+        /// Include redacted, truncated API request/response bodies in the `execute_script` trace
+        #[arg(long)]
+        trace_bodies: bool,
+        /// Maximum bytes of each body in the trace (with `--trace-bodies`)
+        #[arg(long, default_value = "4096")]
+        max_response_log_bytes: usize,
+        /// Extra header names / JSON keys to redact in traced bodies (repeatable)
+        #[arg(long = "redact", num_args = 1)]
+        redact: Vec<String>,
+        /// Maximum idle HTTP connections kept open per upstream host
+        #[arg(long)]
+        pool_max_idle_per_host: Option<usize>,
+        /// Seconds an idle upstream HTTP connection stays in the pool
+        #[arg(long)]
+        pool_idle_timeout: Option<u64>,
+        /// Open a new upstream HTTP connection for every API call
+        #[arg(long)]
+        no_connection_reuse: bool,
+        /// `User-Agent` header sent on every upstream API call
+        #[arg(long)]
+        user_agent: Option<String>,
+        /// Header sent on every upstream API call, as `name=value` (repeatable)
+        #[arg(long = "http-header", num_args = 1)]
+        http_headers: Vec<String>,
+        /// PEM file of extra root certificates to trust for upstream API calls
+        #[arg(long)]
+        ca_bundle: Option<PathBuf>,
+        /// Skip TLS certificate verification for upstream API calls (testing only)
+        #[arg(long)]
+        insecure: bool,
+        /// Point an API at a different base URL, as `name=url` (repeatable)
+        #[arg(long = "api-base-url", num_args = 1)]
+        api_base_urls: Vec<String>,
+        /// Share GET responses across executions for this many seconds
+        #[arg(long)]
+        response_cache_ttl: Option<u64>,
+        /// Maximum responses kept by the `--response-cache-ttl` cache
+        #[arg(long, default_value = "1000")]
+        response_cache_max_entries: usize,
+        /// Maximum upstream API calls in flight at once across all executions; more queue
+        #[arg(long)]
+        max_concurrent_requests: Option<usize>,
+        /// Fail API calls that pass a parameter the spec marks deprecated
+        #[arg(long)]
+        reject_deprecated_params: bool,
+        /// Answer a repeated identical GET within one execution from the first response
+        #[arg(long)]
+        cache_gets: bool,
+        /// Answer a repeated identical read-only MCP tool call within one execution from the first result
+        #[arg(long)]
+        cache_mcp_calls: bool,
+        /// Environment variable scripts may read with `os.getenv` (repeatable)
+        #[arg(long = "env-allow", num_args = 1)]
+        env_allow: Vec<String>,
+        /// Expose only functions with these tags (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        only_tags: Vec<String>,
+        /// Hide functions with these tags (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        exclude_tags: Vec<String>,
         /// I/O directory for sandboxed file access in scripts
         #[arg(long)]
         io_dir: Option<String>,
         /// Upstream MCP servers (`name=command_or_url`)
         #[arg(long = "mcp", num_args = 1)]
         mcp_servers: Vec<String>,
+        /// Reload specs and config on SIGHUP without dropping the listener (HTTP transport)
+        #[arg(long)]
+        reload: bool,
+    },
+    /// Print the manifest that specs/config resolve to, as JSON
+    Inspect {
+        /// Spec sources: `path`, `url`, `-` (stdin), or `name=path`/`name=url`/`name=-`
+        specs: Vec<String>,
+        /// Path to TOML config file
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Print only the function definitions
+        #[arg(long, conflicts_with = "schemas_only")]
+        functions_only: bool,
+        /// Print only the schema definitions
+        #[arg(long)]
+        schemas_only: bool,
+    },
+    /// Print everything an agent sees: server instructions and every function and tool's docs
+    Context {
+        /// Spec sources: `path`, `url`, `-` (stdin), or `name=path`/`name=url`/`name=-`
+        specs: Vec<String>,
+        /// Path to TOML config file
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Upstream MCP servers (`name=command_or_url`)
+        #[arg(long = "mcp", num_args = 1)]
+        mcp_servers: Vec<String>,
+        /// How to handle functions that derive the same name
+        #[arg(long, value_enum, default_value = "suffix")]
+        duplicate_names: DuplicateNamePolicy,
+        /// Scope function names per API
+        #[arg(long, value_enum, default_value = "none")]
+        function_prefix: FunctionNamespace,
+        /// Function name casing
+        #[arg(long, value_enum, default_value = "snake")]
+        naming: FunctionNaming,
+        /// Expose only functions with these tags (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        only_tags: Vec<String>,
+        /// Hide functions with these tags (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        exclude_tags: Vec<String>,
+        /// Point an API at a different base URL, as `name=url` (repeatable)
+        #[arg(long = "api-base-url", num_args = 1)]
+        api_base_urls: Vec<String>,
+    },
+    /// List every function and MCP tool a script can call
+    List {
+        /// Spec sources: `path`, `url`, `-` (stdin), or `name=path`/`name=url`/`name=-`
+        specs: Vec<String>,
+        /// Path to TOML config file
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Upstream MCP servers (`name=command_or_url`)
+        #[arg(long = "mcp", num_args = 1)]
+        mcp_servers: Vec<String>,
+        /// How to handle functions that derive the same name
+        #[arg(long, value_enum, default_value = "suffix")]
+        duplicate_names: DuplicateNamePolicy,
+        /// Scope function names per API
+        #[arg(long, value_enum, default_value = "none")]
+        function_prefix: FunctionNamespace,
+        /// Function name casing
+        #[arg(long, value_enum, default_value = "snake")]
+        naming: FunctionNaming,
+        /// Expose only functions with these tags (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        only_tags: Vec<String>,
+        /// Hide functions with these tags (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        exclude_tags: Vec<String>,
+        /// Point an API at a different base URL, as `name=url` (repeatable)
+        #[arg(long = "api-base-url", num_args = 1)]
+        api_base_urls: Vec<String>,
+        /// Print a JSON array instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+    /// Validate a config file without connecting to anything or serving
+    Check {
+        /// Path to the config file (defaults to toolscript.toml/.yaml in the current directory)
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Upstream API auth to validate: `name:ENV_VAR` or `ENV_VAR`
+        #[arg(long = "auth")]
+        api_auth: Vec<String>,
     },
     /// Generate and serve in one step
     Run {
-        /// Spec sources: `path`, `url`, or `name=path`/`name=url`
+        /// Spec sources: `path`, `url`, `-` (stdin), or `name=path`/`name=url`/`name=-`
         specs: Vec<String>,
         /// Path to TOML config file
         #[arg(long)]
         config: Option<PathBuf>,
+        /// How to handle functions that derive the same name
+        #[arg(long, value_enum, default_value = "suffix")]
+        duplicate_names: DuplicateNamePolicy,
+        /// Scope function names per API
+        #[arg(long, value_enum, default_value = "none")]
+        function_prefix: FunctionNamespace,
+        /// Function name casing
+        #[arg(long, value_enum, default_value = "snake")]
+        naming: FunctionNaming,
         /// Upstream API auth: `name:ENV_VAR` or `ENV_VAR` (for single-spec)
         #[arg(long = "auth")]
         api_auth: Vec<String>,
+        /// Load environment variables from a dotenv file (existing vars win)
+        #[arg(long)]
+        env_file: Option<PathBuf>,
         #[arg(long, default_value = "stdio")]
         transport: String,
         #[arg(long, default_value = "8080")]
@@ -70,19 +280,105 @@ pub enum Command {
         auth_audience: Option<String>,
         #[arg(long, env = "MCP_AUTH_JWKS_URI")]
         auth_jwks_uri: Option<String>,
+        /// Scopes an MCP client token must carry (comma-separated); otherwise 403
+        #[arg(long, env = "MCP_AUTH_REQUIRED_SCOPES", value_delimiter = ',')]
+        auth_required_scopes: Vec<String>,
+        /// Claim an MCP client token must carry, as `name=value` (repeatable); otherwise 403
+        #[arg(long = "auth-required-claim", num_args = 1)]
+        auth_required_claims: Vec<String>,
         #[arg(long, default_value = "30")]
         timeout: u64,
+        /// Seconds a single upstream API call may take before it raises a catchable error
+        #[arg(long)]
+        timeout_per_call: Option<u64>,
         #[arg(long, default_value = "64")]
         memory_limit: usize,
         #[arg(long, default_value = "100")]
         max_api_calls: usize,
+        /// Approximate cap on the script result's JSON size in bytes; larger results are truncated (0 disables)
+        #[arg(long, default_value = "262144")]
+        max_result_bytes: usize,
+        /// Include redacted, truncated API request/response bodies in the `execute_script` trace
+        #[arg(long)]
+        trace_bodies: bool,
+        /// Maximum bytes of each body in the trace (with `--trace-bodies`)
+        #[arg(long, default_value = "4096")]
+        max_response_log_bytes: usize,
+        /// Extra header names / JSON keys to redact in traced bodies (repeatable)
+        #[arg(long = "redact", num_args = 1)]
+        redact: Vec<String>,
+        /// Maximum idle HTTP connections kept open per upstream host
+        #[arg(long)]
+        pool_max_idle_per_host: Option<usize>,
+        /// Seconds an idle upstream HTTP connection stays in the pool
+        #[arg(long)]
+        pool_idle_timeout: Option<u64>,
+        /// Open a new upstream HTTP connection for every API call
+        #[arg(long)]
+        no_connection_reuse: bool,
+        /// `User-Agent` header sent on every upstream API call
+        #[arg(long)]
+        user_agent: Option<String>,
+        /// Header sent on every upstream API call, as `name=value` (repeatable)
+        #[arg(long = "http-header", num_args = 1)]
+        http_headers: Vec<String>,
+        /// PEM file of extra root certificates to trust for upstream API calls
+        #[arg(long)]
+        ca_bundle: Option<PathBuf>,
+        /// Skip TLS certificate verification for upstream API calls (testing only)
+        #[arg(long)]
+        insecure: bool,
+        /// Point an API at a different base URL, as `name=url` (repeatable)
+        #[arg(long = "api-base-url", num_args = 1)]
+        api_base_urls: Vec<String>,
+        /// Share GET responses across executions for this many seconds
+        #[arg(long)]
+        response_cache_ttl: Option<u64>,
+        /// Maximum responses kept by the `--response-cache-ttl` cache
+        #[arg(long, default_value = "1000")]
+        response_cache_max_entries: usize,
+        /// Maximum upstream API calls in flight at once across all executions; more queue
+        #[arg(long)]
+        max_concurrent_requests: Option<usize>,
+        /// Fail API calls that pass a parameter the spec marks deprecated
+        #[arg(long)]
+        reject_deprecated_params: bool,
+        /// Answer a repeated identical GET within one execution from the first response
+        #[arg(long)]
+        cache_gets: bool,
+        /// Answer a repeated identical read-only MCP tool call within one execution from the first result
+        #[arg(long)]
+        cache_mcp_calls: bool,
+        /// Environment variable scripts may read with `os.getenv` (repeatable)
+        #[arg(long = "env-allow", num_args = 1)]
+        env_allow: Vec<String>,
+        /// Expose only functions with these tags (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        only_tags: Vec<String>,
+        /// Hide functions with these tags (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        exclude_tags: Vec<String>,
         /// I/O directory for sandboxed file access in scripts
         #[arg(long)]
         io_dir: Option<String>,
         /// Upstream MCP servers (`name=command_or_url`)
         #[arg(long = "mcp", num_args = 1)]
         mcp_servers: Vec<String>,
+        /// Reload specs and config on SIGHUP without dropping the listener (HTTP transport)
+        #[arg(long)]
+        reload: bool,
     },
+    /// Print a shell completion script to stdout
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
+
+/// Write the completion script for `shell` to `out`.
+pub fn write_completions(shell: Shell, out: &mut dyn std::io::Write) {
+    clap_complete::generate(shell, &mut Cli::command(), "toolscript", out);
 }
 
 #[cfg(test)]
@@ -91,6 +387,22 @@ mod tests {
     use super::*;
     use clap::Parser;
 
+    #[test]
+    fn test_completions_bash() {
+        let cli = Cli::parse_from(["toolscript", "completions", "bash"]);
+        let Command::Completions { shell } = cli.command else {
+            panic!("expected Completions");
+        };
+        assert_eq!(shell, Shell::Bash);
+
+        let mut out = Vec::new();
+        write_completions(shell, &mut out);
+        let script = String::from_utf8(out).unwrap();
+        for subcommand in ["generate", "serve", "run", "inspect", "check"] {
+            assert!(script.contains(subcommand), "missing {subcommand}");
+        }
+    }
+
     #[test]
     fn test_run_with_spec() {
         let cli = Cli::parse_from(["toolscript", "run", "spec.yaml"]);
@@ -169,12 +481,87 @@ mod tests {
                 timeout,
                 memory_limit,
                 max_api_calls,
+                max_result_bytes,
                 ..
             } => {
                 assert_eq!(timeout, 30);
                 assert_eq!(memory_limit, 64);
                 assert_eq!(max_api_calls, 100);
+                assert_eq!(max_result_bytes, 256 * 1024);
+            }
+            _ => panic!("expected Run"),
+        }
+    }
+
+    #[test]
+    fn test_serve_with_tag_filters() {
+        let cli = Cli::parse_from([
+            "toolscript",
+            "serve",
+            "./output",
+            "--only-tags",
+            "pets,stores",
+            "--exclude-tags",
+            "admin",
+        ]);
+        match cli.command {
+            Command::Serve {
+                only_tags,
+                exclude_tags,
+                ..
+            } => {
+                assert_eq!(only_tags, vec!["pets", "stores"]);
+                assert_eq!(exclude_tags, vec!["admin"]);
             }
+            _ => panic!("expected Serve"),
+        }
+    }
+
+    #[test]
+    fn test_run_with_cache_gets() {
+        let cli = Cli::parse_from(["toolscript", "run", "spec.yaml", "--cache-gets"]);
+        match cli.command {
+            Command::Run { cache_gets, .. } => assert!(cache_gets),
+            _ => panic!("expected Run"),
+        }
+        let cli = Cli::parse_from(["toolscript", "serve", "./output"]);
+        match cli.command {
+            Command::Serve { cache_gets, .. } => assert!(!cache_gets),
+            _ => panic!("expected Serve"),
+        }
+    }
+
+    #[test]
+    fn test_serve_with_cache_mcp_calls() {
+        let cli = Cli::parse_from(["toolscript", "serve", "./output", "--cache-mcp-calls"]);
+        match cli.command {
+            Command::Serve {
+                cache_mcp_calls, ..
+            } => assert!(cache_mcp_calls),
+            _ => panic!("expected Serve"),
+        }
+        let cli = Cli::parse_from(["toolscript", "run", "spec.yaml"]);
+        match cli.command {
+            Command::Run {
+                cache_mcp_calls, ..
+            } => assert!(!cache_mcp_calls),
+            _ => panic!("expected Run"),
+        }
+    }
+
+    #[test]
+    fn test_run_with_env_allow() {
+        let cli = Cli::parse_from([
+            "toolscript",
+            "run",
+            "spec.yaml",
+            "--env-allow",
+            "REGION",
+            "--env-allow",
+            "STAGE",
+        ]);
+        match cli.command {
+            Command::Run { env_allow, .. } => assert_eq!(env_allow, vec!["REGION", "STAGE"]),
             _ => panic!("expected Run"),
         }
     }
@@ -187,11 +574,13 @@ mod tests {
                 timeout,
                 memory_limit,
                 max_api_calls,
+                max_result_bytes,
                 ..
             } => {
                 assert_eq!(timeout, 30);
                 assert_eq!(memory_limit, 64);
                 assert_eq!(max_api_calls, 100);
+                assert_eq!(max_result_bytes, 256 * 1024);
             }
             _ => panic!("expected Serve"),
         }
@@ -205,15 +594,133 @@ mod tests {
                 specs,
                 config,
                 output,
+                duplicate_names,
+                manifest_out,
+                continue_on_error,
+                function_prefix,
+                naming,
+                strict_schema,
             } => {
                 assert!(specs.is_empty());
                 assert_eq!(config.unwrap().to_str().unwrap(), "my.toml");
                 assert_eq!(output.to_str().unwrap(), "out");
+                assert_eq!(duplicate_names, DuplicateNamePolicy::Suffix);
+                assert!(manifest_out.is_none());
+                assert!(!continue_on_error);
+                assert_eq!(function_prefix, FunctionNamespace::Flat);
+                assert_eq!(naming, FunctionNaming::Snake);
+                assert!(!strict_schema);
             }
             _ => panic!("expected Generate"),
         }
     }
 
+    #[test]
+    fn test_generate_continue_on_error() {
+        let cli = Cli::parse_from(["toolscript", "generate", "a.yaml", "--continue-on-error"]);
+        match cli.command {
+            Command::Generate {
+                continue_on_error, ..
+            } => assert!(continue_on_error),
+            _ => panic!("expected Generate"),
+        }
+    }
+
+    #[test]
+    fn test_check_command() {
+        let cli = Cli::parse_from([
+            "toolscript",
+            "check",
+            "--config",
+            "toolscript.yaml",
+            "--auth",
+            "petstore:PETSTORE_TOKEN",
+        ]);
+        match cli.command {
+            Command::Check { config, api_auth } => {
+                assert_eq!(config, Some(PathBuf::from("toolscript.yaml")));
+                assert_eq!(api_auth, vec!["petstore:PETSTORE_TOKEN"]);
+            }
+            _ => panic!("expected Check"),
+        }
+    }
+
+    #[test]
+    fn test_context_takes_specs_and_mcp() {
+        let cli = Cli::parse_from([
+            "toolscript",
+            "context",
+            "spec.yaml",
+            "--mcp",
+            "fs=npx server",
+        ]);
+        match cli.command {
+            Command::Context {
+                specs, mcp_servers, ..
+            } => {
+                assert_eq!(specs, vec!["spec.yaml"]);
+                assert_eq!(mcp_servers, vec!["fs=npx server"]);
+            }
+            _ => panic!("expected Context"),
+        }
+    }
+
+    #[test]
+    fn test_list_command() {
+        let cli = Cli::parse_from([
+            "toolscript",
+            "list",
+            "spec.yaml",
+            "--json",
+            "--naming",
+            "camel",
+            "--only-tags",
+            "pets,store",
+        ]);
+        match cli.command {
+            Command::List {
+                specs,
+                json,
+                naming,
+                only_tags,
+                ..
+            } => {
+                assert_eq!(specs, vec!["spec.yaml"]);
+                assert!(json);
+                assert_eq!(naming, FunctionNaming::Camel);
+                assert_eq!(only_tags, vec!["pets", "store"]);
+            }
+            _ => panic!("expected List"),
+        }
+    }
+
+    #[test]
+    fn test_inspect_filters_conflict() {
+        let cli = Cli::parse_from(["toolscript", "inspect", "spec.yaml", "--functions-only"]);
+        match cli.command {
+            Command::Inspect {
+                specs,
+                functions_only,
+                schemas_only,
+                ..
+            } => {
+                assert_eq!(specs, vec!["spec.yaml"]);
+                assert!(functions_only);
+                assert!(!schemas_only);
+            }
+            _ => panic!("expected Inspect"),
+        }
+
+        let result = Cli::try_parse_from([
+            "toolscript",
+            "inspect",
+            "spec.yaml",
+            "--functions-only",
+            "--schemas-only",
+        ]);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_run_with_io_dir() {
         let cli = Cli::parse_from(["toolscript", "run", "spec.yaml", "--io-dir", "/tmp/out"]);
@@ -236,6 +743,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_run_with_reload() {
+        let cli = Cli::parse_from([
+            "toolscript",
+            "run",
+            "spec.yaml",
+            "--transport",
+            "sse",
+            "--reload",
+        ]);
+        match cli.command {
+            Command::Run { reload, .. } => assert!(reload),
+            _ => panic!("expected Run"),
+        }
+    }
+
+    #[test]
+    fn test_log_flags_after_subcommand() {
+        let cli = Cli::parse_from([
+            "toolscript",
+            "serve",
+            "./output",
+            "--log-format",
+            "json",
+            "--log-level",
+            "debug",
+        ]);
+        assert_eq!(cli.log_format, LogFormat::Json);
+        assert_eq!(cli.log_level, "debug");
+
+        let cli = Cli::parse_from(["toolscript", "serve", "./output"]);
+        assert_eq!(cli.log_format, LogFormat::Pretty);
+        assert_eq!(cli.log_level, "info");
+    }
+
     #[test]
     fn test_serve_with_auth() {
         let cli = Cli::parse_from([
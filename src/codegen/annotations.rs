@@ -1,8 +1,35 @@
 use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::Write;
 
-use super::luau_types::{field_type_to_luau, render_enum_type};
-use super::manifest::{FunctionDef, Manifest, McpToolDef, ParamType, SchemaDef};
+use super::luau_types::{
+    enum_values_quoted, field_type_to_luau, optional_marker, render_enum_descriptions,
+    render_enum_type,
+};
+use super::manifest::{
+    FunctionDef, HttpMethod, Manifest, McpToolDef, ParamDef, ParamType, SchemaDef,
+};
+
+/// Build the `@param` description: spec description, example, and any
+/// per-value enum descriptions.
+fn param_doc(param: &ParamDef) -> String {
+    let desc = param.description.as_deref().map_or("", str::trim);
+    let desc = match &param.example {
+        Some(example) if desc.is_empty() => render_example(example),
+        Some(example) => format!("{desc} ({})", render_example(example)),
+        None => desc.to_string(),
+    };
+    match (&param.enum_values, &param.enum_descriptions) {
+        (Some(values), Some(descriptions)) => {
+            let available = render_enum_descriptions(values, descriptions);
+            if desc.is_empty() {
+                available
+            } else {
+                format!("{desc} ({available})")
+            }
+        }
+        _ => desc,
+    }
+}
 
 /// Render a Luau type-annotated documentation block for a single function.
 ///
@@ -63,11 +90,13 @@ pub fn render_function_annotation(func: &FunctionDef) -> String {
         .collect();
 
     for param in &visible_params {
-        if let Some(desc) = &param.description {
-            let desc = desc.trim();
-            if !desc.is_empty() {
-                lines.push(format!("-- @param {} - {desc}", param.name));
-            }
+        let desc = param_doc(param);
+        let desc = desc.as_str();
+        match (param.deprecated, desc.is_empty()) {
+            (true, true) => lines.push(format!("-- @param {} - @deprecated", param.name)),
+            (true, false) => lines.push(format!("-- @param {} - @deprecated {desc}", param.name)),
+            (false, false) => lines.push(format!("-- @param {} - {desc}", param.name)),
+            (false, true) => {}
         }
     }
 
@@ -81,6 +110,10 @@ pub fn render_function_annotation(func: &FunctionDef) -> String {
         }
     }
 
+    if let Some(error_schema) = &func.error_schema {
+        lines.push(format!("-- on error: {error_schema}"));
+    }
+
     // Build table entries for visible params
     let has_visible_params = !visible_params.is_empty();
 
@@ -89,7 +122,7 @@ pub fn render_function_annotation(func: &FunctionDef) -> String {
         .map(|p| {
             let type_str = p.enum_values.as_ref().map_or_else(
                 || param_type_to_luau(&p.param_type),
-                |ev| render_enum_type(ev),
+                |ev| render_enum_type(ev, matches!(p.param_type, ParamType::String)),
             );
             if p.required {
                 format!("{}: {type_str}", p.name)
@@ -113,19 +146,42 @@ pub fn render_function_annotation(func: &FunctionDef) -> String {
     }
     let params_str = args.join(", ");
 
-    let return_type = func
-        .response_schema
-        .as_ref()
-        .map_or_else(String::new, |r| format!(": {r}"));
+    let return_type = render_return_type(func);
 
     lines.push(format!(
         "function sdk.{}({params_str}){return_type} end",
         func.name
     ));
 
+    if func.link_paginated {
+        lines.push(format!(
+            "-- Like sdk.{}, but follows `Link: <...>; rel=\"next\"` headers and concatenates every page",
+            func.name
+        ));
+        lines.push(format!(
+            "function sdk.{}_all({params_str}){return_type} end",
+            func.name
+        ));
+    }
+
     lines.join("\n")
 }
 
+/// The `: T` return annotation for a function, or empty when it is unknown.
+/// HEAD and OPTIONS calls return response metadata rather than a schema.
+fn render_return_type(func: &FunctionDef) -> String {
+    match func.method {
+        HttpMethod::Head => ": { status: number, headers: {[string]: string} }".to_string(),
+        HttpMethod::Options => {
+            ": { status: number, headers: {[string]: string}, body: any? }".to_string()
+        }
+        _ => func
+            .response_schema
+            .as_ref()
+            .map_or_else(String::new, |r| format!(": {r}")),
+    }
+}
+
 /// Transitively resolve all schema names reachable from the initial set.
 ///
 /// Performs a BFS walk: for each schema name in `initial`, looks it up in
@@ -145,6 +201,9 @@ fn resolve_transitive_schemas(
             for field in &schema.fields {
                 field.field_type.collect_refs(&mut queue);
             }
+            if let Some(discriminator) = &schema.discriminator {
+                queue.extend(discriminator.mapping.values().cloned());
+            }
         }
     }
     let mut sorted: Vec<String> = resolved
@@ -159,11 +218,14 @@ fn resolve_transitive_schemas(
 pub fn render_function_docs(func: &FunctionDef, schemas: &[SchemaDef]) -> String {
     let mut output = render_function_annotation(func);
 
-    // Collect directly referenced schema names from response and request body
+    // Collect directly referenced schema names from response, error and request body
     let mut needed: Vec<String> = Vec::new();
     if let Some(ref schema) = func.response_schema {
         needed.push(schema.clone());
     }
+    if let Some(ref schema) = func.error_schema {
+        needed.push(schema.clone());
+    }
     if let Some(ref body) = func.request_body {
         needed.push(body.schema.clone());
     }
@@ -194,11 +256,15 @@ pub fn render_function_docs(func: &FunctionDef, schemas: &[SchemaDef]) -> String
 /// - First line: description (if present), prefixed with `-- `
 /// - One `-- @param` line per parameter: `-- @param name: type[?] - description`
 /// - Optional params get `?` suffix on type
-/// - Function signature: `function sdk.<server>.<tool_name>(params: { ... }): any end`
-/// - If no params, signature is: `function sdk.<server>.<tool_name>(): any end`
-/// - Return type is always `any` (MCP tools return opaque content)
+/// - Function signature: `function sdk.<server>.<tool_name>(params: { ... }): <ret> end`
+/// - If no params, signature is: `function sdk.<server>.<tool_name>(): <ret> end`
+/// - `<ret>` is the tool's `outputSchema` type when advertised, else `any`
 pub fn render_mcp_tool_annotation(tool: &McpToolDef) -> String {
     let mut lines = Vec::new();
+    let return_type = tool
+        .output_type
+        .as_ref()
+        .map_or_else(|| "any".to_string(), field_type_to_luau);
 
     // Description
     if let Some(desc) = &tool.description {
@@ -221,7 +287,7 @@ pub fn render_mcp_tool_annotation(tool: &McpToolDef) -> String {
     // Function signature
     if tool.params.is_empty() {
         lines.push(format!(
-            "function sdk.{}.{}(): any end",
+            "function sdk.{}.{}(): {return_type} end",
             tool.server, tool.name
         ));
     } else {
@@ -234,7 +300,7 @@ pub fn render_mcp_tool_annotation(tool: &McpToolDef) -> String {
             })
             .collect();
         lines.push(format!(
-            "function sdk.{}.{}(params: {{ {} }}): any end",
+            "function sdk.{}.{}(params: {{ {} }}): {return_type} end",
             tool.server,
             tool.name,
             param_entries.join(", ")
@@ -249,7 +315,8 @@ pub fn render_mcp_tool_annotation(tool: &McpToolDef) -> String {
 ///
 /// Like [`render_mcp_tool_annotation`] but uses transitive `$ref` resolution
 /// (the same algorithm as [`render_function_docs`]) to include only the schemas
-/// actually referenced by the tool's parameters, rather than dumping all schemas.
+/// actually referenced by the tool's parameters and return type, rather than
+/// dumping all schemas.
 pub fn render_mcp_tool_docs(tool: &McpToolDef) -> String {
     let mut output = render_mcp_tool_annotation(tool);
 
@@ -265,6 +332,9 @@ pub fn render_mcp_tool_docs(tool: &McpToolDef) -> String {
     for param in &tool.params {
         param.field_type.collect_refs(&mut needed);
     }
+    if let Some(output_type) = &tool.output_type {
+        output_type.collect_refs(&mut needed);
+    }
 
     for name in resolve_transitive_schemas(needed, &schema_map) {
         output.push_str("\n\n");
@@ -296,6 +366,39 @@ pub fn render_schema_annotation(schema: &SchemaDef) -> String {
         }
     }
 
+    if let Some(discriminator) = &schema.discriminator {
+        let mapping: Vec<String> = discriminator
+            .mapping
+            .iter()
+            .map(|(value, target)| format!("\"{value}\" -> {target}"))
+            .collect();
+        if mapping.is_empty() {
+            lines.push(format!(
+                "-- Discriminated by \"{}\"",
+                discriminator.property_name
+            ));
+        } else {
+            lines.push(format!(
+                "-- Discriminated by \"{}\": {}",
+                discriminator.property_name,
+                mapping.join(", ")
+            ));
+        }
+
+        // A discriminated union without its own fields is the union of its variants
+        if schema.fields.is_empty() && !discriminator.mapping.is_empty() {
+            let variants: BTreeSet<&str> =
+                discriminator.mapping.values().map(String::as_str).collect();
+            let variants: Vec<&str> = variants.into_iter().collect();
+            lines.push(format!(
+                "export type {} = {}",
+                schema.name,
+                variants.join(" | ")
+            ));
+            return lines.join("\n");
+        }
+    }
+
     // Type definition opening
     lines.push(format!("export type {} = {{", schema.name));
 
@@ -303,19 +406,24 @@ pub fn render_schema_annotation(schema: &SchemaDef) -> String {
     for field in &schema.fields {
         let type_str = field.enum_values.as_ref().map_or_else(
             || field_type_to_luau(&field.field_type),
-            |ev| render_enum_type(ev),
+            |ev| render_enum_type(ev, enum_values_quoted(&field.field_type)),
         );
-        let optional_marker = if !field.required || field.nullable {
-            "?"
-        } else {
-            ""
-        };
+        let optional_marker = optional_marker(field);
         let mut comment_parts: Vec<String> = Vec::new();
         if let Some(d) = &field.description {
             comment_parts.push(d.trim().to_string());
         }
-        if let Some(f) = &field.format {
-            comment_parts.push(format!("({f})"));
+        match field.format.as_deref() {
+            // The SDK base64-encodes these, so scripts pass the raw bytes
+            Some("byte") => comment_parts.push("(byte: raw string, sent base64)".to_string()),
+            Some(f) => comment_parts.push(format!("({f})")),
+            None => {}
+        }
+        if let Some(example) = &field.example {
+            comment_parts.push(render_example(example));
+        }
+        if let (Some(values), Some(descriptions)) = (&field.enum_values, &field.enum_descriptions) {
+            comment_parts.push(render_enum_descriptions(values, descriptions));
         }
         let desc = if comment_parts.is_empty() {
             String::new()
@@ -335,6 +443,18 @@ pub fn render_schema_annotation(schema: &SchemaDef) -> String {
     lines.join("\n")
 }
 
+/// Longest example rendered in full; longer ones are cut off with `...`.
+const MAX_EXAMPLE_CHARS: usize = 80;
+
+/// Render an example value as a single-line `e.g. <json>` comment fragment.
+fn render_example(example: &serde_json::Value) -> String {
+    let json = example.to_string();
+    match json.char_indices().nth(MAX_EXAMPLE_CHARS) {
+        Some((cut, _)) => format!("e.g. {}...", &json[..cut]),
+        None => format!("e.g. {json}"),
+    }
+}
+
 /// Generate annotation files grouped by tag.
 ///
 /// Returns a `Vec<(filename, content)>` where each file corresponds to
@@ -383,6 +503,9 @@ pub fn generate_annotation_files(manifest: &Manifest) -> Vec<(String, String)> {
             if let Some(ref schema) = func.response_schema {
                 referenced_schemas.insert(schema.as_str());
             }
+            if let Some(ref schema) = func.error_schema {
+                referenced_schemas.insert(schema.as_str());
+            }
             if let Some(ref body) = func.request_body {
                 referenced_schemas.insert(body.schema.as_str());
             }
@@ -468,9 +591,17 @@ mod tests {
                 enum_values: None,
                 format: None,
                 frozen_value: None,
+                deprecated: false,
+                example: None,
+                enum_descriptions: None,
+                boolean_style: None,
+                multiple_of: None,
             }],
             request_body: None,
             response_schema: Some("Pet".to_string()),
+            error_schema: None,
+            auth: None,
+            link_paginated: false,
         };
 
         let output = render_function_annotation(&func);
@@ -514,6 +645,11 @@ mod tests {
                     enum_values: None,
                     format: None,
                     frozen_value: None,
+                    deprecated: false,
+                    example: None,
+                    enum_descriptions: None,
+                    boolean_style: None,
+                    multiple_of: None,
                 },
                 ParamDef {
                     name: "limit".to_string(),
@@ -525,10 +661,18 @@ mod tests {
                     enum_values: None,
                     format: None,
                     frozen_value: None,
+                    deprecated: false,
+                    example: None,
+                    enum_descriptions: None,
+                    boolean_style: None,
+                    multiple_of: None,
                 },
             ],
             request_body: None,
             response_schema: Some("Pet".to_string()),
+            error_schema: None,
+            auth: None,
+            link_paginated: false,
         };
 
         let output = render_function_annotation(&func);
@@ -577,9 +721,17 @@ mod tests {
                 ]),
                 format: None,
                 frozen_value: None,
+                deprecated: false,
+                example: None,
+                enum_descriptions: None,
+                boolean_style: None,
+                multiple_of: None,
             }],
             request_body: None,
             response_schema: None,
+            error_schema: None,
+            auth: None,
+            link_paginated: false,
         };
 
         let output = render_function_annotation(&func);
@@ -589,6 +741,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_render_function_with_integer_enum_param() {
+        let func = FunctionDef {
+            name: "list_responses".to_string(),
+            api: "hooks".to_string(),
+            tag: None,
+            method: HttpMethod::Get,
+            path: "/responses".to_string(),
+            summary: None,
+            description: None,
+            deprecated: false,
+            parameters: vec![ParamDef {
+                name: "status_code".to_string(),
+                location: ParamLocation::Query,
+                param_type: ParamType::Integer,
+                required: true,
+                description: None,
+                default: None,
+                enum_values: Some(vec![
+                    "200".to_string(),
+                    "404".to_string(),
+                    "500".to_string(),
+                ]),
+                format: None,
+                frozen_value: None,
+                deprecated: false,
+                example: None,
+                enum_descriptions: None,
+                boolean_style: None,
+                multiple_of: None,
+            }],
+            request_body: None,
+            response_schema: None,
+            error_schema: None,
+            auth: None,
+            link_paginated: false,
+        };
+
+        let output = render_function_annotation(&func);
+        assert!(
+            output.contains("status_code: (200 | 404 | 500)"),
+            "Integer enum should render unquoted literals. Got:\n{output}"
+        );
+    }
+
     #[test]
     fn test_render_function_deprecated() {
         let func = FunctionDef {
@@ -603,6 +800,9 @@ mod tests {
             parameters: vec![],
             request_body: None,
             response_schema: None,
+            error_schema: None,
+            auth: None,
+            link_paginated: false,
         };
 
         let output = render_function_annotation(&func);
@@ -612,6 +812,246 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_render_examples() {
+        let schema = SchemaDef {
+            name: "Pet".to_string(),
+            description: None,
+            fields: vec![
+                FieldDef {
+                    name: "name".to_string(),
+                    field_type: FieldType::String,
+                    required: true,
+                    description: Some("The pet's name".to_string()),
+                    enum_values: None,
+                    nullable: false,
+                    format: None,
+                    example: Some(serde_json::json!("Fido")),
+                    enum_descriptions: None,
+                },
+                FieldDef {
+                    name: "tags".to_string(),
+                    field_type: FieldType::Array {
+                        items: Box::new(FieldType::String),
+                    },
+                    required: false,
+                    description: None,
+                    enum_values: None,
+                    nullable: false,
+                    format: None,
+                    example: Some(serde_json::json!(["x".repeat(100)])),
+                    enum_descriptions: None,
+                },
+            ],
+            discriminator: None,
+        };
+        let output = render_schema_annotation(&schema);
+        assert!(
+            output.contains(r#"name: string,  -- The pet's name e.g. "Fido""#),
+            "Got:\n{output}"
+        );
+        let tags = output.lines().find(|l| l.contains("tags:")).unwrap();
+        assert!(
+            tags.ends_with(&format!("-- e.g. [\"{}...", "x".repeat(78))),
+            "Got: {tags}"
+        );
+
+        let param = |name: &str, description: Option<&str>| ParamDef {
+            name: name.to_string(),
+            location: ParamLocation::Query,
+            param_type: ParamType::Integer,
+            required: false,
+            description: description.map(str::to_string),
+            default: None,
+            enum_values: None,
+            format: None,
+            frozen_value: None,
+            deprecated: false,
+            example: Some(serde_json::json!(25)),
+            enum_descriptions: None,
+            boolean_style: None,
+            multiple_of: None,
+        };
+        let func = FunctionDef {
+            name: "list_pets".to_string(),
+            api: "petstore".to_string(),
+            tag: None,
+            method: HttpMethod::Get,
+            path: "/pets".to_string(),
+            summary: None,
+            description: None,
+            deprecated: false,
+            parameters: vec![param("limit", Some("Page size")), param("offset", None)],
+            request_body: None,
+            response_schema: None,
+            error_schema: None,
+            auth: None,
+            link_paginated: false,
+        };
+        let output = render_function_annotation(&func);
+        assert!(
+            output.contains("-- @param limit - Page size (e.g. 25)"),
+            "Got:\n{output}"
+        );
+        assert!(
+            output.contains("-- @param offset - e.g. 25"),
+            "Got:\n{output}"
+        );
+    }
+
+    #[test]
+    fn test_render_link_paginated_all_variant() {
+        let func = FunctionDef {
+            name: "list_repos".to_string(),
+            api: "github".to_string(),
+            tag: None,
+            method: HttpMethod::Get,
+            path: "/repos".to_string(),
+            summary: None,
+            description: None,
+            deprecated: false,
+            parameters: vec![],
+            request_body: None,
+            response_schema: Some("{Repo}".to_string()),
+            error_schema: None,
+            auth: None,
+            link_paginated: true,
+        };
+
+        let output = render_function_annotation(&func);
+        assert!(
+            output.contains("function sdk.list_repos(): {Repo} end"),
+            "Got:\n{output}"
+        );
+        assert!(
+            output.contains("function sdk.list_repos_all(): {Repo} end"),
+            "Missing _all variant. Got:\n{output}"
+        );
+    }
+
+    #[test]
+    fn test_render_error_schema() {
+        let func = FunctionDef {
+            name: "get_pet".to_string(),
+            api: "petstore".to_string(),
+            tag: None,
+            method: HttpMethod::Get,
+            path: "/pets/{id}".to_string(),
+            summary: None,
+            description: None,
+            deprecated: false,
+            parameters: vec![],
+            request_body: None,
+            response_schema: Some("Pet".to_string()),
+            error_schema: Some("Error".to_string()),
+            auth: None,
+            link_paginated: false,
+        };
+        let schema = |name: &str| SchemaDef {
+            name: name.to_string(),
+            description: None,
+            fields: vec![],
+            discriminator: None,
+        };
+
+        let output = render_function_docs(&func, &[schema("Pet"), schema("Error")]);
+        assert!(
+            output.contains("-- on error: Error\nfunction sdk.get_pet(): Pet end"),
+            "Got:\n{output}"
+        );
+        assert!(output.contains("export type Error = {"), "Got:\n{output}");
+    }
+
+    #[test]
+    fn test_render_head_and_options_return_metadata() {
+        let mut func = FunctionDef {
+            name: "check_repo".to_string(),
+            api: "github".to_string(),
+            tag: None,
+            method: HttpMethod::Head,
+            path: "/repos".to_string(),
+            summary: None,
+            description: None,
+            deprecated: false,
+            parameters: vec![],
+            request_body: None,
+            response_schema: None,
+            error_schema: None,
+            auth: None,
+            link_paginated: false,
+        };
+
+        let output = render_function_annotation(&func);
+        assert!(
+            output.contains(
+                "function sdk.check_repo(): { status: number, headers: {[string]: string} } end"
+            ),
+            "Got:\n{output}"
+        );
+
+        func.method = HttpMethod::Options;
+        let output = render_function_annotation(&func);
+        assert!(
+            output.contains("headers: {[string]: string}, body: any? } end"),
+            "Got:\n{output}"
+        );
+    }
+
+    #[test]
+    fn test_render_deprecated_params() {
+        let param = |name: &str, description: Option<&str>, deprecated: bool| ParamDef {
+            name: name.to_string(),
+            location: ParamLocation::Query,
+            param_type: ParamType::String,
+            required: false,
+            description: description.map(str::to_string),
+            default: None,
+            enum_values: None,
+            format: None,
+            frozen_value: None,
+            deprecated,
+            example: None,
+            enum_descriptions: None,
+            boolean_style: None,
+            multiple_of: None,
+        };
+        let func = FunctionDef {
+            name: "list_pets".to_string(),
+            api: "petstore".to_string(),
+            tag: None,
+            method: HttpMethod::Get,
+            path: "/pets".to_string(),
+            summary: Some("List pets".to_string()),
+            description: None,
+            deprecated: false,
+            parameters: vec![
+                param("status", Some("Filter by status"), false),
+                param("state", Some("Use status instead"), true),
+                param("legacy", None, true),
+            ],
+            request_body: None,
+            response_schema: None,
+            error_schema: None,
+            auth: None,
+            link_paginated: false,
+        };
+
+        let output = render_function_annotation(&func);
+        assert!(output.contains("-- @param status - Filter by status\n"));
+        assert!(
+            output.contains("-- @param state - @deprecated Use status instead\n"),
+            "Got:\n{output}"
+        );
+        assert!(
+            output.contains("-- @param legacy - @deprecated\n"),
+            "Got:\n{output}"
+        );
+        assert!(
+            !output.lines().any(|l| l == "-- @deprecated"),
+            "function itself is not deprecated. Got:\n{output}"
+        );
+    }
+
     #[test]
     fn test_render_function_with_request_body() {
         let func = FunctionDef {
@@ -631,6 +1071,9 @@ mod tests {
                 description: Some("The pet to create".to_string()),
             }),
             response_schema: Some("Pet".to_string()),
+            error_schema: None,
+            auth: None,
+            link_paginated: false,
         };
 
         let output = render_function_annotation(&func);
@@ -644,6 +1087,85 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_render_discriminated_union_annotation() {
+        let schema = SchemaDef {
+            name: "Shape".to_string(),
+            description: Some("A geometric shape".to_string()),
+            fields: vec![],
+            discriminator: Some(DiscriminatorDef {
+                property_name: "kind".to_string(),
+                mapping: BTreeMap::from([
+                    ("circle".to_string(), "Circle".to_string()),
+                    ("square".to_string(), "Square".to_string()),
+                ]),
+            }),
+        };
+        let output = render_schema_annotation(&schema);
+        assert_eq!(
+            output,
+            "-- A geometric shape\n\
+             -- Discriminated by \"kind\": \"circle\" -> Circle, \"square\" -> Square\n\
+             export type Shape = Circle | Square"
+        );
+    }
+
+    #[test]
+    fn test_function_docs_include_discriminator_variants() {
+        let variant = |name: &str| SchemaDef {
+            name: name.to_string(),
+            description: None,
+            fields: vec![FieldDef {
+                name: "kind".to_string(),
+                field_type: FieldType::String,
+                required: true,
+                description: None,
+                enum_values: None,
+                nullable: false,
+                format: None,
+                example: None,
+                enum_descriptions: None,
+            }],
+            discriminator: None,
+        };
+        let schemas = vec![
+            SchemaDef {
+                name: "Shape".to_string(),
+                description: None,
+                fields: vec![],
+                discriminator: Some(DiscriminatorDef {
+                    property_name: "kind".to_string(),
+                    mapping: BTreeMap::from([("circle".to_string(), "Circle".to_string())]),
+                }),
+            },
+            variant("Circle"),
+            variant("Unrelated"),
+        ];
+        let func = FunctionDef {
+            name: "get_shape".to_string(),
+            api: "shapes".to_string(),
+            tag: None,
+            method: HttpMethod::Get,
+            path: "/shape".to_string(),
+            summary: None,
+            description: None,
+            deprecated: false,
+            parameters: vec![],
+            request_body: None,
+            response_schema: Some("Shape".to_string()),
+            error_schema: None,
+            auth: None,
+            link_paginated: false,
+        };
+        let output = render_function_docs(&func, &schemas);
+        assert!(
+            output.contains("export type Shape = Circle"),
+            "Got:\n{output}"
+        );
+        assert!(output.contains("export type Circle = {"), "Got:\n{output}");
+        assert!(!output.contains("Unrelated"), "Got:\n{output}");
+    }
+
     #[test]
     fn test_render_schema_annotation() {
         let schema = SchemaDef {
@@ -658,6 +1180,8 @@ mod tests {
                     enum_values: None,
                     nullable: false,
                     format: None,
+                    example: None,
+                    enum_descriptions: None,
                 },
                 FieldDef {
                     name: "name".to_string(),
@@ -667,6 +1191,8 @@ mod tests {
                     enum_values: None,
                     nullable: false,
                     format: None,
+                    example: None,
+                    enum_descriptions: None,
                 },
                 FieldDef {
                     name: "tags".to_string(),
@@ -678,6 +1204,8 @@ mod tests {
                     enum_values: None,
                     nullable: false,
                     format: None,
+                    example: None,
+                    enum_descriptions: None,
                 },
                 FieldDef {
                     name: "owner".to_string(),
@@ -689,8 +1217,11 @@ mod tests {
                     enum_values: None,
                     nullable: false,
                     format: None,
+                    example: None,
+                    enum_descriptions: None,
                 },
             ],
+            discriminator: None,
         };
 
         let output = render_schema_annotation(&schema);
@@ -738,6 +1269,8 @@ mod tests {
                     enum_values: None,
                     nullable: false,
                     format: None,
+                    example: None,
+                    enum_descriptions: None,
                 },
                 FieldDef {
                     name: "label".to_string(),
@@ -747,8 +1280,11 @@ mod tests {
                     enum_values: None,
                     nullable: false,
                     format: None,
+                    example: None,
+                    enum_descriptions: None,
                 },
             ],
+            discriminator: None,
         };
 
         let output = render_schema_annotation(&schema);
@@ -783,7 +1319,10 @@ mod tests {
                 ]),
                 nullable: false,
                 format: None,
+                example: None,
+                enum_descriptions: None,
             }],
+            discriminator: None,
         };
 
         let output = render_schema_annotation(&schema);
@@ -798,15 +1337,25 @@ mod tests {
     #[allow(clippy::too_many_lines)]
     fn test_generate_annotation_files() {
         let manifest = Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
             apis: vec![ApiConfig {
                 name: "petstore".to_string(),
                 base_url: "https://petstore.example.com/v1".to_string(),
                 description: Some("A sample petstore API".to_string()),
                 version: Some("1.0.0".to_string()),
-                auth: Some(AuthConfig::Bearer {
-                    header: "Authorization".to_string(),
-                    prefix: "Bearer ".to_string(),
-                }),
+                auth: vec![AuthScheme {
+                    name: "bearerAuth".to_string(),
+                    config: AuthConfig::Bearer {
+                        header: "Authorization".to_string(),
+                        prefix: "Bearer ".to_string(),
+                    },
+                    oauth2: false,
+                }],
+                constant_headers: BTreeMap::new(),
+                rate_limit: None,
+                default_query: BTreeMap::new(),
+                deprecated: false,
+                boolean_style: None,
             }],
             functions: vec![
                 FunctionDef {
@@ -821,6 +1370,9 @@ mod tests {
                     parameters: vec![],
                     request_body: None,
                     response_schema: Some("Pet".to_string()),
+                    error_schema: None,
+                    auth: None,
+                    link_paginated: false,
                 },
                 FunctionDef {
                     name: "create_pet".to_string(),
@@ -839,6 +1391,9 @@ mod tests {
                         description: None,
                     }),
                     response_schema: Some("Pet".to_string()),
+                    error_schema: None,
+                    auth: None,
+                    link_paginated: false,
                 },
             ],
             schemas: vec![
@@ -853,7 +1408,10 @@ mod tests {
                         enum_values: None,
                         nullable: false,
                         format: None,
+                        example: None,
+                        enum_descriptions: None,
                     }],
+                    discriminator: None,
                 },
                 SchemaDef {
                     name: "NewPet".to_string(),
@@ -866,10 +1424,14 @@ mod tests {
                         enum_values: None,
                         nullable: false,
                         format: None,
+                        example: None,
+                        enum_descriptions: None,
                     }],
+                    discriminator: None,
                 },
             ],
             mcp_servers: vec![],
+            generated: None,
         };
 
         let files = generate_annotation_files(&manifest);
@@ -934,6 +1496,8 @@ mod tests {
                     enum_values: None,
                     nullable: false,
                     format: None,
+                    example: None,
+                    enum_descriptions: None,
                 },
                 FieldDef {
                     name: "deleted_at".to_string(),
@@ -943,8 +1507,11 @@ mod tests {
                     enum_values: None,
                     nullable: true,
                     format: Some("date-time".to_string()),
+                    example: None,
+                    enum_descriptions: None,
                 },
             ],
+            discriminator: None,
         };
 
         let output = render_schema_annotation(&schema);
@@ -958,6 +1525,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_render_nullable_required_enum_field() {
+        let schema = SchemaDef {
+            name: "Job".to_string(),
+            description: None,
+            fields: vec![FieldDef {
+                name: "status".to_string(),
+                field_type: FieldType::String,
+                required: true,
+                description: None,
+                enum_values: Some(vec!["a".to_string(), "b".to_string()]),
+                nullable: true,
+                format: None,
+                example: None,
+                enum_descriptions: None,
+            }],
+            discriminator: None,
+        };
+
+        let output = render_schema_annotation(&schema);
+        assert!(
+            output.contains(r#"status: ("a" | "b")?,"#),
+            "Nullable required enum should keep the union and have ?. Got:\n{output}"
+        );
+    }
+
     #[test]
     fn test_render_format_comment() {
         let schema = SchemaDef {
@@ -971,7 +1564,10 @@ mod tests {
                 enum_values: None,
                 nullable: false,
                 format: Some("uuid".to_string()),
+                example: None,
+                enum_descriptions: None,
             }],
+            discriminator: None,
         };
 
         let output = render_schema_annotation(&schema);
@@ -1000,7 +1596,10 @@ mod tests {
                 enum_values: None,
                 nullable: false,
                 format: None,
+                example: None,
+                enum_descriptions: None,
             }],
+            discriminator: None,
         };
 
         let output = render_schema_annotation(&schema);
@@ -1032,6 +1631,11 @@ mod tests {
                     enum_values: None,
                     format: None,
                     frozen_value: None,
+                    deprecated: false,
+                    example: None,
+                    enum_descriptions: None,
+                    boolean_style: None,
+                    multiple_of: None,
                 },
                 ParamDef {
                     name: "limit".to_string(),
@@ -1043,10 +1647,18 @@ mod tests {
                     enum_values: None,
                     format: None,
                     frozen_value: None,
+                    deprecated: false,
+                    example: None,
+                    enum_descriptions: None,
+                    boolean_style: None,
+                    multiple_of: None,
                 },
             ],
             request_body: None,
             response_schema: Some("Pet".to_string()),
+            error_schema: None,
+            auth: None,
+            link_paginated: false,
         };
 
         let output = render_function_annotation(&func);
@@ -1079,6 +1691,11 @@ mod tests {
                 enum_values: None,
                 format: None,
                 frozen_value: None,
+                deprecated: false,
+                example: None,
+                enum_descriptions: None,
+                boolean_style: None,
+                multiple_of: None,
             }],
             request_body: Some(RequestBodyDef {
                 content_type: "application/json".to_string(),
@@ -1087,6 +1704,9 @@ mod tests {
                 description: None,
             }),
             response_schema: Some("Pet".to_string()),
+            error_schema: None,
+            auth: None,
+            link_paginated: false,
         };
 
         let output = render_function_annotation(&func);
@@ -1119,9 +1739,17 @@ mod tests {
                 enum_values: None,
                 format: None,
                 frozen_value: Some("v2".to_string()),
+                deprecated: false,
+                example: None,
+                enum_descriptions: None,
+                boolean_style: None,
+                multiple_of: None,
             }],
             request_body: None,
             response_schema: None,
+            error_schema: None,
+            auth: None,
+            link_paginated: false,
         };
 
         let output = render_function_annotation(&func);
@@ -1152,6 +1780,8 @@ mod tests {
                             enum_values: None,
                             nullable: false,
                             format: None,
+                            example: None,
+                            enum_descriptions: None,
                         },
                         FieldDef {
                             name: "retries".to_string(),
@@ -1161,15 +1791,21 @@ mod tests {
                             enum_values: None,
                             nullable: false,
                             format: None,
+                            example: None,
+                            enum_descriptions: None,
                         },
                     ],
+                    additional: None,
                 },
                 required: true,
                 description: None,
                 enum_values: None,
                 nullable: false,
                 format: None,
+                example: None,
+                enum_descriptions: None,
             }],
+            discriminator: None,
         };
 
         let output = render_schema_annotation(&schema);
@@ -1198,21 +1834,30 @@ mod tests {
                                 enum_values: None,
                                 nullable: false,
                                 format: None,
+                                example: None,
+                                enum_descriptions: None,
                             }],
+                            additional: None,
                         },
                         required: true,
                         description: None,
                         enum_values: None,
                         nullable: false,
                         format: None,
+                        example: None,
+                        enum_descriptions: None,
                     }],
+                    additional: None,
                 },
                 required: true,
                 description: None,
                 enum_values: None,
                 nullable: false,
                 format: None,
+                example: None,
+                enum_descriptions: None,
             }],
+            discriminator: None,
         };
 
         let output = render_schema_annotation(&schema);
@@ -1243,6 +1888,11 @@ mod tests {
                 enum_values: None,
                 format: None,
                 frozen_value: Some("v2".to_string()),
+                deprecated: false,
+                example: None,
+                enum_descriptions: None,
+                boolean_style: None,
+                multiple_of: None,
             }],
             request_body: Some(RequestBodyDef {
                 content_type: "application/json".to_string(),
@@ -1251,6 +1901,9 @@ mod tests {
                 description: None,
             }),
             response_schema: None,
+            error_schema: None,
+            auth: None,
+            link_paginated: false,
         };
 
         let output = render_function_annotation(&func);
@@ -1284,6 +1937,8 @@ mod tests {
             ],
             schemas: vec![],
             output_schemas: vec![],
+            output_type: None,
+            idempotent: false,
         };
         let output = render_mcp_tool_annotation(&tool);
         assert!(
@@ -1325,6 +1980,8 @@ mod tests {
             params: vec![],
             schemas: vec![],
             output_schemas: vec![],
+            output_type: None,
+            idempotent: false,
         };
         let output = render_mcp_tool_annotation(&tool);
         assert!(
@@ -1364,9 +2021,14 @@ mod tests {
                     enum_values: None,
                     nullable: false,
                     format: None,
+                    example: None,
+                    enum_descriptions: None,
                 }],
+                discriminator: None,
             }],
             output_schemas: vec![],
+            output_type: None,
+            idempotent: false,
         };
         let output = render_mcp_tool_docs(&tool);
         assert!(
@@ -1409,7 +2071,10 @@ mod tests {
                         enum_values: None,
                         nullable: false,
                         format: None,
+                        example: None,
+                        enum_descriptions: None,
                     }],
+                    discriminator: None,
                 },
                 SchemaDef {
                     name: "B".to_string(),
@@ -1424,7 +2089,10 @@ mod tests {
                         enum_values: None,
                         nullable: false,
                         format: None,
+                        example: None,
+                        enum_descriptions: None,
                     }],
+                    discriminator: None,
                 },
                 SchemaDef {
                     name: "C".to_string(),
@@ -1437,16 +2105,22 @@ mod tests {
                         enum_values: None,
                         nullable: false,
                         format: None,
+                        example: None,
+                        enum_descriptions: None,
                     }],
+                    discriminator: None,
                 },
                 // This schema should NOT appear (not referenced)
                 SchemaDef {
                     name: "Unused".to_string(),
                     description: None,
                     fields: vec![],
+                    discriminator: None,
                 },
             ],
             output_schemas: vec![],
+            output_type: None,
+            idempotent: false,
         };
 
         let output = render_mcp_tool_docs(&tool);
@@ -1486,8 +2160,11 @@ mod tests {
                 name: "SomeUnused".to_string(),
                 description: None,
                 fields: vec![],
+                discriminator: None,
             }],
             output_schemas: vec![],
+            output_type: None,
+            idempotent: false,
         };
         let output = render_mcp_tool_docs(&tool);
         assert!(
@@ -1,71 +1,146 @@
-use std::collections::HashMap;
-use std::hash::BuildHasher;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
 use openapiv3::OpenAPI;
+use tracing::Instrument;
 
-use super::{annotations, manifest::Manifest, parser};
+use super::annotations;
+use super::manifest::{
+    BooleanStyle, FunctionDef, GeneratedMeta, MANIFEST_SCHEMA_VERSION, Manifest, RateLimit,
+};
+use super::parser::{self, FunctionNaming};
 use crate::config::SpecInput;
 
+/// What to do when two functions derive the same name.
+///
+/// Function names share a single `sdk` namespace at runtime across all specs,
+/// so a duplicate would otherwise silently replace the earlier function.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum DuplicateNamePolicy {
+    /// Keep the first function and rename later ones `name_2`, `name_3`, ...
+    /// with a warning.
+    #[default]
+    Suffix,
+    /// Fail generation.
+    Error,
+}
+
+/// How generated function names are scoped to their API.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum FunctionNamespace {
+    /// Functions keep their own names directly under `sdk` (`sdk.list_pets`).
+    #[default]
+    #[value(name = "none")]
+    Flat,
+    /// Functions are prefixed with their API name (`sdk.petstore_list_pets`).
+    Prefix,
+    /// Functions live in a per-API table (`sdk.petstore.list_pets`). The
+    /// function name is recorded as `petstore.list_pets`.
+    Nested,
+}
+
+impl FunctionNamespace {
+    /// The name `func` is registered and documented under.
+    fn apply(self, api: &str, func: &str) -> String {
+        match self {
+            Self::Flat => func.to_string(),
+            Self::Prefix => format!("{api}_{func}"),
+            Self::Nested => format!("{api}.{func}"),
+        }
+    }
+}
+
+/// Load one spec from a local path or an `http(s)` URL.
+async fn load_spec(source: &str) -> Result<OpenAPI> {
+    let spec = if source.starts_with("http://") || source.starts_with("https://") {
+        parser::load_spec_from_url(source).await?
+    } else {
+        parser::load_spec_from_file(Path::new(source))?
+    };
+    tracing::info!(paths = spec.paths.paths.len(), "loaded spec");
+    Ok(spec)
+}
+
+/// Options for [`generate`], bundled to avoid `clippy::too_many_arguments`.
+/// The default applies no config and writes `output_dir/manifest.json`.
+#[derive(Debug, Clone, Default)]
+pub struct GenerateOptions {
+    /// Parameter names/values frozen for every API.
+    pub global_frozen: HashMap<String, String>,
+    /// API name to per-API frozen params, layered over `global_frozen`.
+    pub per_api_frozen: HashMap<String, HashMap<String, String>>,
+    /// API name to constant headers sent on every call.
+    pub per_api_headers: HashMap<String, HashMap<String, String>>,
+    /// API name to its client-side rate limit.
+    pub per_api_rate_limits: HashMap<String, RateLimit>,
+    /// API name to query params sent unless overridden.
+    pub per_api_default_query: HashMap<String, HashMap<String, String>>,
+    /// API name to how its boolean params are written.
+    pub per_api_boolean_styles: HashMap<String, BooleanStyle>,
+    /// How colliding function names are handled.
+    pub duplicate_names: DuplicateNamePolicy,
+    /// Where manifest.json is written instead of `output_dir/manifest.json`.
+    pub manifest_out: Option<PathBuf>,
+    /// Log and skip a spec that fails to load or parse. The output is still
+    /// written from the specs that succeeded, and an error naming the failed
+    /// sources is returned afterwards.
+    pub continue_on_error: bool,
+    /// How each function name is scoped to its API.
+    pub function_namespace: FunctionNamespace,
+    /// How function names are cased.
+    pub function_naming: FunctionNaming,
+    /// Fail to load a spec containing a `$ref` that does not resolve, instead
+    /// of producing `unknown` types.
+    pub strict_schema: bool,
+}
+
 /// Run the full code generation pipeline: parse specs, build manifest,
 /// write manifest.json and Lua annotation files to disk.
 ///
-/// `global_frozen` contains parameter names/values applied to all APIs.
-/// `per_api_frozen` maps API name to per-API overrides.
-pub async fn generate<S1, S2, S3>(
+/// The manifest records the toolscript version, spec sources, and generation
+/// time in its `generated` field.
+pub async fn generate(
     specs: &[SpecInput],
     output_dir: &Path,
-    global_frozen: &HashMap<String, String, S1>,
-    per_api_frozen: &HashMap<String, HashMap<String, String, S3>, S2>,
-) -> Result<()>
-where
-    S1: BuildHasher + Sync,
-    S2: BuildHasher + Sync,
-    S3: BuildHasher + Sync,
-{
+    options: &GenerateOptions,
+) -> Result<()> {
     std::fs::create_dir_all(output_dir)?;
     let sdk_dir = output_dir.join("sdk");
     std::fs::create_dir_all(&sdk_dir)?;
 
     let mut combined = Manifest {
+        schema_version: MANIFEST_SCHEMA_VERSION,
         apis: vec![],
         functions: vec![],
         schemas: vec![],
         mcp_servers: vec![],
+        generated: Some(GeneratedMeta {
+            toolscript_version: env!("CARGO_PKG_VERSION").to_string(),
+            sources: specs.iter().map(|spec| spec.source.clone()).collect(),
+            generated_at: generation_time(),
+        }),
     };
 
+    let mut failures = Vec::new();
     for spec_input in specs {
-        let spec = if spec_input.source.starts_with("http://")
-            || spec_input.source.starts_with("https://")
-        {
-            parser::load_spec_from_url(&spec_input.source).await?
-        } else {
-            parser::load_spec_from_file(Path::new(&spec_input.source))?
-        };
-        let api_name = spec_input
-            .name
-            .clone()
-            .unwrap_or_else(|| derive_api_name(&spec));
-        let mut manifest = parser::spec_to_manifest(&spec, &api_name)?;
-
-        // Apply frozen parameter values from config.
-        // Build the merged map manually: start with global, then layer per-API on top.
-        let mut api_frozen: HashMap<String, String> = global_frozen
-            .iter()
-            .map(|(k, v)| (k.clone(), v.clone()))
-            .collect();
-        if let Some(per) = per_api_frozen.get(&api_name) {
-            api_frozen.extend(per.iter().map(|(k, v)| (k.clone(), v.clone())));
-        }
-        if !api_frozen.is_empty() {
-            for func in &mut manifest.functions {
-                for param in &mut func.parameters {
-                    if let Some(value) = api_frozen.get(&param.name) {
-                        param.frozen_value = Some(value.clone());
-                    }
+        let (api_name, mut manifest) =
+            match load_spec_manifest(spec_input, options.function_naming, options.strict_schema)
+                .await
+            {
+                Ok(loaded) => loaded,
+                Err(e) if options.continue_on_error => {
+                    tracing::error!(source = %spec_input.source, "skipping spec: {e:#}");
+                    failures.push(spec_input.source.clone());
+                    continue;
                 }
-            }
+                Err(e) => return Err(e),
+            };
+
+        apply_api_config(&mut manifest, &api_name, options);
+
+        for func in &mut manifest.functions {
+            func.name = options.function_namespace.apply(&api_name, &func.name);
         }
 
         combined.apis.extend(manifest.apis);
@@ -73,9 +148,20 @@ where
         combined.schemas.extend(manifest.schemas);
     }
 
+    resolve_duplicate_function_names(&mut combined.functions, options.duplicate_names)?;
+
     // Write manifest.json
+    let manifest_path = options
+        .manifest_out
+        .clone()
+        .unwrap_or_else(|| output_dir.join("manifest.json"));
+    if let Some(parent) = manifest_path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        std::fs::create_dir_all(parent)?;
+    }
     let manifest_json = serde_json::to_string_pretty(&combined)?;
-    std::fs::write(output_dir.join("manifest.json"), manifest_json)?;
+    std::fs::write(&manifest_path, manifest_json)?;
 
     // Write annotation files
     let files = annotations::generate_annotation_files(&combined);
@@ -83,6 +169,140 @@ where
         std::fs::write(sdk_dir.join(filename), content)?;
     }
 
+    if !failures.is_empty() {
+        bail!(
+            "{} of {} specs failed to load: {}",
+            failures.len(),
+            specs.len(),
+            failures.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Apply the config-derived parts of `options` for `api_name` to its freshly
+/// parsed manifest: frozen params, constant headers, default query params,
+/// rate limit and boolean style.
+fn apply_api_config(manifest: &mut Manifest, api_name: &str, options: &GenerateOptions) {
+    // Apply frozen parameter values from config.
+    // Build the merged map manually: start with global, then layer per-API on top.
+    let mut api_frozen: HashMap<String, String> = options
+        .global_frozen
+        .iter()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    if let Some(per) = options.per_api_frozen.get(api_name) {
+        api_frozen.extend(per.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+    if !api_frozen.is_empty() {
+        for func in &mut manifest.functions {
+            for param in &mut func.parameters {
+                if let Some(value) = api_frozen.get(&param.name) {
+                    param.frozen_value = Some(value.clone());
+                }
+            }
+        }
+    }
+
+    // Attach constant headers from config to this spec's API entry.
+    if let Some(headers) = options.per_api_headers.get(api_name) {
+        for api in &mut manifest.apis {
+            api.constant_headers
+                .extend(headers.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+    }
+
+    if let Some(params) = options.per_api_default_query.get(api_name) {
+        for api in &mut manifest.apis {
+            api.default_query
+                .extend(params.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+    }
+
+    if let Some(rate_limit) = options.per_api_rate_limits.get(api_name) {
+        for api in &mut manifest.apis {
+            api.rate_limit = Some(*rate_limit);
+        }
+    }
+
+    if let Some(style) = options.per_api_boolean_styles.get(api_name) {
+        for api in &mut manifest.apis {
+            api.boolean_style = Some(*style);
+        }
+    }
+}
+
+/// The manifest's `generated_at` timestamp: `SOURCE_DATE_EPOCH` when set, so
+/// that regenerating from the same specs reproduces the output byte for byte,
+/// otherwise the current time.
+fn generation_time() -> String {
+    std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.trim().parse::<i64>().ok())
+        .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+        .unwrap_or_else(chrono::Utc::now)
+        .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+/// Load one spec and convert it to a manifest, returning the API name used.
+async fn load_spec_manifest(
+    spec_input: &SpecInput,
+    function_naming: FunctionNaming,
+    strict_schema: bool,
+) -> Result<(String, Manifest)> {
+    let spec = load_spec(&spec_input.source)
+        .instrument(tracing::info_span!("load_spec", source = %spec_input.source))
+        .await?;
+    if strict_schema {
+        parser::check_refs_resolve(&spec)
+            .with_context(|| format!("strict schema check failed for {}", spec_input.source))?;
+    }
+    let api_name = spec_input
+        .name
+        .clone()
+        .unwrap_or_else(|| derive_api_name(&spec));
+    let manifest = parser::spec_to_manifest_with_naming(&spec, &api_name, function_naming)?;
+    Ok((api_name, manifest))
+}
+
+/// Detect functions sharing a name and rename or reject them per `policy`.
+///
+/// The first function with a given name keeps it; later ones get the lowest
+/// free `_N` suffix (starting at 2).
+pub fn resolve_duplicate_function_names(
+    functions: &mut [FunctionDef],
+    policy: DuplicateNamePolicy,
+) -> Result<()> {
+    let describe = |f: &FunctionDef| format!("{} {:?} {}", f.api, f.method, f.path);
+
+    let mut first_owner: HashMap<String, usize> = HashMap::new();
+    let mut taken: HashSet<String> = functions.iter().map(|f| f.name.clone()).collect();
+    for i in 0..functions.len() {
+        let name = functions[i].name.clone();
+        let Some(&owner) = first_owner.get(&name) else {
+            first_owner.insert(name, i);
+            continue;
+        };
+        if policy == DuplicateNamePolicy::Error {
+            bail!(
+                "duplicate function name '{name}': {} conflicts with {}",
+                describe(&functions[i]),
+                describe(&functions[owner])
+            );
+        }
+        let mut n = 2;
+        while taken.contains(&format!("{name}_{n}")) {
+            n += 1;
+        }
+        let renamed = format!("{name}_{n}");
+        tracing::warn!(
+            "duplicate function name '{name}' ({}), renamed to '{renamed}'",
+            describe(&functions[i])
+        );
+        taken.insert(renamed.clone());
+        first_owner.insert(renamed.clone(), i);
+        functions[i].name = renamed;
+    }
     Ok(())
 }
 
@@ -108,6 +328,7 @@ fn derive_api_name(spec: &OpenAPI) -> String {
 mod tests {
     #![allow(clippy::unwrap_used, clippy::expect_used)]
     use super::*;
+    use crate::codegen::manifest::HttpMethod;
     use crate::config::SpecInput;
 
     #[test]
@@ -135,15 +356,13 @@ paths: {}
     #[tokio::test]
     async fn test_generate_creates_output() {
         let output_dir = tempfile::tempdir().unwrap();
-        let no_per_api: HashMap<String, HashMap<String, String>> = HashMap::new();
         generate(
             &[SpecInput {
                 name: None,
                 source: "testdata/petstore.yaml".to_string(),
             }],
             output_dir.path(),
-            &HashMap::new(),
-            &no_per_api,
+            &GenerateOptions::default(),
         )
         .await
         .unwrap();
@@ -166,17 +385,92 @@ paths: {}
     }
 
     #[tokio::test]
-    async fn test_generate_with_explicit_name() {
+    async fn test_generate_continue_on_error_keeps_loadable_specs() {
+        let output_dir = tempfile::tempdir().unwrap();
+        let specs = [
+            SpecInput {
+                name: None,
+                source: "testdata/petstore.yaml".to_string(),
+            },
+            SpecInput {
+                name: None,
+                source: "testdata/does-not-exist.yaml".to_string(),
+            },
+        ];
+        let options_with = |continue_on_error| GenerateOptions {
+            continue_on_error,
+            ..GenerateOptions::default()
+        };
+
+        let err = generate(&specs, output_dir.path(), &options_with(false))
+            .await
+            .unwrap_err();
+        assert!(!output_dir.path().join("manifest.json").exists());
+        assert!(format!("{err:#}").contains("does-not-exist.yaml"));
+
+        let err = generate(&specs, output_dir.path(), &options_with(true))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("1 of 2 specs failed"), "got: {err}");
+        assert!(err.contains("testdata/does-not-exist.yaml"), "got: {err}");
+
+        let json = std::fs::read_to_string(output_dir.path().join("manifest.json")).unwrap();
+        let manifest: Manifest = serde_json::from_str(&json).unwrap();
+        assert_eq!(manifest.apis.len(), 1);
+        assert_eq!(manifest.apis[0].name, "petstore");
+        assert!(manifest.functions.iter().any(|f| f.name == "list_pets"));
+    }
+
+    #[tokio::test]
+    async fn test_generate_records_metadata_at_manifest_out() {
+        let output_dir = tempfile::tempdir().unwrap();
+        let manifest_out = output_dir.path().join("meta").join("petstore.json");
+        generate(
+            &[SpecInput {
+                name: None,
+                source: "testdata/petstore.yaml".to_string(),
+            }],
+            output_dir.path(),
+            &GenerateOptions {
+                manifest_out: Some(manifest_out.clone()),
+                ..GenerateOptions::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(!output_dir.path().join("manifest.json").exists());
+        let json = std::fs::read_to_string(&manifest_out).unwrap();
+        let manifest: Manifest = serde_json::from_str(&json).unwrap();
+        let meta = manifest
+            .generated
+            .as_ref()
+            .expect("generated metadata missing");
+        assert_eq!(meta.toolscript_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(meta.sources, vec!["testdata/petstore.yaml"]);
+        assert!(
+            chrono::DateTime::parse_from_rfc3339(&meta.generated_at).is_ok(),
+            "bad timestamp: {}",
+            meta.generated_at
+        );
+
+        // Metadata survives a serialize/deserialize round trip
+        let roundtripped: Manifest =
+            serde_json::from_str(&serde_json::to_string(&manifest).unwrap()).unwrap();
+        assert_eq!(roundtripped.generated, manifest.generated);
+    }
+
+    #[tokio::test]
+    async fn test_options_with_explicit_name() {
         let output_dir = tempfile::tempdir().unwrap();
-        let no_per_api: HashMap<String, HashMap<String, String>> = HashMap::new();
         generate(
             &[SpecInput {
                 name: Some("mystore".to_string()),
                 source: "testdata/petstore.yaml".to_string(),
             }],
             output_dir.path(),
-            &HashMap::new(),
-            &no_per_api,
+            &GenerateOptions::default(),
         )
         .await
         .unwrap();
@@ -191,7 +485,118 @@ paths: {}
     }
 
     #[tokio::test]
-    async fn test_generate_with_frozen_params() {
+    async fn test_generate_function_namespace_modes() {
+        for (mode, expected) in [
+            (FunctionNamespace::Prefix, "petstore_list_pets"),
+            (FunctionNamespace::Nested, "petstore.list_pets"),
+        ] {
+            let output_dir = tempfile::tempdir().unwrap();
+            generate(
+                &[SpecInput {
+                    name: Some("petstore".to_string()),
+                    source: "testdata/petstore.yaml".to_string(),
+                }],
+                output_dir.path(),
+                &GenerateOptions {
+                    function_namespace: mode,
+                    ..GenerateOptions::default()
+                },
+            )
+            .await
+            .unwrap();
+
+            let manifest: Manifest = serde_json::from_str(
+                &std::fs::read_to_string(output_dir.path().join("manifest.json")).unwrap(),
+            )
+            .unwrap();
+            assert!(
+                manifest.functions.iter().any(|f| f.name == expected),
+                "{mode:?}: no function named {expected}"
+            );
+            assert!(!manifest.functions.iter().any(|f| f.name == "list_pets"));
+
+            let annotations: String = std::fs::read_dir(output_dir.path().join("sdk"))
+                .unwrap()
+                .map(|e| std::fs::read_to_string(e.unwrap().path()).unwrap())
+                .collect();
+            assert!(
+                annotations.contains(&format!("function sdk.{expected}(")),
+                "{mode:?}: annotations missing sdk.{expected}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_strict_schema_rejects_unresolved_ref() {
+        let dir = tempfile::tempdir().unwrap();
+        let spec_path = dir.path().join("spec.yaml");
+        std::fs::write(
+            &spec_path,
+            r##"
+openapi: "3.0.3"
+info:
+  title: Test
+  version: "1.0.0"
+paths:
+  /items:
+    get:
+      operationId: listItems
+      responses:
+        "200":
+          description: OK
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/Missing"
+"##,
+        )
+        .unwrap();
+        let specs = [SpecInput {
+            name: Some("test".to_string()),
+            source: spec_path.to_string_lossy().into_owned(),
+        }];
+
+        for strict in [false, true] {
+            let output_dir = tempfile::tempdir().unwrap();
+            let result = generate(
+                &specs,
+                output_dir.path(),
+                &GenerateOptions {
+                    strict_schema: strict,
+                    ..GenerateOptions::default()
+                },
+            )
+            .await;
+            if strict {
+                let err = format!("{:#}", result.unwrap_err());
+                assert!(err.contains("#/components/schemas/Missing"), "{err}");
+                assert!(!output_dir.path().join("manifest.json").exists());
+            } else {
+                result.unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_function_namespace_value_names() {
+        use clap::ValueEnum;
+        assert_eq!(
+            FunctionNamespace::from_str("none", false),
+            Ok(FunctionNamespace::Flat)
+        );
+        assert_eq!(
+            FunctionNamespace::from_str("prefix", false),
+            Ok(FunctionNamespace::Prefix)
+        );
+        assert_eq!(
+            FunctionNamespace::from_str("nested", false),
+            Ok(FunctionNamespace::Nested)
+        );
+        assert!(FunctionNamespace::from_str("flat", false).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_options_with_frozen_params() {
         let output_dir = tempfile::tempdir().unwrap();
         let mut frozen = HashMap::new();
         frozen.insert("limit".to_string(), "10".to_string());
@@ -205,8 +610,10 @@ paths: {}
                 source: "testdata/petstore.yaml".to_string(),
             }],
             output_dir.path(),
-            &HashMap::new(),
-            &per_api,
+            &GenerateOptions {
+                per_api_frozen: per_api,
+                ..GenerateOptions::default()
+            },
         )
         .await
         .unwrap();
@@ -238,4 +645,138 @@ paths: {}
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_options_with_constant_headers() {
+        let output_dir = tempfile::tempdir().unwrap();
+        let mut headers = HashMap::new();
+        headers.insert("X-Tenant-ID".to_string(), "acme".to_string());
+
+        let mut per_api_headers = HashMap::new();
+        per_api_headers.insert("petstore".to_string(), headers);
+
+        generate(
+            &[SpecInput {
+                name: Some("petstore".to_string()),
+                source: "testdata/petstore.yaml".to_string(),
+            }],
+            output_dir.path(),
+            &GenerateOptions {
+                per_api_headers,
+                ..GenerateOptions::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let manifest: Manifest = serde_json::from_str(
+            &std::fs::read_to_string(output_dir.path().join("manifest.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.apis[0].constant_headers.get("X-Tenant-ID"),
+            Some(&"acme".to_string())
+        );
+
+        // Constant headers are not part of any function's parameters or annotations.
+        for entry in std::fs::read_dir(output_dir.path().join("sdk")).unwrap() {
+            let content = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+            assert!(!content.contains("X-Tenant-ID"));
+        }
+    }
+
+    const DUPLICATE_NAME_SPEC: &str = r#"
+openapi: "3.0.3"
+info:
+  title: Dupes
+  version: "1.0"
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        "200":
+          description: ok
+  /v2/pets:
+    get:
+      operationId: list_pets
+      responses:
+        "200":
+          description: ok
+"#;
+
+    #[tokio::test]
+    async fn test_generate_suffixes_duplicate_function_names() {
+        let spec_dir = tempfile::tempdir().unwrap();
+        let spec_path = spec_dir.path().join("dupes.yaml");
+        std::fs::write(&spec_path, DUPLICATE_NAME_SPEC).unwrap();
+        let output_dir = tempfile::tempdir().unwrap();
+        let spec = SpecInput {
+            name: None,
+            source: spec_path.to_string_lossy().to_string(),
+        };
+
+        generate(
+            std::slice::from_ref(&spec),
+            output_dir.path(),
+            &GenerateOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        let manifest_str =
+            std::fs::read_to_string(output_dir.path().join("manifest.json")).unwrap();
+        let manifest: Manifest = serde_json::from_str(&manifest_str).unwrap();
+        let mut names: Vec<(&str, &str)> = manifest
+            .functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.path.as_str()))
+            .collect();
+        names.sort_unstable();
+        assert_eq!(names, [("list_pets", "/pets"), ("list_pets_2", "/v2/pets")]);
+
+        let err = generate(
+            &[spec],
+            output_dir.path(),
+            &GenerateOptions {
+                duplicate_names: DuplicateNamePolicy::Error,
+                ..GenerateOptions::default()
+            },
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("duplicate function name 'list_pets'"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn test_duplicate_suffix_skips_taken_names() {
+        let func = |name: &str, path: &str| FunctionDef {
+            name: name.to_string(),
+            api: "a".to_string(),
+            tag: None,
+            method: HttpMethod::Get,
+            path: path.to_string(),
+            summary: None,
+            description: None,
+            deprecated: false,
+            parameters: vec![],
+            request_body: None,
+            response_schema: None,
+            error_schema: None,
+            auth: None,
+            link_paginated: false,
+        };
+        let mut functions = vec![
+            func("get", "/one"),
+            func("get_2", "/two"),
+            func("get", "/three"),
+        ];
+        resolve_duplicate_function_names(&mut functions, DuplicateNamePolicy::Suffix).unwrap();
+        let names: Vec<&str> = functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["get", "get_2", "get_3"]);
+    }
 }
@@ -2,6 +2,8 @@
 //!
 //! Used by both the `OpenAPI` codegen path and MCP tool schema conversion.
 
+use std::collections::BTreeMap;
+
 use serde_json::Value;
 
 use super::manifest::{FieldDef, FieldType, McpParamDef, SchemaDef};
@@ -26,7 +28,7 @@ pub fn json_schema_to_params(schema: &Value) -> Vec<McpParamDef> {
             let enum_values = extract_json_schema_enum(prop);
             let luau_type = enum_values.as_ref().map_or_else(
                 || field_type_to_luau(&field_type),
-                |ev| render_enum_type(ev),
+                |ev| render_enum_type(ev, enum_values_quoted(&field_type)),
             );
             let description = prop
                 .get("description")
@@ -65,16 +67,34 @@ pub fn json_schema_prop_to_field_def(name: &str, prop: &Value, required: bool) -
         enum_values: extract_json_schema_enum(prop),
         nullable: is_json_schema_nullable(prop),
         format: extract_json_schema_format(prop),
+        example: extract_json_schema_example(prop),
+        enum_descriptions: extract_json_schema_enum(prop)
+            .and_then(|values| extract_enum_descriptions(prop, &values)),
     }
 }
 
-/// Extract string enum values from a JSON Schema property's `"enum"` array.
+/// Extract enum values from a JSON Schema property's `"enum"` array, or its
+/// `"const"` as a single value.
+///
+/// String, integer, and boolean members are kept (non-strings in their JSON
+/// text form, e.g. `"200"`, `"true"`); anything else is skipped.
 fn extract_json_schema_enum(prop: &Value) -> Option<Vec<String>> {
-    let arr = prop.get("enum").and_then(Value::as_array)?;
-    let values: Vec<String> = arr
+    let members = match (
+        prop.get("enum").and_then(Value::as_array),
+        prop.get("const"),
+    ) {
+        (Some(arr), _) => arr.as_slice(),
+        (None, Some(pinned)) => std::slice::from_ref(pinned),
+        (None, None) => return None,
+    };
+    let values: Vec<String> = members
         .iter()
-        .filter_map(Value::as_str)
-        .map(String::from)
+        .filter_map(|v| match v {
+            Value::String(s) => Some(s.clone()),
+            Value::Number(n) if n.is_i64() || n.is_u64() => Some(n.to_string()),
+            Value::Bool(b) => Some(b.to_string()),
+            _ => None,
+        })
         .collect();
     if values.is_empty() {
         None
@@ -83,6 +103,49 @@ fn extract_json_schema_enum(prop: &Value) -> Option<Vec<String>> {
     }
 }
 
+/// Extract per-value enum descriptions from the `x-enum-descriptions` (or
+/// `x-enumDescriptions`) extension, falling back to `x-enumNames`.
+///
+/// An array lines up with `values` by position; an object maps values to
+/// descriptions. Values without a description are left out.
+pub fn extract_enum_descriptions(
+    prop: &Value,
+    values: &[String],
+) -> Option<BTreeMap<String, String>> {
+    let extension = ["x-enum-descriptions", "x-enumDescriptions", "x-enumNames"]
+        .iter()
+        .find_map(|key| prop.get(*key))?;
+    let descriptions: BTreeMap<String, String> = match extension {
+        Value::Array(items) => values
+            .iter()
+            .zip(items)
+            .filter_map(|(value, desc)| Some((value.clone(), desc.as_str()?.to_string())))
+            .collect(),
+        Value::Object(map) => values
+            .iter()
+            .filter_map(|value| Some((value.clone(), map.get(value)?.as_str()?.to_string())))
+            .collect(),
+        _ => return None,
+    };
+    if descriptions.is_empty() {
+        None
+    } else {
+        Some(descriptions)
+    }
+}
+
+/// Render enum descriptions in enum order as `a: first; b: second`.
+pub fn render_enum_descriptions(
+    values: &[String],
+    descriptions: &BTreeMap<String, String>,
+) -> String {
+    values
+        .iter()
+        .filter_map(|value| Some(format!("{value}: {}", descriptions.get(value)?)))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
 /// Check whether a JSON Schema property is nullable.
 ///
 /// Supports both `OpenAPI` 3.0 (`"nullable": true`) and JSON Schema 2020-12
@@ -99,6 +162,18 @@ fn is_json_schema_nullable(prop: &Value) -> bool {
     false
 }
 
+/// Extract an example value: `"example"` (`OpenAPI` 3.0), or the first entry
+/// of `"examples"` (JSON Schema 2020-12).
+fn extract_json_schema_example(prop: &Value) -> Option<Value> {
+    prop.get("example")
+        .or_else(|| {
+            prop.get("examples")
+                .and_then(Value::as_array)
+                .and_then(|examples| examples.first())
+        })
+        .cloned()
+}
+
 /// Extract the `"format"` string from a JSON Schema property.
 fn extract_json_schema_format(prop: &Value) -> Option<String> {
     prop.get("format").and_then(Value::as_str).map(String::from)
@@ -154,8 +229,9 @@ pub fn json_schema_prop_to_field_type(prop: &Value) -> FieldType {
 /// between objects with explicit `properties` ([`FieldType::InlineObject`]) and
 /// bare objects ([`FieldType::Map`]).
 ///
-/// When `properties` is present, `additionalProperties` is intentionally
-/// ignored — the object is treated as a struct with known fields.
+/// When `properties` is present, the object is treated as a struct with known
+/// fields. A schema-valued `additionalProperties` adds a `[string]` index
+/// signature; `true`, `false`, or absent leaves the struct closed.
 fn object_field_type(prop: &Value) -> FieldType {
     let Some(properties) = prop.get("properties").and_then(Value::as_object) else {
         // No explicit properties — check additionalProperties for map value type.
@@ -176,7 +252,11 @@ fn object_field_type(prop: &Value) -> FieldType {
         .collect();
 
     fields.sort_by(|a, b| a.name.cmp(&b.name));
-    FieldType::InlineObject { fields }
+    let additional = prop
+        .get("additionalProperties")
+        .filter(|ap| ap.is_object())
+        .map(|ap| Box::new(json_schema_prop_to_field_type(ap)));
+    FieldType::InlineObject { fields, additional }
 }
 
 /// Derive a [`FieldType::Map`] from the `additionalProperties` key of a JSON
@@ -242,6 +322,7 @@ pub fn extract_schema_defs(schema: &Value) -> Vec<SchemaDef> {
                     .and_then(Value::as_str)
                     .map(String::from),
                 fields,
+                discriminator: None,
             }
         })
         .collect();
@@ -261,18 +342,20 @@ pub fn field_type_to_luau(field_type: &FieldType) -> String {
         FieldType::Boolean => "boolean".to_string(),
         FieldType::Array { items } => format!("{{{}}}", field_type_to_luau(items)),
         FieldType::Object { schema } => schema.clone(),
-        FieldType::InlineObject { fields } => {
-            let entries: Vec<String> = fields
+        FieldType::InlineObject { fields, additional } => {
+            let mut entries: Vec<String> = fields
                 .iter()
                 .map(|f| {
                     let type_str = f.enum_values.as_ref().map_or_else(
                         || field_type_to_luau(&f.field_type),
-                        |ev| render_enum_type(ev),
+                        |ev| render_enum_type(ev, enum_values_quoted(&f.field_type)),
                     );
-                    let optional = if !f.required || f.nullable { "?" } else { "" };
-                    format!("{}: {type_str}{optional}", f.name)
+                    format!("{}: {type_str}{}", f.name, optional_marker(f))
                 })
                 .collect();
+            if let Some(additional) = additional {
+                entries.push(format!("[string]: {}", field_type_to_luau(additional)));
+            }
             format!("{{ {} }}", entries.join(", "))
         }
         FieldType::Map { value } => format!("{{ [string]: {} }}", field_type_to_luau(value)),
@@ -280,13 +363,45 @@ pub fn field_type_to_luau(field_type: &FieldType) -> String {
 }
 
 /// Render an enum type as a Luau literal union: `"val1" | "val2" | "val3"`.
-pub fn render_enum_type(values: &[String]) -> String {
+///
+/// Integer and boolean enums pass `quoted: false` to render bare literals,
+/// e.g. `(200 | 404 | 500)`. A single value (such as a `const`) renders as
+/// the bare literal, e.g. `"cat"`.
+pub fn render_enum_type(values: &[String], quoted: bool) -> String {
     let inner = values
         .iter()
-        .map(|v| format!("\"{v}\""))
+        .map(|v| {
+            if quoted {
+                format!("\"{v}\"")
+            } else {
+                v.clone()
+            }
+        })
         .collect::<Vec<_>>()
         .join(" | ");
-    format!("({inner})")
+    if values.len() == 1 {
+        inner
+    } else {
+        format!("({inner})")
+    }
+}
+
+/// The `?` suffix for a field that may be absent or `nil`: optional fields and
+/// nullable ones, whatever their type (`("a" | "b")?`).
+pub const fn optional_marker(field: &FieldDef) -> &'static str {
+    if !field.required || field.nullable {
+        "?"
+    } else {
+        ""
+    }
+}
+
+/// Whether enum literals of this field type are rendered as quoted strings.
+pub const fn enum_values_quoted(field_type: &FieldType) -> bool {
+    !matches!(
+        field_type,
+        FieldType::Integer | FieldType::Number | FieldType::Boolean
+    )
 }
 
 #[cfg(test)]
@@ -358,7 +473,7 @@ mod tests {
             }
         });
         match json_schema_prop_to_field_type(&obj) {
-            FieldType::InlineObject { fields } => {
+            FieldType::InlineObject { fields, .. } => {
                 assert_eq!(fields.len(), 1);
                 assert_eq!(fields[0].name, "x");
             }
@@ -390,6 +505,10 @@ mod tests {
             ])
         );
         assert_eq!(field.field_type, FieldType::String);
+
+        let pinned = serde_json::json!({ "type": "string", "const": "cat" });
+        let field = json_schema_prop_to_field_def("pet_type", &pinned, true);
+        assert_eq!(field.enum_values, Some(vec!["cat".to_string()]));
     }
 
     #[test]
@@ -451,6 +570,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_properties_with_typed_additional_properties() {
+        let prop = serde_json::json!({
+            "type": "object",
+            "properties": { "id": { "type": "string" } },
+            "required": ["id"],
+            "additionalProperties": { "$ref": "#/components/schemas/Item" }
+        });
+        let field_type = json_schema_prop_to_field_type(&prop);
+        let FieldType::InlineObject { fields, additional } = &field_type else {
+            panic!("Expected InlineObject, got {field_type:?}");
+        };
+        assert_eq!(fields[0].name, "id");
+        assert_eq!(
+            additional.as_deref(),
+            Some(&FieldType::Object {
+                schema: "Item".to_string(),
+            })
+        );
+        assert_eq!(
+            field_type_to_luau(&field_type),
+            "{ id: string, [string]: Item }"
+        );
+
+        let mut refs = Vec::new();
+        field_type.collect_refs(&mut refs);
+        assert_eq!(refs, vec!["Item"]);
+    }
+
     #[test]
     fn test_additional_properties_ref() {
         let prop = serde_json::json!({
@@ -528,7 +676,20 @@ mod tests {
     #[test]
     fn test_render_enum_type_basic() {
         let values = vec!["a".to_string(), "b".to_string()];
-        assert_eq!(render_enum_type(&values), "(\"a\" | \"b\")");
+        assert_eq!(render_enum_type(&values, true), "(\"a\" | \"b\")");
+    }
+
+    #[test]
+    fn test_integer_enum_field_renders_bare_literals() {
+        let prop = serde_json::json!({ "type": "integer", "enum": [200, 404, 500] });
+        let field = json_schema_prop_to_field_def("status_code", &prop, true);
+        assert_eq!(field.field_type, FieldType::Integer);
+        let values = field.enum_values.unwrap();
+        assert_eq!(values, vec!["200", "404", "500"]);
+        assert_eq!(
+            render_enum_type(&values, enum_values_quoted(&field.field_type)),
+            "(200 | 404 | 500)"
+        );
     }
 
     #[test]
@@ -1,14 +1,121 @@
+use std::collections::BTreeMap;
+use std::num::NonZeroU32;
+
 use serde::{Deserialize, Serialize};
 
+use crate::codegen::luau_types::{
+    extract_schema_defs, json_schema_prop_to_field_type, json_schema_to_params,
+};
+
+/// Manifest format version written by this build. Bump it when a change to
+/// [`Manifest`] needs a step in [`Manifest::from_json`] to load older files.
+pub const MANIFEST_SCHEMA_VERSION: u32 = 1;
+
 /// The top-level manifest produced by codegen. Contains API configurations,
 /// function definitions, and schema definitions extracted from `OpenAPI` specs.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Manifest {
+    /// Format version; manifests written before versioning read as 0.
+    #[serde(default)]
+    pub schema_version: u32,
     pub apis: Vec<ApiConfig>,
     pub functions: Vec<FunctionDef>,
     pub schemas: Vec<SchemaDef>,
     #[serde(default)]
     pub mcp_servers: Vec<McpServerEntry>,
+    /// Provenance recorded by `generate`. Absent in older manifests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generated: Option<GeneratedMeta>,
+}
+
+/// Where and when a manifest was generated, for auditing provenance.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GeneratedMeta {
+    /// Version of toolscript that generated the manifest.
+    pub toolscript_version: String,
+    /// Spec sources (file paths or URLs), in the order they were given.
+    pub sources: Vec<String>,
+    /// Generation time as an RFC 3339 UTC timestamp, taken from
+    /// `SOURCE_DATE_EPOCH` when that is set.
+    pub generated_at: String,
+}
+
+impl Manifest {
+    /// Parse a manifest.json. Manifests written before versioning read as
+    /// version 0 and parse as-is, since every field added since has a serde
+    /// default; the result is stamped with the current version. Fails on
+    /// versions newer than this build understands.
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        let version = value
+            .get("schema_version")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(0);
+        if version > u64::from(MANIFEST_SCHEMA_VERSION) {
+            anyhow::bail!(
+                "manifest schema version {version} is newer than this toolscript supports \
+                 ({MANIFEST_SCHEMA_VERSION}); upgrade toolscript or regenerate the manifest"
+            );
+        }
+        let mut manifest: Self = serde_json::from_value(value)?;
+        manifest.schema_version = MANIFEST_SCHEMA_VERSION;
+        Ok(manifest)
+    }
+
+    /// Drop functions whose tag is not in `only` (when non-empty) or is in
+    /// `exclude`. Untagged functions are dropped only by an `only` filter.
+    pub fn retain_tags(&mut self, only: &[String], exclude: &[String]) {
+        self.functions.retain(|func| {
+            let tag = func.tag.as_deref();
+            let allowed = only.is_empty() || tag.is_some_and(|t| only.iter().any(|o| o == t));
+            let excluded = tag.is_some_and(|t| exclude.iter().any(|e| e == t));
+            allowed && !excluded
+        });
+    }
+
+    /// Point `api` at `base_url` instead of the URL recorded at generation.
+    /// The URL must be an absolute `http` or `https` URL.
+    pub fn override_base_url(&mut self, api: &str, base_url: &str) -> anyhow::Result<()> {
+        let parsed = reqwest::Url::parse(base_url)
+            .map_err(|e| anyhow::anyhow!("invalid base URL '{base_url}' for API '{api}': {e}"))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            anyhow::bail!("invalid base URL '{base_url}' for API '{api}': expected http or https");
+        }
+        let config = self
+            .apis
+            .iter_mut()
+            .find(|config| config.name == api)
+            .ok_or_else(|| anyhow::anyhow!("cannot override base URL of unknown API '{api}'"))?;
+        config.base_url = base_url.trim_end_matches('/').to_string();
+        Ok(())
+    }
+
+    /// Reject names that would collide once scripts run. `OpenAPI` functions
+    /// live directly under `sdk.<function>` and each MCP server gets its own
+    /// `sdk.<server>` table, so a server may share a name with neither a
+    /// function nor an API (APIs and servers are listed side by side).
+    pub fn check_namespaces(&self) -> anyhow::Result<()> {
+        for server in &self.mcp_servers {
+            if self.apis.iter().any(|api| api.name == server.name) {
+                anyhow::bail!(
+                    "MCP server '{0}' has the same name as the OpenAPI API '{0}'; \
+                     rename one of them",
+                    server.name
+                );
+            }
+            if let Some(func) = self.functions.iter().find(|f| f.name == server.name) {
+                anyhow::bail!(
+                    "function '{}' of API '{}' collides with MCP server '{}' at `sdk.{}`; \
+                     rename the MCP server",
+                    func.name,
+                    func.api,
+                    server.name,
+                    server.name
+                );
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Configuration for a single API, extracted from info + servers + security.
@@ -18,18 +125,151 @@ pub struct ApiConfig {
     pub base_url: String,
     pub description: Option<String>,
     pub version: Option<String>,
-    pub auth: Option<AuthConfig>,
+    /// Security schemes applied to every call, all at once. Usually zero or one;
+    /// several when the spec requires e.g. both an API key and a bearer token.
+    #[serde(default, deserialize_with = "deserialize_auth_schemes")]
+    pub auth: Vec<AuthScheme>,
+    /// Headers sent on every call to this API (from `constant_headers` in the
+    /// TOML config). Not exposed in docs; explicit header params take precedence.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub constant_headers: BTreeMap<String, String>,
+    /// Query params sent on every call to this API (from `default_query` in the
+    /// TOML config). Not exposed in docs; explicit query params take precedence.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub default_query: BTreeMap<String, String>,
+    /// Client-side pacing for calls to this API (from `rate_limit` in the TOML
+    /// config). Independent of the per-script `max_api_calls` cap.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimit>,
+    /// How boolean parameter values are written (from `boolean_style` in the
+    /// TOML config). Unset means `true`/`false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub boolean_style: Option<BooleanStyle>,
+    /// The whole API is deprecated: its spec says `x-deprecated: true`, or
+    /// every one of its operations is deprecated.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+}
+
+/// Token-bucket rate limit: `burst` calls may go back-to-back, after which
+/// calls are paced to `requests_per_second`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RateLimit {
+    pub requests_per_second: NonZeroU32,
+    #[serde(default = "default_burst")]
+    pub burst: NonZeroU32,
+}
+
+const fn default_burst() -> NonZeroU32 {
+    NonZeroU32::MIN
+}
+
+/// How a boolean path, query, or header parameter is written on the wire.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BooleanStyle {
+    /// `true` / `false`
+    TrueFalse,
+    /// `1` / `0`
+    Integer,
+    /// `yes` / `no`
+    YesNo,
+}
+
+impl BooleanStyle {
+    /// The wire form of `value` in this style.
+    pub const fn render(self, value: bool) -> &'static str {
+        match (self, value) {
+            (Self::TrueFalse, true) => "true",
+            (Self::TrueFalse, false) => "false",
+            (Self::Integer, true) => "1",
+            (Self::Integer, false) => "0",
+            (Self::YesNo, true) => "yes",
+            (Self::YesNo, false) => "no",
+        }
+    }
 }
 
 /// Authentication configuration for an API.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum AuthConfig {
-    Bearer { header: String, prefix: String },
-    ApiKey { header: String },
+    Bearer {
+        header: String,
+        prefix: String,
+    },
+    ApiKey {
+        /// Name of the header, query parameter, or cookie carrying the key.
+        header: String,
+        #[serde(default, skip_serializing_if = "ApiKeyLocation::is_header")]
+        location: ApiKeyLocation,
+    },
     Basic,
 }
 
+/// Where an API key is sent, per the scheme's `in` field.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyLocation {
+    #[default]
+    Header,
+    Query,
+    Cookie,
+}
+
+impl ApiKeyLocation {
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    const fn is_header(&self) -> bool {
+        matches!(self, Self::Header)
+    }
+}
+
+/// A named security scheme from the spec's `components.securitySchemes`.
+///
+/// The name lets credentials target one scheme (`<api>.<scheme>`) when an API
+/// applies several.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AuthScheme {
+    pub name: String,
+    #[serde(flatten)]
+    pub config: AuthConfig,
+    /// The spec declares an `oauth2` or `openIdConnect` scheme. toolscript
+    /// runs no OAuth flow; the access token is supplied as a bearer credential.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub oauth2: bool,
+}
+
+/// Accept both the list form and the single-object/`null` form written by
+/// older manifests, naming legacy entries after their type.
+fn deserialize_auth_schemes<'de, D>(deserializer: D) -> Result<Vec<AuthScheme>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum AuthField {
+        List(Vec<AuthScheme>),
+        Single(AuthConfig),
+    }
+
+    Ok(match Option::<AuthField>::deserialize(deserializer)? {
+        Some(AuthField::List(schemes)) => schemes,
+        Some(AuthField::Single(config)) => {
+            let name = match config {
+                AuthConfig::Bearer { .. } => "bearer",
+                AuthConfig::ApiKey { .. } => "api_key",
+                AuthConfig::Basic => "basic",
+            };
+            vec![AuthScheme {
+                name: name.to_string(),
+                config,
+                oauth2: false,
+            }]
+        }
+        None => Vec::new(),
+    })
+}
+
 /// A single function (API operation) exposed in the manifest.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct FunctionDef {
@@ -44,6 +284,19 @@ pub struct FunctionDef {
     pub parameters: Vec<ParamDef>,
     pub request_body: Option<RequestBodyDef>,
     pub response_schema: Option<String>,
+    /// Schema of the error body, from the first 4xx/5xx response that
+    /// references one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_schema: Option<String>,
+    /// Auth schemes for this operation when they differ from the API's.
+    /// `None` inherits the API's schemes; an empty list (from `security: []`)
+    /// marks a public endpoint that is called without credentials.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<Vec<AuthScheme>>,
+    /// The operation paginates via `Link: <...>; rel="next"` response headers
+    /// (`x-pagination: link` in the spec), so it also gets an `_all` variant.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub link_paginated: bool,
 }
 
 /// HTTP method for a function.
@@ -55,6 +308,10 @@ pub enum HttpMethod {
     Put,
     Patch,
     Delete,
+    /// Returns only the response status and headers.
+    Head,
+    /// Returns the response status and headers, plus the body if there is one.
+    Options,
 }
 
 /// A parameter definition for a function.
@@ -71,6 +328,22 @@ pub struct ParamDef {
     pub format: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub frozen_value: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+    /// An example value from the spec, shown in the docs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub example: Option<serde_json::Value>,
+    /// Descriptions of individual enum values, from `x-enum-descriptions`
+    /// or `x-enumNames`, shown in the docs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enum_descriptions: Option<BTreeMap<String, String>>,
+    /// How a boolean value is written, from the spec's `x-boolean-style`.
+    /// Overrides the API's `boolean_style`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub boolean_style: Option<BooleanStyle>,
+    /// The spec's `multipleOf`: numeric values must be a whole multiple of it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multiple_of: Option<serde_json::Number>,
 }
 
 /// Where a parameter is located in the request.
@@ -107,6 +380,16 @@ pub struct SchemaDef {
     pub name: String,
     pub description: Option<String>,
     pub fields: Vec<FieldDef>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discriminator: Option<DiscriminatorDef>,
+}
+
+/// The discriminator of a polymorphic schema: the property whose value selects
+/// the variant, and the mapping from property values to variant schema names.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DiscriminatorDef {
+    pub property_name: String,
+    pub mapping: BTreeMap<String, String>,
 }
 
 /// A single field within a schema.
@@ -121,6 +404,13 @@ pub struct FieldDef {
     pub nullable: bool,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub format: Option<String>,
+    /// An example value from the spec, shown in the docs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub example: Option<serde_json::Value>,
+    /// Descriptions of individual enum values, from `x-enum-descriptions`
+    /// or `x-enumNames`, shown in the docs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enum_descriptions: Option<BTreeMap<String, String>>,
 }
 
 /// The type of a schema field, including compound types.
@@ -138,8 +428,12 @@ pub enum FieldType {
     Object {
         schema: String,
     },
+    /// An object with known fields, plus a `[string]` index signature when
+    /// `additional` is set (from a typed `additionalProperties` schema).
     InlineObject {
         fields: Vec<FieldDef>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        additional: Option<Box<Self>>,
     },
     Map {
         value: Box<Self>,
@@ -152,10 +446,13 @@ impl FieldType {
         match self {
             Self::Object { schema } => refs.push(schema.clone()),
             Self::Array { items } => items.collect_refs(refs),
-            Self::InlineObject { fields } => {
+            Self::InlineObject { fields, additional } => {
                 for f in fields {
                     f.field_type.collect_refs(refs);
                 }
+                if let Some(additional) = additional {
+                    additional.collect_refs(refs);
+                }
             }
             Self::Map { value } => value.collect_refs(refs),
             _ => {}
@@ -182,6 +479,45 @@ pub struct McpToolDef {
     pub schemas: Vec<SchemaDef>,
     #[serde(default)]
     pub output_schemas: Vec<SchemaDef>,
+    /// Return type from the tool's `outputSchema`; `None` renders as `any`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_type: Option<FieldType>,
+    /// Whether repeating an identical call has no further effect, i.e. the
+    /// tool's annotations set `readOnlyHint` or `idempotentHint`. Only such
+    /// calls may be answered from the per-execution call cache.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub idempotent: bool,
+}
+
+impl McpToolDef {
+    /// Build the manifest entry for a tool discovered on upstream server
+    /// `server`, converting its input and output JSON Schemas to Luau types.
+    pub fn from_tool(server: &str, tool: &rmcp::model::Tool) -> Self {
+        let input_schema = serde_json::Value::Object(tool.input_schema.as_ref().clone());
+        let (output_type, output_schemas) =
+            tool.output_schema
+                .as_ref()
+                .map_or((None, vec![]), |schema| {
+                    let output_schema = serde_json::Value::Object(schema.as_ref().clone());
+                    (
+                        Some(json_schema_prop_to_field_type(&output_schema)),
+                        extract_schema_defs(&output_schema),
+                    )
+                });
+        Self {
+            name: tool.name.to_string(),
+            server: server.to_string(),
+            description: tool.description.as_ref().map(ToString::to_string),
+            params: json_schema_to_params(&input_schema),
+            schemas: extract_schema_defs(&input_schema),
+            output_schemas,
+            output_type,
+            idempotent: tool
+                .annotations
+                .as_ref()
+                .is_some_and(|a| a.read_only_hint == Some(true) || a.idempotent_hint == Some(true)),
+        }
+    }
 }
 
 /// A parameter definition for an MCP tool, using Luau type names.
@@ -204,19 +540,175 @@ mod tests {
     #![allow(clippy::unwrap_used, clippy::expect_used)]
     use super::*;
 
+    fn namespace_manifest(server_name: &str) -> Manifest {
+        Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
+            apis: vec![ApiConfig {
+                name: "petstore".to_string(),
+                base_url: "https://petstore.example.com".to_string(),
+                description: None,
+                version: None,
+                auth: vec![],
+                constant_headers: BTreeMap::new(),
+                rate_limit: None,
+                default_query: BTreeMap::new(),
+                deprecated: false,
+                boolean_style: None,
+            }],
+            functions: vec![FunctionDef {
+                name: "list_pets".to_string(),
+                api: "petstore".to_string(),
+                tag: None,
+                method: HttpMethod::Get,
+                path: "/pets".to_string(),
+                summary: None,
+                description: None,
+                deprecated: false,
+                parameters: vec![],
+                request_body: None,
+                response_schema: None,
+                error_schema: None,
+                auth: None,
+                link_paginated: false,
+            }],
+            schemas: vec![],
+            mcp_servers: vec![McpServerEntry {
+                name: server_name.to_string(),
+                description: None,
+                tools: vec![],
+            }],
+            generated: None,
+        }
+    }
+
+    #[test]
+    fn test_check_namespaces_distinct_names() {
+        namespace_manifest("filesystem").check_namespaces().unwrap();
+    }
+
+    #[test]
+    fn test_check_namespaces_server_named_like_api() {
+        let err = namespace_manifest("petstore")
+            .check_namespaces()
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("MCP server 'petstore' has the same name as the OpenAPI API"),
+            "error was: {err}"
+        );
+    }
+
+    #[test]
+    fn test_check_namespaces_server_named_like_function() {
+        let err = namespace_manifest("list_pets")
+            .check_namespaces()
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("function 'list_pets' of API 'petstore' collides with MCP server"),
+            "error was: {err}"
+        );
+        assert!(err.contains("`sdk.list_pets`"), "error was: {err}");
+    }
+
+    #[test]
+    fn test_override_base_url() {
+        let mut manifest = namespace_manifest("filesystem");
+        manifest
+            .override_base_url("petstore", "https://staging.example.com/v2/")
+            .unwrap();
+        assert_eq!(manifest.apis[0].base_url, "https://staging.example.com/v2");
+
+        let err = manifest
+            .override_base_url("petstore", "staging.example.com")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("invalid base URL"), "error was: {err}");
+        let err = manifest
+            .override_base_url("petstore", "ftp://staging.example.com")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("expected http or https"), "error was: {err}");
+        let err = manifest
+            .override_base_url("billing", "https://staging.example.com")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("unknown API 'billing'"), "error was: {err}");
+    }
+
+    #[test]
+    fn test_retain_tags() {
+        let func = |name: &str, tag: Option<&str>| FunctionDef {
+            name: name.to_string(),
+            api: "shop".to_string(),
+            tag: tag.map(str::to_string),
+            method: HttpMethod::Get,
+            path: format!("/{name}"),
+            summary: None,
+            description: None,
+            deprecated: false,
+            parameters: vec![],
+            request_body: None,
+            response_schema: None,
+            error_schema: None,
+            auth: None,
+            link_paginated: false,
+        };
+        let manifest = Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
+            apis: vec![],
+            functions: vec![
+                func("list_pets", Some("pets")),
+                func("list_orders", Some("store")),
+                func("purge", Some("admin")),
+                func("ping", None),
+            ],
+            schemas: vec![],
+            mcp_servers: vec![],
+            generated: None,
+        };
+        let names =
+            |m: &Manifest| -> Vec<String> { m.functions.iter().map(|f| f.name.clone()).collect() };
+
+        let mut only = manifest.clone();
+        only.retain_tags(&["pets".to_string(), "store".to_string()], &[]);
+        assert_eq!(names(&only), ["list_pets", "list_orders"]);
+
+        let mut exclude = manifest.clone();
+        exclude.retain_tags(&[], &["admin".to_string()]);
+        assert_eq!(names(&exclude), ["list_pets", "list_orders", "ping"]);
+
+        let mut both = manifest;
+        both.retain_tags(
+            &["pets".to_string(), "admin".to_string()],
+            &["admin".to_string()],
+        );
+        assert_eq!(names(&both), ["list_pets"]);
+    }
+
     #[test]
     #[allow(clippy::too_many_lines)]
     fn test_manifest_serialization_roundtrip() {
         let manifest = Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
             apis: vec![ApiConfig {
                 name: "petstore".to_string(),
                 base_url: "https://petstore.example.com/v1".to_string(),
                 description: Some("A sample petstore API".to_string()),
                 version: Some("1.0.0".to_string()),
-                auth: Some(AuthConfig::Bearer {
-                    header: "Authorization".to_string(),
-                    prefix: "Bearer ".to_string(),
-                }),
+                auth: vec![AuthScheme {
+                    name: "bearerAuth".to_string(),
+                    config: AuthConfig::Bearer {
+                        header: "Authorization".to_string(),
+                        prefix: "Bearer ".to_string(),
+                    },
+                    oauth2: false,
+                }],
+                constant_headers: BTreeMap::new(),
+                rate_limit: None,
+                default_query: BTreeMap::new(),
+                deprecated: false,
+                boolean_style: None,
             }],
             functions: vec![FunctionDef {
                 name: "list_pets".to_string(),
@@ -242,6 +734,11 @@ mod tests {
                         ]),
                         format: None,
                         frozen_value: None,
+                        deprecated: false,
+                        example: None,
+                        enum_descriptions: None,
+                        boolean_style: None,
+                        multiple_of: None,
                     },
                     ParamDef {
                         name: "limit".to_string(),
@@ -253,10 +750,18 @@ mod tests {
                         enum_values: None,
                         format: None,
                         frozen_value: None,
+                        deprecated: false,
+                        example: None,
+                        enum_descriptions: None,
+                        boolean_style: None,
+                        multiple_of: None,
                     },
                 ],
                 request_body: None,
                 response_schema: Some("Pet".to_string()),
+                error_schema: None,
+                auth: None,
+                link_paginated: false,
             }],
             schemas: vec![SchemaDef {
                 name: "Pet".to_string(),
@@ -270,6 +775,8 @@ mod tests {
                         enum_values: None,
                         nullable: false,
                         format: None,
+                        example: None,
+                        enum_descriptions: None,
                     },
                     FieldDef {
                         name: "name".to_string(),
@@ -279,6 +786,8 @@ mod tests {
                         enum_values: None,
                         nullable: false,
                         format: None,
+                        example: None,
+                        enum_descriptions: None,
                     },
                     FieldDef {
                         name: "status".to_string(),
@@ -292,6 +801,8 @@ mod tests {
                         ]),
                         nullable: false,
                         format: None,
+                        example: None,
+                        enum_descriptions: None,
                     },
                     FieldDef {
                         name: "tag".to_string(),
@@ -301,10 +812,14 @@ mod tests {
                         enum_values: None,
                         nullable: false,
                         format: None,
+                        example: None,
+                        enum_descriptions: None,
                     },
                 ],
+                discriminator: None,
             }],
             mcp_servers: vec![],
+            generated: None,
         };
 
         // Serialize to JSON
@@ -323,10 +838,14 @@ mod tests {
         );
         assert_eq!(
             roundtripped.apis[0].auth,
-            Some(AuthConfig::Bearer {
-                header: "Authorization".to_string(),
-                prefix: "Bearer ".to_string(),
-            })
+            vec![AuthScheme {
+                name: "bearerAuth".to_string(),
+                config: AuthConfig::Bearer {
+                    header: "Authorization".to_string(),
+                    prefix: "Bearer ".to_string(),
+                },
+                oauth2: false,
+            }]
         );
 
         assert_eq!(roundtripped.functions.len(), 1);
@@ -354,6 +873,7 @@ mod tests {
 
         let api_key = AuthConfig::ApiKey {
             header: "X-API-Key".to_string(),
+            location: ApiKeyLocation::Header,
         };
         let json = serde_json::to_string(&api_key).unwrap();
         assert!(json.contains(r#""type":"api_key"#));
@@ -363,6 +883,64 @@ mod tests {
         assert!(json.contains(r#""type":"basic"#));
     }
 
+    #[test]
+    fn test_auth_schemes_accept_legacy_single_object() {
+        let legacy = r#"{"name":"a","base_url":"/","description":null,"version":null,
+            "auth":{"type":"api_key","header":"X-Key"}}"#;
+        let api: ApiConfig = serde_json::from_str(legacy).unwrap();
+        assert_eq!(
+            api.auth,
+            vec![AuthScheme {
+                name: "api_key".to_string(),
+                config: AuthConfig::ApiKey {
+                    header: "X-Key".to_string(),
+                    location: ApiKeyLocation::Header,
+                },
+                oauth2: false,
+            }]
+        );
+
+        let none = r#"{"name":"a","base_url":"/","description":null,"version":null,"auth":null}"#;
+        let api: ApiConfig = serde_json::from_str(none).unwrap();
+        assert!(api.auth.is_empty());
+
+        let json = serde_json::to_string(&api_with_two_schemes()).unwrap();
+        let roundtripped: ApiConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(roundtripped, api_with_two_schemes());
+    }
+
+    fn api_with_two_schemes() -> ApiConfig {
+        ApiConfig {
+            name: "billing".to_string(),
+            base_url: "/".to_string(),
+            description: None,
+            version: None,
+            auth: vec![
+                AuthScheme {
+                    name: "apiKey".to_string(),
+                    config: AuthConfig::ApiKey {
+                        header: "X-Api-Key".to_string(),
+                        location: ApiKeyLocation::Header,
+                    },
+                    oauth2: false,
+                },
+                AuthScheme {
+                    name: "bearer".to_string(),
+                    config: AuthConfig::Bearer {
+                        header: "Authorization".to_string(),
+                        prefix: "Bearer ".to_string(),
+                    },
+                    oauth2: false,
+                },
+            ],
+            constant_headers: BTreeMap::new(),
+            rate_limit: None,
+            default_query: BTreeMap::new(),
+            deprecated: false,
+            boolean_style: None,
+        }
+    }
+
     #[test]
     fn test_http_method_serde() {
         let get = HttpMethod::Get;
@@ -400,18 +978,30 @@ mod tests {
     #[test]
     fn test_manifest_yaml_roundtrip() {
         let manifest = Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
             apis: vec![ApiConfig {
                 name: "test_api".to_string(),
                 base_url: "https://api.example.com".to_string(),
                 description: None,
                 version: Some("2.0.0".to_string()),
-                auth: Some(AuthConfig::ApiKey {
-                    header: "X-API-Key".to_string(),
-                }),
+                auth: vec![AuthScheme {
+                    name: "apiKey".to_string(),
+                    config: AuthConfig::ApiKey {
+                        header: "X-API-Key".to_string(),
+                        location: ApiKeyLocation::Header,
+                    },
+                    oauth2: false,
+                }],
+                constant_headers: BTreeMap::new(),
+                rate_limit: None,
+                default_query: BTreeMap::new(),
+                deprecated: false,
+                boolean_style: None,
             }],
             functions: vec![],
             schemas: vec![],
             mcp_servers: vec![],
+            generated: None,
         };
 
         let yaml = serde_yaml::to_string(&manifest).expect("Failed to serialize to YAML");
@@ -423,15 +1013,25 @@ mod tests {
     #[test]
     fn test_manifest_json_structure() {
         let manifest = Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
             apis: vec![ApiConfig {
                 name: "myapi".to_string(),
                 base_url: "https://api.example.com".to_string(),
                 description: None,
                 version: None,
-                auth: Some(AuthConfig::Bearer {
-                    header: "Authorization".to_string(),
-                    prefix: "Bearer ".to_string(),
-                }),
+                auth: vec![AuthScheme {
+                    name: "bearerAuth".to_string(),
+                    config: AuthConfig::Bearer {
+                        header: "Authorization".to_string(),
+                        prefix: "Bearer ".to_string(),
+                    },
+                    oauth2: false,
+                }],
+                constant_headers: BTreeMap::new(),
+                rate_limit: None,
+                default_query: BTreeMap::new(),
+                deprecated: false,
+                boolean_style: None,
             }],
             functions: vec![FunctionDef {
                 name: "get_item".to_string(),
@@ -452,21 +1052,31 @@ mod tests {
                     enum_values: None,
                     format: None,
                     frozen_value: None,
+                    deprecated: false,
+                    example: None,
+                    enum_descriptions: None,
+                    boolean_style: None,
+                    multiple_of: None,
                 }],
                 request_body: None,
                 response_schema: None,
+                error_schema: None,
+                auth: None,
+                link_paginated: false,
             }],
             schemas: vec![],
             mcp_servers: vec![],
+            generated: None,
         };
 
         let value: serde_json::Value = serde_json::to_value(&manifest).unwrap();
 
         // Verify the JSON structure matches expected layout
         assert!(value["apis"].is_array());
-        assert_eq!(value["apis"][0]["auth"]["type"], "bearer");
-        assert_eq!(value["apis"][0]["auth"]["header"], "Authorization");
-        assert_eq!(value["apis"][0]["auth"]["prefix"], "Bearer ");
+        assert_eq!(value["apis"][0]["auth"][0]["name"], "bearerAuth");
+        assert_eq!(value["apis"][0]["auth"][0]["type"], "bearer");
+        assert_eq!(value["apis"][0]["auth"][0]["header"], "Authorization");
+        assert_eq!(value["apis"][0]["auth"][0]["prefix"], "Bearer ");
         assert_eq!(value["functions"][0]["method"], "GET");
         assert_eq!(value["functions"][0]["deprecated"], true);
         assert_eq!(value["functions"][0]["parameters"][0]["location"], "path");
@@ -495,6 +1105,9 @@ mod tests {
                 description: Some("The pet to create".to_string()),
             }),
             response_schema: Some("Pet".to_string()),
+            error_schema: None,
+            auth: None,
+            link_paginated: false,
         };
 
         let json = serde_json::to_string(&func).unwrap();
@@ -549,6 +1162,11 @@ mod tests {
             enum_values: None,
             format: None,
             frozen_value: Some("v2".to_string()),
+            deprecated: false,
+            example: None,
+            enum_descriptions: None,
+            boolean_style: None,
+            multiple_of: None,
         };
         let json = serde_json::to_string(&param).unwrap();
         assert!(
@@ -571,6 +1189,11 @@ mod tests {
             enum_values: None,
             format: None,
             frozen_value: None,
+            deprecated: false,
+            example: None,
+            enum_descriptions: None,
+            boolean_style: None,
+            multiple_of: None,
         };
         let json = serde_json::to_string(&param).unwrap();
         assert!(
@@ -591,6 +1214,8 @@ mod tests {
                     enum_values: None,
                     nullable: false,
                     format: None,
+                    example: None,
+                    enum_descriptions: None,
                 },
                 FieldDef {
                     name: "retries".to_string(),
@@ -600,14 +1225,55 @@ mod tests {
                     enum_values: None,
                     nullable: false,
                     format: None,
+                    example: None,
+                    enum_descriptions: None,
                 },
             ],
+            additional: None,
         };
         let json = serde_json::to_string(&inline).unwrap();
         let deserialized: FieldType = serde_json::from_str(&json).unwrap();
         assert_eq!(deserialized, inline);
     }
 
+    #[test]
+    fn test_mcp_tool_def_from_tool() {
+        let mut tool = rmcp::model::Tool::new(
+            "lookup",
+            "Look up a record",
+            rmcp::model::object(serde_json::json!({
+                "type": "object",
+                "required": ["id"],
+                "properties": { "id": { "type": "string" } }
+            })),
+        );
+        tool.output_schema = Some(std::sync::Arc::new(rmcp::model::object(
+            serde_json::json!({
+                "type": "object",
+                "properties": { "name": { "type": "string" } }
+            }),
+        )));
+        let def = McpToolDef::from_tool("records", &tool);
+        assert_eq!(def.name, "lookup");
+        assert_eq!(def.server, "records");
+        assert_eq!(def.description.as_deref(), Some("Look up a record"));
+        assert_eq!(def.params.len(), 1);
+        assert_eq!(def.params[0].name, "id");
+        assert!(def.params[0].required);
+        assert!(matches!(
+            def.output_type,
+            Some(FieldType::InlineObject { ref fields, .. }) if fields[0].name == "name"
+        ));
+        assert!(!def.idempotent);
+
+        tool.output_schema = None;
+        tool.annotations = Some(rmcp::model::ToolAnnotations::new().idempotent(true));
+        let def = McpToolDef::from_tool("records", &tool);
+        assert_eq!(def.output_type, None);
+        assert!(def.output_schemas.is_empty());
+        assert!(def.idempotent);
+    }
+
     #[test]
     fn test_mcp_server_entry_roundtrip() {
         let entry = McpServerEntry {
@@ -626,6 +1292,8 @@ mod tests {
                 }],
                 schemas: vec![],
                 output_schemas: vec![],
+                output_type: None,
+                idempotent: false,
             }],
         };
         let json = serde_json::to_string(&entry).unwrap();
@@ -638,6 +1306,7 @@ mod tests {
     #[test]
     fn test_manifest_with_mcp_servers_roundtrip() {
         let manifest = Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
             apis: vec![],
             functions: vec![],
             schemas: vec![],
@@ -646,12 +1315,49 @@ mod tests {
                 description: None,
                 tools: vec![],
             }],
+            generated: None,
         };
         let json = serde_json::to_string(&manifest).unwrap();
         let roundtripped: Manifest = serde_json::from_str(&json).unwrap();
         assert_eq!(roundtripped.mcp_servers.len(), 1);
     }
 
+    #[test]
+    fn test_manifest_without_generated_meta_deserializes() {
+        let json = r#"{"apis": [], "functions": [], "schemas": []}"#;
+        let manifest: Manifest = serde_json::from_str(json).unwrap();
+        assert!(manifest.generated.is_none());
+        // And is not written back out as null
+        let value = serde_json::to_value(&manifest).unwrap();
+        assert!(value.get("generated").is_none());
+    }
+
+    #[test]
+    fn test_from_json_reads_unversioned_manifest() {
+        let json = r#"{
+            "apis": [{"name": "petstore", "base_url": "https://petstore.example.com", "description": null, "version": null}],
+            "functions": [],
+            "schemas": []
+        }"#;
+        let manifest = Manifest::from_json(json).unwrap();
+        assert_eq!(manifest.schema_version, MANIFEST_SCHEMA_VERSION);
+        assert!(manifest.mcp_servers.is_empty());
+        assert_eq!(manifest.apis[0].name, "petstore");
+    }
+
+    #[test]
+    fn test_from_json_rejects_newer_schema_version() {
+        let json = format!(
+            r#"{{"schema_version": {}, "apis": [], "functions": [], "schemas": []}}"#,
+            MANIFEST_SCHEMA_VERSION + 1
+        );
+        let err = Manifest::from_json(&json).unwrap_err().to_string();
+        assert!(
+            err.contains("newer than this toolscript supports"),
+            "got: {err}"
+        );
+    }
+
     #[test]
     fn test_field_def_new_fields_serde() {
         let field = FieldDef {
@@ -662,6 +1368,8 @@ mod tests {
             enum_values: None,
             nullable: true,
             format: Some("date-time".to_string()),
+            example: None,
+            enum_descriptions: None,
         };
         let json = serde_json::to_string(&field).unwrap();
         let roundtripped: FieldDef = serde_json::from_str(&json).unwrap();
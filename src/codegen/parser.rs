@@ -1,66 +1,483 @@
-use std::path::Path;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use openapiv3::{
-    OpenAPI, Parameter, ParameterSchemaOrContent, ReferenceOr, Schema, SchemaKind, SecurityScheme,
-    Type,
+    APIKeyLocation, OpenAPI, Parameter, ParameterSchemaOrContent, ReferenceOr, Schema, SchemaKind,
+    SecurityRequirement, SecurityScheme, Type,
 };
 
 use super::manifest::{
-    ApiConfig, AuthConfig, FieldDef, FieldType, FunctionDef, HttpMethod, Manifest, ParamDef,
-    ParamLocation, ParamType, RequestBodyDef, SchemaDef,
+    ApiConfig, ApiKeyLocation, AuthConfig, AuthScheme, DiscriminatorDef, FieldDef, FieldType,
+    FunctionDef, HttpMethod, MANIFEST_SCHEMA_VERSION, Manifest, ParamDef, ParamLocation, ParamType,
+    RequestBodyDef, SchemaDef,
 };
 
+/// Spec source that means "read the spec from standard input".
+pub const STDIN_SOURCE: &str = "-";
+
 /// Load an `OpenAPI` spec from a local YAML or JSON file.
+///
+/// External `$ref`s to other local files (e.g. `./schemas/pet.yaml#/Pet`) are
+/// resolved relative to the referencing file and inlined before parsing.
+/// A path of [`STDIN_SOURCE`] (`-`) reads the spec from standard input.
 pub fn load_spec_from_file(path: &Path) -> Result<OpenAPI> {
-    let content = std::fs::read_to_string(path)
-        .with_context(|| format!("Failed to read {}", path.display()))?;
+    if path == Path::new(STDIN_SOURCE) {
+        return load_spec_from_reader(std::io::stdin().lock());
+    }
+    let mut document = load_document(path)?;
+    ExternalRefResolver::default().resolve_document(&mut document, path)?;
+    const_to_enum(&mut document);
 
-    // Try YAML first (which is a superset of JSON), then fall back to JSON
-    let spec: OpenAPI = serde_yaml::from_str(&content)
-        .or_else(|_| serde_json::from_str(&content))
+    let spec: OpenAPI = serde_yaml::from_value(document)
         .with_context(|| format!("Failed to parse OpenAPI spec from {}", path.display()))?;
 
     Ok(spec)
 }
 
+/// Parse an `OpenAPI` spec (YAML or JSON) from a reader, such as stdin.
+///
+/// Relative external `$ref`s resolve against the current directory.
+pub fn load_spec_from_reader(mut reader: impl std::io::Read) -> Result<OpenAPI> {
+    let mut content = String::new();
+    reader
+        .read_to_string(&mut content)
+        .context("Failed to read spec from stdin")?;
+    let mut document = parse_document(&content, "stdin")?;
+    ExternalRefResolver::default().resolve_document(&mut document, Path::new(STDIN_SOURCE))?;
+    const_to_enum(&mut document);
+
+    serde_yaml::from_value(document).context("Failed to parse OpenAPI spec from stdin")
+}
+
 /// Fetch and parse an `OpenAPI` spec from a URL.
+///
+/// Non-2xx responses and HTML pages are rejected with a targeted message
+/// instead of a generic parse failure.
 pub async fn load_spec_from_url(url: &str) -> Result<OpenAPI> {
     let response = reqwest::get(url)
         .await
         .with_context(|| format!("Failed to fetch spec from {url}"))?;
 
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!("Failed to fetch spec from {url}: HTTP {status}");
+    }
+
+    let is_html_content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.to_ascii_lowercase().contains("text/html"));
+
     let content = response
         .text()
         .await
         .with_context(|| format!("Failed to read response body from {url}"))?;
 
-    let spec: OpenAPI = serde_yaml::from_str(&content)
-        .or_else(|_| serde_json::from_str(&content))
+    if is_html_content_type || looks_like_html(&content) {
+        anyhow::bail!(
+            "URL {url} returned HTML, not an OpenAPI spec \u{2014} did you link to the docs page instead of the raw spec?"
+        );
+    }
+
+    let mut document = parse_document(&content, url)
+        .with_context(|| format!("Failed to parse OpenAPI spec from {url}"))?;
+    const_to_enum(&mut document);
+    let spec: OpenAPI = serde_yaml::from_value(document)
         .with_context(|| format!("Failed to parse OpenAPI spec from {url}"))?;
 
     Ok(spec)
 }
 
-/// Convert an `OpenAPI` spec into a `Manifest`.
+/// Whether a response body is an HTML document rather than YAML/JSON.
+fn looks_like_html(content: &str) -> bool {
+    let start: String = content
+        .trim_start()
+        .chars()
+        .take(15)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    start.starts_with("<!doctype html") || start.starts_with("<html")
+}
+
+// ---------------------------------------------------------------------------
+// External $ref resolution
+// ---------------------------------------------------------------------------
+
+/// Read a YAML or JSON file into a generic document tree.
+fn load_document(path: &Path) -> Result<serde_yaml::Value> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    parse_document(&content, &path.display().to_string())
+}
+
+/// Parse spec text as YAML or JSON. `origin` names the source in errors.
+fn parse_document(content: &str, origin: &str) -> Result<serde_yaml::Value> {
+    // Try YAML first (which is a superset of JSON), then fall back to JSON
+    serde_yaml::from_str(content)
+        .or_else(|_| {
+            serde_json::from_str::<serde_json::Value>(content)
+                .map_err(anyhow::Error::from)
+                .and_then(|json| serde_yaml::to_value(json).map_err(anyhow::Error::from))
+        })
+        .with_context(|| format!("Failed to parse {origin}"))
+}
+
+/// Inlines `$ref`s that point into other local files.
+///
+/// References inside an external file are resolved against that file, so an
+/// internal `#/...` ref there is inlined too. Refs in the root document that
+/// start with `#` are left alone for the parser, as are remote (URL) refs.
+/// A target that refers back to itself can't be inlined, so it is hoisted
+/// into the root's `components/schemas` and referenced locally instead.
+#[derive(Default)]
+struct ExternalRefResolver {
+    documents: HashMap<PathBuf, serde_yaml::Value>,
+    /// `file#pointer` targets currently being inlined, for cycle detection.
+    stack: Vec<String>,
+    /// Local schema names of recursive targets, by `file#pointer`.
+    hoisted_names: HashMap<String, String>,
+    /// Resolved recursive targets, to be added to `components/schemas`.
+    hoisted: BTreeMap<String, serde_yaml::Value>,
+    /// Schema names already used in the root document or by hoisted targets.
+    taken: BTreeSet<String>,
+    /// Root schemas that are just a `$ref` to an external target, by `file#pointer`.
+    aliases: HashMap<String, String>,
+}
+
+impl ExternalRefResolver {
+    /// Inline the external refs of a root document, then add any hoisted
+    /// recursive targets to its `components/schemas`.
+    fn resolve_document(mut self, document: &mut serde_yaml::Value, path: &Path) -> Result<()> {
+        if let Some(schemas) = document
+            .get("components")
+            .and_then(|components| components.get("schemas"))
+            .and_then(serde_yaml::Value::as_mapping)
+        {
+            for (name, schema) in schemas {
+                let Some(name) = name.as_str() else { continue };
+                self.taken.insert(name.to_string());
+                if let Some(reference) = schema.get("$ref").and_then(serde_yaml::Value::as_str)
+                    && let Some((file, pointer)) = ref_target(reference, path, false)
+                    && let Ok(file) = file.canonicalize()
+                {
+                    self.aliases
+                        .insert(format!("{}#{pointer}", file.display()), name.to_string());
+                }
+            }
+        }
+
+        self.resolve(document, path, false)?;
+        if self.hoisted.is_empty() {
+            return Ok(());
+        }
+
+        let mut schemas = document
+            .get_mut("components")
+            .and_then(|components| components.get_mut("schemas"))
+            .and_then(serde_yaml::Value::as_mapping_mut);
+        if schemas.is_none() {
+            let root = document
+                .as_mapping_mut()
+                .context("spec root is not a mapping")?;
+            let components = root
+                .entry("components".into())
+                .or_insert_with(|| serde_yaml::Mapping::new().into())
+                .as_mapping_mut()
+                .context("spec components is not a mapping")?;
+            schemas = components
+                .entry("schemas".into())
+                .or_insert_with(|| serde_yaml::Mapping::new().into())
+                .as_mapping_mut();
+        }
+        let schemas = schemas.context("spec components/schemas is not a mapping")?;
+        // An alias entry is replaced by the schema it pointed at
+        for (name, schema) in std::mem::take(&mut self.hoisted) {
+            schemas.insert(name.into(), schema);
+        }
+        Ok(())
+    }
+
+    fn resolve(
+        &mut self,
+        value: &mut serde_yaml::Value,
+        base: &Path,
+        external: bool,
+    ) -> Result<()> {
+        match value {
+            serde_yaml::Value::Mapping(map) => {
+                if let Some(serde_yaml::Value::String(reference)) = map.get("$ref")
+                    && let Some((file, pointer)) = ref_target(reference, base, external)
+                {
+                    *value = self.load_target(&file, &pointer)?;
+                    return Ok(());
+                }
+                for (_, child) in map.iter_mut() {
+                    self.resolve(child, base, external)?;
+                }
+            }
+            serde_yaml::Value::Sequence(items) => {
+                for child in items {
+                    self.resolve(child, base, external)?;
+                }
+            }
+            serde_yaml::Value::Tagged(tagged) => self.resolve(&mut tagged.value, base, external)?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn load_target(&mut self, file: &Path, pointer: &str) -> Result<serde_yaml::Value> {
+        let file = file
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve $ref to {}", file.display()))?;
+        let key = format!("{}#{pointer}", file.display());
+        if self.stack.contains(&key) {
+            return Ok(local_schema_ref(&self.hoisted_name(&key, &file, pointer)));
+        }
+        if let Some(name) = self.hoisted_names.get(&key)
+            && self.hoisted.contains_key(name)
+        {
+            return Ok(local_schema_ref(name));
+        }
+
+        if !self.documents.contains_key(&file) {
+            let document = load_document(&file)?;
+            self.documents.insert(file.clone(), document);
+        }
+        let mut target = self
+            .documents
+            .get(&file)
+            .and_then(|document| resolve_pointer(document, pointer))
+            .cloned()
+            .with_context(|| format!("$ref target '#{pointer}' not found in {}", file.display()))?;
+
+        self.stack.push(key);
+        self.resolve(&mut target, &file, true)?;
+        let key = self.stack.pop().unwrap_or_default();
+        match self.hoisted_names.get(&key) {
+            Some(name) => {
+                let name = name.clone();
+                self.hoisted.insert(name.clone(), target);
+                Ok(local_schema_ref(&name))
+            }
+            None => Ok(target),
+        }
+    }
+
+    /// The `components/schemas` name a recursive target is hoisted under:
+    /// the root schema aliasing it, else the pointer's last segment (or the
+    /// file stem), made unique.
+    fn hoisted_name(&mut self, key: &str, file: &Path, pointer: &str) -> String {
+        if let Some(name) = self.hoisted_names.get(key) {
+            return name.clone();
+        }
+        let name = self.aliases.get(key).cloned().unwrap_or_else(|| {
+            let base: String = pointer
+                .rsplit('/')
+                .next()
+                .filter(|token| !token.is_empty())
+                .map(|token| token.replace("~1", "/").replace("~0", "~"))
+                .or_else(|| {
+                    file.file_stem()
+                        .map(|stem| stem.to_string_lossy().into_owned())
+                })
+                .unwrap_or_else(|| "Schema".to_string())
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            let mut name = base.clone();
+            let mut suffix = 2;
+            while self.taken.contains(&name) {
+                name = format!("{base}{suffix}");
+                suffix += 1;
+            }
+            name
+        });
+        self.taken.insert(name.clone());
+        self.hoisted_names.insert(key.to_string(), name.clone());
+        name
+    }
+}
+
+/// A `$ref` to `#/components/schemas/<name>`.
+fn local_schema_ref(name: &str) -> serde_yaml::Value {
+    let mut reference = serde_yaml::Mapping::new();
+    reference.insert("$ref".into(), format!("#/components/schemas/{name}").into());
+    reference.into()
+}
+
+/// Determine which file and JSON pointer a `$ref` should be inlined from, or
+/// `None` if it should be left as-is.
+fn ref_target(reference: &str, base: &Path, external: bool) -> Option<(PathBuf, String)> {
+    let (file_part, pointer) = reference.split_once('#').unwrap_or((reference, ""));
+    if file_part.is_empty() {
+        return external.then(|| (base.to_path_buf(), pointer.to_string()));
+    }
+    if file_part.contains("://") {
+        return None;
+    }
+    let dir = base.parent().unwrap_or_else(|| Path::new("."));
+    Some((dir.join(file_part), pointer.to_string()))
+}
+
+/// Follow a JSON pointer (`/a/b/0`) into a document. An empty pointer is the whole document.
+fn resolve_pointer<'a>(
+    document: &'a serde_yaml::Value,
+    pointer: &str,
+) -> Option<&'a serde_yaml::Value> {
+    pointer
+        .split('/')
+        .filter(|token| !token.is_empty())
+        .try_fold(document, |current, token| {
+            let token = token.replace("~1", "/").replace("~0", "~");
+            match current {
+                serde_yaml::Value::Mapping(map) => map.get(token.as_str()),
+                serde_yaml::Value::Sequence(items) => {
+                    token.parse::<usize>().ok().and_then(|i| items.get(i))
+                }
+                _ => None,
+            }
+        })
+}
+
+/// Rewrite JSON Schema `const` into a single-value `enum`.
+///
+/// The `OpenAPI` model drops `const`, so without this a field pinned to
+/// `"cat"` would read as a plain `string`. A schema without a `type` gets one
+/// inferred from the value. Example and default values are left untouched.
+fn const_to_enum(value: &mut serde_yaml::Value) {
+    match value {
+        serde_yaml::Value::Mapping(map) => {
+            let pinned = match map.get("const") {
+                Some(
+                    v @ (serde_yaml::Value::String(_)
+                    | serde_yaml::Value::Number(_)
+                    | serde_yaml::Value::Bool(_)),
+                ) => Some(v.clone()),
+                _ => None,
+            };
+            if let Some(pinned) = pinned
+                && !map.contains_key("enum")
+            {
+                if !map.contains_key("type") {
+                    let inferred = match &pinned {
+                        serde_yaml::Value::String(_) => "string",
+                        serde_yaml::Value::Bool(_) => "boolean",
+                        serde_yaml::Value::Number(n) if n.is_f64() => "number",
+                        _ => "integer",
+                    };
+                    map.insert("type".into(), inferred.into());
+                }
+                map.remove("const");
+                map.insert("enum".into(), serde_yaml::Value::Sequence(vec![pinned]));
+            }
+            for (key, child) in map.iter_mut() {
+                if !matches!(key.as_str(), Some("example" | "examples" | "default")) {
+                    const_to_enum(child);
+                }
+            }
+        }
+        serde_yaml::Value::Sequence(items) => items.iter_mut().for_each(const_to_enum),
+        serde_yaml::Value::Tagged(tagged) => const_to_enum(&mut tagged.value),
+        _ => {}
+    }
+}
+
+/// How function names are derived from `operationId`s.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum FunctionNaming {
+    /// `listPets` becomes `list_pets`.
+    #[default]
+    Snake,
+    /// `list_pets` becomes `listPets`.
+    Camel,
+    /// The `operationId` is used as written. Operations without one still
+    /// get a `snake_case` name from their method and path.
+    Original,
+}
+
+/// Convert an `OpenAPI` spec into a `Manifest`, with `snake_case` function names.
 ///
 /// Walks the spec and extracts:
 /// 1. `ApiConfig` from info + servers + security schemes
 /// 2. `FunctionDef` from each path + operation
 /// 3. `SchemaDef` from components/schemas
 pub fn spec_to_manifest(spec: &OpenAPI, api_name: &str) -> Result<Manifest> {
-    let api_config = extract_api_config(spec, api_name);
-    let functions = extract_functions(spec, api_name)?;
+    spec_to_manifest_with_naming(spec, api_name, FunctionNaming::Snake)
+}
+
+/// Convert an `OpenAPI` spec into a `Manifest`, naming functions per `naming`.
+pub fn spec_to_manifest_with_naming(
+    spec: &OpenAPI,
+    api_name: &str,
+    naming: FunctionNaming,
+) -> Result<Manifest> {
+    let mut api_config = extract_api_config(spec, api_name);
+    let functions = extract_functions(spec, api_name, &api_config.auth, naming)?;
     let schemas = extract_schemas(spec);
+    api_config.deprecated |= !functions.is_empty() && functions.iter().all(|f| f.deprecated);
 
     Ok(Manifest {
+        schema_version: MANIFEST_SCHEMA_VERSION,
         apis: vec![api_config],
         functions,
         schemas,
         mcp_servers: vec![],
+        generated: None,
     })
 }
 
+/// Fail with every `$ref` in `spec` that does not resolve to something in the
+/// spec itself. Unresolved schema refs otherwise become `unknown` or dangling
+/// type names in the manifest.
+pub fn check_refs_resolve(spec: &OpenAPI) -> Result<()> {
+    let document = serde_json::to_value(spec)?;
+    let mut unresolved = BTreeSet::new();
+    collect_unresolved_refs(&document, &document, &mut unresolved);
+    if !unresolved.is_empty() {
+        bail!(
+            "unresolved $ref: {}",
+            unresolved.into_iter().collect::<Vec<_>>().join(", ")
+        );
+    }
+    Ok(())
+}
+
+fn collect_unresolved_refs<'a>(
+    root: &serde_json::Value,
+    value: &'a serde_json::Value,
+    out: &mut BTreeSet<&'a str>,
+) {
+    match value {
+        serde_json::Value::Object(map) => {
+            if let Some(serde_json::Value::String(reference)) = map.get("$ref")
+                && reference
+                    .strip_prefix('#')
+                    .is_none_or(|pointer| root.pointer(pointer).is_none())
+            {
+                out.insert(reference);
+            }
+            for child in map.values() {
+                collect_unresolved_refs(root, child, out);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for child in items {
+                collect_unresolved_refs(root, child, out);
+            }
+        }
+        _ => {}
+    }
+}
+
 // ---------------------------------------------------------------------------
 // API config extraction
 // ---------------------------------------------------------------------------
@@ -71,7 +488,7 @@ fn extract_api_config(spec: &OpenAPI, api_name: &str) -> ApiConfig {
         .first()
         .map_or_else(|| "/".to_string(), |s| s.url.clone());
 
-    let auth = spec.components.as_ref().and_then(extract_auth_config);
+    let auth = extract_auth_schemes(spec);
 
     ApiConfig {
         name: api_name.to_string(),
@@ -79,68 +496,199 @@ fn extract_api_config(spec: &OpenAPI, api_name: &str) -> ApiConfig {
         description: spec.info.description.clone(),
         version: Some(spec.info.version.clone()),
         auth,
+        constant_headers: BTreeMap::new(),
+        rate_limit: None,
+        default_query: BTreeMap::new(),
+        deprecated: is_spec_deprecated(spec),
+        boolean_style: None,
     }
 }
 
-fn extract_auth_config(components: &openapiv3::Components) -> Option<AuthConfig> {
-    for (_name, scheme_ref) in &components.security_schemes {
-        if let ReferenceOr::Item(scheme) = scheme_ref {
-            match scheme {
-                SecurityScheme::HTTP {
-                    scheme,
-                    description: _,
-                    bearer_format: _,
-                    extensions: _,
-                } => {
-                    if scheme.eq_ignore_ascii_case("bearer") {
-                        return Some(AuthConfig::Bearer {
-                            header: "Authorization".to_string(),
-                            prefix: "Bearer ".to_string(),
-                        });
-                    } else if scheme.eq_ignore_ascii_case("basic") {
-                        return Some(AuthConfig::Basic);
+/// Whether the spec declares `x-deprecated: true` at the top level or in `info`.
+fn is_spec_deprecated(spec: &OpenAPI) -> bool {
+    [&spec.extensions, &spec.info.extensions]
+        .iter()
+        .any(|extensions| extensions.get("x-deprecated") == Some(&serde_json::Value::Bool(true)))
+}
+
+/// Pick the security schemes applied to every call of this API.
+///
+/// Uses the first non-empty top-level `security` requirement (all of its
+/// schemes apply together). Without one, falls back to every scheme named by
+/// operation-level requirements, and finally to the first defined scheme.
+fn extract_auth_schemes(spec: &OpenAPI) -> Vec<AuthScheme> {
+    let Some(components) = spec.components.as_ref() else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<&str> = spec
+        .security
+        .iter()
+        .flatten()
+        .find(|requirement| !requirement.is_empty())
+        .map(|requirement| requirement.keys().map(String::as_str).collect())
+        .unwrap_or_default();
+
+    if names.is_empty() {
+        for (_path, _method, operation) in spec.operations() {
+            for requirement in operation.security.iter().flatten() {
+                for name in requirement.keys() {
+                    if !names.contains(&name.as_str()) {
+                        names.push(name);
                     }
                 }
-                SecurityScheme::APIKey {
-                    location: _,
-                    name,
-                    description: _,
-                    extensions: _,
-                } => {
-                    return Some(AuthConfig::ApiKey {
-                        header: name.clone(),
-                    });
-                }
-                _ => {}
             }
         }
     }
-    None
+
+    if names.is_empty() {
+        return components
+            .security_schemes
+            .iter()
+            .find_map(|(name, scheme)| named_auth_scheme(name, scheme))
+            .into_iter()
+            .collect();
+    }
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            components
+                .security_schemes
+                .get(name)
+                .and_then(|scheme| named_auth_scheme(name, scheme))
+        })
+        .collect()
+}
+
+/// Resolve an operation's own `security` into an auth override.
+///
+/// Returns `None` when the operation inherits the API's schemes: no
+/// operation-level `security`, one that allows anonymous access alongside
+/// credentials (`{}` among other requirements), or one naming the same
+/// schemes as the API. `security: []` (or only `{}`) yields an empty override,
+/// so the call is made without credentials.
+fn extract_operation_auth(
+    spec: &OpenAPI,
+    security: Option<&[SecurityRequirement]>,
+    api_auth: &[AuthScheme],
+) -> Option<Vec<AuthScheme>> {
+    let requirements = security?;
+    let anonymous = requirements
+        .iter()
+        .filter(|requirement| requirement.is_empty())
+        .count();
+    if anonymous == requirements.len() {
+        return Some(Vec::new());
+    }
+    if anonymous > 0 {
+        return None;
+    }
+
+    let schemes: Vec<AuthScheme> = requirements
+        .first()?
+        .keys()
+        .filter_map(|name| {
+            spec.components
+                .as_ref()?
+                .security_schemes
+                .get(name)
+                .and_then(|scheme| named_auth_scheme(name, scheme))
+        })
+        .collect();
+    (schemes != api_auth).then_some(schemes)
+}
+
+fn named_auth_scheme(name: &str, scheme: &ReferenceOr<SecurityScheme>) -> Option<AuthScheme> {
+    let ReferenceOr::Item(scheme) = scheme else {
+        return None;
+    };
+    let oauth2 = matches!(
+        scheme,
+        SecurityScheme::OAuth2 { .. } | SecurityScheme::OpenIDConnect { .. }
+    );
+    let config = match scheme {
+        SecurityScheme::HTTP { scheme, .. } if scheme.eq_ignore_ascii_case("bearer") => {
+            AuthConfig::Bearer {
+                header: "Authorization".to_string(),
+                prefix: "Bearer ".to_string(),
+            }
+        }
+        SecurityScheme::HTTP { scheme, .. } if scheme.eq_ignore_ascii_case("basic") => {
+            AuthConfig::Basic
+        }
+        SecurityScheme::APIKey { location, name, .. } => AuthConfig::ApiKey {
+            header: name.clone(),
+            location: match location {
+                APIKeyLocation::Header => ApiKeyLocation::Header,
+                APIKeyLocation::Query => ApiKeyLocation::Query,
+                APIKeyLocation::Cookie => ApiKeyLocation::Cookie,
+            },
+        },
+        // No OAuth flow is run: the caller supplies an access token.
+        SecurityScheme::OAuth2 { .. } | SecurityScheme::OpenIDConnect { .. } => {
+            AuthConfig::Bearer {
+                header: "Authorization".to_string(),
+                prefix: "Bearer ".to_string(),
+            }
+        }
+        SecurityScheme::HTTP { .. } => return None,
+    };
+    Some(AuthScheme {
+        name: name.to_string(),
+        config,
+        oauth2,
+    })
 }
 
 // ---------------------------------------------------------------------------
 // Function extraction
 // ---------------------------------------------------------------------------
 
-fn extract_functions(spec: &OpenAPI, api_name: &str) -> Result<Vec<FunctionDef>> {
+fn extract_functions(
+    spec: &OpenAPI,
+    api_name: &str,
+    api_auth: &[AuthScheme],
+    naming: FunctionNaming,
+) -> Result<Vec<FunctionDef>> {
     let mut functions = Vec::new();
 
-    for (path, method, operation) in spec.operations() {
+    let operations = spec
+        .paths
+        .iter()
+        .filter_map(|(path, item)| item.as_item().map(|item| (path.as_str(), item)))
+        .flat_map(|(path, item)| {
+            item.iter()
+                .map(move |(method, operation)| (path, item, method, operation))
+        });
+    for (path, path_item, method, operation) in operations {
         let http_method = match method {
             "get" => HttpMethod::Get,
             "post" => HttpMethod::Post,
             "put" => HttpMethod::Put,
             "patch" => HttpMethod::Patch,
             "delete" => HttpMethod::Delete,
-            _ => continue, // Skip unsupported methods (options, head, trace)
+            "head" => HttpMethod::Head,
+            "options" => HttpMethod::Options,
+            _ => continue, // Skip unsupported methods (trace)
         };
 
-        let name = derive_function_name(operation.operation_id.as_deref(), method, path);
+        let name = derive_function_name(operation.operation_id.as_deref(), method, path, naming);
         let tag = operation.tags.first().cloned();
 
-        let parameters = extract_parameters(&operation.parameters, spec)?;
+        let parameters = merge_parameters(
+            extract_parameters(&path_item.parameters, spec)?,
+            extract_parameters(&operation.parameters, spec)?,
+        );
         let request_body = extract_request_body(operation.request_body.as_ref(), spec)?;
         let response_schema = extract_response_schema(&operation.responses);
+        let error_schema = extract_error_schema(&operation.responses);
+        let auth = extract_operation_auth(spec, operation.security.as_deref(), api_auth);
+        let link_paginated = operation
+            .extensions
+            .get("x-pagination")
+            .and_then(serde_json::Value::as_str)
+            == Some("link");
 
         functions.push(FunctionDef {
             name,
@@ -154,15 +702,52 @@ fn extract_functions(spec: &OpenAPI, api_name: &str) -> Result<Vec<FunctionDef>>
             parameters,
             request_body,
             response_schema,
+            error_schema,
+            auth,
+            link_paginated,
         });
     }
 
     Ok(functions)
 }
 
-/// Derive a `snake_case` function name from an `operationId` or method+path.
-fn derive_function_name(operation_id: Option<&str>, method: &str, path: &str) -> String {
-    operation_id.map_or_else(|| fallback_function_name(method, path), camel_to_snake)
+/// Derive a function name from an `operationId` or method+path.
+fn derive_function_name(
+    operation_id: Option<&str>,
+    method: &str,
+    path: &str,
+    naming: FunctionNaming,
+) -> String {
+    match (naming, operation_id) {
+        (FunctionNaming::Original, Some(id)) => id.to_string(),
+        (FunctionNaming::Camel, _) => snake_to_camel(&derive_function_name(
+            operation_id,
+            method,
+            path,
+            FunctionNaming::Snake,
+        )),
+        (_, Some(id)) => camel_to_snake(id),
+        (_, None) => fallback_function_name(method, path),
+    }
+}
+
+/// Convert `snake_case` to `camelCase`.
+///
+/// Examples:
+///   `list_pets` -> `listPets`
+///   `get_pets_by_pet_id` -> `getPetsByPetId`
+fn snake_to_camel(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for (i, word) in s.split('_').filter(|w| !w.is_empty()).enumerate() {
+        let mut chars = word.chars();
+        if i > 0
+            && let Some(first) = chars.next()
+        {
+            result.extend(first.to_uppercase());
+        }
+        result.extend(chars);
+    }
+    result
 }
 
 /// Convert `camelCase` to `snake_case`.
@@ -240,6 +825,14 @@ fn extract_parameters(params: &[ReferenceOr<Parameter>], spec: &OpenAPI) -> Resu
         };
 
         let (param_type, default_val, enum_values, format) = extract_param_type_info(&data.format);
+        let enum_descriptions = match (&data.format, &enum_values) {
+            (ParameterSchemaOrContent::Schema(ReferenceOr::Item(schema)), Some(values)) => {
+                serde_json::to_value(schema)
+                    .ok()
+                    .and_then(|json| super::luau_types::extract_enum_descriptions(&json, values))
+            }
+            _ => None,
+        };
 
         result.push(ParamDef {
             name: data.name.clone(),
@@ -251,12 +844,52 @@ fn extract_parameters(params: &[ReferenceOr<Parameter>], spec: &OpenAPI) -> Resu
             enum_values,
             format,
             frozen_value: None,
+            deprecated: data.deprecated.unwrap_or(false),
+            example: extract_param_example(data),
+            enum_descriptions,
+            boolean_style: data
+                .extensions
+                .get("x-boolean-style")
+                .and_then(|style| serde_json::from_value(style.clone()).ok()),
+            multiple_of: extract_multiple_of(&data.format),
         });
     }
 
     Ok(result)
 }
 
+/// Combine path-item-level parameters with an operation's own. An operation
+/// parameter replaces a shared one with the same name and location.
+fn merge_parameters(shared: Vec<ParamDef>, own: Vec<ParamDef>) -> Vec<ParamDef> {
+    let mut merged: Vec<ParamDef> = shared
+        .into_iter()
+        .filter(|p| {
+            !own.iter()
+                .any(|o| o.name == p.name && o.location == p.location)
+        })
+        .collect();
+    merged.extend(own);
+    merged
+}
+
+/// The parameter's `example`, else its first `examples` entry with an inline
+/// value, else its schema's `example`.
+fn extract_param_example(data: &openapiv3::ParameterData) -> Option<serde_json::Value> {
+    if let Some(example) = &data.example {
+        return Some(example.clone());
+    }
+    let from_examples = data.examples.values().find_map(|example| match example {
+        ReferenceOr::Item(example) => example.value.clone(),
+        ReferenceOr::Reference { .. } => None,
+    });
+    from_examples.or_else(|| match &data.format {
+        ParameterSchemaOrContent::Schema(ReferenceOr::Item(schema)) => {
+            schema.schema_data.example.clone()
+        }
+        _ => None,
+    })
+}
+
 fn resolve_parameter<'a>(
     param_ref: &'a ReferenceOr<Parameter>,
     spec: &'a OpenAPI,
@@ -303,6 +936,22 @@ fn extract_param_type_info(
     }
 }
 
+/// The `multipleOf` of an inline integer or number parameter schema.
+fn extract_multiple_of(format: &ParameterSchemaOrContent) -> Option<serde_json::Number> {
+    let ParameterSchemaOrContent::Schema(ReferenceOr::Item(schema)) = format else {
+        return None;
+    };
+    match &schema.schema_kind {
+        SchemaKind::Type(Type::Integer(integer_type)) => {
+            integer_type.multiple_of.map(serde_json::Number::from)
+        }
+        SchemaKind::Type(Type::Number(number_type)) => number_type
+            .multiple_of
+            .and_then(serde_json::Number::from_f64),
+        _ => None,
+    }
+}
+
 const fn schema_type_to_param_type(kind: &SchemaKind) -> ParamType {
     match kind {
         SchemaKind::Type(Type::Integer(_)) => ParamType::Integer,
@@ -312,20 +961,36 @@ const fn schema_type_to_param_type(kind: &SchemaKind) -> ParamType {
     }
 }
 
+/// Extract enum values for string, integer, and boolean schemas.
+///
+/// Values are stored as strings; integers and booleans use their literal form
+/// (`"200"`, `"true"`), which is also how parameter values are serialized.
 fn extract_enum_values(kind: &SchemaKind) -> Option<Vec<String>> {
-    if let SchemaKind::Type(Type::String(string_type)) = kind
-        && !string_type.enumeration.is_empty()
-    {
-        let values: Vec<String> = string_type
+    let values: Vec<String> = match kind {
+        SchemaKind::Type(Type::String(string_type)) => string_type
             .enumeration
             .iter()
             .filter_map(Clone::clone)
-            .collect();
-        if !values.is_empty() {
-            return Some(values);
-        }
+            .collect(),
+        SchemaKind::Type(Type::Integer(integer_type)) => integer_type
+            .enumeration
+            .iter()
+            .flatten()
+            .map(ToString::to_string)
+            .collect(),
+        SchemaKind::Type(Type::Boolean(boolean_type)) => boolean_type
+            .enumeration
+            .iter()
+            .flatten()
+            .map(ToString::to_string)
+            .collect(),
+        _ => return None,
+    };
+    if values.is_empty() {
+        None
+    } else {
+        Some(values)
     }
-    None
 }
 
 fn extract_request_body(
@@ -440,6 +1105,24 @@ fn extract_response_schema(responses: &openapiv3::Responses) -> Option<String> {
     None
 }
 
+/// The schema of the first 4xx/5xx response with a JSON `$ref` body, checking
+/// exact codes before the `4XX`/`5XX` ranges.
+fn extract_error_schema(responses: &openapiv3::Responses) -> Option<String> {
+    let statuses = (400..=599u16)
+        .map(openapiv3::StatusCode::Code)
+        .chain([4, 5].map(openapiv3::StatusCode::Range));
+    for status in statuses {
+        if let Some(ReferenceOr::Item(response)) = responses.responses.get(&status)
+            && let Some(media_type) = response.content.get("application/json")
+            && let Some(schema_ref) = &media_type.schema
+            && let Some(name) = extract_ref_name(schema_ref)
+        {
+            return Some(name);
+        }
+    }
+    None
+}
+
 // ---------------------------------------------------------------------------
 // Schema extraction
 // ---------------------------------------------------------------------------
@@ -482,6 +1165,7 @@ fn extract_schema_def(
                 name: name.to_string(),
                 description: schema.schema_data.description.clone(),
                 fields,
+                discriminator: extract_discriminator(schema, &[]),
             })
         }
         SchemaKind::AllOf { all_of } => {
@@ -500,18 +1184,79 @@ fn extract_schema_def(
                 })
                 .collect();
 
+            // The outer description wins; otherwise use the first inline
+            // member's, since a `$ref` member describes the base schema.
+            let description = schema.schema_data.description.clone().or_else(|| {
+                all_of.iter().find_map(|member| match member {
+                    ReferenceOr::Item(member) => member.schema_data.description.clone(),
+                    ReferenceOr::Reference { .. } => None,
+                })
+            });
+
             Some(SchemaDef {
                 name: name.to_string(),
-                description: schema.schema_data.description.clone(),
+                description,
                 fields,
+                discriminator: extract_discriminator(schema, &[]),
+            })
+        }
+        SchemaKind::OneOf { one_of } => {
+            // Only discriminated unions have a named shape worth documenting
+            let discriminator = extract_discriminator(schema, one_of)?;
+            Some(SchemaDef {
+                name: name.to_string(),
+                description: schema.schema_data.description.clone(),
+                fields: Vec::new(),
+                discriminator: Some(discriminator),
             })
         }
-        _ => None, // Only extract object and allOf schemas as SchemaDefs
+        _ => None, // Only extract object, allOf, and discriminated oneOf schemas as SchemaDefs
+    }
+}
+
+/// Extract a schema's discriminator, resolving mapping targets to schema names.
+///
+/// `$ref` variants without an explicit mapping entry are mapped implicitly by
+/// schema name, as the `OpenAPI` spec prescribes.
+fn extract_discriminator(
+    schema: &Schema,
+    variants: &[ReferenceOr<Schema>],
+) -> Option<DiscriminatorDef> {
+    let discriminator = schema.schema_data.discriminator.as_ref()?;
+
+    let mut mapping: BTreeMap<String, String> = discriminator
+        .mapping
+        .iter()
+        .map(|(value, target)| {
+            let schema_name = target
+                .strip_prefix("#/components/schemas/")
+                .unwrap_or(target);
+            (value.clone(), schema_name.to_string())
+        })
+        .collect();
+
+    for variant in variants {
+        if let Some(variant_name) = extract_ref_name(variant)
+            && !mapping.values().any(|target| *target == variant_name)
+        {
+            mapping.insert(variant_name.clone(), variant_name);
+        }
     }
+
+    Some(DiscriminatorDef {
+        property_name: discriminator.property_name.clone(),
+        mapping,
+    })
 }
 
 /// Recursively collect properties and required fields from a schema reference,
-/// handling both Object types and nested `AllOf` compositions.
+/// handling Object types, nested `AllOf` compositions, and `OneOf`/`AnyOf`
+/// members.
+///
+/// A property defined more than once (e.g. a base field narrowed by a later
+/// `allOf` member) keeps its first position but takes the last definition.
+/// Untyped members contribute their inline `properties` and `required`, so a
+/// bare `{ required: [...] }` member marks inherited fields as required.
 fn collect_object_properties(
     schema_ref: &ReferenceOr<Schema>,
     components: &openapiv3::Components,
@@ -534,7 +1279,7 @@ fn collect_object_properties(
     match &schema.schema_kind {
         SchemaKind::Type(Type::Object(obj)) => {
             for (name, prop_ref) in &obj.properties {
-                properties.push((name.clone(), prop_ref.clone()));
+                push_property(properties, name, prop_ref);
             }
             required.extend(obj.required.iter().cloned());
         }
@@ -543,10 +1288,80 @@ fn collect_object_properties(
                 collect_object_properties(sub_ref, components, properties, required);
             }
         }
+        SchemaKind::OneOf { one_of: variants } | SchemaKind::AnyOf { any_of: variants } => {
+            collect_common_properties(variants, components, properties, required);
+        }
+        SchemaKind::Any(any) => {
+            for (name, prop_ref) in &any.properties {
+                push_property(properties, name, prop_ref);
+            }
+            required.extend(any.required.iter().cloned());
+            for sub_ref in &any.all_of {
+                collect_object_properties(sub_ref, components, properties, required);
+            }
+            for variants in [&any.one_of, &any.any_of] {
+                collect_common_properties(variants, components, properties, required);
+            }
+        }
         _ => {}
     }
 }
 
+/// Collect the properties shared by every member of a `oneOf`/`anyOf`.
+///
+/// Whichever member matches, these properties are present, so they belong to
+/// the composed object. A shared property is required only when every member
+/// requires it.
+fn collect_common_properties(
+    variants: &[ReferenceOr<Schema>],
+    components: &openapiv3::Components,
+    properties: &mut Vec<(String, ReferenceOr<Box<Schema>>)>,
+    required: &mut Vec<String>,
+) {
+    let collected: Vec<_> = variants
+        .iter()
+        .map(|variant| {
+            let mut variant_properties = Vec::new();
+            let mut variant_required = Vec::new();
+            collect_object_properties(
+                variant,
+                components,
+                &mut variant_properties,
+                &mut variant_required,
+            );
+            (variant_properties, variant_required)
+        })
+        .collect();
+    let Some(((first_properties, _), rest)) = collected.split_first() else {
+        return;
+    };
+
+    for (name, prop_ref) in first_properties {
+        let shared = rest
+            .iter()
+            .all(|(props, _)| props.iter().any(|(other, _)| other == name));
+        if !shared {
+            continue;
+        }
+        push_property(properties, name, prop_ref);
+        if collected.iter().all(|(_, req)| req.contains(name)) {
+            required.push(name.clone());
+        }
+    }
+}
+
+fn push_property(
+    properties: &mut Vec<(String, ReferenceOr<Box<Schema>>)>,
+    name: &str,
+    prop_ref: &ReferenceOr<Box<Schema>>,
+) {
+    if let Some(existing) = properties.iter_mut().find(|(existing, _)| existing == name) {
+        existing.1 = prop_ref.clone();
+    } else {
+        properties.push((name.to_string(), prop_ref.clone()));
+    }
+}
+
 fn extract_field_def(
     name: &str,
     schema_ref: &ReferenceOr<Box<Schema>>,
@@ -559,12 +1374,23 @@ fn extract_field_def(
                 .strip_prefix("#/components/schemas/")
                 .unwrap_or(reference)
                 .to_string();
-            // Look up the referenced schema to get its description
-            let description = components
+            let referenced = components
                 .schemas
                 .get(&schema_name)
-                .and_then(|s| s.as_item())
-                .and_then(|s| s.schema_data.description.clone());
+                .and_then(|s| s.as_item());
+            // A named enum has no type of its own in the SDK, so inline its values
+            if let Some(target) = referenced
+                && extract_enum_values(&target.schema_kind).is_some()
+            {
+                let json_value = serde_json::to_value(target).unwrap_or_default();
+                return super::luau_types::json_schema_prop_to_field_def(
+                    name,
+                    &json_value,
+                    required,
+                );
+            }
+            // Look up the referenced schema to get its description
+            let description = referenced.and_then(|s| s.schema_data.description.clone());
             FieldDef {
                 name: name.to_string(),
                 field_type: FieldType::Object {
@@ -575,9 +1401,27 @@ fn extract_field_def(
                 enum_values: None,
                 nullable: false,
                 format: None,
+                example: None,
+                enum_descriptions: None,
             }
         }
         ReferenceOr::Item(schema) => {
+            // `nullable` + `allOf: [$ref]` is the OpenAPI 3.0 spelling of a nullable reference
+            if let SchemaKind::AllOf { all_of } = &schema.schema_kind
+                && let [ReferenceOr::Reference { reference }] = all_of.as_slice()
+            {
+                let inner = ReferenceOr::Reference {
+                    reference: reference.clone(),
+                };
+                let mut field = extract_field_def(name, &inner, required, components);
+                field.nullable |= schema.schema_data.nullable;
+                if schema.schema_data.description.is_some() {
+                    field
+                        .description
+                        .clone_from(&schema.schema_data.description);
+                }
+                return field;
+            }
             let json_value = serde_json::to_value(schema.as_ref()).unwrap_or_default();
             super::luau_types::json_schema_prop_to_field_def(name, &json_value, required)
         }
@@ -618,6 +1462,23 @@ mod tests {
         assert!(!spec.paths.paths.is_empty());
     }
 
+    #[test]
+    fn test_load_spec_from_reader_builds_manifest() {
+        let yaml = std::fs::read("testdata/petstore.yaml").unwrap();
+        let spec = load_spec_from_reader(std::io::Cursor::new(yaml)).unwrap();
+        let manifest = spec_to_manifest(&spec, "stdin").unwrap();
+        assert_eq!(manifest.apis[0].name, "stdin");
+        assert!(manifest.functions.iter().any(|f| f.name == "list_pets"));
+    }
+
+    #[test]
+    fn test_load_spec_from_reader_accepts_json() {
+        let json =
+            r#"{"openapi": "3.0.3", "info": {"title": "Piped", "version": "1"}, "paths": {}}"#;
+        let spec = load_spec_from_reader(json.as_bytes()).unwrap();
+        assert_eq!(spec.info.title, "Piped");
+    }
+
     #[test]
     fn test_load_spec_from_file_info() {
         let spec = load_spec_from_file(Path::new("testdata/petstore.yaml")).unwrap();
@@ -667,6 +1528,240 @@ mod tests {
         assert!(result.is_err());
     }
 
+    // -----------------------------------------------------------------------
+    // Spec URL tests
+    // -----------------------------------------------------------------------
+
+    /// Serve fixed responses on an ephemeral local port and return the base URL.
+    async fn spawn_spec_server() -> String {
+        use axum::http::{StatusCode, header};
+        use axum::routing::get;
+
+        let petstore = std::fs::read_to_string("testdata/petstore.yaml").unwrap();
+        let app = axum::Router::new()
+            .route("/spec.yaml", get(move || async move { petstore }))
+            .route(
+                "/missing.yaml",
+                get(|| async { (StatusCode::NOT_FOUND, "Not Found") }),
+            )
+            .route(
+                "/docs",
+                get(|| async {
+                    (
+                        [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+                        "<!DOCTYPE html><html><body>API docs</body></html>",
+                    )
+                }),
+            )
+            .route(
+                "/untyped",
+                get(|| async { "\n  <html><head><title>Docs</title></head></html>" }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        format!("http://{addr}")
+    }
+
+    #[tokio::test]
+    async fn test_load_spec_from_url() {
+        let base = spawn_spec_server().await;
+        let spec = load_spec_from_url(&format!("{base}/spec.yaml"))
+            .await
+            .unwrap();
+        assert_eq!(spec.info.title, "Petstore");
+    }
+
+    #[tokio::test]
+    async fn test_load_spec_from_url_404() {
+        let base = spawn_spec_server().await;
+        let err = load_spec_from_url(&format!("{base}/missing.yaml"))
+            .await
+            .unwrap_err();
+        let msg = format!("{err:#}");
+        assert!(
+            msg.contains("HTTP 404 Not Found"),
+            "unexpected error: {msg}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_load_spec_from_url_html() {
+        let base = spawn_spec_server().await;
+        for path in ["/docs", "/untyped"] {
+            let err = load_spec_from_url(&format!("{base}{path}"))
+                .await
+                .unwrap_err();
+            let msg = format!("{err:#}");
+            assert!(
+                msg.contains("returned HTML, not an OpenAPI spec"),
+                "unexpected error for {path}: {msg}"
+            );
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // External $ref tests
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_load_spec_resolves_external_refs() {
+        let spec = load_spec_from_file(Path::new("testdata/split/api.yaml")).unwrap();
+        let manifest = spec_to_manifest(&spec, "split").unwrap();
+
+        let pet = manifest.schemas.iter().find(|s| s.name == "Pet").unwrap();
+        assert_eq!(pet.description.as_deref(), Some("A pet in the store"));
+        let field_names: Vec<&str> = pet.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(field_names, vec!["id", "name", "owner"]);
+
+        // The internal ref inside the external file is inlined as well
+        let owner = pet.fields.iter().find(|f| f.name == "owner").unwrap();
+        let FieldType::InlineObject { fields, .. } = &owner.field_type else {
+            panic!("expected inline object, got {:?}", owner.field_type);
+        };
+        assert_eq!(fields[0].name, "email");
+        assert_eq!(fields[0].format.as_deref(), Some("email"));
+
+        let get_pet = manifest
+            .functions
+            .iter()
+            .find(|f| f.name == "get_pet")
+            .unwrap();
+        assert_eq!(get_pet.response_schema.as_deref(), Some("Pet"));
+    }
+
+    #[test]
+    fn test_load_spec_external_ref_cycle_is_hoisted() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("api.yaml"),
+            r#"
+openapi: "3.0.3"
+info: { title: Cycle, version: "1.0.0" }
+paths: {}
+components:
+  schemas:
+    Node:
+      $ref: "./node.yaml#/Node"
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("node.yaml"),
+            r##"
+Node:
+  type: object
+  properties:
+    next:
+      $ref: "#/Node"
+"##,
+        )
+        .unwrap();
+
+        let spec = load_spec_from_file(&dir.path().join("api.yaml")).unwrap();
+        let manifest = spec_to_manifest(&spec, "cycle").unwrap();
+
+        // The alias entry is replaced by the schema, which refers to itself
+        let node = manifest.schemas.iter().find(|s| s.name == "Node").unwrap();
+        assert_eq!(node.fields[0].name, "next");
+        assert_eq!(
+            node.fields[0].field_type,
+            FieldType::Object {
+                schema: "Node".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_load_spec_hoists_recursive_external_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("api.yaml"),
+            r#"
+openapi: "3.0.3"
+info: { title: Tree, version: "1.0.0" }
+paths:
+  /tree:
+    get:
+      operationId: getTree
+      responses:
+        "200":
+          description: OK
+          content:
+            application/json:
+              schema:
+                $ref: "./tree.yaml#/TreeNode"
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("tree.yaml"),
+            r##"
+TreeNode:
+  type: object
+  properties:
+    label:
+      type: string
+    children:
+      type: array
+      items:
+        $ref: "#/TreeNode"
+"##,
+        )
+        .unwrap();
+
+        let spec = load_spec_from_file(&dir.path().join("api.yaml")).unwrap();
+        let manifest = spec_to_manifest(&spec, "tree").unwrap();
+
+        let get_tree = manifest
+            .functions
+            .iter()
+            .find(|f| f.name == "get_tree")
+            .unwrap();
+        assert_eq!(get_tree.response_schema.as_deref(), Some("TreeNode"));
+        let tree = manifest
+            .schemas
+            .iter()
+            .find(|s| s.name == "TreeNode")
+            .unwrap();
+        let children = tree.fields.iter().find(|f| f.name == "children").unwrap();
+        assert_eq!(
+            children.field_type,
+            FieldType::Array {
+                items: Box::new(FieldType::Object {
+                    schema: "TreeNode".to_string()
+                })
+            }
+        );
+    }
+
+    #[test]
+    fn test_load_spec_external_ref_missing_target() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("api.yaml"),
+            r#"
+openapi: "3.0.3"
+info: { title: Missing, version: "1.0.0" }
+paths: {}
+components:
+  schemas:
+    Pet:
+      $ref: "./schemas.yaml#/Missing"
+"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("schemas.yaml"), "Pet:\n  type: object\n").unwrap();
+
+        let err = load_spec_from_file(&dir.path().join("api.yaml")).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("not found"),
+            "unexpected error: {err:#}"
+        );
+    }
+
     // -----------------------------------------------------------------------
     // camel_to_snake tests
     // -----------------------------------------------------------------------
@@ -768,7 +1863,7 @@ mod tests {
 
         // Auth
         assert!(
-            manifest.apis[0].auth.is_some(),
+            !manifest.apis[0].auth.is_empty(),
             "Expected auth configuration"
         );
     }
@@ -988,14 +2083,110 @@ mod tests {
         let spec = load_spec_from_file(Path::new("testdata/petstore.yaml")).unwrap();
         let manifest = spec_to_manifest(&spec, "petstore").unwrap();
 
-        let auth = manifest.apis[0].auth.as_ref().unwrap();
         assert_eq!(
-            *auth,
-            AuthConfig::Bearer {
-                header: "Authorization".to_string(),
-                prefix: "Bearer ".to_string(),
+            manifest.apis[0].auth,
+            vec![AuthScheme {
+                name: "bearerAuth".to_string(),
+                config: AuthConfig::Bearer {
+                    header: "Authorization".to_string(),
+                    prefix: "Bearer ".to_string(),
+                },
+                oauth2: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_oauth2_and_openid_connect_map_to_bearer() {
+        let yaml = r#"
+openapi: "3.0.3"
+info:
+  title: Test
+  version: "1.0.0"
+security:
+  - oauth: [read]
+  - oidc: []
+paths: {}
+components:
+  securitySchemes:
+    oauth:
+      type: oauth2
+      flows:
+        clientCredentials:
+          tokenUrl: https://auth.example.com/token
+          scopes:
+            read: Read access
+    oidc:
+      type: openIdConnect
+      openIdConnectUrl: https://auth.example.com/.well-known/openid-configuration
+"#;
+        let spec: OpenAPI = serde_yaml::from_str(yaml).unwrap();
+        let manifest = spec_to_manifest(&spec, "test").unwrap();
+        assert_eq!(
+            manifest.apis[0].auth,
+            vec![AuthScheme {
+                name: "oauth".to_string(),
+                config: AuthConfig::Bearer {
+                    header: "Authorization".to_string(),
+                    prefix: "Bearer ".to_string(),
+                },
+                oauth2: true,
+            }]
+        );
+
+        let oidc = named_auth_scheme(
+            "oidc",
+            &spec.components.as_ref().unwrap().security_schemes["oidc"],
+        )
+        .unwrap();
+        assert!(oidc.oauth2);
+        assert!(matches!(oidc.config, AuthConfig::Bearer { .. }));
+    }
+
+    #[test]
+    fn test_multiple_security_schemes_in_one_requirement() {
+        let yaml = r#"
+openapi: "3.0.3"
+info:
+  title: Billing
+  version: "1.0.0"
+paths:
+  /invoices:
+    get:
+      operationId: listInvoices
+      security:
+        - apiKey: []
+          bearer: []
+      responses:
+        "200":
+          description: OK
+components:
+  securitySchemes:
+    basic:
+      type: http
+      scheme: basic
+    apiKey:
+      type: apiKey
+      in: header
+      name: X-Api-Key
+    bearer:
+      type: http
+      scheme: bearer
+"#;
+        let spec: OpenAPI = serde_yaml::from_str(yaml).unwrap();
+        let manifest = spec_to_manifest(&spec, "billing").unwrap();
+
+        let auth = &manifest.apis[0].auth;
+        let names: Vec<&str> = auth.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["apiKey", "bearer"]);
+        assert_eq!(
+            auth[0].config,
+            AuthConfig::ApiKey {
+                header: "X-Api-Key".to_string(),
+                location: ApiKeyLocation::Header,
             }
         );
+        assert!(matches!(auth[1].config, AuthConfig::Bearer { .. }));
     }
 
     #[test]
@@ -1023,6 +2214,112 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_operation_security_override() {
+        let spec: OpenAPI = serde_yaml::from_str(
+            r#"
+openapi: "3.0.3"
+info:
+  title: Test
+  version: "1.0"
+security:
+  - bearerAuth: []
+components:
+  securitySchemes:
+    bearerAuth:
+      type: http
+      scheme: bearer
+    apiKey:
+      type: apiKey
+      in: header
+      name: X-Api-Key
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        "200":
+          description: ok
+  /health:
+    get:
+      operationId: health
+      security: []
+      responses:
+        "200":
+          description: ok
+  /admin:
+    get:
+      operationId: admin
+      security:
+        - apiKey: []
+      responses:
+        "200":
+          description: ok
+  /feed:
+    get:
+      operationId: feed
+      security:
+        - {}
+        - bearerAuth: []
+      responses:
+        "200":
+          description: ok
+"#,
+        )
+        .unwrap();
+        let manifest = spec_to_manifest(&spec, "test").unwrap();
+        let auth_of = |name: &str| {
+            manifest
+                .functions
+                .iter()
+                .find(|f| f.name == name)
+                .unwrap()
+                .auth
+                .clone()
+        };
+
+        assert_eq!(auth_of("list_pets"), None);
+        assert_eq!(auth_of("health"), Some(vec![]));
+        assert_eq!(auth_of("feed"), None, "optional auth inherits the API's");
+        let admin = auth_of("admin").unwrap();
+        assert_eq!(admin.len(), 1);
+        assert_eq!(admin[0].name, "apiKey");
+    }
+
+    #[test]
+    fn test_deprecated_param_extracted() {
+        let spec: OpenAPI = serde_yaml::from_str(
+            r#"
+openapi: "3.0.3"
+info:
+  title: Test
+  version: "1.0"
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      parameters:
+        - name: status
+          in: query
+          schema:
+            type: string
+        - name: state
+          in: query
+          deprecated: true
+          schema:
+            type: string
+      responses:
+        "200":
+          description: ok
+"#,
+        )
+        .unwrap();
+        let manifest = spec_to_manifest(&spec, "test").unwrap();
+        let params = &manifest.functions[0].parameters;
+        assert!(!params[0].deprecated);
+        assert!(params[1].deprecated);
+    }
+
     #[test]
     fn test_manifest_serializes_to_json() {
         let spec = load_spec_from_file(Path::new("testdata/petstore.yaml")).unwrap();
@@ -1153,60 +2450,683 @@ mod tests {
     }
 
     #[test]
-    fn test_header_params_extracted() {
-        let spec = load_spec_from_file(Path::new("testdata/advanced.yaml")).unwrap();
-        let manifest = spec_to_manifest(&spec, "advanced").unwrap();
-
-        let list = manifest
-            .functions
+    fn test_inline_object_with_typed_additional_properties() {
+        let yaml = r##"
+openapi: "3.0.3"
+info:
+  title: Test
+  version: "1.0.0"
+paths: {}
+components:
+  schemas:
+    Label:
+      type: object
+      properties:
+        color:
+          type: string
+    Project:
+      type: object
+      properties:
+        labels:
+          type: object
+          required: [default]
+          properties:
+            default:
+              $ref: "#/components/schemas/Label"
+          additionalProperties:
+            type: integer
+        closed:
+          type: object
+          properties:
+            name:
+              type: string
+          additionalProperties: false
+"##;
+        let spec: OpenAPI = serde_yaml::from_str(yaml).unwrap();
+        let manifest = spec_to_manifest(&spec, "test").unwrap();
+        let project = manifest
+            .schemas
             .iter()
-            .find(|f| f.name == "list_resources")
-            .unwrap();
-        let header_param = list.parameters.iter().find(|p| p.name == "X-Request-ID");
-        assert!(
-            header_param.is_some(),
-            "Header param X-Request-ID should be extracted"
-        );
-        assert_eq!(header_param.unwrap().location, ParamLocation::Header);
+            .find(|s| s.name == "Project")
+            .expect("Project schema missing");
+        let field = |name: &str| {
+            let f = project.fields.iter().find(|f| f.name == name).unwrap();
+            crate::codegen::luau_types::field_type_to_luau(&f.field_type)
+        };
 
-        let update = manifest
-            .functions
-            .iter()
-            .find(|f| f.name == "update_resource")
-            .unwrap();
-        let idemp = update
-            .parameters
-            .iter()
-            .find(|p| p.name == "X-Idempotency-Key");
-        assert!(
-            idemp.is_some(),
-            "Header param X-Idempotency-Key should be extracted"
-        );
-        assert!(idemp.unwrap().required);
+        assert_eq!(field("labels"), "{ default: Label, [string]: number }");
+        assert_eq!(field("closed"), "{ name: string? }");
     }
 
     #[test]
-    fn test_allof_required_field_inheritance() {
-        let spec = load_spec_from_file(Path::new("testdata/advanced.yaml")).unwrap();
-        let manifest = spec_to_manifest(&spec, "advanced").unwrap();
-
-        let resource = manifest
-            .schemas
-            .iter()
-            .find(|s| s.name == "Resource")
-            .expect("Resource schema missing");
+    fn test_api_deprecated_when_all_operations_are() {
+        let spec = |extra: &str, second_deprecated: bool| {
+            let yaml = format!(
+                r#"
+openapi: "3.0.3"
+info:
+  title: Test
+  version: "1.0.0"
+  {extra}
+paths:
+  /a:
+    get:
+      operationId: getA
+      deprecated: true
+      responses:
+        "200":
+          description: OK
+  /b:
+    get:
+      operationId: getB
+      deprecated: {second_deprecated}
+      responses:
+        "200":
+          description: OK
+"#
+            );
+            let spec: OpenAPI = serde_yaml::from_str(&yaml).unwrap();
+            spec_to_manifest(&spec, "test").unwrap().apis[0].deprecated
+        };
 
-        // Inherited from BaseResource required: [id, created_at]
-        let id_field = resource.fields.iter().find(|f| f.name == "id").unwrap();
-        assert!(
-            id_field.required,
-            "id should be required (inherited from BaseResource)"
-        );
+        assert!(spec("", true));
+        assert!(!spec("", false));
+        assert!(spec("x-deprecated: true", false));
+    }
 
-        let created_at = resource
-            .fields
-            .iter()
-            .find(|f| f.name == "created_at")
+    #[test]
+    fn test_head_and_options_operations_extracted() {
+        let yaml = r#"
+openapi: "3.0.3"
+info:
+  title: Test
+  version: "1.0.0"
+paths:
+  /pets:
+    head:
+      operationId: checkPets
+      responses:
+        "200":
+          description: OK
+    options:
+      operationId: petsOptions
+      responses:
+        "204":
+          description: No Content
+    trace:
+      operationId: tracePets
+      responses:
+        "200":
+          description: OK
+"#;
+        let spec: OpenAPI = serde_yaml::from_str(yaml).unwrap();
+        let manifest = spec_to_manifest(&spec, "test").unwrap();
+        let method = |name: &str| {
+            manifest
+                .functions
+                .iter()
+                .find(|f| f.name == name)
+                .map(|f| f.method.clone())
+        };
+        assert_eq!(method("check_pets"), Some(HttpMethod::Head));
+        assert_eq!(method("pets_options"), Some(HttpMethod::Options));
+        assert_eq!(manifest.functions.len(), 2, "trace is still skipped");
+    }
+
+    #[test]
+    fn test_examples_extracted() {
+        let yaml = r#"
+openapi: "3.0.3"
+info:
+  title: Test
+  version: "1.0.0"
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      parameters:
+        - name: limit
+          in: query
+          example: 25
+          schema:
+            type: integer
+        - name: status
+          in: query
+          examples:
+            sold:
+              value: sold
+          schema:
+            type: string
+        - name: sort
+          in: query
+          schema:
+            type: string
+            example: name
+      responses:
+        "200":
+          description: OK
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        name:
+          type: string
+          example: Fido
+        age:
+          type: integer
+"#;
+        let spec: OpenAPI = serde_yaml::from_str(yaml).unwrap();
+        let manifest = spec_to_manifest(&spec, "test").unwrap();
+
+        let params: Vec<_> = manifest.functions[0]
+            .parameters
+            .iter()
+            .map(|p| (p.name.as_str(), p.example.clone()))
+            .collect();
+        assert_eq!(
+            params,
+            vec![
+                ("limit", Some(serde_json::json!(25))),
+                ("status", Some(serde_json::json!("sold"))),
+                ("sort", Some(serde_json::json!("name"))),
+            ]
+        );
+
+        let pet = manifest.schemas.iter().find(|s| s.name == "Pet").unwrap();
+        let example = |name: &str| {
+            pet.fields
+                .iter()
+                .find(|f| f.name == name)
+                .unwrap()
+                .example
+                .clone()
+        };
+        assert_eq!(example("name"), Some(serde_json::json!("Fido")));
+        assert_eq!(example("age"), None);
+    }
+
+    #[test]
+    fn test_const_becomes_single_value_enum() {
+        let yaml = r#"
+openapi: "3.0.3"
+info:
+  title: Test
+  version: "1.0.0"
+paths:
+  /cats:
+    get:
+      operationId: listCats
+      parameters:
+        - name: kind
+          in: query
+          schema:
+            const: cat
+      responses:
+        "200":
+          description: OK
+components:
+  schemas:
+    Cat:
+      type: object
+      required: [pet_type]
+      properties:
+        pet_type:
+          type: string
+          const: cat
+          example:
+            const: not-a-keyword
+"#;
+        let spec = load_spec_from_reader(yaml.as_bytes()).unwrap();
+        let manifest = spec_to_manifest(&spec, "test").unwrap();
+
+        let cat = manifest.schemas.iter().find(|s| s.name == "Cat").unwrap();
+        assert_eq!(cat.fields[0].enum_values, Some(vec!["cat".to_string()]));
+        assert_eq!(
+            cat.fields[0].example,
+            Some(serde_json::json!({ "const": "not-a-keyword" }))
+        );
+        let output = crate::codegen::annotations::render_schema_annotation(cat);
+        assert!(
+            output.contains(r#"pet_type: "cat","#),
+            "Expected literal type. Got:\n{output}"
+        );
+
+        let kind = &manifest.functions[0].parameters[0];
+        assert_eq!(kind.enum_values, Some(vec!["cat".to_string()]));
+        assert!(crate::runtime::validate::validate_param_value("list_cats", kind, "cat").is_ok());
+        let err = crate::runtime::validate::validate_param_value("list_cats", kind, "dog")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("expected one of [cat], got 'dog'"), "{err}");
+    }
+
+    #[test]
+    fn test_nullable_ref_to_enum_keeps_values() {
+        let yaml = r#"
+openapi: "3.0.3"
+info:
+  title: Test
+  version: "1.0.0"
+paths: {}
+components:
+  schemas:
+    Device:
+      type: object
+      required: [mode, fallback]
+      properties:
+        mode:
+          nullable: true
+          description: Current mode
+          allOf:
+            - $ref: '#/components/schemas/Mode'
+        fallback:
+          $ref: '#/components/schemas/Mode'
+    Mode:
+      type: string
+      enum: ["on", "off"]
+"#;
+        let spec = load_spec_from_reader(yaml.as_bytes()).unwrap();
+        let manifest = spec_to_manifest(&spec, "test").unwrap();
+
+        let device = manifest
+            .schemas
+            .iter()
+            .find(|s| s.name == "Device")
+            .unwrap();
+        let mode = device.fields.iter().find(|f| f.name == "mode").unwrap();
+        assert!(mode.nullable);
+        assert_eq!(mode.description.as_deref(), Some("Current mode"));
+        let output = crate::codegen::annotations::render_schema_annotation(device);
+        assert!(
+            output.contains(r#"mode: ("on" | "off")?,"#),
+            "Expected nullable enum union. Got:\n{output}"
+        );
+        assert!(
+            output.contains(r#"fallback: ("on" | "off"),"#),
+            "Expected inlined enum union. Got:\n{output}"
+        );
+    }
+
+    #[test]
+    fn test_boolean_style_extension_extracted() {
+        let yaml = r#"
+openapi: "3.0.3"
+info:
+  title: Test
+  version: "1.0.0"
+paths:
+  /items:
+    get:
+      operationId: listItems
+      parameters:
+        - name: archived
+          in: query
+          x-boolean-style: integer
+          schema:
+            type: boolean
+        - name: shared
+          in: query
+          schema:
+            type: boolean
+      responses:
+        "200":
+          description: OK
+"#;
+        let spec = load_spec_from_reader(yaml.as_bytes()).unwrap();
+        let manifest = spec_to_manifest(&spec, "test").unwrap();
+        let params = &manifest.functions[0].parameters;
+        assert_eq!(
+            params[0].boolean_style,
+            Some(crate::codegen::manifest::BooleanStyle::Integer)
+        );
+        assert_eq!(params[1].boolean_style, None);
+    }
+
+    #[test]
+    fn test_function_naming_modes() {
+        let spec = load_spec_from_file(Path::new("testdata/petstore.yaml")).unwrap();
+        let names = |naming| {
+            let manifest = spec_to_manifest_with_naming(&spec, "petstore", naming).unwrap();
+            let mut names: Vec<String> = manifest.functions.into_iter().map(|f| f.name).collect();
+            names.sort();
+            names
+        };
+        assert_eq!(
+            names(FunctionNaming::Snake),
+            ["create_pet", "get_pet_by_id", "list_pets"]
+        );
+        assert_eq!(
+            names(FunctionNaming::Original),
+            ["createPet", "getPetById", "listPets"]
+        );
+        assert_eq!(
+            names(FunctionNaming::Camel),
+            ["createPet", "getPetById", "listPets"]
+        );
+    }
+
+    #[test]
+    fn test_camel_naming_without_operation_id() {
+        assert_eq!(
+            derive_function_name(None, "get", "/pets/{petId}", FunctionNaming::Camel),
+            "getPetsByPetId"
+        );
+        assert_eq!(
+            derive_function_name(Some("list_all_pets"), "get", "/pets", FunctionNaming::Camel),
+            "listAllPets"
+        );
+        assert_eq!(
+            derive_function_name(None, "get", "/pets/{petId}", FunctionNaming::Original),
+            "get_pets_by_pet_id"
+        );
+    }
+
+    #[test]
+    fn test_multiple_of_extracted() {
+        let yaml = r#"
+openapi: "3.0.3"
+info:
+  title: Test
+  version: "1.0.0"
+paths:
+  /payments:
+    get:
+      operationId: listPayments
+      parameters:
+        - name: amount
+          in: query
+          schema:
+            type: number
+            multipleOf: 0.01
+        - name: page_size
+          in: query
+          schema:
+            type: integer
+            multipleOf: 10
+        - name: cursor
+          in: query
+          schema:
+            type: string
+      responses:
+        "200":
+          description: OK
+"#;
+        let spec = load_spec_from_reader(yaml.as_bytes()).unwrap();
+        let manifest = spec_to_manifest(&spec, "test").unwrap();
+        let params = &manifest.functions[0].parameters;
+        assert_eq!(params[0].multiple_of, serde_json::Number::from_f64(0.01));
+        assert_eq!(params[1].multiple_of, Some(serde_json::Number::from(10)));
+        assert_eq!(params[2].multiple_of, None);
+    }
+
+    #[test]
+    fn test_enum_descriptions_extracted() {
+        let yaml = r#"
+openapi: "3.0.3"
+info:
+  title: Test
+  version: "1.0.0"
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      parameters:
+        - name: sort
+          in: query
+          schema:
+            type: string
+            enum: [name, age]
+            x-enumNames: [By name, By age]
+      responses:
+        "200":
+          description: OK
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        status:
+          type: string
+          enum: [available, pending, sold]
+          x-enum-descriptions:
+            available: ready to adopt
+            pending: on hold
+"#;
+        let spec: OpenAPI = serde_yaml::from_str(yaml).unwrap();
+        let manifest = spec_to_manifest(&spec, "test").unwrap();
+
+        let sort = &manifest.functions[0].parameters[0];
+        assert_eq!(
+            sort.enum_descriptions,
+            Some(BTreeMap::from([
+                ("age".to_string(), "By age".to_string()),
+                ("name".to_string(), "By name".to_string()),
+            ]))
+        );
+
+        let pet = manifest.schemas.iter().find(|s| s.name == "Pet").unwrap();
+        assert_eq!(
+            pet.fields[0].enum_descriptions,
+            Some(BTreeMap::from([
+                ("available".to_string(), "ready to adopt".to_string()),
+                ("pending".to_string(), "on hold".to_string()),
+            ]))
+        );
+
+        let output = crate::codegen::annotations::render_schema_annotation(pet);
+        assert!(
+            output.contains("-- available: ready to adopt; pending: on hold"),
+            "Missing enum descriptions. Got:\n{output}"
+        );
+        let output =
+            crate::codegen::annotations::render_function_annotation(&manifest.functions[0]);
+        assert!(
+            output.contains("-- @param sort - name: By name; age: By age"),
+            "Missing param enum descriptions. Got:\n{output}"
+        );
+    }
+
+    #[test]
+    fn test_check_refs_resolve_lists_missing_schemas() {
+        let yaml = r##"
+openapi: "3.0.3"
+info:
+  title: Test
+  version: "1.0.0"
+paths:
+  /items:
+    post:
+      operationId: createItem
+      requestBody:
+        content:
+          application/json:
+            schema:
+              $ref: "#/definitions/Item"
+      responses:
+        "200":
+          description: OK
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/Missing"
+components:
+  schemas:
+    Present:
+      type: object
+"##;
+        let spec: OpenAPI = serde_yaml::from_str(yaml).unwrap();
+
+        let err = check_refs_resolve(&spec).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "unresolved $ref: #/components/schemas/Missing, #/definitions/Item"
+        );
+
+        // Without the check, the unresolvable body ref still becomes `unknown`
+        let manifest = spec_to_manifest(&spec, "test").unwrap();
+        let body = manifest.functions[0].request_body.as_ref().unwrap();
+        assert_eq!(body.schema, "unknown");
+    }
+
+    #[test]
+    fn test_path_item_parameters_shared_by_operations() {
+        let yaml = r#"
+openapi: "3.0.3"
+info:
+  title: Test
+  version: "1.0.0"
+paths:
+  /items/{id}:
+    parameters:
+      - name: id
+        in: path
+        required: true
+        schema:
+          type: string
+      - name: verbose
+        in: query
+        description: Shared flag
+        schema:
+          type: boolean
+    get:
+      operationId: getItem
+      responses:
+        "200":
+          description: OK
+    delete:
+      operationId: deleteItem
+      parameters:
+        - name: verbose
+          in: query
+          description: Delete-specific flag
+          schema:
+            type: boolean
+      responses:
+        "204":
+          description: Deleted
+"#;
+        let spec: OpenAPI = serde_yaml::from_str(yaml).unwrap();
+        let manifest = spec_to_manifest(&spec, "test").unwrap();
+        for func in &manifest.functions {
+            let path_param = func.parameters.iter().find(|p| p.name == "id").unwrap();
+            assert_eq!(path_param.location, ParamLocation::Path, "{}", func.name);
+            assert!(path_param.required);
+            let verbose_count = func
+                .parameters
+                .iter()
+                .filter(|p| p.name == "verbose")
+                .count();
+            assert_eq!(verbose_count, 1, "{}", func.name);
+        }
+        let delete = manifest
+            .functions
+            .iter()
+            .find(|f| f.name == "delete_item")
+            .unwrap();
+        let verbose = delete
+            .parameters
+            .iter()
+            .find(|p| p.name == "verbose")
+            .unwrap();
+        assert_eq!(verbose.description.as_deref(), Some("Delete-specific flag"));
+        assert_eq!(manifest.functions.len(), 2);
+    }
+
+    #[test]
+    fn test_x_pagination_link_marks_function() {
+        let yaml = r#"
+openapi: "3.0.3"
+info:
+  title: Test
+  version: "1.0.0"
+paths:
+  /repos:
+    get:
+      operationId: listRepos
+      x-pagination: link
+      responses:
+        "200":
+          description: OK
+  /repos/{id}:
+    get:
+      operationId: getRepo
+      parameters:
+        - name: id
+          in: path
+          required: true
+          schema:
+            type: string
+      responses:
+        "200":
+          description: OK
+"#;
+        let spec: OpenAPI = serde_yaml::from_str(yaml).unwrap();
+        let manifest = spec_to_manifest(&spec, "test").unwrap();
+        let paginated: Vec<(&str, bool)> = manifest
+            .functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.link_paginated))
+            .collect();
+        assert!(paginated.contains(&("list_repos", true)));
+        assert!(paginated.contains(&("get_repo", false)));
+    }
+
+    #[test]
+    fn test_header_params_extracted() {
+        let spec = load_spec_from_file(Path::new("testdata/advanced.yaml")).unwrap();
+        let manifest = spec_to_manifest(&spec, "advanced").unwrap();
+
+        let list = manifest
+            .functions
+            .iter()
+            .find(|f| f.name == "list_resources")
+            .unwrap();
+        let header_param = list.parameters.iter().find(|p| p.name == "X-Request-ID");
+        assert!(
+            header_param.is_some(),
+            "Header param X-Request-ID should be extracted"
+        );
+        assert_eq!(header_param.unwrap().location, ParamLocation::Header);
+
+        let update = manifest
+            .functions
+            .iter()
+            .find(|f| f.name == "update_resource")
+            .unwrap();
+        let idemp = update
+            .parameters
+            .iter()
+            .find(|p| p.name == "X-Idempotency-Key");
+        assert!(
+            idemp.is_some(),
+            "Header param X-Idempotency-Key should be extracted"
+        );
+        assert!(idemp.unwrap().required);
+    }
+
+    #[test]
+    fn test_allof_required_field_inheritance() {
+        let spec = load_spec_from_file(Path::new("testdata/advanced.yaml")).unwrap();
+        let manifest = spec_to_manifest(&spec, "advanced").unwrap();
+
+        let resource = manifest
+            .schemas
+            .iter()
+            .find(|s| s.name == "Resource")
+            .expect("Resource schema missing");
+
+        // Inherited from BaseResource required: [id, created_at]
+        let id_field = resource.fields.iter().find(|f| f.name == "id").unwrap();
+        assert!(
+            id_field.required,
+            "id should be required (inherited from BaseResource)"
+        );
+
+        let created_at = resource
+            .fields
+            .iter()
+            .find(|f| f.name == "created_at")
             .unwrap();
         assert!(
             created_at.required,
@@ -1239,6 +3159,85 @@ mod tests {
         assert!(!metadata_field.required, "metadata should not be required");
     }
 
+    #[test]
+    fn test_oneof_discriminator_extraction() {
+        let yaml = r##"
+openapi: "3.0.3"
+info:
+  title: Test
+  version: "1.0.0"
+paths: {}
+components:
+  schemas:
+    Shape:
+      description: A geometric shape
+      oneOf:
+        - $ref: "#/components/schemas/Circle"
+        - $ref: "#/components/schemas/Square"
+        - $ref: "#/components/schemas/Triangle"
+      discriminator:
+        propertyName: kind
+        mapping:
+          circle: "#/components/schemas/Circle"
+          square: Square
+    Circle:
+      type: object
+      properties:
+        kind: { type: string }
+        radius: { type: number }
+    Square:
+      type: object
+      properties:
+        kind: { type: string }
+        side: { type: number }
+    Triangle:
+      type: object
+      properties:
+        kind: { type: string }
+    Untagged:
+      oneOf:
+        - $ref: "#/components/schemas/Circle"
+        - $ref: "#/components/schemas/Square"
+"##;
+        let spec: OpenAPI = serde_yaml::from_str(yaml).unwrap();
+        let manifest = spec_to_manifest(&spec, "test").unwrap();
+
+        let shape = manifest
+            .schemas
+            .iter()
+            .find(|s| s.name == "Shape")
+            .expect("Shape schema missing");
+        assert!(shape.fields.is_empty());
+        assert_eq!(shape.description.as_deref(), Some("A geometric shape"));
+        let discriminator = shape.discriminator.as_ref().unwrap();
+        assert_eq!(discriminator.property_name, "kind");
+        // Explicit mappings (by ref or bare name) plus the implicit one for Triangle
+        let mapping: Vec<(&str, &str)> = discriminator
+            .mapping
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        assert_eq!(
+            mapping,
+            vec![
+                ("Triangle", "Triangle"),
+                ("circle", "Circle"),
+                ("square", "Square")
+            ]
+        );
+
+        // Variants are ordinary schemas without a discriminator
+        let circle = manifest
+            .schemas
+            .iter()
+            .find(|s| s.name == "Circle")
+            .unwrap();
+        assert!(circle.discriminator.is_none());
+
+        // oneOf without a discriminator is still not extracted
+        assert!(!manifest.schemas.iter().any(|s| s.name == "Untagged"));
+    }
+
     #[test]
     fn test_allof_three_levels() {
         let yaml = r##"
@@ -1320,6 +3319,193 @@ components:
         assert!(!c_field.required, "c_field should not be required");
     }
 
+    #[test]
+    fn test_allof_description_from_inline_member() {
+        let yaml = r##"
+openapi: "3.0.3"
+info:
+  title: Test
+  version: "1.0.0"
+paths: {}
+components:
+  schemas:
+    Base:
+      type: object
+      description: Fields shared by every resource.
+      properties:
+        id:
+          type: string
+    Pet:
+      allOf:
+        - $ref: "#/components/schemas/Base"
+        - type: object
+          description: A pet in the store.
+          required: [name]
+          properties:
+            name:
+              type: string
+    Toy:
+      description: Something to play with.
+      allOf:
+        - $ref: "#/components/schemas/Base"
+        - type: object
+          description: Ignored in favour of the outer description.
+          properties:
+            label:
+              type: string
+"##;
+        let spec: OpenAPI = serde_yaml::from_str(yaml).unwrap();
+        let manifest = spec_to_manifest(&spec, "test").unwrap();
+        let description = |name: &str| {
+            manifest
+                .schemas
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap()
+                .description
+                .clone()
+        };
+
+        assert_eq!(description("Pet").as_deref(), Some("A pet in the store."));
+        assert_eq!(
+            description("Toy").as_deref(),
+            Some("Something to play with.")
+        );
+        let pet = manifest.schemas.iter().find(|s| s.name == "Pet").unwrap();
+        let fields: Vec<&str> = pet.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(fields, ["id", "name"]);
+    }
+
+    #[test]
+    fn test_error_schema_from_4xx_response() {
+        let yaml = r##"
+openapi: "3.0.3"
+info:
+  title: Test
+  version: "1.0.0"
+paths:
+  /pets/{id}:
+    get:
+      operationId: getPet
+      parameters:
+        - name: id
+          in: path
+          required: true
+          schema:
+            type: string
+      responses:
+        "200":
+          description: The pet
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/Pet"
+        "400":
+          description: Bad request
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/Error"
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        "200":
+          description: OK
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        id:
+          type: string
+    Error:
+      type: object
+      properties:
+        message:
+          type: string
+"##;
+        let spec: OpenAPI = serde_yaml::from_str(yaml).unwrap();
+        let manifest = spec_to_manifest(&spec, "test").unwrap();
+        let func = |name: &str| manifest.functions.iter().find(|f| f.name == name).unwrap();
+        assert_eq!(func("get_pet").response_schema.as_deref(), Some("Pet"));
+        assert_eq!(func("get_pet").error_schema, Some("Error".to_string()));
+        assert_eq!(func("list_pets").error_schema, None);
+    }
+
+    #[test]
+    fn test_allof_with_oneof_member() {
+        let yaml = r##"
+openapi: "3.0.3"
+info:
+  title: Test
+  version: "1.0.0"
+paths: {}
+components:
+  schemas:
+    Base:
+      type: object
+      required: [id]
+      properties:
+        id:
+          type: string
+        created_at:
+          type: string
+    Card:
+      type: object
+      required: [amount, last4]
+      properties:
+        amount:
+          type: number
+        currency:
+          type: string
+        last4:
+          type: string
+    Transfer:
+      type: object
+      required: [amount, iban]
+      properties:
+        amount:
+          type: number
+        currency:
+          type: string
+        iban:
+          type: string
+    Payment:
+      allOf:
+        - $ref: "#/components/schemas/Base"
+        - oneOf:
+            - $ref: "#/components/schemas/Card"
+            - $ref: "#/components/schemas/Transfer"
+        - required: [created_at]
+"##;
+        let spec: OpenAPI = serde_yaml::from_str(yaml).unwrap();
+        let manifest = spec_to_manifest(&spec, "test").unwrap();
+
+        let payment = manifest
+            .schemas
+            .iter()
+            .find(|s| s.name == "Payment")
+            .expect("Payment schema missing");
+        let fields: Vec<(&str, bool)> = payment
+            .fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.required))
+            .collect();
+
+        // Base fields, then the properties every oneOf member shares. The
+        // bare `required` member marks the inherited created_at as required.
+        assert_eq!(
+            fields,
+            vec![
+                ("id", true),
+                ("created_at", true),
+                ("amount", true),
+                ("currency", false),
+            ]
+        );
+    }
+
     #[test]
     fn test_additional_properties_object_ref() {
         let yaml = r##"
@@ -1384,6 +3570,51 @@ components:
         );
     }
 
+    #[test]
+    fn test_integer_and_boolean_enum_params() {
+        let yaml = r#"
+openapi: "3.0.3"
+info:
+  title: Test
+  version: "1.0.0"
+paths:
+  /responses:
+    get:
+      operationId: listResponses
+      parameters:
+        - name: status_code
+          in: query
+          schema:
+            type: integer
+            enum: [200, 404, 500]
+        - name: archived
+          in: query
+          schema:
+            type: boolean
+            enum: [false]
+      responses:
+        "200":
+          description: OK
+"#;
+        let spec: OpenAPI = serde_yaml::from_str(yaml).unwrap();
+        let manifest = spec_to_manifest(&spec, "test").unwrap();
+        let params = &manifest.functions[0].parameters;
+
+        let status_code = params.iter().find(|p| p.name == "status_code").unwrap();
+        assert_eq!(status_code.param_type, ParamType::Integer);
+        assert_eq!(
+            status_code.enum_values.as_deref(),
+            Some(&["200".to_string(), "404".to_string(), "500".to_string()][..])
+        );
+
+        let archived = params.iter().find(|p| p.name == "archived").unwrap();
+        assert_eq!(archived.param_type, ParamType::Boolean);
+        assert_eq!(
+            archived.enum_values.as_deref(),
+            Some(&["false".to_string()][..])
+        );
+    }
+
     #[test]
     fn test_response_schema_204_no_content() {
         let yaml = r#"
@@ -1574,7 +3805,7 @@ components:
             .find(|f| f.name == "settings")
             .unwrap();
         match &settings_field.field_type {
-            FieldType::InlineObject { fields } => {
+            FieldType::InlineObject { fields, .. } => {
                 assert!(
                     fields.iter().any(|f| f.name == "timeout"),
                     "Missing timeout field"
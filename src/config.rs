@@ -1,8 +1,10 @@
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use serde::Deserialize;
 
+use crate::codegen::manifest::{BooleanStyle, RateLimit};
+use crate::codegen::parser::STDIN_SOURCE;
 use crate::runtime::http::{AuthCredentials, AuthCredentialsMap};
 
 /// A spec input with an optional user-chosen name.
@@ -12,8 +14,8 @@ pub struct SpecInput {
     pub source: String,
 }
 
-/// Auth entry in a TOML config file. Uses serde untagged enum.
-#[derive(Debug, Clone, Deserialize)]
+/// Auth entry in a config file. Uses serde untagged enum.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 #[serde(untagged)]
 pub enum ConfigAuth {
     Direct(String),
@@ -28,27 +30,50 @@ pub enum ConfigAuth {
     },
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 pub struct ConfigApiEntry {
     pub spec: String,
     #[serde(default)]
     pub auth: Option<ConfigAuth>,
     #[serde(default)]
     pub auth_env: Option<String>,
+    /// Path to a file holding the credential; re-read on every request.
+    #[serde(default)]
+    pub auth_file: Option<String>,
     #[serde(default)]
     pub frozen_params: Option<HashMap<String, String>>,
+    /// Credentials for individual security schemes, keyed by scheme name, for
+    /// APIs that require several schemes at once.
+    #[serde(default)]
+    pub scheme_auth: Option<HashMap<String, ConfigAuth>>,
+    /// Headers sent on every call to this API, hidden from docs.
+    #[serde(default)]
+    pub constant_headers: Option<HashMap<String, String>>,
+    /// Query params sent on every call to this API unless a call sets them.
+    #[serde(default)]
+    pub default_query: Option<HashMap<String, String>>,
+    /// Client-side pacing: `{ requests_per_second = N, burst = M }`.
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
+    /// How boolean params are written: `true_false` (default), `integer`, or `yes_no`.
+    #[serde(default)]
+    pub boolean_style: Option<BooleanStyle>,
+    /// Base URL used at serve time instead of the spec's server URL.
+    #[serde(default)]
+    pub base_url_override: Option<String>,
 }
 
 /// I/O configuration for sandboxed file access in scripts.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 pub struct IoConfig {
     pub dir: Option<String>,
     pub max_bytes: Option<u64>,
+    pub max_files: Option<usize>,
     pub enabled: Option<bool>,
 }
 
 /// Configuration for an upstream MCP server (stdio or HTTP).
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 pub struct McpServerConfigEntry {
     pub command: Option<String>,
     #[serde(default)]
@@ -56,9 +81,25 @@ pub struct McpServerConfigEntry {
     #[serde(default)]
     pub env: Option<HashMap<String, String>>,
     pub url: Option<String>,
+    /// Seconds a single tool call may take before it fails with a catchable error.
+    #[serde(default)]
+    pub call_timeout_secs: Option<u64>,
+    /// Seconds between keepalive pings (at least 1); a failed ping triggers a
+    /// reconnect.
+    #[serde(default)]
+    pub ping_interval_secs: Option<u64>,
+}
+
+/// Defaults applied to every upstream API request.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct HttpConfig {
+    pub user_agent: Option<String>,
+    /// Headers sent on every call unless the call sets one of the same name.
+    #[serde(default)]
+    pub headers: Option<HashMap<String, String>>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 pub struct ToolScriptConfig {
     #[serde(default)]
     pub apis: HashMap<String, ConfigApiEntry>,
@@ -68,13 +109,22 @@ pub struct ToolScriptConfig {
     pub io: Option<IoConfig>,
     #[serde(default)]
     pub mcp_servers: Option<HashMap<String, McpServerConfigEntry>>,
+    #[serde(default)]
+    pub http: Option<HttpConfig>,
 }
 
 /// Parses `name=source` or plain `source`.
 ///
 /// Finds the first `=` and checks whether the part before it contains `://`.
 /// If it does not, treat it as `name=source`. Otherwise, the whole string is a plain URL/path.
+/// A bare `-` reads the spec from stdin under the API name `stdin`.
 pub fn parse_spec_arg(arg: &str) -> SpecInput {
+    if arg == STDIN_SOURCE {
+        return SpecInput {
+            name: Some("stdin".to_string()),
+            source: arg.to_string(),
+        };
+    }
     arg.find('=').map_or_else(
         || SpecInput {
             name: None,
@@ -118,13 +168,31 @@ pub fn parse_auth_arg(arg: &str) -> anyhow::Result<(Option<String>, String)> {
     )
 }
 
-/// Read and parse a TOML config file.
+/// Config file names checked, in order, when no specs or `--config` are given.
+pub const CONFIG_FILE_NAMES: &[&str] = &["toolscript.toml", "toolscript.yaml", "toolscript.yml"];
+
+/// Find the first of [`CONFIG_FILE_NAMES`] that exists in `dir`.
+pub fn discover_config(dir: &Path) -> Option<PathBuf> {
+    CONFIG_FILE_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.exists())
+}
+
+/// Read and parse a config file. Files ending in `.yaml` or `.yml` are
+/// parsed as YAML, anything else as TOML.
 pub fn load_config(path: &Path) -> anyhow::Result<ToolScriptConfig> {
     let content = std::fs::read_to_string(path)
         .map_err(|e| anyhow::anyhow!("failed to read config file {}: {e}", path.display()))?;
-    let config: ToolScriptConfig = toml::from_str(&content)
-        .map_err(|e| anyhow::anyhow!("failed to parse config file {}: {e}", path.display()))?;
-    Ok(config)
+    let is_yaml = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml"));
+    let config: Result<ToolScriptConfig, String> = if is_yaml {
+        serde_yaml::from_str(&content).map_err(|e| e.to_string())
+    } else {
+        toml::from_str(&content).map_err(|e| e.to_string())
+    };
+    config.map_err(|e| anyhow::anyhow!("failed to parse config file {}: {e}", path.display()))
 }
 
 /// Merge global and per-API frozen params. Per-API values override global.
@@ -163,6 +231,8 @@ pub fn parse_mcp_arg(arg: &str) -> anyhow::Result<(String, McpServerConfigEntry)
                 args: None,
                 env: None,
                 url: Some(value.to_string()),
+                call_timeout_secs: None,
+                ping_interval_secs: None,
             },
         ))
     } else {
@@ -180,6 +250,8 @@ pub fn parse_mcp_arg(arg: &str) -> anyhow::Result<(String, McpServerConfigEntry)
                 args,
                 env: None,
                 url: None,
+                call_timeout_secs: None,
+                ping_interval_secs: None,
             },
         ))
     }
@@ -211,12 +283,42 @@ pub fn validate_mcp_server_entry(name: &str, entry: &McpServerConfigEntry) -> an
             }
         }
     }
+    if entry.ping_interval_secs == Some(0) {
+        anyhow::bail!("mcp_servers.{name}: 'ping_interval_secs' must be at least 1");
+    }
     Ok(())
 }
 
+/// Load `KEY=VALUE` pairs from a dotenv file into the process environment.
+///
+/// Variables that are already set are left untouched. The whole file is
+/// validated before anything is loaded; a malformed line is reported with its
+/// 1-based line number.
+pub fn load_env_file(path: &Path) -> anyhow::Result<()> {
+    let to_anyhow = |e: dotenvy::Error| match e {
+        dotenvy::Error::LineParse(line, _) => {
+            let line_number = std::fs::read_to_string(path)
+                .ok()
+                .and_then(|text| text.lines().position(|l| l.trim() == line.trim()))
+                .map_or_else(String::new, |i| format!(" line {}", i + 1));
+            anyhow::anyhow!(
+                "malformed env file {}{line_number}: '{line}'",
+                path.display()
+            )
+        }
+        other => anyhow::anyhow!("failed to read env file {}: {other}", path.display()),
+    };
+
+    for item in dotenvy::from_path_iter(path).map_err(to_anyhow)? {
+        item.map_err(to_anyhow)?;
+    }
+    dotenvy::from_path(path).map_err(to_anyhow)
+}
+
 /// Read env vars and build auth map from CLI `--auth` arguments.
 ///
 /// Unnamed auth only works with exactly one spec. Unknown names are errors.
+/// A name may target one security scheme of an API as `<api>.<scheme>`.
 /// Stores values as `AuthCredentials::BearerToken`.
 pub fn resolve_cli_auth(
     auth_args: &[(Option<String>, String)],
@@ -229,7 +331,8 @@ pub fn resolve_cli_auth(
             .map_err(|_| anyhow::anyhow!("environment variable '{env_var}' is not set"))?;
 
         let api_name = if let Some(n) = name {
-            if !api_names.contains(n) {
+            let api_part = n.split_once('.').map_or(n.as_str(), |(api, _)| api);
+            if !api_names.contains(n) && !api_names.iter().any(|a| a == api_part) {
                 return Err(anyhow::anyhow!(
                     "auth name '{n}' does not match any known API (known: {api_names:?})"
                 ));
@@ -254,52 +357,138 @@ pub fn resolve_cli_auth(
 ///
 /// Direct strings become `BearerToken`, Basic becomes `Basic`, `EnvRef` reads the env var.
 /// The `auth_env` field on `ConfigApiEntry` is an alternative to the `EnvRef` variant.
+/// The `auth_file` field becomes `FromFile`, which is read on each request;
+/// setting it together with `auth` or `auth_env` is an error.
 pub fn resolve_config_auth(config: &ToolScriptConfig) -> anyhow::Result<AuthCredentialsMap> {
     let mut map = AuthCredentialsMap::new();
-
     for (name, entry) in &config.apis {
-        // The `auth_env` field on ConfigApiEntry is an alternative to the EnvRef variant
-        if let Some(env_var) = &entry.auth_env {
-            let token = std::env::var(env_var).map_err(|_| {
-                anyhow::anyhow!(
-                    "environment variable '{env_var}' (from auth_env for '{name}') is not set"
-                )
-            })?;
-            map.insert(name.clone(), AuthCredentials::BearerToken(token));
-            continue;
+        resolve_api_auth(name, entry, &mut map)?;
+    }
+    Ok(map)
+}
+
+/// Resolve one API entry's credentials into `map`.
+fn resolve_api_auth(
+    name: &str,
+    entry: &ConfigApiEntry,
+    map: &mut AuthCredentialsMap,
+) -> anyhow::Result<()> {
+    if let Some(path) = &entry.auth_file {
+        if entry.auth.is_some() || entry.auth_env.is_some() {
+            anyhow::bail!(
+                "API '{name}' sets auth_file together with auth or auth_env; use only one"
+            );
         }
+        map.insert(
+            name.to_string(),
+            AuthCredentials::FromFile { path: path.into() },
+        );
+        return Ok(());
+    }
 
-        if let Some(auth) = &entry.auth {
-            match auth {
-                ConfigAuth::Direct(token) => {
-                    map.insert(name.clone(), AuthCredentials::BearerToken(token.clone()));
-                }
-                ConfigAuth::Basic {
-                    auth_type: _,
-                    username,
-                    password,
-                } => {
-                    map.insert(
-                        name.clone(),
-                        AuthCredentials::Basic {
-                            username: username.clone(),
-                            password: password.clone(),
-                        },
-                    );
-                }
-                ConfigAuth::EnvRef { auth_env } => {
-                    let token = std::env::var(auth_env).map_err(|_| {
-                        anyhow::anyhow!(
-                            "environment variable '{auth_env}' (from auth.auth_env for '{name}') is not set"
-                        )
-                    })?;
-                    map.insert(name.clone(), AuthCredentials::BearerToken(token));
-                }
-            }
+    // The `auth_env` field on ConfigApiEntry is an alternative to the EnvRef variant
+    if let Some(env_var) = &entry.auth_env {
+        let token = std::env::var(env_var).map_err(|_| {
+            anyhow::anyhow!(
+                "environment variable '{env_var}' (from auth_env for '{name}') is not set"
+            )
+        })?;
+        map.insert(name.to_string(), AuthCredentials::BearerToken(token));
+        return Ok(());
+    }
+
+    if let Some(auth) = &entry.auth {
+        map.insert(name.to_string(), config_auth_to_credentials(name, auth)?);
+    }
+
+    for (scheme, auth) in entry.scheme_auth.iter().flatten() {
+        let key = format!("{name}.{scheme}");
+        let credentials = config_auth_to_credentials(&key, auth)?;
+        map.insert(key, credentials);
+    }
+    Ok(())
+}
+
+/// Validate a config file without connecting to anything.
+///
+/// Parses the file, validates every MCP server entry, resolves every API's
+/// credentials and each `--auth` argument in `auth_args`, and loads and
+/// parses every local spec file (URL specs are not fetched). Returns every
+/// problem found, in a stable order; an empty list means the config is usable.
+pub fn check_config(path: &Path, auth_args: &[String]) -> Vec<String> {
+    let config = match load_config(path) {
+        Ok(config) => config,
+        Err(e) => return vec![e.to_string()],
+    };
+    let mut problems = Vec::new();
+
+    let mut servers: Vec<_> = config.mcp_servers.iter().flatten().collect();
+    servers.sort_by_key(|(name, _)| name.as_str());
+    for (name, entry) in servers {
+        if let Err(e) = validate_mcp_server_entry(name, entry) {
+            problems.push(e.to_string());
         }
     }
 
-    Ok(map)
+    let mut apis: Vec<_> = config.apis.iter().collect();
+    apis.sort_by_key(|(name, _)| name.as_str());
+    for (name, entry) in &apis {
+        if let Err(e) = resolve_api_auth(name, entry, &mut AuthCredentialsMap::new()) {
+            problems.push(format!("apis.{name}: {e}"));
+        }
+        if let Err(e) = check_spec(name, &entry.spec) {
+            problems.push(format!("apis.{name}: {e:#}"));
+        }
+    }
+
+    let api_names: Vec<String> = apis.iter().map(|(name, _)| (*name).clone()).collect();
+    for arg in auth_args {
+        let resolved = parse_auth_arg(arg)
+            .and_then(|parsed| resolve_cli_auth(std::slice::from_ref(&parsed), &api_names));
+        if let Err(e) = resolved {
+            problems.push(format!("--auth {arg}: {e}"));
+        }
+    }
+
+    problems
+}
+
+/// Load and parse a local spec file. URLs are accepted as-is.
+fn check_spec(name: &str, source: &str) -> anyhow::Result<()> {
+    // URLs are not fetched and stdin is left for the command that consumes it
+    if source.starts_with("http://") || source.starts_with("https://") || source == STDIN_SOURCE {
+        return Ok(());
+    }
+    let path = Path::new(source);
+    if !path.exists() {
+        anyhow::bail!("spec file '{source}' does not exist");
+    }
+    let spec = crate::codegen::parser::load_spec_from_file(path)?;
+    crate::codegen::parser::spec_to_manifest(&spec, name)?;
+    Ok(())
+}
+
+/// Convert one TOML auth entry into credentials. `name` is used in error messages.
+fn config_auth_to_credentials(name: &str, auth: &ConfigAuth) -> anyhow::Result<AuthCredentials> {
+    Ok(match auth {
+        ConfigAuth::Direct(token) => AuthCredentials::BearerToken(token.clone()),
+        ConfigAuth::Basic {
+            auth_type: _,
+            username,
+            password,
+        } => AuthCredentials::Basic {
+            username: username.clone(),
+            password: password.clone(),
+        },
+        ConfigAuth::EnvRef { auth_env } => {
+            let token = std::env::var(auth_env).map_err(|_| {
+                anyhow::anyhow!(
+                    "environment variable '{auth_env}' (from auth.auth_env for '{name}') is not set"
+                )
+            })?;
+            AuthCredentials::BearerToken(token)
+        }
+    })
 }
 
 #[cfg(test)]
@@ -356,6 +545,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_spec_arg_stdin() {
+        assert_eq!(
+            parse_spec_arg("-"),
+            SpecInput {
+                name: Some("stdin".to_string()),
+                source: "-".to_string(),
+            }
+        );
+        assert_eq!(
+            parse_spec_arg("petstore=-"),
+            SpecInput {
+                name: Some("petstore".to_string()),
+                source: "-".to_string(),
+            }
+        );
+    }
+
     #[test]
     fn test_parse_auth_arg_named() {
         let result = parse_auth_arg("petstore:MY_TOKEN").unwrap();
@@ -541,7 +748,14 @@ auth_env = "MY_API_TOKEN"
                 spec: "petstore.yaml".to_string(),
                 auth: Some(ConfigAuth::Direct("sk-direct-token".to_string())),
                 auth_env: None,
+                auth_file: None,
                 frozen_params: None,
+                scheme_auth: None,
+                constant_headers: None,
+                rate_limit: None,
+                default_query: None,
+                boolean_style: None,
+                base_url_override: None,
             },
         );
         let config = ToolScriptConfig {
@@ -549,6 +763,7 @@ auth_env = "MY_API_TOKEN"
             frozen_params: None,
             io: None,
             mcp_servers: None,
+            http: None,
         };
         let result = resolve_config_auth(&config).unwrap();
 
@@ -558,6 +773,36 @@ auth_env = "MY_API_TOKEN"
         }
     }
 
+    #[test]
+    fn test_resolve_config_auth_file_conflicts_with_auth() {
+        let mut apis = HashMap::new();
+        apis.insert(
+            "petstore".to_string(),
+            ConfigApiEntry {
+                spec: "petstore.yaml".to_string(),
+                auth: Some(ConfigAuth::Direct("sk-direct-token".to_string())),
+                auth_env: None,
+                auth_file: Some("/var/run/secrets/token".to_string()),
+                frozen_params: None,
+                scheme_auth: None,
+                constant_headers: None,
+                rate_limit: None,
+                default_query: None,
+                boolean_style: None,
+                base_url_override: None,
+            },
+        );
+        let config = ToolScriptConfig {
+            apis,
+            frozen_params: None,
+            io: None,
+            mcp_servers: None,
+            http: None,
+        };
+        let err = resolve_config_auth(&config).unwrap_err().to_string();
+        assert!(err.contains("auth_file together with auth"), "{err}");
+    }
+
     #[test]
     fn test_resolve_config_auth_basic() {
         let mut apis = HashMap::new();
@@ -571,7 +816,14 @@ auth_env = "MY_API_TOKEN"
                     password: "hunter2".to_string(),
                 }),
                 auth_env: None,
+                auth_file: None,
                 frozen_params: None,
+                scheme_auth: None,
+                constant_headers: None,
+                rate_limit: None,
+                default_query: None,
+                boolean_style: None,
+                base_url_override: None,
             },
         );
         let config = ToolScriptConfig {
@@ -579,6 +831,7 @@ auth_env = "MY_API_TOKEN"
             frozen_params: None,
             io: None,
             mcp_servers: None,
+            http: None,
         };
         let result = resolve_config_auth(&config).unwrap();
 
@@ -604,7 +857,14 @@ auth_env = "MY_API_TOKEN"
                     auth_env: "TEST_CONFIG_ENV_REF".to_string(),
                 }),
                 auth_env: None,
+                auth_file: None,
                 frozen_params: None,
+                scheme_auth: None,
+                constant_headers: None,
+                rate_limit: None,
+                default_query: None,
+                boolean_style: None,
+                base_url_override: None,
             },
         );
         let config = ToolScriptConfig {
@@ -612,6 +872,7 @@ auth_env = "MY_API_TOKEN"
             frozen_params: None,
             io: None,
             mcp_servers: None,
+            http: None,
         };
         let result = resolve_config_auth(&config).unwrap();
         unsafe { std::env::remove_var("TEST_CONFIG_ENV_REF") };
@@ -622,6 +883,193 @@ auth_env = "MY_API_TOKEN"
         }
     }
 
+    #[test]
+    fn test_resolve_config_scheme_auth() {
+        let toml_content = r#"
+[apis.billing]
+spec = "billing.yaml"
+
+[apis.billing.scheme_auth]
+apiKey = "key-123"
+bearer = "tok-456"
+"#;
+        let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+        tmpfile.write_all(toml_content.as_bytes()).unwrap();
+
+        let config = load_config(tmpfile.path()).unwrap();
+        let result = resolve_config_auth(&config).unwrap();
+        assert!(
+            matches!(&result["billing.apiKey"], AuthCredentials::BearerToken(t) if t == "key-123")
+        );
+        assert!(
+            matches!(&result["billing.bearer"], AuthCredentials::BearerToken(t) if t == "tok-456")
+        );
+        assert!(!result.contains_key("billing"));
+    }
+
+    #[test]
+    fn test_env_file_credentials_picked_up() {
+        let mut env_file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(env_file, "# credentials").unwrap();
+        writeln!(env_file, "TEST_ENV_FILE_TOKEN=from-dotenv").unwrap();
+        writeln!(env_file, "TEST_ENV_FILE_PRESET=from-dotenv").unwrap();
+        // SAFETY: test-only env manipulation; tests run serially for env vars
+        unsafe { std::env::set_var("TEST_ENV_FILE_PRESET", "already-set") };
+
+        load_env_file(env_file.path()).unwrap();
+
+        let auth = resolve_cli_auth(
+            &[(None, "TEST_ENV_FILE_TOKEN".to_string())],
+            &["petstore".to_string()],
+        )
+        .unwrap();
+        let preset = std::env::var("TEST_ENV_FILE_PRESET").unwrap();
+        unsafe {
+            std::env::remove_var("TEST_ENV_FILE_TOKEN");
+            std::env::remove_var("TEST_ENV_FILE_PRESET");
+        };
+
+        match &auth["petstore"] {
+            AuthCredentials::BearerToken(t) => assert_eq!(t, "from-dotenv"),
+            other => panic!("Expected BearerToken, got {other:?}"),
+        }
+        assert_eq!(
+            preset, "already-set",
+            "existing vars must not be overwritten"
+        );
+    }
+
+    #[test]
+    fn test_env_file_malformed_line_reports_line_number() {
+        let mut env_file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(env_file, "TEST_ENV_FILE_OK=1").unwrap();
+        writeln!(env_file, "not a valid line").unwrap();
+
+        let err = load_env_file(env_file.path()).unwrap_err().to_string();
+        assert!(err.contains("line 2"), "unexpected error: {err}");
+        assert!(
+            std::env::var("TEST_ENV_FILE_OK").is_err(),
+            "nothing should be loaded from a malformed file"
+        );
+    }
+
+    #[test]
+    fn test_load_config_yaml_matches_toml() {
+        let toml_content = r#"
+[frozen_params]
+api_version = "v2"
+
+[apis.petstore]
+spec = "petstore.yaml"
+auth_env = "PETSTORE_TOKEN"
+rate_limit = { requests_per_second = 5, burst = 10 }
+
+[apis.petstore.frozen_params]
+tenant_id = "abc-123"
+
+[apis.legacy]
+spec = "legacy.yaml"
+
+[apis.legacy.auth]
+type = "basic"
+username = "user1"
+password = "pass1"
+
+[io]
+dir = "./out"
+max_bytes = 1024
+
+[mcp_servers.filesystem]
+command = "npx"
+args = ["-y", "@modelcontextprotocol/server-filesystem"]
+"#;
+        let yaml_content = r#"
+frozen_params:
+  api_version: v2
+apis:
+  petstore:
+    spec: petstore.yaml
+    auth_env: PETSTORE_TOKEN
+    rate_limit: { requests_per_second: 5, burst: 10 }
+    frozen_params:
+      tenant_id: abc-123
+  legacy:
+    spec: legacy.yaml
+    auth:
+      type: basic
+      username: user1
+      password: pass1
+io:
+  dir: ./out
+  max_bytes: 1024
+mcp_servers:
+  filesystem:
+    command: npx
+    args: ["-y", "@modelcontextprotocol/server-filesystem"]
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let toml_path = dir.path().join("toolscript.toml");
+        std::fs::write(&toml_path, toml_content).unwrap();
+        for name in ["toolscript.yaml", "toolscript.yml"] {
+            let yaml_path = dir.path().join(name);
+            std::fs::write(&yaml_path, yaml_content).unwrap();
+            assert_eq!(
+                load_config(&yaml_path).unwrap(),
+                load_config(&toml_path).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_load_config_yaml_parse_error_names_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("toolscript.yaml");
+        std::fs::write(&path, "apis: [not, a, map]").unwrap();
+        let err = load_config(&path).unwrap_err().to_string();
+        assert!(err.contains("toolscript.yaml"), "error was: {err}");
+    }
+
+    #[test]
+    fn test_discover_config_prefers_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(discover_config(dir.path()).is_none());
+
+        std::fs::write(dir.path().join("toolscript.yaml"), "apis: {}").unwrap();
+        assert_eq!(
+            discover_config(dir.path()),
+            Some(dir.path().join("toolscript.yaml"))
+        );
+
+        std::fs::write(dir.path().join("toolscript.toml"), "").unwrap();
+        assert_eq!(
+            discover_config(dir.path()),
+            Some(dir.path().join("toolscript.toml"))
+        );
+    }
+
+    #[test]
+    fn test_load_config_with_rate_limit() {
+        let mut f = tempfile::NamedTempFile::new().unwrap();
+        writeln!(
+            f,
+            r#"
+[apis.petstore]
+spec = "petstore.yaml"
+rate_limit = {{ requests_per_second = 5, burst = 10 }}
+
+[apis.other]
+spec = "other.yaml"
+rate_limit = {{ requests_per_second = 2 }}
+"#
+        )
+        .unwrap();
+        let config = load_config(f.path()).unwrap();
+        let limit = config.apis["petstore"].rate_limit.unwrap();
+        assert_eq!(limit.requests_per_second.get(), 5);
+        assert_eq!(limit.burst.get(), 10);
+        assert_eq!(config.apis["other"].rate_limit.unwrap().burst.get(), 1);
+    }
+
     #[test]
     fn test_load_config_with_frozen_params() {
         let toml_content = r#"
@@ -645,6 +1093,58 @@ tenant_id = "abc-123"
         assert_eq!(api_frozen.get("tenant_id").unwrap(), "abc-123");
     }
 
+    #[test]
+    fn test_load_config_with_http_defaults() {
+        let toml_content = r#"
+[http]
+user_agent = "acme-bot/1.0"
+
+[http.headers]
+X-Partner = "acme"
+"#;
+        let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+        tmpfile.write_all(toml_content.as_bytes()).unwrap();
+
+        let config = load_config(tmpfile.path()).unwrap();
+        let http = config.http.unwrap();
+        assert_eq!(http.user_agent.as_deref(), Some("acme-bot/1.0"));
+        assert_eq!(http.headers.unwrap()["X-Partner"], "acme");
+    }
+
+    #[test]
+    fn test_load_config_with_constant_headers() {
+        let toml_content = r#"
+[apis.petstore]
+spec = "petstore.yaml"
+
+[apis.petstore.constant_headers]
+X-Tenant-ID = "acme"
+"#;
+        let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+        tmpfile.write_all(toml_content.as_bytes()).unwrap();
+
+        let config = load_config(tmpfile.path()).unwrap();
+        let headers = config.apis["petstore"].constant_headers.as_ref().unwrap();
+        assert_eq!(headers.get("X-Tenant-ID").unwrap(), "acme");
+    }
+
+    #[test]
+    fn test_load_config_with_default_query() {
+        let toml_content = r#"
+[apis.azure]
+spec = "azure.yaml"
+
+[apis.azure.default_query]
+api-version = "2023-01-01"
+"#;
+        let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+        tmpfile.write_all(toml_content.as_bytes()).unwrap();
+
+        let config = load_config(tmpfile.path()).unwrap();
+        let params = config.apis["azure"].default_query.as_ref().unwrap();
+        assert_eq!(params.get("api-version").unwrap(), "2023-01-01");
+    }
+
     #[test]
     fn test_load_config_without_frozen_params() {
         let toml_content = r#"
@@ -757,6 +1257,8 @@ args = ["-y", "@modelcontextprotocol/server-filesystem", "/tmp"]
             args: None,
             env: None,
             url: Some("https://example.com".to_string()),
+            call_timeout_secs: None,
+            ping_interval_secs: None,
         };
         assert!(validate_mcp_server_entry("test", &entry).is_err());
 
@@ -766,6 +1268,8 @@ args = ["-y", "@modelcontextprotocol/server-filesystem", "/tmp"]
             args: None,
             env: None,
             url: None,
+            call_timeout_secs: None,
+            ping_interval_secs: None,
         };
         assert!(validate_mcp_server_entry("test", &entry).is_err());
 
@@ -775,8 +1279,66 @@ args = ["-y", "@modelcontextprotocol/server-filesystem", "/tmp"]
             args: Some(vec!["foo".to_string()]),
             env: None,
             url: Some("https://example.com".to_string()),
+            call_timeout_secs: None,
+            ping_interval_secs: None,
         };
         assert!(validate_mcp_server_entry("test", &entry).is_err());
+
+        // zero ping interval = error
+        let entry = McpServerConfigEntry {
+            command: None,
+            args: None,
+            env: None,
+            url: Some("https://example.com".to_string()),
+            call_timeout_secs: None,
+            ping_interval_secs: Some(0),
+        };
+        let err = validate_mcp_server_entry("test", &entry).unwrap_err();
+        assert!(err.to_string().contains("ping_interval_secs"), "{err}");
+    }
+
+    #[test]
+    fn test_check_config_valid() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("toolscript.toml");
+        let spec = concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/petstore.yaml");
+        std::fs::write(
+            &path,
+            format!("[apis.petstore]\nspec = \"{spec}\"\n\n[mcp_servers.fs]\ncommand = \"npx\"\n"),
+        )
+        .unwrap();
+        assert!(check_config(&path, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_check_config_reports_every_problem() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("toolscript.toml");
+        std::fs::write(
+            &path,
+            r#"
+[apis.petstore]
+spec = "does/not/exist.yaml"
+auth_env = "TOOLSCRIPT_CHECK_TEST_UNSET_VAR"
+
+[mcp_servers.broken]
+command = "npx"
+url = "https://example.com/mcp"
+"#,
+        )
+        .unwrap();
+        let problems = check_config(&path, &["nope:SOME_VAR".to_string()]);
+        assert_eq!(problems.len(), 4, "problems were: {problems:?}");
+        assert!(problems[0].contains("broken"));
+        assert!(problems[1].contains("TOOLSCRIPT_CHECK_TEST_UNSET_VAR"));
+        assert!(problems[2].contains("does/not/exist.yaml"));
+        assert!(problems[3].contains("nope"));
+    }
+
+    #[test]
+    fn test_check_config_unreadable_file() {
+        let problems = check_config(Path::new("/nonexistent/toolscript.toml"), &[]);
+        assert_eq!(problems.len(), 1);
     }
 
     #[test]
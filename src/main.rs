@@ -1,24 +1,47 @@
-mod cli;
-
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
+use arc_swap::ArcSwap;
 use clap::Parser;
-use cli::{Cli, Command};
+use futures::FutureExt;
+use futures::future::BoxFuture;
 
-use toolscript::codegen::generate::generate;
-use toolscript::codegen::luau_types::{extract_schema_defs, json_schema_to_params};
-use toolscript::codegen::manifest::{Manifest, McpServerEntry, McpToolDef};
+use toolscript::cli::{self, Cli, Command, LogFormat};
+use toolscript::codegen::generate::{
+    DuplicateNamePolicy, FunctionNamespace, GenerateOptions, generate,
+};
+use toolscript::codegen::manifest::{
+    BooleanStyle, MANIFEST_SCHEMA_VERSION, Manifest, McpServerEntry, McpToolDef, RateLimit,
+};
+use toolscript::codegen::parser::FunctionNaming;
 use toolscript::config::{
-    McpServerConfigEntry, SpecInput, ToolScriptConfig, load_config, parse_auth_arg, parse_mcp_arg,
-    parse_spec_arg, resolve_cli_auth, resolve_config_auth, validate_mcp_server_entry,
+    McpServerConfigEntry, SpecInput, ToolScriptConfig, check_config, discover_config, load_config,
+    load_env_file, parse_auth_arg, parse_mcp_arg, parse_spec_arg, resolve_cli_auth,
+    resolve_config_auth, validate_mcp_server_entry,
 };
 use toolscript::runtime::executor::{ExecutorConfig, IoConfig};
-use toolscript::runtime::http::{AuthCredentialsMap, HttpHandler};
+use toolscript::runtime::http::{
+    AuthCredentialsMap, BodyTraceConfig, HttpClientConfig, HttpHandler,
+};
 use toolscript::runtime::mcp_client::{McpClientManager, McpServerResolvedConfig};
-use toolscript::server::ToolScriptServer;
 use toolscript::server::auth::McpAuthConfig;
+use toolscript::server::{
+    ToolScriptServer, function_list, render_function_list, render_script_context,
+};
+
+/// How `run` shapes its manifest beyond the specs and config, bundled to
+/// avoid `clippy::too_many_arguments`.
+#[derive(Clone)]
+struct RunManifestOptions {
+    duplicate_names: DuplicateNamePolicy,
+    function_namespace: FunctionNamespace,
+    function_naming: FunctionNaming,
+    only_tags: Vec<String>,
+    exclude_tags: Vec<String>,
+    api_base_urls: Vec<String>,
+}
 
 /// Bundled arguments for the `serve` function to avoid `clippy::too_many_arguments`.
 struct ServeArgs {
@@ -27,29 +50,161 @@ struct ServeArgs {
     port: u16,
     mcp_auth: Option<McpAuthConfig>,
     auth: AuthCredentialsMap,
-    timeout: u64,
-    memory_limit: usize,
-    max_api_calls: usize,
+    executor: ExecutorConfig,
+    http_client: HttpClientConfig,
+    /// Seconds GET responses are shared across executions, if enabled.
+    response_cache_ttl: Option<u64>,
+    response_cache_max_entries: usize,
+    /// Cap on upstream API calls in flight at once, if any.
+    max_concurrent_requests: Option<usize>,
     io_config: Option<IoConfig>,
     mcp_client: Arc<McpClientManager>,
+    /// Rebuilds the manifest on SIGHUP when `--reload` is set.
+    reload: Option<ManifestLoader>,
 }
 
+/// Re-reads specs/config into a fresh manifest for `--reload`.
+type ManifestLoader = Arc<dyn Fn() -> BoxFuture<'static, anyhow::Result<Manifest>> + Send + Sync>;
+
+/// Builds a server around a freshly loaded manifest for `--reload`.
+type ServerRebuild =
+    Arc<dyn Fn() -> BoxFuture<'static, anyhow::Result<ToolScriptServer>> + Send + Sync>;
+
 #[tokio::main]
 #[allow(clippy::too_many_lines)]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
+    init_logging(cli.log_format, &cli.log_level)?;
     match cli.command {
         Command::Generate {
             specs,
             output,
             config,
+            duplicate_names,
+            manifest_out,
+            continue_on_error,
+            function_prefix: function_namespace,
+            naming: function_naming,
+            strict_schema,
+        } => {
+            let (spec_inputs, config_obj) = resolve_spec_inputs(&specs, config.as_deref())?;
+            let options = GenerateOptions {
+                duplicate_names,
+                manifest_out,
+                continue_on_error,
+                function_namespace,
+                function_naming,
+                strict_schema,
+                ..config_generate_options(config_obj.as_ref())
+            };
+            generate(&spec_inputs, &output, &options).await?;
+            tracing::info!("generated output to {}", output.display());
+            Ok(())
+        }
+        Command::Inspect {
+            specs,
+            config,
+            functions_only,
+            schemas_only,
         } => {
             let (spec_inputs, config_obj) = resolve_spec_inputs(&specs, config.as_deref())?;
-            let (global_frozen, per_api_frozen) = extract_frozen_params(config_obj.as_ref());
-            generate(&spec_inputs, &output, &global_frozen, &per_api_frozen).await?;
-            eprintln!("Generated output to {}", output.display());
+            let tmpdir = tempfile::tempdir()?;
+            generate(
+                &spec_inputs,
+                tmpdir.path(),
+                &config_generate_options(config_obj.as_ref()),
+            )
+            .await?;
+            let manifest = load_manifest(tmpdir.path())?;
+            let output = if functions_only {
+                serde_json::to_string_pretty(&manifest.functions)?
+            } else if schemas_only {
+                serde_json::to_string_pretty(&manifest.schemas)?
+            } else {
+                serde_json::to_string_pretty(&manifest)?
+            };
+            println!("{output}");
+            Ok(())
+        }
+        Command::Context {
+            specs,
+            config,
+            mcp_servers: cli_mcp,
+            duplicate_names,
+            function_prefix: function_namespace,
+            naming: function_naming,
+            only_tags,
+            exclude_tags,
+            api_base_urls,
+        } => {
+            let options = RunManifestOptions {
+                duplicate_names,
+                function_namespace,
+                function_naming,
+                only_tags,
+                exclude_tags,
+                api_base_urls,
+            };
+            let manifest =
+                build_preview_manifest(&specs, config.as_deref(), &cli_mcp, &options).await?;
+            print!("{}", render_script_context(&manifest));
+            Ok(())
+        }
+        Command::List {
+            specs,
+            config,
+            mcp_servers: cli_mcp,
+            duplicate_names,
+            function_prefix: function_namespace,
+            naming: function_naming,
+            only_tags,
+            exclude_tags,
+            api_base_urls,
+            json,
+        } => {
+            let options = RunManifestOptions {
+                duplicate_names,
+                function_namespace,
+                function_naming,
+                only_tags,
+                exclude_tags,
+                api_base_urls,
+            };
+            let manifest =
+                build_preview_manifest(&specs, config.as_deref(), &cli_mcp, &options).await?;
+            if json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&function_list(&manifest))?
+                );
+            } else {
+                print!("{}", render_function_list(&manifest));
+            }
             Ok(())
         }
+        Command::Completions { shell } => {
+            cli::write_completions(shell, &mut std::io::stdout());
+            Ok(())
+        }
+        Command::Check { config, api_auth } => {
+            let path = match config {
+                Some(path) => path,
+                None => discover_config(Path::new(".")).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "no config file found (toolscript.toml, toolscript.yaml or toolscript.yml); pass --config"
+                    )
+                })?,
+            };
+            let problems = check_config(&path, &api_auth);
+            if problems.is_empty() {
+                eprintln!("{}: OK", path.display());
+                return Ok(());
+            }
+            for problem in &problems {
+                eprintln!("error: {problem}");
+            }
+            anyhow::bail!("{} has {} problem(s)", path.display(), problems.len())
+        }
         Command::Serve {
             dir,
             transport,
@@ -57,26 +212,79 @@ async fn main() -> anyhow::Result<()> {
             auth_authority,
             auth_audience,
             auth_jwks_uri,
+            auth_required_scopes,
+            auth_required_claims,
             api_auth,
+            env_file,
             timeout,
+            timeout_per_call,
             memory_limit,
             max_api_calls,
+            max_result_bytes,
+            trace_bodies,
+            max_response_log_bytes,
+            redact,
+            pool_max_idle_per_host,
+            pool_idle_timeout,
+            no_connection_reuse,
+            user_agent,
+            http_headers,
+            ca_bundle,
+            insecure,
+            api_base_urls,
+            response_cache_ttl,
+            response_cache_max_entries,
+            max_concurrent_requests,
+            reject_deprecated_params,
+            cache_gets,
+            cache_mcp_calls,
+            env_allow,
+            only_tags,
+            exclude_tags,
             io_dir,
             mcp_servers: cli_mcp,
+            reload,
         } => {
-            let mcp_auth = build_mcp_auth_config(auth_authority, auth_audience, auth_jwks_uri)?;
+            if let Some(path) = &env_file {
+                load_env_file(path)?;
+            }
+            let mcp_auth = build_mcp_auth_config(
+                auth_authority,
+                auth_audience,
+                auth_jwks_uri,
+                auth_required_scopes,
+                &auth_required_claims,
+            )?;
 
             // Resolve MCP configs (Serve has no TOML config, only CLI --mcp flags)
-            let mcp_configs = resolve_mcp_configs(None, &cli_mcp)?;
-            let (mcp_client, mcp_server_entries) = discover_mcp_tools(mcp_configs).await?;
+            let (mcp_configs, mcp_timings) = resolve_mcp_configs(None, &cli_mcp)?;
+            let (mcp_client, mcp_server_entries) =
+                discover_mcp_tools(mcp_configs, mcp_timings).await?;
 
-            let mut manifest = load_manifest(&dir)?;
-            manifest.mcp_servers = mcp_server_entries;
+            let manifest = load_serve_manifest(
+                &dir,
+                mcp_server_entries.clone(),
+                &only_tags,
+                &exclude_tags,
+                &api_base_urls,
+            )?;
+            let reload = reload.then(|| -> ManifestLoader {
+                Arc::new(move || {
+                    let manifest = load_serve_manifest(
+                        &dir,
+                        mcp_server_entries.clone(),
+                        &only_tags,
+                        &exclude_tags,
+                        &api_base_urls,
+                    );
+                    std::future::ready(manifest).boxed()
+                })
+            });
 
             if manifest.apis.is_empty() && manifest.mcp_servers.is_empty() {
                 anyhow::bail!(
                     "no APIs or MCP servers configured. \
-                     Add [apis] or [mcp_servers] to toolscript.toml, or pass specs/--mcp flags"
+                     Add [apis] or [mcp_servers] to the config file, or pass specs/--mcp flags"
                 );
             }
 
@@ -98,85 +306,149 @@ async fn main() -> anyhow::Result<()> {
                 port,
                 mcp_auth,
                 auth,
-                timeout,
-                memory_limit,
-                max_api_calls,
+                executor: build_executor_config(ExecutorFlags {
+                    timeout,
+                    timeout_per_call,
+                    memory_limit,
+                    max_api_calls,
+                    max_result_bytes,
+                    body_trace: build_body_trace_config(
+                        trace_bodies,
+                        max_response_log_bytes,
+                        redact,
+                    ),
+                    reject_deprecated_params,
+                    cache_gets,
+                    cache_mcp_calls,
+                    env_allowlist: env_allow,
+                }),
+                http_client: build_http_client_config(
+                    HttpClientFlags {
+                        pool_max_idle_per_host,
+                        pool_idle_timeout,
+                        no_connection_reuse,
+                        user_agent,
+                        http_headers,
+                        ca_bundle,
+                        insecure,
+                    },
+                    None, // no TOML config for bare serve
+                )?,
+                response_cache_ttl,
+                response_cache_max_entries,
+                max_concurrent_requests,
                 io_config,
                 mcp_client,
+                reload,
             })
             .await
         }
         Command::Run {
             specs,
             config,
+            duplicate_names,
+            function_prefix: function_namespace,
+            naming: function_naming,
             api_auth,
+            env_file,
             transport,
             port,
             auth_authority,
             auth_audience,
             auth_jwks_uri,
+            auth_required_scopes,
+            auth_required_claims,
             timeout,
+            timeout_per_call,
             memory_limit,
             max_api_calls,
+            max_result_bytes,
+            trace_bodies,
+            max_response_log_bytes,
+            redact,
+            pool_max_idle_per_host,
+            pool_idle_timeout,
+            no_connection_reuse,
+            user_agent,
+            http_headers,
+            ca_bundle,
+            insecure,
+            api_base_urls,
+            response_cache_ttl,
+            response_cache_max_entries,
+            max_concurrent_requests,
+            reject_deprecated_params,
+            cache_gets,
+            cache_mcp_calls,
+            env_allow,
+            only_tags,
+            exclude_tags,
             io_dir,
             mcp_servers: cli_mcp,
+            reload,
         } => {
-            let mcp_auth = build_mcp_auth_config(auth_authority, auth_audience, auth_jwks_uri)?;
-
-            // Resolve spec inputs. When no explicit specs or --config are given,
-            // auto-discover toolscript.toml. This allows TOML files with only
-            // [mcp_servers] (no [apis]) to work. If no TOML either, fall back
-            // to MCP-only mode when CLI --mcp flags are present.
-            let (spec_inputs, config_obj) = if specs.is_empty() && config.is_none() {
-                let default_path = Path::new("toolscript.toml");
-                if default_path.exists() {
-                    let cfg = load_config(default_path)?;
-                    let inputs: Vec<SpecInput> = cfg
-                        .apis
-                        .iter()
-                        .map(|(name, entry)| SpecInput {
-                            name: Some(name.clone()),
-                            source: entry.spec.clone(),
-                        })
-                        .collect();
-                    (inputs, Some(cfg))
-                } else if !cli_mcp.is_empty() {
-                    // No TOML, but CLI --mcp flags → MCP-only mode
-                    (vec![], None)
-                } else {
-                    anyhow::bail!(
-                        "no specs provided. Pass spec paths/URLs, use --config, or create toolscript.toml"
-                    );
-                }
-            } else {
-                resolve_run_inputs(&specs, config.as_deref())?
-            };
+            if let Some(path) = &env_file {
+                load_env_file(path)?;
+            }
+            let mcp_auth = build_mcp_auth_config(
+                auth_authority,
+                auth_audience,
+                auth_jwks_uri,
+                auth_required_scopes,
+                &auth_required_claims,
+            )?;
+
+            let (spec_inputs, config_obj) =
+                resolve_run_sources(&specs, config.as_deref(), !cli_mcp.is_empty())?;
 
             // Resolve MCP configs: merge TOML [mcp_servers] with CLI --mcp flags
-            let mcp_configs = resolve_mcp_configs(config_obj.as_ref(), &cli_mcp)?;
-            let (mcp_client, mcp_server_entries) = discover_mcp_tools(mcp_configs).await?;
-
-            // Build manifest: generate from specs if we have any, otherwise create empty
-            let manifest = if spec_inputs.is_empty() {
-                Manifest {
-                    apis: vec![],
-                    functions: vec![],
-                    schemas: vec![],
-                    mcp_servers: mcp_server_entries,
-                }
-            } else {
-                let tmpdir = tempfile::tempdir()?;
-                let (global_frozen, per_api_frozen) = extract_frozen_params(config_obj.as_ref());
-                generate(&spec_inputs, tmpdir.path(), &global_frozen, &per_api_frozen).await?;
-                let mut m = load_manifest(tmpdir.path())?;
-                m.mcp_servers = mcp_server_entries;
-                m
+            let (mcp_configs, mcp_timings) = resolve_mcp_configs(config_obj.as_ref(), &cli_mcp)?;
+            let (mcp_client, mcp_server_entries) =
+                discover_mcp_tools(mcp_configs, mcp_timings).await?;
+
+            let manifest_options = RunManifestOptions {
+                duplicate_names,
+                function_namespace,
+                function_naming,
+                only_tags,
+                exclude_tags,
+                api_base_urls,
             };
+            let manifest = build_run_manifest(
+                &spec_inputs,
+                config_obj.as_ref(),
+                mcp_server_entries.clone(),
+                &manifest_options,
+            )
+            .await?;
+            // Reload re-reads the config and re-fetches specs; upstream MCP
+            // connections are kept from startup.
+            let has_cli_mcp = !cli_mcp.is_empty();
+            let reload = reload.then(|| -> ManifestLoader {
+                Arc::new(move || {
+                    let specs = specs.clone();
+                    let config = config.clone();
+                    let mcp_server_entries = mcp_server_entries.clone();
+                    let manifest_options = manifest_options.clone();
+                    async move {
+                        let (spec_inputs, config_obj) =
+                            resolve_run_sources(&specs, config.as_deref(), has_cli_mcp)?;
+                        build_run_manifest(
+                            &spec_inputs,
+                            config_obj.as_ref(),
+                            mcp_server_entries,
+                            &manifest_options,
+                        )
+                        .await
+                    }
+                    .boxed()
+                })
+            });
 
             if manifest.apis.is_empty() && manifest.mcp_servers.is_empty() {
                 anyhow::bail!(
                     "no APIs or MCP servers configured. \
-                     Add [apis] or [mcp_servers] to toolscript.toml, or pass specs/--mcp flags"
+                     Add [apis] or [mcp_servers] to the config file, or pass specs/--mcp flags"
                 );
             }
 
@@ -204,17 +476,66 @@ async fn main() -> anyhow::Result<()> {
                 port,
                 mcp_auth,
                 auth,
-                timeout,
-                memory_limit,
-                max_api_calls,
+                executor: build_executor_config(ExecutorFlags {
+                    timeout,
+                    timeout_per_call,
+                    memory_limit,
+                    max_api_calls,
+                    max_result_bytes,
+                    body_trace: build_body_trace_config(
+                        trace_bodies,
+                        max_response_log_bytes,
+                        redact,
+                    ),
+                    reject_deprecated_params,
+                    cache_gets,
+                    cache_mcp_calls,
+                    env_allowlist: env_allow,
+                }),
+                http_client: build_http_client_config(
+                    HttpClientFlags {
+                        pool_max_idle_per_host,
+                        pool_idle_timeout,
+                        no_connection_reuse,
+                        user_agent,
+                        http_headers,
+                        ca_bundle,
+                        insecure,
+                    },
+                    config_obj.as_ref(),
+                )?,
+                response_cache_ttl,
+                response_cache_max_entries,
+                max_concurrent_requests,
                 io_config,
                 mcp_client,
+                reload,
             })
             .await
         }
     }
 }
 
+/// Install the global `tracing` subscriber, writing to stderr so the stdio
+/// transport keeps stdout to itself.
+fn init_logging(format: LogFormat, level: &str) -> anyhow::Result<()> {
+    use tracing_subscriber::EnvFilter;
+
+    let filter = match EnvFilter::try_from_default_env() {
+        Ok(filter) => filter,
+        Err(_) => EnvFilter::try_new(level)
+            .map_err(|e| anyhow::anyhow!("invalid --log-level '{level}': {e}"))?,
+    };
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr);
+    match format {
+        LogFormat::Pretty => builder.init(),
+        LogFormat::Json => builder.json().init(),
+    }
+    Ok(())
+}
+
 /// Resolve spec inputs for the Generate command from either positional args or config file.
 fn resolve_spec_inputs(
     specs: &[String],
@@ -242,7 +563,7 @@ fn resolve_spec_inputs(
 }
 
 /// Resolve spec inputs for the Run command. Also returns the config object for auth resolution.
-/// Supports auto-discovery of `toolscript.toml` when no specs or config are provided.
+/// Supports auto-discovery of `toolscript.toml` or `toolscript.yaml` when no specs or config are provided.
 fn resolve_run_inputs(
     specs: &[String],
     config_path: Option<&Path>,
@@ -263,10 +584,9 @@ fn resolve_run_inputs(
         return Ok((inputs, Some(config)));
     }
     if specs.is_empty() {
-        // Auto-discover toolscript.toml
-        let default_path = Path::new("toolscript.toml");
-        if default_path.exists() {
-            let config = load_config(default_path)?;
+        // Auto-discover toolscript.toml (or .yaml)
+        if let Some(default_path) = discover_config(Path::new(".")) {
+            let config = load_config(&default_path)?;
             let inputs: Vec<SpecInput> = config
                 .apis
                 .iter()
@@ -278,12 +598,157 @@ fn resolve_run_inputs(
             return Ok((inputs, Some(config)));
         }
         anyhow::bail!(
-            "no specs provided. Pass spec paths/URLs, use --config, or create toolscript.toml"
+            "no specs provided. Pass spec paths/URLs, use --config, or create toolscript.toml or toolscript.yaml"
         );
     }
     Ok((specs.iter().map(|s| parse_spec_arg(s)).collect(), None))
 }
 
+/// Resolve the `run` spec inputs and config. When no explicit specs or
+/// `--config` are given, auto-discover toolscript.toml (or .yaml). This allows
+/// config files with only `[mcp_servers]` (no `[apis]`) to work. If there is no
+/// config either, fall back to MCP-only mode when CLI `--mcp` flags are present.
+fn resolve_run_sources(
+    specs: &[String],
+    config_path: Option<&Path>,
+    has_cli_mcp: bool,
+) -> anyhow::Result<(Vec<SpecInput>, Option<ToolScriptConfig>)> {
+    if !specs.is_empty() || config_path.is_some() {
+        return resolve_run_inputs(specs, config_path);
+    }
+    if let Some(default_path) = discover_config(Path::new(".")) {
+        let cfg = load_config(&default_path)?;
+        let inputs: Vec<SpecInput> = cfg
+            .apis
+            .iter()
+            .map(|(name, entry)| SpecInput {
+                name: Some(name.clone()),
+                source: entry.spec.clone(),
+            })
+            .collect();
+        Ok((inputs, Some(cfg)))
+    } else if has_cli_mcp {
+        // No config file, but CLI --mcp flags → MCP-only mode
+        Ok((vec![], None))
+    } else {
+        anyhow::bail!(
+            "no specs provided. Pass spec paths/URLs, use --config, or create toolscript.toml or toolscript.yaml"
+        );
+    }
+}
+
+/// Build the `run` manifest: generate from specs if we have any, otherwise
+/// serve only the upstream MCP servers.
+async fn build_run_manifest(
+    spec_inputs: &[SpecInput],
+    config_obj: Option<&ToolScriptConfig>,
+    mcp_servers: Vec<McpServerEntry>,
+    options: &RunManifestOptions,
+) -> anyhow::Result<Manifest> {
+    let manifest = if spec_inputs.is_empty() {
+        Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
+            apis: vec![],
+            functions: vec![],
+            schemas: vec![],
+            mcp_servers,
+            generated: None,
+        }
+    } else {
+        let tmpdir = tempfile::tempdir()?;
+        let generate_options = GenerateOptions {
+            duplicate_names: options.duplicate_names,
+            function_namespace: options.function_namespace,
+            function_naming: options.function_naming,
+            ..config_generate_options(config_obj)
+        };
+        generate(spec_inputs, tmpdir.path(), &generate_options).await?;
+        let mut m = load_manifest(tmpdir.path())?;
+        m.mcp_servers = mcp_servers;
+        m.retain_tags(&options.only_tags, &options.exclude_tags);
+        apply_base_url_overrides(&mut m, &options.api_base_urls, config_obj)?;
+        m
+    };
+    manifest.check_namespaces()?;
+    Ok(manifest)
+}
+
+/// Build the manifest `run` would serve with `options`, for the `context`
+/// and `list` previews. Connects to the MCP upstreams to discover their tools.
+async fn build_preview_manifest(
+    specs: &[String],
+    config: Option<&Path>,
+    cli_mcp: &[String],
+    options: &RunManifestOptions,
+) -> anyhow::Result<Manifest> {
+    let (spec_inputs, config_obj) = resolve_run_sources(specs, config, !cli_mcp.is_empty())?;
+    let (mcp_configs, mcp_timings) = resolve_mcp_configs(config_obj.as_ref(), cli_mcp)?;
+    let (_mcp_client, mcp_server_entries) = discover_mcp_tools(mcp_configs, mcp_timings).await?;
+    build_run_manifest(
+        &spec_inputs,
+        config_obj.as_ref(),
+        mcp_server_entries,
+        options,
+    )
+    .await
+}
+
+/// Load a generated manifest directory for `serve`, attaching the discovered
+/// MCP servers and applying the tag filters and base URL overrides.
+fn load_serve_manifest(
+    dir: &Path,
+    mcp_servers: Vec<McpServerEntry>,
+    only_tags: &[String],
+    exclude_tags: &[String],
+    api_base_urls: &[String],
+) -> anyhow::Result<Manifest> {
+    let mut manifest = load_manifest(dir)?;
+    manifest.mcp_servers = mcp_servers;
+    manifest.retain_tags(only_tags, exclude_tags);
+    apply_base_url_overrides(&mut manifest, api_base_urls, None)?;
+    manifest.check_namespaces()?;
+    Ok(manifest)
+}
+
+/// Repoint APIs at new base URLs: `base_url_override` entries from the
+/// config first, then `--api-base-url name=url` flags, which win.
+fn apply_base_url_overrides(
+    manifest: &mut Manifest,
+    api_base_urls: &[String],
+    config: Option<&ToolScriptConfig>,
+) -> anyhow::Result<()> {
+    if let Some(config) = config {
+        for (name, entry) in &config.apis {
+            if let Some(base_url) = &entry.base_url_override {
+                manifest.override_base_url(name, base_url)?;
+            }
+        }
+    }
+    for arg in api_base_urls {
+        let (name, base_url) = arg
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("invalid --api-base-url '{arg}': expected name=url"))?;
+        manifest.override_base_url(name, base_url)?;
+    }
+    Ok(())
+}
+
+/// The [`GenerateOptions`] taken from a config object (if present): frozen
+/// params, constant headers, rate limits, default query params and boolean
+/// styles. Everything else keeps its default.
+fn config_generate_options(config: Option<&ToolScriptConfig>) -> GenerateOptions {
+    let (global_frozen, per_api_frozen) = extract_frozen_params(config);
+    GenerateOptions {
+        global_frozen,
+        per_api_frozen,
+        per_api_headers: extract_constant_headers(config),
+        per_api_rate_limits: extract_rate_limits(config),
+        per_api_default_query: extract_default_query(config),
+        per_api_boolean_styles: extract_boolean_styles(config),
+        ..GenerateOptions::default()
+    }
+}
+
 /// Extract global and per-API frozen params from a config object (if present).
 fn extract_frozen_params(
     config: Option<&ToolScriptConfig>,
@@ -308,6 +773,86 @@ fn extract_frozen_params(
     (global, per_api)
 }
 
+/// Build the body trace settings from `--trace-bodies`, `--max-response-log-bytes`,
+/// and `--redact`. Extra redact keys are added to the defaults.
+fn build_body_trace_config(
+    trace_bodies: bool,
+    max_bytes: usize,
+    extra_redact: Vec<String>,
+) -> Option<BodyTraceConfig> {
+    if !trace_bodies {
+        return None;
+    }
+    let mut config = BodyTraceConfig {
+        max_bytes,
+        ..BodyTraceConfig::default()
+    };
+    config.redact.extend(extra_redact);
+    Some(config)
+}
+
+/// Extract per-API constant headers from a config object (if present).
+fn extract_constant_headers(
+    config: Option<&ToolScriptConfig>,
+) -> HashMap<String, HashMap<String, String>> {
+    let Some(config) = config else {
+        return HashMap::new();
+    };
+    config
+        .apis
+        .iter()
+        .filter_map(|(name, entry)| {
+            entry
+                .constant_headers
+                .as_ref()
+                .map(|headers| (name.clone(), headers.clone()))
+        })
+        .collect()
+}
+
+/// Extract per-API default query parameters from a config object (if present).
+fn extract_default_query(
+    config: Option<&ToolScriptConfig>,
+) -> HashMap<String, HashMap<String, String>> {
+    let Some(config) = config else {
+        return HashMap::new();
+    };
+    config
+        .apis
+        .iter()
+        .filter_map(|(name, entry)| {
+            entry
+                .default_query
+                .as_ref()
+                .map(|params| (name.clone(), params.clone()))
+        })
+        .collect()
+}
+
+/// Extract per-API rate limits from a config object (if present).
+fn extract_rate_limits(config: Option<&ToolScriptConfig>) -> HashMap<String, RateLimit> {
+    let Some(config) = config else {
+        return HashMap::new();
+    };
+    config
+        .apis
+        .iter()
+        .filter_map(|(name, entry)| entry.rate_limit.map(|limit| (name.clone(), limit)))
+        .collect()
+}
+
+/// Extract per-API boolean parameter styles from a config object (if present).
+fn extract_boolean_styles(config: Option<&ToolScriptConfig>) -> HashMap<String, BooleanStyle> {
+    let Some(config) = config else {
+        return HashMap::new();
+    };
+    config
+        .apis
+        .iter()
+        .filter_map(|(name, entry)| entry.boolean_style.map(|style| (name.clone(), style)))
+        .collect()
+}
+
 /// Build the resolved I/O config from CLI flags, TOML config, and mode.
 ///
 /// In local mode (not hosted), file I/O is enabled by default with a sensible
@@ -352,17 +897,31 @@ fn resolve_io_config(
         .and_then(|o| o.max_bytes)
         .unwrap_or(50 * 1024 * 1024);
 
-    Some(IoConfig { dir, max_bytes })
+    let max_files = config
+        .and_then(|c| c.io.as_ref())
+        .and_then(|o| o.max_files)
+        .unwrap_or(1000);
+
+    Some(IoConfig {
+        dir,
+        max_bytes,
+        max_files,
+    })
 }
 
 /// Warn about APIs that declare auth in their spec but have no credentials configured.
 fn warn_missing_auth(manifest: &Manifest, auth: &AuthCredentialsMap) {
     for api in &manifest.apis {
-        if api.auth.is_some() && !auth.contains_key(&api.name) {
-            eprintln!(
-                "warning: {}: spec declares auth but no credentials configured. \
-                 API calls will likely fail with 401.",
-                api.name
+        let has_credentials = auth.contains_key(&api.name)
+            || api
+                .auth
+                .iter()
+                .any(|scheme| auth.contains_key(&format!("{}.{}", api.name, scheme.name)));
+        if !api.auth.is_empty() && !has_credentials {
+            tracing::warn!(
+                api = %api.name,
+                "spec declares auth but no credentials configured. \
+                 API calls will likely fail with 401."
             );
         }
     }
@@ -378,23 +937,137 @@ fn load_manifest(dir: &Path) -> anyhow::Result<Manifest> {
             e
         )
     })?;
-    let manifest: Manifest = serde_json::from_str(&manifest_str)?;
-    Ok(manifest)
+    Manifest::from_json(&manifest_str)
+}
+
+/// Script execution flags shared by `serve` and `run`, bundled to avoid
+/// `clippy::too_many_arguments`.
+struct ExecutorFlags {
+    timeout: u64,
+    timeout_per_call: Option<u64>,
+    memory_limit: usize,
+    max_api_calls: usize,
+    /// 0 disables the result size cap.
+    max_result_bytes: usize,
+    body_trace: Option<BodyTraceConfig>,
+    reject_deprecated_params: bool,
+    cache_gets: bool,
+    cache_mcp_calls: bool,
+    env_allowlist: Vec<String>,
+}
+
+/// Build the per-execution settings from the CLI flags. Timeouts are given
+/// in seconds and the memory limit in MB.
+fn build_executor_config(flags: ExecutorFlags) -> ExecutorConfig {
+    ExecutorConfig {
+        timeout_ms: flags.timeout.saturating_mul(1000),
+        memory_limit: Some(flags.memory_limit * 1024 * 1024),
+        max_api_calls: Some(flags.max_api_calls),
+        max_result_bytes: (flags.max_result_bytes > 0).then_some(flags.max_result_bytes),
+        body_trace: flags.body_trace,
+        reject_deprecated_params: flags.reject_deprecated_params,
+        cache_gets: flags.cache_gets,
+        cache_mcp_calls: flags.cache_mcp_calls,
+        env_allowlist: flags.env_allowlist,
+        per_call_timeout_ms: flags.timeout_per_call.map(|secs| secs.saturating_mul(1000)),
+        ..ExecutorConfig::default()
+    }
+}
+
+/// Upstream HTTP client flags shared by `serve` and `run`, bundled to avoid
+/// `clippy::too_many_arguments`.
+struct HttpClientFlags {
+    pool_max_idle_per_host: Option<usize>,
+    pool_idle_timeout: Option<u64>,
+    no_connection_reuse: bool,
+    user_agent: Option<String>,
+    http_headers: Vec<String>,
+    ca_bundle: Option<PathBuf>,
+    insecure: bool,
+}
+
+/// Build the upstream HTTP client settings. The user agent and default
+/// headers come from the `[http]` config section, with `--user-agent` and
+/// `--http-header name=value` flags winning per header.
+fn build_http_client_config(
+    flags: HttpClientFlags,
+    config: Option<&ToolScriptConfig>,
+) -> anyhow::Result<HttpClientConfig> {
+    let HttpClientFlags {
+        pool_max_idle_per_host,
+        pool_idle_timeout,
+        no_connection_reuse,
+        user_agent,
+        http_headers,
+        ca_bundle,
+        insecure,
+    } = flags;
+    let http = config.and_then(|c| c.http.as_ref());
+    let mut default_headers: Vec<(String, String)> = http
+        .and_then(|h| h.headers.as_ref())
+        .map(|headers| {
+            let mut headers: Vec<_> = headers
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+            headers.sort();
+            headers
+        })
+        .unwrap_or_default();
+    for header in &http_headers {
+        let (name, value) = header.split_once('=').ok_or_else(|| {
+            anyhow::anyhow!("invalid --http-header '{header}': expected name=value")
+        })?;
+        default_headers.retain(|(k, _)| !k.eq_ignore_ascii_case(name));
+        default_headers.push((name.to_string(), value.to_string()));
+    }
+    Ok(HttpClientConfig {
+        pool_max_idle_per_host,
+        pool_idle_timeout: pool_idle_timeout.map(Duration::from_secs),
+        reuse_connections: !no_connection_reuse,
+        user_agent: user_agent.or_else(|| http.and_then(|h| h.user_agent.clone())),
+        default_headers,
+        ca_bundle,
+        accept_invalid_certs: insecure,
+    })
 }
 
-/// Validate MCP auth CLI flags: authority and audience must both be set or both omitted.
+/// Validate MCP auth CLI flags: authority and audience must both be set or both omitted,
+/// and scope/claim requirements need both. Claims are parsed from `name=value`.
 fn build_mcp_auth_config(
     auth_authority: Option<String>,
     auth_audience: Option<String>,
     auth_jwks_uri: Option<String>,
+    required_scopes: Vec<String>,
+    required_claims: &[String],
 ) -> anyhow::Result<Option<McpAuthConfig>> {
+    let required_claims = required_claims
+        .iter()
+        .map(|claim| {
+            claim
+                .split_once('=')
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .ok_or_else(|| {
+                    anyhow::anyhow!("invalid --auth-required-claim '{claim}': expected name=value")
+                })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
     match (auth_authority, auth_audience) {
         (Some(authority), Some(audience)) => Ok(Some(McpAuthConfig {
             authority,
             audience,
             jwks_uri_override: auth_jwks_uri,
+            required_scopes,
+            required_claims,
         })),
-        (None, None) => Ok(None),
+        (None, None) => {
+            if !required_scopes.is_empty() || !required_claims.is_empty() {
+                anyhow::bail!(
+                    "--auth-required-scopes and --auth-required-claim need --auth-authority and --auth-audience"
+                );
+            }
+            Ok(None)
+        }
         _ => {
             anyhow::bail!("--auth-authority and --auth-audience must both be set (or both omitted)")
         }
@@ -403,11 +1076,12 @@ fn build_mcp_auth_config(
 
 /// Merge MCP server config from TOML `[mcp_servers]` and CLI `--mcp` flags, validate, and resolve.
 ///
-/// CLI entries override TOML entries with the same name.
+/// CLI entries override TOML entries with the same name. Also returns the
+/// per-server tool call timeouts and keepalive ping intervals.
 fn resolve_mcp_configs(
     config: Option<&ToolScriptConfig>,
     cli_mcp: &[String],
-) -> anyhow::Result<HashMap<String, McpServerResolvedConfig>> {
+) -> anyhow::Result<(HashMap<String, McpServerResolvedConfig>, McpServerTimings)> {
     let mut entries: HashMap<String, McpServerConfigEntry> = HashMap::new();
 
     // Start with config file entries
@@ -430,42 +1104,52 @@ fn resolve_mcp_configs(
 
     // Resolve to McpServerResolvedConfig
     let mut resolved = HashMap::new();
+    let mut timings = McpServerTimings::default();
     for (name, entry) in entries {
+        if let Some(secs) = entry.call_timeout_secs {
+            timings
+                .call_timeouts
+                .insert(name.clone(), Duration::from_secs(secs));
+        }
+        if let Some(secs) = entry.ping_interval_secs {
+            timings
+                .ping_intervals
+                .insert(name.clone(), Duration::from_secs(secs));
+        }
         resolved.insert(name, McpServerResolvedConfig::from_entry(&entry)?);
     }
 
-    Ok(resolved)
+    Ok((resolved, timings))
+}
+
+/// Per-server durations from `[mcp_servers]` entries, applied to the client manager.
+#[derive(Default)]
+struct McpServerTimings {
+    call_timeouts: HashMap<String, Duration>,
+    ping_intervals: HashMap<String, Duration>,
 }
 
 /// Connect to upstream MCP servers, discover their tools, and build manifest entries.
 async fn discover_mcp_tools(
     configs: HashMap<String, McpServerResolvedConfig>,
+    timings: McpServerTimings,
 ) -> anyhow::Result<(Arc<McpClientManager>, Vec<McpServerEntry>)> {
     if configs.is_empty() {
         return Ok((Arc::new(McpClientManager::empty()), vec![]));
     }
 
-    let client = McpClientManager::connect_all(configs).await?;
+    let client = McpClientManager::connect_all(configs)
+        .await?
+        .with_call_timeouts(timings.call_timeouts)
+        .with_ping_intervals(timings.ping_intervals);
     let all_tools = client.list_all_tools().await?;
 
     let mut servers = Vec::new();
     for (server_name, tools) in all_tools {
-        let mut tool_defs = Vec::new();
-        for tool in tools {
-            let input_schema = serde_json::Value::Object(tool.input_schema.as_ref().clone());
-
-            let params = json_schema_to_params(&input_schema);
-            let schemas = extract_schema_defs(&input_schema);
-
-            tool_defs.push(McpToolDef {
-                name: tool.name.to_string(),
-                server: server_name.clone(),
-                description: tool.description.map(|d| d.to_string()),
-                params,
-                schemas,
-                output_schemas: vec![],
-            });
-        }
+        let tool_defs = tools
+            .iter()
+            .map(|tool| McpToolDef::from_tool(&server_name, tool))
+            .collect();
 
         servers.push(McpServerEntry {
             name: server_name,
@@ -480,25 +1164,59 @@ async fn discover_mcp_tools(
 
 /// Create a `ToolScriptServer` from a manifest and serve it with the given transport.
 async fn serve(args: ServeArgs) -> anyhow::Result<()> {
-    let handler = Arc::new(HttpHandler::new());
-    let config = ExecutorConfig {
-        timeout_ms: args.timeout * 1000,
-        memory_limit: Some(args.memory_limit * 1024 * 1024),
-        max_api_calls: Some(args.max_api_calls),
-    };
+    if args.reload.is_some() && args.transport == "stdio" {
+        anyhow::bail!("--reload requires the http transport");
+    }
+    let config = args.executor;
     let mcp_client = args.mcp_client;
-    let server = ToolScriptServer::new(
-        args.manifest,
-        handler,
-        args.auth,
-        config,
-        args.io_config,
-        mcp_client.clone(),
-    );
+    mcp_client.spawn_keepalive();
+    let upstream_prompts = mcp_client.list_all_prompts().await;
+    // Everything but the manifest is fixed at startup; the HTTP handler is
+    // rebuilt so reloaded rate limits take effect.
+    let build = {
+        let mcp_client = mcp_client.clone();
+        Arc::new(
+            move |manifest: Manifest| -> anyhow::Result<ToolScriptServer> {
+                let rate_limits = manifest
+                    .apis
+                    .iter()
+                    .filter_map(|api| api.rate_limit.map(|limit| (api.name.clone(), limit)));
+                let mut handler =
+                    HttpHandler::new(&args.http_client)?.with_rate_limits(rate_limits);
+                if let Some(ttl) = args.response_cache_ttl {
+                    handler = handler.with_response_cache(
+                        Duration::from_secs(ttl),
+                        args.response_cache_max_entries,
+                    );
+                }
+                if let Some(max) = args.max_concurrent_requests {
+                    handler = handler.with_max_concurrent_requests(max);
+                }
+                let handler = Arc::new(handler);
+                Ok(ToolScriptServer::new(
+                    manifest,
+                    handler,
+                    args.auth.clone(),
+                    config.clone(),
+                    args.io_config.clone(),
+                    mcp_client.clone(),
+                )
+                .with_prompts(upstream_prompts.clone()))
+            },
+        )
+    };
+    let server = build(args.manifest)?;
+    let rebuild = args.reload.map(|load| -> ServerRebuild {
+        Arc::new(move || {
+            let load = load.clone();
+            let build = build.clone();
+            async move { build(load().await?) }.boxed()
+        })
+    });
 
     match args.transport.as_str() {
         "stdio" => serve_stdio(server, mcp_client).await,
-        "sse" | "http" => serve_http(server, args.port, args.mcp_auth, mcp_client).await,
+        "sse" | "http" => serve_http(server, args.port, args.mcp_auth, mcp_client, rebuild).await,
         other => anyhow::bail!("Unknown transport: '{other}'. Use 'stdio' or 'sse'."),
     }
 }
@@ -522,10 +1240,9 @@ async fn serve_http(
     port: u16,
     auth_config: Option<McpAuthConfig>,
     mcp_client: Arc<McpClientManager>,
+    rebuild: Option<ServerRebuild>,
 ) -> anyhow::Result<()> {
-    use rmcp::transport::streamable_http_server::{
-        StreamableHttpServerConfig, StreamableHttpService,
-    };
+    use rmcp::transport::streamable_http_server::StreamableHttpServerConfig;
     use tokio_util::sync::CancellationToken;
     use toolscript::server::auth::{JwtValidator, auth_middleware};
 
@@ -536,27 +1253,13 @@ async fn serve_http(
         ..Default::default()
     };
 
-    // Create an Arc<ToolScriptServer> and build tool routes that work with it.
-    let server = Arc::new(server);
-
-    let service: StreamableHttpService<
-        rmcp::handler::server::router::Router<Arc<ToolScriptServer>>,
-    > = StreamableHttpService::new(
-        {
-            let server = server.clone();
-            move || {
-                let router = rmcp::handler::server::router::Router::new(server.clone())
-                    .with_tool(toolscript::server::tools::list_apis_tool_arc())
-                    .with_tool(toolscript::server::tools::list_functions_tool_arc())
-                    .with_tool(toolscript::server::tools::get_function_docs_tool_arc())
-                    .with_tool(toolscript::server::tools::search_docs_tool_arc())
-                    .with_tool(toolscript::server::tools::execute_script_tool_arc());
-                Ok(router)
-            }
-        },
-        Arc::default(),
-        config,
-    );
+    // The server sits behind an ArcSwap so `--reload` can replace it while the
+    // listener keeps running.
+    let server = Arc::new(ArcSwap::from_pointee(server));
+    let service = toolscript::server::http_service(server.clone(), config);
+    if let Some(rebuild) = rebuild {
+        spawn_sighup_reload(server, rebuild)?;
+    }
 
     let app = if let Some(auth_config) = auth_config {
         let validator = Arc::new(JwtValidator::new(auth_config.clone()));
@@ -582,10 +1285,12 @@ async fn serve_http(
     } else {
         axum::Router::new().nest_service("/mcp", service)
     };
+    // Probes are merged after the auth layer so they never require a token
+    let app = app.merge(toolscript::server::health_router(mcp_client.clone()));
 
-    let addr = format!("0.0.0.0:{port}");
-    let listener = tokio::net::TcpListener::bind(&addr).await?;
-    eprintln!("MCP server listening on http://{addr}/mcp");
+    // Port 0 binds an ephemeral port; report the one actually chosen
+    let (listener, addr) = toolscript::server::bind_http_listener(port).await?;
+    tracing::info!("MCP server listening on http://{addr}/mcp");
 
     axum::serve(listener, app)
         .with_graceful_shutdown(async move {
@@ -597,3 +1302,109 @@ async fn serve_http(
     mcp_client.close_all().await;
     Ok(())
 }
+
+/// Reload the served manifest on every SIGHUP.
+#[cfg(unix)]
+fn spawn_sighup_reload(
+    server: Arc<ArcSwap<ToolScriptServer>>,
+    rebuild: ServerRebuild,
+) -> anyhow::Result<()> {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut hangups = signal(SignalKind::hangup())?;
+    let (tx, rx) = tokio::sync::mpsc::channel(1);
+    tokio::spawn(async move {
+        while hangups.recv().await.is_some() {
+            // A reload that is already queued picks up this change too
+            tx.try_send(()).ok();
+        }
+    });
+    tokio::spawn(toolscript::server::reload::reload_on(
+        server,
+        rx,
+        move || rebuild(),
+    ));
+    tracing::info!("reloading specs and config on SIGHUP");
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn spawn_sighup_reload(
+    _server: Arc<ArcSwap<ToolScriptServer>>,
+    _rebuild: ServerRebuild,
+) -> anyhow::Result<()> {
+    anyhow::bail!("--reload is only supported on Unix")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn executor_flags() -> ExecutorFlags {
+        ExecutorFlags {
+            timeout: 30,
+            timeout_per_call: None,
+            memory_limit: 64,
+            max_api_calls: 100,
+            max_result_bytes: 256 * 1024,
+            body_trace: None,
+            reject_deprecated_params: false,
+            cache_gets: false,
+            cache_mcp_calls: false,
+            env_allowlist: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_build_executor_config_passes_cache_gets() {
+        assert!(!build_executor_config(executor_flags()).cache_gets);
+        let config = build_executor_config(ExecutorFlags {
+            cache_gets: true,
+            ..executor_flags()
+        });
+        assert!(config.cache_gets);
+        assert_eq!(config.timeout_ms, 30_000);
+        assert_eq!(config.memory_limit, Some(64 * 1024 * 1024));
+    }
+
+    #[test]
+    fn test_build_executor_config_passes_cache_mcp_calls() {
+        assert!(!build_executor_config(executor_flags()).cache_mcp_calls);
+        let config = build_executor_config(ExecutorFlags {
+            cache_mcp_calls: true,
+            ..executor_flags()
+        });
+        assert!(config.cache_mcp_calls);
+        assert!(!config.cache_gets);
+    }
+
+    #[test]
+    fn test_build_executor_config_passes_env_allowlist() {
+        assert!(
+            build_executor_config(executor_flags())
+                .env_allowlist
+                .is_empty()
+        );
+        let config = build_executor_config(ExecutorFlags {
+            env_allowlist: vec!["REGION".to_string()],
+            ..executor_flags()
+        });
+        assert_eq!(config.env_allowlist, vec!["REGION"]);
+    }
+
+    #[test]
+    fn test_build_executor_config_passes_max_result_bytes() {
+        let config = build_executor_config(executor_flags());
+        assert_eq!(config.max_result_bytes, Some(256 * 1024));
+        let config = build_executor_config(ExecutorFlags {
+            max_result_bytes: 1024,
+            ..executor_flags()
+        });
+        assert_eq!(config.max_result_bytes, Some(1024));
+        let config = build_executor_config(ExecutorFlags {
+            max_result_bytes: 0,
+            ..executor_flags()
+        });
+        assert_eq!(config.max_result_bytes, None);
+    }
+}
@@ -1,26 +1,51 @@
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicUsize;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use mlua::{LuaSerdeExt, Value, VmState};
+use mlua::{LuaSerdeExt, Value};
 
 use crate::codegen::manifest::Manifest;
-use crate::runtime::http::{AuthCredentialsMap, HttpHandler};
+use crate::runtime::http::{
+    AuthCredentialsMap, BodyTraceConfig, CallTrace, CallWarnings, CorrelationId, HttpHandler,
+    ResponseCache,
+};
 use crate::runtime::io::{FileTouched, IoContext, register_io};
-use crate::runtime::mcp_client::McpClientManager;
-use crate::runtime::registry;
+use crate::runtime::mcp_client::{McpCallCache, McpClientManager};
+use crate::runtime::registry::{self, McpToolOptions, RegistryOptions};
 use crate::runtime::sandbox::{Sandbox, SandboxConfig};
+use crate::runtime::validate;
+
+/// Placeholder in [`IoConfig::dir`] that is replaced by a fresh id on every
+/// execution, e.g. `./out/{exec_id}`.
+pub const EXEC_ID_PLACEHOLDER: &str = "{exec_id}";
 
 /// Resolved I/O configuration for sandboxed file access.
+#[derive(Clone)]
 pub struct IoConfig {
-    /// Directory where files will be written.
+    /// Directory where files will be written. If it contains
+    /// [`EXEC_ID_PLACEHOLDER`], each execution gets its own fresh directory.
     pub dir: PathBuf,
     /// Maximum total bytes that can be written per script execution.
     pub max_bytes: u64,
+    /// Maximum number of new files a script execution may create.
+    pub max_files: usize,
+}
+
+impl IoConfig {
+    /// The I/O directory for one execution, with the placeholder replaced by `exec_id`.
+    fn dir_for(&self, exec_id: &str) -> PathBuf {
+        let dir = self.dir.to_string_lossy();
+        if dir.contains(EXEC_ID_PLACEHOLDER) {
+            PathBuf::from(dir.replace(EXEC_ID_PLACEHOLDER, exec_id))
+        } else {
+            self.dir.clone()
+        }
+    }
 }
 
 /// Configuration for the script executor.
+#[derive(Clone)]
 pub struct ExecutorConfig {
     /// Execution timeout in milliseconds. Default: 30000 (30s).
     pub timeout_ms: u64,
@@ -28,6 +53,39 @@ pub struct ExecutorConfig {
     pub memory_limit: Option<usize>,
     /// Maximum number of API calls per script execution. Default: 100.
     pub max_api_calls: Option<usize>,
+    /// Maximum number of Luau interrupt checkpoints (function calls and loop
+    /// iterations) per script execution. Default: 100 million.
+    pub max_instructions: Option<u64>,
+    /// Maximum total bytes of `print()`/`log.*()` output per script execution. Default: 1 MB.
+    pub max_log_bytes: Option<usize>,
+    /// Header carrying the per-execution correlation ID on every API call.
+    /// `None` disables correlation IDs. Default: `X-Request-Id`.
+    pub correlation_header: Option<String>,
+    /// Maximum size of a single API response body, in bytes. Default: 10 MB.
+    pub max_response_bytes: Option<usize>,
+    /// When set, every API call is recorded in the execution trace with its
+    /// redacted, truncated request and response bodies. Default: off.
+    pub body_trace: Option<BodyTraceConfig>,
+    /// Fail API calls that pass a parameter the spec marks deprecated. Default: off.
+    pub reject_deprecated_params: bool,
+    /// Approximate cap on the serialized script result, in bytes. Larger
+    /// results are truncated without changing their JSON type, shortened
+    /// containers are marked with `__truncated`, and a warning records the
+    /// original size. `None` disables the cap. Default: 256 KB.
+    pub max_result_bytes: Option<usize>,
+    /// Answer a repeated identical GET (same URL, query, and headers) within
+    /// one execution from the first response. Default: off.
+    pub cache_gets: bool,
+    /// Answer a repeated identical call to a read-only or idempotent MCP tool
+    /// (same server, tool, and arguments) within one execution from the first
+    /// successful result. Default: off.
+    pub cache_mcp_calls: bool,
+    /// Environment variables scripts may read with `os.getenv`. Any other
+    /// name reads as nil. Default: none.
+    pub env_allowlist: Vec<String>,
+    /// Timeout for a single API call in milliseconds. A call that exceeds it
+    /// raises a catchable error while the script keeps running. Default: none.
+    pub per_call_timeout_ms: Option<u64>,
 }
 
 impl Default for ExecutorConfig {
@@ -36,19 +94,51 @@ impl Default for ExecutorConfig {
             timeout_ms: 30000,
             memory_limit: Some(64 * 1024 * 1024),
             max_api_calls: Some(100),
+            max_instructions: Some(100_000_000),
+            max_log_bytes: Some(1024 * 1024),
+            correlation_header: Some("X-Request-Id".to_string()),
+            max_response_bytes: Some(10 * 1024 * 1024),
+            body_trace: None,
+            reject_deprecated_params: false,
+            max_result_bytes: Some(256 * 1024),
+            cache_gets: false,
+            cache_mcp_calls: false,
+            env_allowlist: Vec::new(),
+            per_call_timeout_ms: None,
         }
     }
 }
 
+/// Identity of the authenticated caller, exposed to scripts as the read-only
+/// `context` global.
+#[derive(Clone, Debug, Default)]
+pub struct CallerContext {
+    /// The token's `sub` claim.
+    pub subject: String,
+    /// Every claim in the validated token.
+    pub claims: serde_json::Map<String, serde_json::Value>,
+}
+
 /// Result of executing a Lua script.
 #[derive(Debug)]
 pub struct ExecutionResult {
     /// The return value of the script, serialized as JSON.
     pub result: serde_json::Value,
+    /// The return value again when the script returned a table that maps to a
+    /// JSON object, for use as MCP structured content. Array-like tables and
+    /// scalars are `None`, since structured content must be an object.
+    pub structured_content: Option<serde_json::Value>,
     /// Captured log output from `print()` calls.
     pub logs: Vec<String>,
     /// Files touched (written, appended, removed) via the `io` library during execution.
     pub files_touched: Vec<FileTouched>,
+    /// Correlation ID sent with this execution's API calls, if enabled.
+    pub correlation_id: Option<String>,
+    /// One entry per API call when body tracing is enabled, otherwise `None`.
+    pub trace: Option<Vec<serde_json::Value>>,
+    /// Warnings about the execution, such as upstream deprecation notices or
+    /// a truncated result.
+    pub warnings: Vec<String>,
 }
 
 /// Orchestrates script execution: creates sandbox, registers SDK, runs script.
@@ -85,21 +175,85 @@ impl ScriptExecutor {
     ///
     /// If `timeout_ms` is provided, it overrides the default timeout from the
     /// executor configuration for this single execution.
-    #[allow(clippy::unused_async)] // async is part of the public API contract
     pub async fn execute(
         &self,
         script: &str,
         auth: &AuthCredentialsMap,
         timeout_ms: Option<u64>,
     ) -> anyhow::Result<ExecutionResult> {
+        self.execute_with_correlation_id(script, auth, timeout_ms, None, None)
+            .await
+    }
+
+    /// Execute a Lua script, tagging its API calls with a correlation ID.
+    ///
+    /// Uses `correlation_id` when given, otherwise generates a fresh one. No
+    /// header is sent when the executor has no `correlation_header` configured.
+    ///
+    /// `caller` is the authenticated identity behind the request, if any. The
+    /// script sees it as `context.subject` and `context.claims`, both nil
+    /// when there is no caller.
+    #[allow(clippy::unused_async)] // async is part of the public API contract
+    pub async fn execute_with_correlation_id(
+        &self,
+        script: &str,
+        auth: &AuthCredentialsMap,
+        timeout_ms: Option<u64>,
+        correlation_id: Option<&str>,
+        caller: Option<&CallerContext>,
+    ) -> anyhow::Result<ExecutionResult> {
+        let correlation_id = self
+            .config
+            .correlation_header
+            .as_ref()
+            .map(|header| CorrelationId {
+                header: header.clone(),
+                value: correlation_id.map_or_else(generate_correlation_id, str::to_string),
+            });
+
+        let span = tracing::info_span!(
+            "execute",
+            script_bytes = script.len(),
+            correlation_id = correlation_id.as_ref().map(|id| id.value.as_str()),
+        );
+        let started = Instant::now();
+        let result = span
+            .in_scope(|| self.execute_in_sandbox(script, auth, timeout_ms, correlation_id, caller));
+        let duration_ms = started.elapsed().as_millis();
+        span.in_scope(|| match &result {
+            Ok(_) => tracing::info!(duration_ms, "script executed"),
+            Err(e) => tracing::warn!(duration_ms, "script failed: {e}"),
+        });
+        result
+    }
+
+    /// Run one script in a fresh sandbox with the SDK, MCP tools, and I/O registered.
+    fn execute_in_sandbox(
+        &self,
+        script: &str,
+        auth: &AuthCredentialsMap,
+        timeout_ms: Option<u64>,
+        correlation_id: Option<CorrelationId>,
+        caller: Option<&CallerContext>,
+    ) -> anyhow::Result<ExecutionResult> {
+        let trace = self.config.body_trace.clone().map(CallTrace::new);
+        let warnings = CallWarnings::default();
+
         // 1. Create fresh sandbox
         let sandbox = Sandbox::new(SandboxConfig {
             memory_limit: self.config.memory_limit,
+            max_log_bytes: self.config.max_log_bytes,
+            env_allowlist: self.config.env_allowlist.clone(),
         })?;
 
         // 2. Set up API call counter
         let api_call_counter = Arc::new(AtomicUsize::new(0));
 
+        // The script deadline also bounds rate limit waits and MCP tool calls,
+        // so compute it up front
+        let effective_timeout = timeout_ms.unwrap_or(self.config.timeout_ms);
+        let deadline = Instant::now() + std::time::Duration::from_millis(effective_timeout);
+
         // 3. Register SDK functions
         registry::register_functions(
             &sandbox,
@@ -107,7 +261,17 @@ impl ScriptExecutor {
             Arc::clone(&self.handler),
             Arc::new(auth.clone()),
             Arc::clone(&api_call_counter),
-            self.config.max_api_calls,
+            RegistryOptions {
+                max_api_calls: self.config.max_api_calls,
+                correlation_id: correlation_id.clone(),
+                max_response_bytes: self.config.max_response_bytes,
+                trace: trace.clone(),
+                reject_deprecated_params: self.config.reject_deprecated_params,
+                cache: self.config.cache_gets.then(ResponseCache::default),
+                per_call_timeout: self.config.per_call_timeout_ms.map(Duration::from_millis),
+                deadline: Some(deadline),
+                warnings: Some(warnings.clone()),
+            },
         )?;
 
         // 3a. Register MCP tools as sdk.<server>.<tool>() closures
@@ -116,38 +280,39 @@ impl ScriptExecutor {
             &self.manifest,
             Arc::clone(&self.mcp_client),
             Arc::clone(&api_call_counter),
-            self.config.max_api_calls,
+            McpToolOptions {
+                max_api_calls: self.config.max_api_calls,
+                deadline: Some(deadline),
+                cache: self.config.cache_mcp_calls.then(McpCallCache::default),
+            },
         )?;
 
         // 3c. Register sandboxed io library if I/O is configured
         let io_ctx = if let Some(ref io_config) = self.io_config {
-            let ctx = IoContext::new(io_config.dir.clone(), io_config.max_bytes);
+            let dir = io_config.dir_for(&generate_correlation_id());
+            std::fs::create_dir_all(&dir)?;
+            let ctx = IoContext::new(dir, io_config.max_bytes, io_config.max_files);
             register_io(sandbox.lua(), ctx.clone())?;
             Some(ctx)
         } else {
             None
         };
 
+        // 3d. Expose the caller's identity as the read-only `context` global
+        register_context(sandbox.lua(), caller)?;
+
+        // 3e. Expose `validate(value, "SchemaName")` over the manifest schemas
+        validate::register_validate(sandbox.lua(), &self.manifest.schemas)?;
+
         // 3b. Enable Luau sandbox mode now that all globals are set up
         sandbox.enable_sandbox()?;
 
-        // 4. Set up timeout via Luau interrupt
-        let effective_timeout = timeout_ms.unwrap_or(self.config.timeout_ms);
-        let deadline = Instant::now() + std::time::Duration::from_millis(effective_timeout);
-        sandbox.lua().set_interrupt(move |_lua| {
-            if Instant::now() >= deadline {
-                Err(mlua::Error::external(anyhow::anyhow!(
-                    "script execution timed out"
-                )))
-            } else {
-                Ok(VmState::Continue)
-            }
-        });
+        // 4. Set up timeout and instruction budget via Luau interrupt
+        sandbox.set_limits(deadline, self.config.max_instructions);
 
         // 5. Execute the script
         let script_owned = script.to_string();
-        let lua_result =
-            tokio::task::block_in_place(|| sandbox.lua().load(&script_owned).eval::<Value>());
+        let lua_result = tokio::task::block_in_place(|| run_script(sandbox.lua(), &script_owned));
 
         // 6. Collect logs
         let logs = sandbox.take_logs();
@@ -159,6 +324,20 @@ impl ScriptExecutor {
                 return Err(anyhow::anyhow!("{e}"));
             }
         };
+        let result_json = match self.config.max_result_bytes {
+            Some(max_bytes) => {
+                let (truncated, original_bytes) = truncate_result(result_json, max_bytes);
+                if let Some(original_bytes) = original_bytes {
+                    warnings.push(format!(
+                        "result truncated: {original_bytes} bytes of JSON exceeds the {max_bytes} byte limit"
+                    ));
+                }
+                truncated
+            }
+            None => result_json,
+        };
+        // Only tables convert to JSON objects
+        let structured_content = result_json.is_object().then(|| result_json.clone());
 
         let files_touched = io_ctx
             .map(|ctx| ctx.collect_final_state())
@@ -166,12 +345,215 @@ impl ScriptExecutor {
 
         Ok(ExecutionResult {
             result: result_json,
+            structured_content,
             logs,
             files_touched,
+            correlation_id: correlation_id.map(|id| id.value),
+            trace: trace.map(|t| t.take()),
+            warnings: warnings.take(),
         })
     }
 }
 
+/// Run a script chunk and return its first value.
+///
+/// The chunk runs under `pcall` so that a table raised as an error (such as an
+/// HTTP error from an SDK call) surfaces as its `tostring` message rather than
+/// an opaque table address.
+fn run_script(lua: &mlua::Lua, script: &str) -> mlua::Result<Value> {
+    let chunk = lua.load(script).into_function()?;
+    let pcall: mlua::Function = lua.globals().get("pcall")?;
+    let (ok, value): (bool, Value) = pcall.call(chunk)?;
+    if ok {
+        return Ok(value);
+    }
+    match value {
+        Value::Error(err) => Err(*err),
+        Value::String(message) => Err(mlua::Error::RuntimeError(message.to_str()?.to_string())),
+        other => {
+            let tostring: mlua::Function = lua.globals().get("tostring")?;
+            Err(mlua::Error::RuntimeError(tostring.call(other)?))
+        }
+    }
+}
+
+/// Set the `context` global to a read-only table describing the caller.
+fn register_context(lua: &mlua::Lua, caller: Option<&CallerContext>) -> anyhow::Result<()> {
+    let context = lua.create_table()?;
+    if let Some(caller) = caller {
+        context.set("subject", caller.subject.as_str())?;
+        context.set("claims", lua.to_value(&caller.claims)?)?;
+    }
+    set_readonly_deep(&context);
+    lua.globals().set("context", context)?;
+    Ok(())
+}
+
+fn set_readonly_deep(table: &mlua::Table) {
+    table.set_readonly(true);
+    for (_, value) in table.pairs::<Value, Value>().flatten() {
+        if let Value::Table(inner) = value {
+            set_readonly_deep(&inner);
+        }
+    }
+}
+
+/// Generate a random (v4) UUID, used as a correlation ID and to name
+/// per-execution I/O directories.
+fn generate_correlation_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Room left in each truncated container for its `__truncated` marker.
+const TRUNCATION_MARKER_BYTES: usize = 64;
+
+/// Cap a script result at roughly `max_bytes` of serialized JSON.
+///
+/// Results within the limit are returned unchanged with `None`. Otherwise the
+/// value is pruned depth-first, keeping its JSON type: objects keep their
+/// leading keys, arrays their leading items, and strings a prefix. Every
+/// shortened container records what was dropped (`__truncated` and
+/// `__omitted_keys` on objects, a trailing `{ __truncated, __omitted_items }`
+/// element on arrays). The original size is returned alongside.
+fn truncate_result(
+    value: serde_json::Value,
+    max_bytes: usize,
+) -> (serde_json::Value, Option<usize>) {
+    let size = JsonSize::of(&value);
+    if size.bytes <= max_bytes {
+        return (value, None);
+    }
+    let (value, _) = shrink_json(value, &size, max_bytes);
+    (value, Some(size.bytes))
+}
+
+/// Serialized size of a JSON value and of each of its children, measured in
+/// one pass so pruning never re-serializes a subtree.
+struct JsonSize {
+    bytes: usize,
+    children: Vec<Self>,
+}
+
+impl JsonSize {
+    fn of(value: &serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Object(map) => {
+                let children: Vec<Self> = map.values().map(Self::of).collect();
+                let entries: usize = map
+                    .keys()
+                    .zip(&children)
+                    .map(|(key, child)| scalar_len(key) + 1 + child.bytes)
+                    .sum();
+                Self {
+                    bytes: 2 + entries + map.len().saturating_sub(1),
+                    children,
+                }
+            }
+            serde_json::Value::Array(items) => {
+                let children: Vec<Self> = items.iter().map(Self::of).collect();
+                let entries: usize = children.iter().map(|child| child.bytes).sum();
+                Self {
+                    bytes: 2 + entries + items.len().saturating_sub(1),
+                    children,
+                }
+            }
+            other => Self {
+                bytes: scalar_len(other),
+                children: Vec::new(),
+            },
+        }
+    }
+}
+
+/// Prune `value` (measured by `size`) to roughly `budget` bytes. Returns the
+/// pruned value and an upper bound on its serialized size.
+fn shrink_json(
+    value: serde_json::Value,
+    size: &JsonSize,
+    budget: usize,
+) -> (serde_json::Value, usize) {
+    if size.bytes <= budget {
+        return (value, size.bytes);
+    }
+    let inner = budget.saturating_sub(TRUNCATION_MARKER_BYTES);
+    match value {
+        serde_json::Value::Object(map) => {
+            let total = map.len();
+            let mut out = serde_json::Map::new();
+            let mut used = 2;
+            for ((key, child), child_size) in map.into_iter().zip(&size.children) {
+                // Quotes, colon, and separating comma around the key
+                let key_bytes = key.len() + 4;
+                if used + key_bytes >= inner {
+                    break;
+                }
+                let (child, child_bytes) = shrink_json(child, child_size, inner - used - key_bytes);
+                used += key_bytes + child_bytes;
+                out.insert(key, child);
+                if used >= inner {
+                    break;
+                }
+            }
+            let omitted = total - out.len();
+            out.insert("__truncated".to_string(), serde_json::Value::Bool(true));
+            if omitted > 0 {
+                out.insert("__omitted_keys".to_string(), omitted.into());
+            }
+            (
+                serde_json::Value::Object(out),
+                used + TRUNCATION_MARKER_BYTES,
+            )
+        }
+        serde_json::Value::Array(items) => {
+            let total = items.len();
+            let mut out = Vec::new();
+            let mut used = 2;
+            for (item, item_size) in items.into_iter().zip(&size.children) {
+                let remaining = inner.saturating_sub(used);
+                if item_size.bytes < remaining {
+                    used += item_size.bytes + 1;
+                    out.push(item);
+                } else {
+                    // Keep a shortened first item rather than an empty array
+                    if out.is_empty() && remaining > 0 {
+                        let (item, item_bytes) = shrink_json(item, item_size, remaining);
+                        used += item_bytes + 1;
+                        out.push(item);
+                    }
+                    break;
+                }
+            }
+            out.push(serde_json::json!({
+                "__truncated": true,
+                "__omitted_items": total - out.len(),
+            }));
+            (
+                serde_json::Value::Array(out),
+                used + TRUNCATION_MARKER_BYTES,
+            )
+        }
+        serde_json::Value::String(text) => {
+            let mut cut = inner.min(text.len());
+            while !text.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            let text = serde_json::Value::String(format!(
+                "{}...[truncated, {} bytes total]",
+                &text[..cut],
+                text.len()
+            ));
+            let bytes = scalar_len(&text);
+            (text, bytes)
+        }
+        other => (other, size.bytes),
+    }
+}
+
+/// Serialized size of a JSON scalar or object key.
+fn scalar_len<T: serde::Serialize + ?Sized>(value: &T) -> usize {
+    serde_json::to_vec(value).map_or(0, |bytes| bytes.len())
+}
+
 /// Convert a Lua `Value` to `serde_json::Value`.
 fn lua_value_to_json(lua: &mlua::Lua, value: Value) -> anyhow::Result<serde_json::Value> {
     match value {
@@ -197,20 +579,27 @@ fn lua_value_to_json(lua: &mlua::Lua, value: Value) -> anyhow::Result<serde_json
 #[cfg(test)]
 mod tests {
     #![allow(clippy::unwrap_used, clippy::expect_used)]
+    use std::collections::BTreeMap;
     use std::sync::atomic::Ordering;
 
     use super::*;
     use crate::codegen::manifest::*;
-    use crate::runtime::http::HttpHandler;
+    use crate::runtime::http::{HttpClientConfig, HttpHandler};
 
     fn test_manifest() -> Manifest {
         Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
             apis: vec![ApiConfig {
                 name: "petstore".to_string(),
                 base_url: "https://petstore.example.com/v1".to_string(),
                 description: None,
                 version: None,
-                auth: None,
+                auth: vec![],
+                constant_headers: BTreeMap::new(),
+                rate_limit: None,
+                default_query: BTreeMap::new(),
+                deprecated: false,
+                boolean_style: None,
             }],
             functions: vec![FunctionDef {
                 name: "get_pet".to_string(),
@@ -231,21 +620,32 @@ mod tests {
                     enum_values: None,
                     format: None,
                     frozen_value: None,
+                    deprecated: false,
+                    example: None,
+                    enum_descriptions: None,
+                    boolean_style: None,
+                    multiple_of: None,
                 }],
                 request_body: None,
                 response_schema: None,
+                error_schema: None,
+                auth: None,
+                link_paginated: false,
             }],
             schemas: vec![],
             mcp_servers: vec![],
+            generated: None,
         }
     }
 
     fn empty_manifest() -> Manifest {
         Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
             apis: vec![],
             functions: vec![],
             schemas: vec![],
             mcp_servers: vec![],
+            generated: None,
         }
     }
 
@@ -264,6 +664,153 @@ mod tests {
         assert_eq!(result.result, serde_json::json!(42));
     }
 
+    /// Shared buffer a test `tracing` subscriber writes formatted events into.
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_execute_emits_info_span_with_size_and_duration() {
+        let logs = CapturedLogs::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::INFO)
+            .with_ansi(false)
+            .with_writer({
+                let logs = logs.clone();
+                move || logs.clone()
+            })
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let executor = ScriptExecutor::new(
+            empty_manifest(),
+            Arc::new(HttpHandler::mock(|_, _, _, _| Ok(serde_json::json!({})))),
+            ExecutorConfig::default(),
+            None,
+            Arc::new(McpClientManager::empty()),
+        );
+        executor
+            .execute("return 42", &AuthCredentialsMap::new(), None)
+            .await
+            .unwrap();
+
+        let output = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        assert!(output.contains("INFO"), "got: {output}");
+        assert!(output.contains("execute{script_bytes=9"), "got: {output}");
+        assert!(output.contains("script executed"), "got: {output}");
+        assert!(output.contains("duration_ms="), "got: {output}");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_execute_truncates_oversized_result() {
+        let executor = ScriptExecutor::new(
+            empty_manifest(),
+            Arc::new(HttpHandler::mock(|_, _, _, _| Ok(serde_json::json!({})))),
+            ExecutorConfig {
+                max_result_bytes: Some(1024),
+                ..ExecutorConfig::default()
+            },
+            None,
+            Arc::new(McpClientManager::empty()),
+        );
+        let auth = AuthCredentialsMap::new();
+
+        // Small results are untouched
+        let result = executor
+            .execute("return { ok = true }", &auth, None)
+            .await
+            .unwrap();
+        assert_eq!(result.result, serde_json::json!({ "ok": true }));
+        assert!(result.warnings.is_empty());
+
+        let result = executor
+            .execute(
+                r#"
+                local rows = {}
+                for i = 1, 1000 do rows[i] = { id = i, name = "row " .. i } end
+                return { count = 1000, rows = rows }
+                "#,
+                &auth,
+                None,
+            )
+            .await
+            .unwrap();
+        let json = &result.result;
+        assert_eq!(json["__truncated"], true);
+        assert_eq!(json["count"], 1000);
+        assert_eq!(result.warnings.len(), 1);
+        assert!(
+            result.warnings[0].contains("exceeds the 1024 byte limit"),
+            "{:?}",
+            result.warnings
+        );
+        assert!(serde_json::to_vec(json).unwrap().len() <= 1024);
+
+        // The array keeps its first items and notes how many were dropped
+        let rows = json["rows"].as_array().unwrap();
+        assert_eq!(rows[0]["id"], 1);
+        let marker = rows.last().unwrap();
+        assert_eq!(marker["__truncated"], true);
+        assert_eq!(marker["__omitted_items"], 1000 - (rows.len() - 1));
+        // Structured content reflects the truncated value
+        assert_eq!(result.structured_content.as_ref(), Some(json));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_execute_truncation_keeps_non_object_result_type() {
+        let executor = ScriptExecutor::new(
+            empty_manifest(),
+            Arc::new(HttpHandler::mock(|_, _, _, _| Ok(serde_json::json!({})))),
+            ExecutorConfig {
+                max_result_bytes: Some(256),
+                ..ExecutorConfig::default()
+            },
+            None,
+            Arc::new(McpClientManager::empty()),
+        );
+        let auth = AuthCredentialsMap::new();
+
+        let result = executor
+            .execute("return string.rep('x', 10000)", &auth, None)
+            .await
+            .unwrap();
+        // The result stays a string; the original size is reported as a warning
+        let text = result.result.as_str().unwrap();
+        assert!(
+            text.ends_with("...[truncated, 10000 bytes total]"),
+            "{text}"
+        );
+        assert!(text.len() <= 256, "{}", text.len());
+        assert!(
+            result.warnings[0].starts_with("result truncated: 10002 bytes"),
+            "{:?}",
+            result.warnings
+        );
+
+        // Arrays stay arrays
+        let result = executor
+            .execute(
+                "local t = {} for i = 1, 1000 do t[i] = i end return t",
+                &auth,
+                None,
+            )
+            .await
+            .unwrap();
+        let items = result.result.as_array().unwrap();
+        assert_eq!(items[0], 1);
+        assert_eq!(items.last().unwrap()["__truncated"], true);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_execute_captures_logs() {
         let executor = ScriptExecutor::new(
@@ -291,6 +838,33 @@ mod tests {
         assert_eq!(result.logs, vec!["hello", "world"]);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_execute_captures_log_library() {
+        let executor = ScriptExecutor::new(
+            empty_manifest(),
+            Arc::new(HttpHandler::mock(|_, _, _, _| Ok(serde_json::json!({})))),
+            ExecutorConfig::default(),
+            None,
+            Arc::new(McpClientManager::empty()),
+        );
+        let auth = AuthCredentialsMap::new();
+
+        let result = executor
+            .execute(
+                r#"
+                log.info("fetched", 2, "pets")
+                print("done")
+                return true
+            "#,
+                &auth,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.logs, vec!["[info] fetched\t2\tpets", "done"]);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_execute_with_sdk_calls() {
         let call_count = Arc::new(AtomicUsize::new(0));
@@ -323,6 +897,29 @@ mod tests {
         assert_eq!(result.result, serde_json::json!("Fido"));
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_execute_getenv_uses_config_allowlist() {
+        let executor = ScriptExecutor::new(
+            empty_manifest(),
+            Arc::new(HttpHandler::mock(|_, _, _, _| Ok(serde_json::json!({})))),
+            ExecutorConfig {
+                env_allowlist: vec!["CARGO_PKG_NAME".to_string()],
+                ..ExecutorConfig::default()
+            },
+            None,
+            Arc::new(McpClientManager::empty()),
+        );
+        let result = executor
+            .execute(
+                "return { os.getenv('CARGO_PKG_NAME'), os.getenv('CARGO_MANIFEST_DIR') }",
+                &AuthCredentialsMap::new(),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.result, serde_json::json!([env!("CARGO_PKG_NAME")]));
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_execute_timeout() {
         let executor = ScriptExecutor::new(
@@ -332,6 +929,17 @@ mod tests {
                 timeout_ms: 50, // very short timeout
                 memory_limit: Some(64 * 1024 * 1024),
                 max_api_calls: Some(100),
+                max_instructions: None,
+                max_log_bytes: None,
+                correlation_header: None,
+                max_response_bytes: None,
+                body_trace: None,
+                reject_deprecated_params: false,
+                max_result_bytes: None,
+                cache_gets: false,
+                cache_mcp_calls: false,
+                env_allowlist: Vec::new(),
+                per_call_timeout_ms: None,
             },
             None,
             Arc::new(McpClientManager::empty()),
@@ -348,6 +956,40 @@ mod tests {
         );
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_execute_instruction_limit() {
+        let executor = ScriptExecutor::new(
+            empty_manifest(),
+            Arc::new(HttpHandler::mock(|_, _, _, _| Ok(serde_json::json!({})))),
+            ExecutorConfig {
+                timeout_ms: 30_000,
+                max_instructions: Some(100_000),
+                ..ExecutorConfig::default()
+            },
+            None,
+            Arc::new(McpClientManager::empty()),
+        );
+        let auth = AuthCredentialsMap::new();
+
+        // A busy loop is stopped by the budget long before the 30s timeout
+        let start = Instant::now();
+        let result = executor.execute("while true do end", &auth, None).await;
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("instruction limit"), "error was: {err}");
+
+        // A normal script stays well within the budget
+        let result = executor
+            .execute(
+                "local sum = 0\nfor i = 1, 1000 do sum = sum + i end\nreturn sum",
+                &auth,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.result, serde_json::json!(500_500));
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_execute_parse_error() {
         let executor = ScriptExecutor::new(
@@ -366,46 +1008,394 @@ mod tests {
         assert!(result.is_err());
     }
 
-    #[tokio::test(flavor = "multi_thread")]
-    async fn test_execute_multiple_api_calls() {
-        let call_count = Arc::new(AtomicUsize::new(0));
-        let call_count_clone = Arc::clone(&call_count);
-
-        let executor = ScriptExecutor::new(
+    fn not_found_executor() -> ScriptExecutor {
+        ScriptExecutor::new(
             test_manifest(),
-            Arc::new(HttpHandler::mock(move |_, _, _, _| {
-                call_count_clone.fetch_add(1, Ordering::SeqCst);
-                Ok(serde_json::json!({"id": "1"}))
+            Arc::new(HttpHandler::mock(|_, _, _, _| {
+                Err(crate::runtime::http::HttpError {
+                    status: 404,
+                    reason: "Not Found".to_string(),
+                    body: r#"{"error":"no such pet"}"#.to_string(),
+                    retry_after: None,
+                }
+                .into())
             })),
             ExecutorConfig::default(),
             None,
             Arc::new(McpClientManager::empty()),
-        );
-        let auth = AuthCredentialsMap::new();
+        )
+    }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_http_error_catchable_with_pcall() {
+        let executor = not_found_executor();
         let result = executor
             .execute(
                 r#"
-                sdk.get_pet({ pet_id = "1" })
-                sdk.get_pet({ pet_id = "2" })
-                sdk.get_pet({ pet_id = "3" })
-                return "done"
+                local ok, err = pcall(sdk.get_pet, { pet_id = "missing" })
+                return { ok = ok, status = err.status, detail = err.body.error, message = tostring(err) }
             "#,
-                &auth,
+                &AuthCredentialsMap::new(),
                 None,
             )
             .await
             .unwrap();
 
-        assert_eq!(result.result, serde_json::json!("done"));
-        assert_eq!(call_count.load(Ordering::SeqCst), 3);
+        assert_eq!(result.result["ok"], false);
+        assert_eq!(result.result["status"], 404);
+        assert_eq!(result.result["detail"], "no such pet");
+        assert_eq!(
+            result.result["message"],
+            r#"HTTP 404 Not Found: {"error":"no such pet"}"#
+        );
     }
 
     #[tokio::test(flavor = "multi_thread")]
-    async fn test_execute_fresh_sandbox() {
+    async fn test_http_error_names_error_schema() {
+        let mut manifest = test_manifest();
+        manifest.functions[0].error_schema = Some("Error".to_string());
         let executor = ScriptExecutor::new(
-            empty_manifest(),
-            Arc::new(HttpHandler::mock(|_, _, _, _| Ok(serde_json::json!({})))),
+            manifest,
+            Arc::new(HttpHandler::mock(|_, _, _, _| {
+                Err(crate::runtime::http::HttpError {
+                    status: 400,
+                    reason: "Bad Request".to_string(),
+                    body: r#"{"error":"bad id"}"#.to_string(),
+                    retry_after: None,
+                }
+                .into())
+            })),
+            ExecutorConfig::default(),
+            None,
+            Arc::new(McpClientManager::empty()),
+        );
+
+        let result = executor
+            .execute(
+                r#"
+                local ok, err = pcall(sdk.get_pet, { pet_id = "1" })
+                return { ok = ok, schema = err.schema }
+            "#,
+                &AuthCredentialsMap::new(),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.result["ok"], false);
+        assert_eq!(result.result["schema"], "Error");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_rate_limited_error_carries_retry_after() {
+        let app = axum::Router::new().route(
+            "/v1/pets/{pet_id}",
+            axum::routing::get(|| async {
+                (
+                    axum::http::StatusCode::TOO_MANY_REQUESTS,
+                    [("retry-after", "30")],
+                    "slow down",
+                )
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let mut manifest = test_manifest();
+        manifest.apis[0].base_url = format!("http://{addr}/v1");
+        let executor = ScriptExecutor::new(
+            manifest,
+            Arc::new(HttpHandler::new(&HttpClientConfig::default()).unwrap()),
+            ExecutorConfig::default(),
+            None,
+            Arc::new(McpClientManager::empty()),
+        );
+
+        let result = executor
+            .execute(
+                r#"
+                local ok, err = pcall(sdk.get_pet, { pet_id = "1" })
+                return { ok = ok, status = err.status, retry_after = err.retry_after }
+            "#,
+                &AuthCredentialsMap::new(),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.result["ok"], false);
+        assert_eq!(result.result["status"], 429);
+        assert_eq!(result.result["retry_after"], 30);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_text_variant_returns_undecoded_body() {
+        let app = axum::Router::new().route(
+            "/v1/pets/{pet_id}",
+            axum::routing::get(|| async { "not json" }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let mut manifest = test_manifest();
+        manifest.apis[0].base_url = format!("http://{addr}/v1");
+        let executor = ScriptExecutor::new(
+            manifest,
+            Arc::new(HttpHandler::new(&HttpClientConfig::default()).unwrap()),
+            ExecutorConfig::default(),
+            None,
+            Arc::new(McpClientManager::empty()),
+        );
+
+        let result = executor
+            .execute(
+                r#"
+                local ok = pcall(sdk.get_pet, { pet_id = "1" })
+                return { ok = ok, text = sdk.get_pet_text({ pet_id = "1" }) }
+            "#,
+                &AuthCredentialsMap::new(),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.result["ok"], false);
+        assert_eq!(result.result["text"], "not json");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_accept_option_negotiates_csv_as_text() {
+        let app = axum::Router::new().route(
+            "/v1/pets/{pet_id}",
+            axum::routing::get(|headers: axum::http::HeaderMap| async move {
+                use axum::response::IntoResponse;
+                if headers.get("accept").is_some_and(|v| v == "text/csv") {
+                    ([("content-type", "text/csv")], "id,name\n1,Fido\n").into_response()
+                } else {
+                    axum::Json(serde_json::json!({ "id": "1", "name": "Fido" })).into_response()
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let mut manifest = test_manifest();
+        manifest.apis[0].base_url = format!("http://{addr}/v1");
+        let executor = ScriptExecutor::new(
+            manifest,
+            Arc::new(HttpHandler::new(&HttpClientConfig::default()).unwrap()),
+            ExecutorConfig::default(),
+            None,
+            Arc::new(McpClientManager::empty()),
+        );
+
+        let result = executor
+            .execute(
+                r#"
+                local csv = sdk.get_pet({ pet_id = "1" }, { accept = "text/csv" })
+                local pet = sdk.get_pet({ pet_id = "1" })
+                return { csv = csv, name = pet.name }
+            "#,
+                &AuthCredentialsMap::new(),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.result["csv"], "id,name\n1,Fido\n");
+        assert_eq!(result.result["name"], "Fido");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_per_call_timeout_fails_only_the_slow_call() {
+        let app = axum::Router::new().route(
+            "/v1/pets/{pet_id}",
+            axum::routing::get(
+                |axum::extract::Path(pet_id): axum::extract::Path<String>| async move {
+                    if pet_id == "slow" {
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                    }
+                    axum::Json(serde_json::json!({ "id": pet_id }))
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let mut manifest = test_manifest();
+        manifest.apis[0].base_url = format!("http://{addr}/v1");
+        let executor = ScriptExecutor::new(
+            manifest,
+            Arc::new(HttpHandler::new(&HttpClientConfig::default()).unwrap()),
+            ExecutorConfig {
+                per_call_timeout_ms: Some(200),
+                ..ExecutorConfig::default()
+            },
+            None,
+            Arc::new(McpClientManager::empty()),
+        );
+
+        let result = executor
+            .execute(
+                r#"
+                local ok, err = pcall(sdk.get_pet, { pet_id = "slow" })
+                local pet = sdk.get_pet({ pet_id = "fast" })
+                return { ok = ok, err = tostring(err), id = pet.id }
+            "#,
+                &AuthCredentialsMap::new(),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.result["ok"], false);
+        assert!(
+            result.result["err"]
+                .as_str()
+                .unwrap()
+                .contains("request timed out after 200ms"),
+            "error was: {}",
+            result.result["err"]
+        );
+        assert_eq!(result.result["id"], "fast");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_response_cache_shared_across_executions() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let hits_clone = Arc::clone(&hits);
+        let handler = HttpHandler::mock(move |_method, _url, _query, _body| {
+            hits_clone.fetch_add(1, Ordering::SeqCst);
+            Ok(serde_json::json!({ "id": "1", "name": "Fido" }))
+        })
+        .with_response_cache(Duration::from_millis(200), 100);
+        let executor = ScriptExecutor::new(
+            test_manifest(),
+            Arc::new(handler),
+            ExecutorConfig::default(),
+            None,
+            Arc::new(McpClientManager::empty()),
+        );
+        let auth = AuthCredentialsMap::new();
+        let run = || executor.execute(r#"return sdk.get_pet({ pet_id = "1" }).name"#, &auth, None);
+
+        assert_eq!(run().await.unwrap().result, "Fido");
+        assert_eq!(run().await.unwrap().result, "Fido");
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        // Once the entry expires the next execution refetches
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(run().await.unwrap().result, "Fido");
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_sunset_header_adds_deprecation_warning() {
+        let app = axum::Router::new().route(
+            "/v1/pets/{pet_id}",
+            axum::routing::get(|| async {
+                (
+                    [("Sunset", "Sat, 01 Jan 2028 00:00:00 GMT")],
+                    axum::Json(serde_json::json!({ "id": "1" })),
+                )
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let mut manifest = test_manifest();
+        manifest.apis[0].base_url = format!("http://{addr}/v1");
+        let executor = ScriptExecutor::new(
+            manifest,
+            Arc::new(HttpHandler::new(&HttpClientConfig::default()).unwrap()),
+            ExecutorConfig::default(),
+            None,
+            Arc::new(McpClientManager::empty()),
+        );
+
+        let result = executor
+            .execute(
+                r#"
+                sdk.get_pet({ pet_id = "1" })
+                return sdk.get_pet({ pet_id = "1" }).id
+            "#,
+                &AuthCredentialsMap::new(),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.result, "1");
+        assert_eq!(
+            result.warnings,
+            vec![
+                "sdk.get_pet: endpoint is deprecated and sunsets Sat, 01 Jan 2028 00:00:00 GMT"
+                    .to_string()
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_uncaught_http_error_reports_message() {
+        let executor = not_found_executor();
+        let err = executor
+            .execute(
+                r#"return sdk.get_pet({ pet_id = "missing" })"#,
+                &AuthCredentialsMap::new(),
+                None,
+            )
+            .await
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("HTTP 404 Not Found"), "error was: {err}");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_execute_multiple_api_calls() {
+        let call_count = Arc::new(AtomicUsize::new(0));
+        let call_count_clone = Arc::clone(&call_count);
+
+        let executor = ScriptExecutor::new(
+            test_manifest(),
+            Arc::new(HttpHandler::mock(move |_, _, _, _| {
+                call_count_clone.fetch_add(1, Ordering::SeqCst);
+                Ok(serde_json::json!({"id": "1"}))
+            })),
+            ExecutorConfig::default(),
+            None,
+            Arc::new(McpClientManager::empty()),
+        );
+        let auth = AuthCredentialsMap::new();
+
+        let result = executor
+            .execute(
+                r#"
+                sdk.get_pet({ pet_id = "1" })
+                sdk.get_pet({ pet_id = "2" })
+                sdk.get_pet({ pet_id = "3" })
+                return "done"
+            "#,
+                &auth,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.result, serde_json::json!("done"));
+        assert_eq!(call_count.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_execute_fresh_sandbox() {
+        let executor = ScriptExecutor::new(
+            empty_manifest(),
+            Arc::new(HttpHandler::mock(|_, _, _, _| Ok(serde_json::json!({})))),
             ExecutorConfig::default(),
             None,
             Arc::new(McpClientManager::empty()),
@@ -439,6 +1429,7 @@ mod tests {
             Some(IoConfig {
                 dir: output_dir.path().to_path_buf(),
                 max_bytes: 50 * 1024 * 1024,
+                max_files: 1000,
             }),
             Arc::new(McpClientManager::empty()),
         );
@@ -467,6 +1458,41 @@ mod tests {
         assert_eq!(content, r#"{"hello":"world"}"#);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_execute_io_dir_per_execution() {
+        let output_dir = tempfile::tempdir().unwrap();
+        let executor = ScriptExecutor::new(
+            empty_manifest(),
+            Arc::new(HttpHandler::mock(|_, _, _, _| Ok(serde_json::json!({})))),
+            ExecutorConfig::default(),
+            Some(IoConfig {
+                dir: output_dir.path().join("runs").join(EXEC_ID_PLACEHOLDER),
+                max_bytes: 50 * 1024 * 1024,
+                max_files: 1000,
+            }),
+            Arc::new(McpClientManager::empty()),
+        );
+        let auth = AuthCredentialsMap::new();
+        let script = r#"
+            io.write_file("out.txt", "data")
+            return #io.list()
+        "#;
+
+        let first = executor.execute(script, &auth, None).await.unwrap();
+        let second = executor.execute(script, &auth, None).await.unwrap();
+
+        // Each execution starts from an empty directory of its own
+        assert_eq!(first.result, serde_json::json!(1));
+        assert_eq!(second.result, serde_json::json!(1));
+        assert_eq!(first.files_touched[0].name, "out.txt");
+        assert_eq!(second.files_touched[0].name, "out.txt");
+        let first_path = PathBuf::from(&first.files_touched[0].path);
+        let second_path = PathBuf::from(&second.files_touched[0].path);
+        assert_ne!(first_path.parent(), second_path.parent());
+        assert!(first_path.starts_with(output_dir.path().join("runs")));
+        assert!(first_path.exists() && second_path.exists());
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_execute_no_io_when_disabled() {
         let executor = ScriptExecutor::new(
@@ -485,4 +1511,215 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_execute_exposes_caller_context() {
+        let executor = ScriptExecutor::new(
+            test_manifest(),
+            Arc::new(HttpHandler::mock(|_, _, _, _| Ok(serde_json::json!({})))),
+            ExecutorConfig::default(),
+            None,
+            Arc::new(McpClientManager::empty()),
+        );
+        let auth = AuthCredentialsMap::new();
+        let script = "return { subject = context.subject, tenant = context.claims and context.claims.tenant }";
+
+        let mut claims = serde_json::Map::new();
+        claims.insert("tenant".to_string(), serde_json::json!("acme"));
+        let caller = CallerContext {
+            subject: "user-123".to_string(),
+            claims,
+        };
+        let result = executor
+            .execute_with_correlation_id(script, &auth, None, None, Some(&caller))
+            .await
+            .unwrap();
+        assert_eq!(
+            result.result,
+            serde_json::json!({"subject": "user-123", "tenant": "acme"})
+        );
+
+        let result = executor
+            .execute(
+                "return { is_nil = context.subject == nil and context.claims == nil }",
+                &auth,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.result, serde_json::json!({"is_nil": true}));
+
+        let err = executor
+            .execute_with_correlation_id(
+                "context.claims.tenant = 'evil'",
+                &auth,
+                None,
+                None,
+                Some(&caller),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("readonly"), "error was: {err}");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_execute_sends_correlation_id_header() {
+        let mut manifest = test_manifest();
+        manifest.functions[0].parameters.push(ParamDef {
+            name: "X-Request-Id".to_string(),
+            location: ParamLocation::Header,
+            param_type: ParamType::String,
+            required: false,
+            description: None,
+            default: None,
+            enum_values: None,
+            format: None,
+            frozen_value: None,
+            deprecated: false,
+            example: None,
+            enum_descriptions: None,
+            boolean_style: None,
+            multiple_of: None,
+        });
+
+        let captured = Arc::new(std::sync::Mutex::new(Vec::<Vec<(String, String)>>::new()));
+        let captured_clone = Arc::clone(&captured);
+        let handler =
+            HttpHandler::mock_with_headers(move |_method, _url, _query, headers, _body| {
+                captured_clone.lock().unwrap().push(headers.to_vec());
+                Ok(serde_json::json!({"id": "1"}))
+            });
+        let executor = ScriptExecutor::new(
+            manifest,
+            Arc::new(handler),
+            ExecutorConfig::default(),
+            None,
+            Arc::new(McpClientManager::empty()),
+        );
+        let auth = AuthCredentialsMap::new();
+
+        let result = executor
+            .execute_with_correlation_id(
+                r#"
+                sdk.get_pet({ pet_id = "1" })
+                sdk.get_pet({ pet_id = "2" })
+                sdk.get_pet({ pet_id = "3", ["X-Request-Id"] = "explicit" })
+                "#,
+                &auth,
+                None,
+                Some("corr-123"),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.correlation_id.as_deref(), Some("corr-123"));
+
+        let request_ids: Vec<Vec<String>> = captured
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|headers| {
+                headers
+                    .iter()
+                    .filter(|(k, _)| k == "X-Request-Id")
+                    .map(|(_, v)| v.clone())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            request_ids,
+            vec![vec!["corr-123"], vec!["corr-123"], vec!["explicit"]]
+        );
+
+        // Without an explicit ID, a fresh one is generated per execution.
+        let first = executor.execute("return 1", &auth, None).await.unwrap();
+        let second = executor.execute("return 1", &auth, None).await.unwrap();
+        let first_id = first.correlation_id.unwrap();
+        let uuid = uuid::Uuid::parse_str(&first_id).unwrap();
+        assert_eq!(uuid.get_version(), Some(uuid::Version::Random));
+        assert_ne!(Some(first_id), second.correlation_id);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_execute_response_size_limit() {
+        let handler = HttpHandler::mock(|_, _, _, _| {
+            Ok(serde_json::json!({"id": "1", "notes": "x".repeat(500)}))
+        });
+        let executor = ScriptExecutor::new(
+            test_manifest(),
+            Arc::new(handler),
+            ExecutorConfig {
+                max_response_bytes: Some(64),
+                ..ExecutorConfig::default()
+            },
+            None,
+            Arc::new(McpClientManager::empty()),
+        );
+
+        let err = executor
+            .execute(
+                r#"return sdk.get_pet({ pet_id = "1" })"#,
+                &AuthCredentialsMap::new(),
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("response exceeded 64 bytes"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_execute_body_trace() {
+        let mut manifest = test_manifest();
+        manifest.functions[0].method = HttpMethod::Post;
+        manifest.functions[0].request_body = Some(RequestBodyDef {
+            content_type: "application/json".to_string(),
+            schema: "Login".to_string(),
+            required: true,
+            description: None,
+        });
+        let handler = HttpHandler::mock(|_, _, _, _| {
+            Ok(serde_json::json!({"access_token": "sk-live", "notes": "x".repeat(200)}))
+        });
+        let executor = ScriptExecutor::new(
+            manifest,
+            Arc::new(handler),
+            ExecutorConfig {
+                body_trace: Some(BodyTraceConfig {
+                    max_bytes: 64,
+                    ..BodyTraceConfig::default()
+                }),
+                ..ExecutorConfig::default()
+            },
+            None,
+            Arc::new(McpClientManager::empty()),
+        );
+
+        let result = executor
+            .execute(
+                r#"sdk.get_pet({ pet_id = "1" }, { user = "fido", password = "hunter2" })"#,
+                &AuthCredentialsMap::new(),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let trace = result.trace.unwrap();
+        assert_eq!(trace.len(), 1);
+        assert_eq!(trace[0]["method"], "POST");
+        let request_body = trace[0]["request_body"].as_str().unwrap();
+        assert!(
+            request_body.contains(r#""password":"***""#),
+            "{request_body}"
+        );
+        assert!(!request_body.contains("hunter2"));
+        let response_body = trace[0]["response_body"].as_str().unwrap();
+        assert!(
+            response_body.contains(r#""access_token":"***""#),
+            "{response_body}"
+        );
+        assert!(response_body.contains("...[truncated,"), "{response_body}");
+    }
 }
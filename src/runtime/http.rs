@@ -1,20 +1,546 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::fmt::{self, Write as _};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use crate::codegen::manifest::AuthConfig;
+use base64::Engine as _;
+
+use crate::codegen::manifest::{ApiKeyLocation, AuthConfig, RateLimit};
 
 /// Authentication credentials for a single API.
-#[derive(Clone, Debug)]
+///
+/// `Debug` and `Display` mask the secret as `***`, so credentials can be
+/// logged or embedded in errors without leaking it.
+#[derive(Clone)]
 pub enum AuthCredentials {
     BearerToken(String),
     ApiKey(String),
-    Basic { username: String, password: String },
+    Basic {
+        username: String,
+        password: String,
+    },
+    /// Secret re-read from a file on every request, so rotated tokens apply without
+    /// a restart. Interpreted according to the API's auth scheme (`user:pass` for Basic).
+    FromFile {
+        path: PathBuf,
+    },
     None,
 }
 
+/// Placeholder shown in place of a secret.
+const REDACTED: &str = "***";
+
+impl fmt::Debug for AuthCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BearerToken(_) => f.debug_tuple("BearerToken").field(&REDACTED).finish(),
+            Self::ApiKey(_) => f.debug_tuple("ApiKey").field(&REDACTED).finish(),
+            Self::Basic { username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .field("password", &REDACTED)
+                .finish(),
+            Self::FromFile { path } => f.debug_struct("FromFile").field("path", path).finish(),
+            Self::None => f.write_str("None"),
+        }
+    }
+}
+
+impl fmt::Display for AuthCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BearerToken(_) => write!(f, "Bearer {REDACTED}"),
+            Self::ApiKey(_) => write!(f, "API key {REDACTED}"),
+            Self::Basic { username, .. } => write!(f, "Basic {username}:{REDACTED}"),
+            Self::FromFile { path } => write!(f, "credential file '{}'", path.display()),
+            Self::None => f.write_str("no credentials"),
+        }
+    }
+}
+
 /// Map from API name to its credentials.
 pub type AuthCredentialsMap = HashMap<String, AuthCredentials>;
 
+/// Correlation ID sent as a header on every API call made during one script execution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CorrelationId {
+    /// Header name, e.g. `X-Request-Id`.
+    pub header: String,
+    /// Header value shared by all calls in the execution.
+    pub value: String,
+}
+
+/// Key fragments redacted from traced headers and JSON bodies by default.
+pub const DEFAULT_REDACT_KEYS: &[&str] = &[
+    "password",
+    "token",
+    "secret",
+    "authorization",
+    "api_key",
+    "api-key",
+    "apikey",
+    "cookie",
+];
+
+/// Body of an API request.
+#[derive(Clone, Debug)]
+pub enum RequestBody {
+    /// Serialized as JSON and sent with `Content-Type: application/json`.
+    Json(serde_json::Value),
+    /// Sent verbatim with the operation's own content type (e.g. `text/plain`).
+    Raw { content_type: String, text: String },
+}
+
+impl RequestBody {
+    /// JSON view of the body, as passed to mock handlers and the call trace.
+    /// Raw bodies become a JSON string.
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            Self::Json(value) => value.clone(),
+            Self::Raw { text, .. } => serde_json::Value::String(text.clone()),
+        }
+    }
+}
+
+/// A non-2xx response from an upstream API. Scripts receive it as a
+/// catchable `{ status, body, message, retry_after? }` error table.
+#[derive(Debug, thiserror::Error)]
+#[error("HTTP {status} {reason}: {body}")]
+pub struct HttpError {
+    pub status: u16,
+    pub reason: String,
+    pub body: String,
+    /// Seconds to wait before retrying, from the response's `Retry-After`.
+    pub retry_after: Option<u64>,
+}
+
+/// Connection pool and default header settings for the real HTTP client.
+#[derive(Clone, Debug)]
+pub struct HttpClientConfig {
+    /// Maximum idle connections kept open per host. `None` uses the reqwest default.
+    pub pool_max_idle_per_host: Option<usize>,
+    /// How long an idle pooled connection stays open. `None` uses the reqwest default (90s).
+    pub pool_idle_timeout: Option<Duration>,
+    /// Keep connections alive for reuse across requests. When false, no idle
+    /// connections are pooled and every request opens a new connection.
+    pub reuse_connections: bool,
+    /// `User-Agent` sent on every request. `None` sends reqwest's default (none).
+    pub user_agent: Option<String>,
+    /// Headers sent on every request unless the call sets a header of the same name.
+    pub default_headers: Vec<(String, String)>,
+    /// PEM file of extra root certificates to trust, e.g. an internal CA.
+    pub ca_bundle: Option<PathBuf>,
+    /// Skip TLS certificate verification entirely. For testing only.
+    pub accept_invalid_certs: bool,
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        Self {
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: None,
+            reuse_connections: true,
+            user_agent: None,
+            default_headers: Vec::new(),
+            ca_bundle: None,
+            accept_invalid_certs: false,
+        }
+    }
+}
+
+/// Settings for including request/response bodies in the per-call trace.
+#[derive(Clone, Debug)]
+pub struct BodyTraceConfig {
+    /// Maximum bytes of each traced body; longer bodies are truncated.
+    pub max_bytes: usize,
+    /// Header names and JSON keys containing any of these fragments
+    /// (case-insensitive) have their values replaced with `***`.
+    pub redact: Vec<String>,
+}
+
+impl Default for BodyTraceConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: 4096,
+            redact: DEFAULT_REDACT_KEYS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        }
+    }
+}
+
+impl BodyTraceConfig {
+    fn is_redacted(&self, key: &str) -> bool {
+        let key = key.to_ascii_lowercase();
+        self.redact
+            .iter()
+            .any(|fragment| key.contains(&fragment.to_ascii_lowercase()))
+    }
+
+    /// Mask the values of sensitive headers.
+    pub fn redact_headers(&self, headers: &[(String, String)]) -> Vec<(String, String)> {
+        headers
+            .iter()
+            .map(|(name, value)| {
+                let value = if self.is_redacted(name) {
+                    "***".to_string()
+                } else {
+                    value.clone()
+                };
+                (name.clone(), value)
+            })
+            .collect()
+    }
+
+    /// Mask sensitive keys at any depth of a JSON value.
+    pub fn redact_json(&self, value: &serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::Object(map) => map
+                .iter()
+                .map(|(k, v)| {
+                    let v = if self.is_redacted(k) {
+                        serde_json::Value::String("***".to_string())
+                    } else {
+                        self.redact_json(v)
+                    };
+                    (k.clone(), v)
+                })
+                .collect(),
+            serde_json::Value::Array(items) => items.iter().map(|v| self.redact_json(v)).collect(),
+            other => other.clone(),
+        }
+    }
+
+    /// Redact and serialize a body, truncating it to `max_bytes`.
+    pub fn trace_body(&self, value: &serde_json::Value) -> String {
+        let text = serde_json::to_string(&self.redact_json(value)).unwrap_or_default();
+        if text.len() <= self.max_bytes {
+            return text;
+        }
+        let mut cut = self.max_bytes;
+        while !text.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        format!("{}...[truncated, {} bytes total]", &text[..cut], text.len())
+    }
+}
+
+/// Per-execution log of API calls, with redacted and truncated bodies.
+#[derive(Clone, Debug)]
+pub struct CallTrace {
+    config: BodyTraceConfig,
+    entries: Arc<Mutex<Vec<serde_json::Value>>>,
+}
+
+impl CallTrace {
+    /// Create an empty trace using the given body settings.
+    pub fn new(config: BodyTraceConfig) -> Self {
+        Self {
+            config,
+            entries: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Record one API call. `result` is the parsed response or the error message.
+    pub fn record(
+        &self,
+        method: &str,
+        url: &str,
+        headers: &[(String, String)],
+        body: Option<&serde_json::Value>,
+        result: Result<&serde_json::Value, String>,
+    ) {
+        let headers: serde_json::Map<String, serde_json::Value> = self
+            .config
+            .redact_headers(headers)
+            .into_iter()
+            .map(|(k, v)| (k, serde_json::Value::String(v)))
+            .collect();
+        let mut entry = serde_json::json!({
+            "method": method,
+            "url": url,
+            "headers": headers,
+            "request_body": body.map(|b| self.config.trace_body(b)),
+        });
+        match result {
+            Ok(response) => entry["response_body"] = self.config.trace_body(response).into(),
+            Err(error) => entry["error"] = error.into(),
+        }
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.push(entry);
+        }
+    }
+
+    /// Take all recorded entries, leaving the trace empty.
+    pub fn take(&self) -> Vec<serde_json::Value> {
+        self.entries
+            .lock()
+            .map(|mut entries| std::mem::take(&mut *entries))
+            .unwrap_or_default()
+    }
+}
+
+/// Per-execution list of warnings about API calls, such as upstream
+/// deprecation notices. Repeated warnings are kept once.
+#[derive(Clone, Debug, Default)]
+pub struct CallWarnings {
+    entries: Arc<Mutex<Vec<String>>>,
+}
+
+impl CallWarnings {
+    /// Record a warning unless the same one was already recorded.
+    pub fn push(&self, warning: String) {
+        if let Ok(mut entries) = self.entries.lock()
+            && !entries.contains(&warning)
+        {
+            entries.push(warning);
+        }
+    }
+
+    /// Take all recorded warnings, leaving the list empty.
+    pub fn take(&self) -> Vec<String> {
+        self.entries
+            .lock()
+            .map(|mut entries| std::mem::take(&mut *entries))
+            .unwrap_or_default()
+    }
+}
+
+/// One API call for [`HttpHandler::request`]: where it goes and what it sends.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ApiRequest<'a> {
+    /// Name of the API the call belongs to, for rate limiting.
+    pub api: &'a str,
+    pub method: &'a str,
+    pub url: &'a str,
+    /// Auth schemes to apply, each with its credentials.
+    pub auth: &'a [(AuthConfig, AuthCredentials)],
+    pub query_params: &'a [(String, String)],
+    pub headers: &'a [(String, String)],
+    pub body: Option<&'a RequestBody>,
+}
+
+/// Per-call settings for [`HttpHandler::request`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RequestOptions<'a> {
+    /// Sent as a header unless the request already has one of the same name.
+    pub correlation_id: Option<&'a CorrelationId>,
+    /// Responses larger than this fail the call.
+    pub max_response_bytes: Option<usize>,
+    /// Real requests not finished within this fail the call.
+    pub timeout: Option<Duration>,
+    /// A rate limit wait that would run past this fails the call instead.
+    pub deadline: Option<std::time::Instant>,
+    /// How a successful response body is returned.
+    pub format: ResponseFormat,
+}
+
+/// How a successful response body is handed back to the caller.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ResponseFormat {
+    /// Decode the body as JSON.
+    #[default]
+    Json,
+    /// Return the body undecoded, as a string.
+    Text,
+    /// Decode the body as JSON when the response's `Content-Type` is JSON (or
+    /// missing), and return it as a string otherwise.
+    Auto,
+}
+
+/// Per-execution cache of successful GET responses, keyed by URL, query
+/// params, and headers.
+#[derive(Clone, Debug, Default)]
+pub struct ResponseCache {
+    entries: Arc<Mutex<HashMap<String, serde_json::Value>>>,
+}
+
+impl ResponseCache {
+    /// Look up the cached response for a GET request.
+    pub fn get(
+        &self,
+        url: &str,
+        query_params: &[(String, String)],
+        headers: &[(String, String)],
+    ) -> Option<serde_json::Value> {
+        let key = Self::key(url, query_params, headers);
+        self.entries.lock().ok()?.get(&key).cloned()
+    }
+
+    /// Remember the response for a GET request.
+    pub fn insert(
+        &self,
+        url: &str,
+        query_params: &[(String, String)],
+        headers: &[(String, String)],
+        response: serde_json::Value,
+    ) {
+        let key = Self::key(url, query_params, headers);
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(key, response);
+        }
+    }
+
+    fn key(url: &str, query_params: &[(String, String)], headers: &[(String, String)]) -> String {
+        serde_json::json!([url, query_params, headers]).to_string()
+    }
+}
+
+/// A response as returned by [`HttpHandler::request_with_links`].
+type Exchange = (serde_json::Value, HashMap<String, String>, Option<String>);
+
+/// Server-wide cache of successful GET responses, shared across executions.
+///
+/// Entries are keyed by URL, query params, and auth headers, then told apart
+/// by request headers: the ones the response's `Vary` header names, or all of
+/// them when it has none. Responses with `Cache-Control: no-store` or
+/// `Vary: *` are not stored. Entries expire after `ttl`; when `max_entries`
+/// are held, the one closest to expiry makes room for a new one.
+#[derive(Debug)]
+pub struct SharedResponseCache {
+    ttl: Duration,
+    max_entries: usize,
+    entries: Mutex<HashMap<String, Vec<CachedResponse>>>,
+}
+
+#[derive(Debug)]
+struct CachedResponse {
+    /// Lowercase request header names the response varies on; `None` for all.
+    vary: Option<Vec<String>>,
+    /// The request's values for those headers.
+    headers: Vec<(String, String)>,
+    expires: std::time::Instant,
+    exchange: Exchange,
+}
+
+impl SharedResponseCache {
+    fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            ttl,
+            max_entries,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn key(
+        url: &str,
+        query_params: &[(String, String)],
+        auth_headers: &[(String, String)],
+    ) -> String {
+        serde_json::json!([url, query_params, auth_headers]).to_string()
+    }
+
+    /// The request headers that tell variants apart, lowercased and sorted.
+    fn select_headers(
+        vary: Option<&[String]>,
+        headers: &[(String, String)],
+    ) -> Vec<(String, String)> {
+        let mut selected: Vec<(String, String)> = headers
+            .iter()
+            .map(|(name, value)| (name.to_ascii_lowercase(), value.clone()))
+            .filter(|(name, _)| vary.is_none_or(|vary| vary.contains(name)))
+            .collect();
+        selected.sort();
+        selected
+    }
+
+    fn get(&self, key: &str, headers: &[(String, String)]) -> Option<Exchange> {
+        let now = std::time::Instant::now();
+        let mut entries = self.entries.lock().ok()?;
+        let variants = entries.get_mut(key)?;
+        variants.retain(|cached| cached.expires > now);
+        let hit = variants
+            .iter()
+            .find(|cached| Self::select_headers(cached.vary.as_deref(), headers) == cached.headers)
+            .map(|cached| cached.exchange.clone());
+        drop(entries);
+        hit
+    }
+
+    fn insert(
+        &self,
+        key: &str,
+        headers: &[(String, String)],
+        policy: &CachePolicy,
+        exchange: &Exchange,
+    ) {
+        if policy.no_store || self.max_entries == 0 {
+            return;
+        }
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+        let now = std::time::Instant::now();
+        if entries.values().map(Vec::len).sum::<usize>() >= self.max_entries {
+            for variants in entries.values_mut() {
+                variants.retain(|cached| cached.expires > now);
+            }
+            entries.retain(|_, variants| !variants.is_empty());
+        }
+        if entries.values().map(Vec::len).sum::<usize>() >= self.max_entries
+            && let Some((oldest_key, index)) = entries
+                .iter()
+                .flat_map(|(key, variants)| {
+                    variants
+                        .iter()
+                        .enumerate()
+                        .map(move |(index, cached)| (cached.expires, key, index))
+                })
+                .min()
+                .map(|(_, key, index)| (key.clone(), index))
+            && let Some(variants) = entries.get_mut(&oldest_key)
+        {
+            variants.remove(index);
+        }
+        let headers = Self::select_headers(policy.vary.as_deref(), headers);
+        let variants = entries.entry(key.to_string()).or_default();
+        variants.retain(|cached| cached.vary != policy.vary || cached.headers != headers);
+        variants.push(CachedResponse {
+            vary: policy.vary.clone(),
+            headers,
+            expires: now + self.ttl,
+            exchange: exchange.clone(),
+        });
+    }
+}
+
+/// What a response's `Cache-Control` and `Vary` headers let the shared
+/// cache keep.
+#[derive(Debug, Default)]
+struct CachePolicy {
+    no_store: bool,
+    /// Lowercase request header names from `Vary`; `None` without the header.
+    vary: Option<Vec<String>>,
+}
+
+fn cache_policy(headers: &reqwest::header::HeaderMap) -> CachePolicy {
+    let directives = |name| {
+        headers
+            .get_all(name)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|directive| directive.trim().to_ascii_lowercase())
+            .filter(|directive| !directive.is_empty())
+            .collect::<Vec<_>>()
+    };
+    let vary = headers
+        .contains_key(reqwest::header::VARY)
+        .then(|| directives(reqwest::header::VARY));
+    CachePolicy {
+        no_store: directives(reqwest::header::CACHE_CONTROL)
+            .iter()
+            .any(|d| d == "no-store")
+            || vary
+                .as_ref()
+                .is_some_and(|vary| vary.iter().any(|v| v == "*")),
+        vary,
+    }
+}
+
 /// Mock function signature: (method, url, `query_params`, body) -> `Result<serde_json::Value>`
 type MockFn = Arc<
     dyn Fn(
@@ -44,6 +570,62 @@ type MockWithHeadersFn = Arc<
 #[derive(Clone)]
 pub struct HttpHandler {
     inner: HttpHandlerInner,
+    /// Per-API token buckets, shared by every clone of the handler.
+    rate_limiters: Arc<HashMap<String, TokenBucket>>,
+    /// GET responses shared across executions, when enabled.
+    response_cache: Option<Arc<SharedResponseCache>>,
+    /// Slots for calls in flight at once, shared by every clone of the handler.
+    in_flight: Option<Arc<tokio::sync::Semaphore>>,
+}
+
+/// Token bucket pacing calls to one API.
+///
+/// Each call reserves a token, letting the balance go negative, then sleeps
+/// until the balance it reserved against would have refilled. Reserving under
+/// the lock keeps concurrent callers queued in order.
+struct TokenBucket {
+    rate: f64,
+    burst: f64,
+    state: tokio::sync::Mutex<(f64, tokio::time::Instant)>,
+}
+
+impl TokenBucket {
+    fn new(limit: RateLimit) -> Self {
+        let burst = f64::from(limit.burst.get());
+        Self {
+            rate: f64::from(limit.requests_per_second.get()),
+            burst,
+            state: tokio::sync::Mutex::new((burst, tokio::time::Instant::now())),
+        }
+    }
+
+    /// Wait for a token. Fails without waiting, and returns the token, when
+    /// the wait would run past `deadline`.
+    async fn acquire(&self, deadline: Option<std::time::Instant>) -> anyhow::Result<()> {
+        let now = tokio::time::Instant::now();
+        let mut state = self.state.lock().await;
+        let (tokens, last) = *state;
+        let refilled = now.duration_since(last).as_secs_f64();
+        let balance = refilled.mul_add(self.rate, tokens).min(self.burst) - 1.0;
+        if balance >= 0.0 {
+            *state = (balance, now);
+            return Ok(());
+        }
+        let wait = Duration::from_secs_f64(-balance / self.rate);
+        if let Some(deadline) = deadline
+            && deadline.saturating_duration_since(now.into_std()) < wait
+        {
+            *state = (balance + 1.0, now);
+            anyhow::bail!(
+                "rate limit wait of {}ms would pass the script deadline",
+                wait.as_millis()
+            );
+        }
+        *state = (balance, now);
+        drop(state);
+        tokio::time::sleep(wait).await;
+        Ok(())
+    }
 }
 
 #[derive(Clone)]
@@ -53,18 +635,92 @@ enum HttpHandlerInner {
     MockWithHeaders(MockWithHeadersFn),
 }
 
-impl Default for HttpHandler {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 impl HttpHandler {
-    /// Create a real HTTP handler.
-    pub fn new() -> Self {
-        Self {
-            inner: HttpHandlerInner::Real(reqwest::Client::new()),
+    /// Create a real HTTP handler whose client uses the given pool settings,
+    /// user agent, default headers, and TLS trust settings.
+    pub fn new(config: &HttpClientConfig) -> anyhow::Result<Self> {
+        let mut builder = reqwest::Client::builder();
+        if let Some(user_agent) = &config.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        let mut default_headers = reqwest::header::HeaderMap::new();
+        for (name, value) in &config.default_headers {
+            let name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| anyhow::anyhow!("invalid default header name '{name}': {e}"))?;
+            let value = reqwest::header::HeaderValue::from_str(value)
+                .map_err(|e| anyhow::anyhow!("invalid value for default header '{name}': {e}"))?;
+            default_headers.insert(name, value);
+        }
+        builder = builder.default_headers(default_headers);
+        if config.reuse_connections {
+            if let Some(max_idle) = config.pool_max_idle_per_host {
+                builder = builder.pool_max_idle_per_host(max_idle);
+            }
+            if let Some(idle_timeout) = config.pool_idle_timeout {
+                builder = builder.pool_idle_timeout(idle_timeout);
+            }
+        } else {
+            builder = builder.pool_max_idle_per_host(0);
+        }
+        if let Some(path) = &config.ca_bundle {
+            let pem = std::fs::read(path)
+                .map_err(|e| anyhow::anyhow!("failed to read CA bundle {}: {e}", path.display()))?;
+            let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+                .map_err(|e| anyhow::anyhow!("invalid CA bundle {}: {e}", path.display()))?;
+            if certificates.is_empty() {
+                anyhow::bail!("CA bundle {} contains no certificates", path.display());
+            }
+            for certificate in certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
         }
+        if config.accept_invalid_certs {
+            tracing::warn!(
+                "TLS certificate verification is DISABLED for upstream API calls; \
+                 responses can be intercepted or forged. Use only for testing."
+            );
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+        let client = builder
+            .build()
+            .map_err(|e| anyhow::anyhow!("failed to build HTTP client: {e}"))?;
+        Ok(Self {
+            inner: HttpHandlerInner::Real(client),
+            rate_limiters: Arc::default(),
+            response_cache: None,
+            in_flight: None,
+        })
+    }
+
+    /// Pace calls to the given APIs, keyed by API name.
+    #[must_use]
+    pub fn with_rate_limits(
+        mut self,
+        limits: impl IntoIterator<Item = (String, RateLimit)>,
+    ) -> Self {
+        self.rate_limiters = Arc::new(
+            limits
+                .into_iter()
+                .map(|(api, limit)| (api, TokenBucket::new(limit)))
+                .collect(),
+        );
+        self
+    }
+
+    /// Share successful GET responses across executions for `ttl`, keeping
+    /// at most `max_entries` of them (see [`SharedResponseCache`]).
+    #[must_use]
+    pub fn with_response_cache(mut self, ttl: Duration, max_entries: usize) -> Self {
+        self.response_cache = Some(Arc::new(SharedResponseCache::new(ttl, max_entries)));
+        self
+    }
+
+    /// Allow at most `max` calls in flight at once across every execution
+    /// using this handler (minimum 1). Further calls queue for a free slot.
+    #[must_use]
+    pub fn with_max_concurrent_requests(mut self, max: usize) -> Self {
+        self.in_flight = Some(Arc::new(tokio::sync::Semaphore::new(max.max(1))));
+        self
     }
 
     /// Create a mock HTTP handler for testing.
@@ -82,6 +738,9 @@ impl HttpHandler {
     {
         Self {
             inner: HttpHandlerInner::Mock(Arc::new(f)),
+            rate_limiters: Arc::default(),
+            response_cache: None,
+            in_flight: None,
         }
     }
 
@@ -101,24 +760,157 @@ impl HttpHandler {
     {
         Self {
             inner: HttpHandlerInner::MockWithHeaders(Arc::new(f)),
+            rate_limiters: Arc::default(),
+            response_cache: None,
+            in_flight: None,
         }
     }
 
-    /// Make an HTTP request with auth injection.
-    #[allow(clippy::too_many_arguments)]
+    /// Make an HTTP request with auth and correlation ID injection.
+    ///
+    /// Every `(scheme, credentials)` pair in `request.auth` is applied, so an
+    /// API that requires several schemes at once gets all of their headers. The
+    /// correlation ID header is only added when `request.headers` does not
+    /// already contain a header of the same name, so explicit header params win.
+    /// Responses larger than `max_response_bytes` are rejected while reading,
+    /// without buffering the rest of the body. Raw bodies are handed to mocks
+    /// as a JSON string, with their `Content-Type` added to the mock's headers.
+    /// If the API has a rate limit, the call first waits for a permit, failing
+    /// instead when the wait would run past `deadline`. A real request that
+    /// has not finished reading its response within `timeout` fails; the rate
+    /// limit wait does not count toward it.
     pub async fn request(
         &self,
-        method: &str,
-        url: &str,
-        auth_config: Option<&AuthConfig>,
-        credentials: &AuthCredentials,
-        query_params: &[(String, String)],
-        headers: &[(String, String)],
-        body: Option<&serde_json::Value>,
+        request: &ApiRequest<'_>,
+        options: &RequestOptions<'_>,
     ) -> anyhow::Result<serde_json::Value> {
-        match &self.inner {
-            HttpHandlerInner::Mock(f) => f(method, url, query_params, body),
-            HttpHandlerInner::MockWithHeaders(f) => f(method, url, query_params, headers, body),
+        self.request_with_links(request, options)
+            .await
+            .map(|(json, _, _)| json)
+    }
+
+    /// Like [`request`](Self::request), but also returns the response's RFC
+    /// 8288 `Link` header as a `rel` to absolute URL map (see
+    /// [`parse_link_header`]) and its deprecation notice (see
+    /// [`deprecation_notice`]). Mock handlers never return either.
+    /// With [`ResponseFormat::Text`] the body comes back as a JSON string,
+    /// undecoded; [`ResponseFormat::Auto`] does the same for non-JSON content
+    /// types. Both bypass the shared response cache.
+    #[allow(clippy::too_many_lines)]
+    pub async fn request_with_links(
+        &self,
+        request: &ApiRequest<'_>,
+        options: &RequestOptions<'_>,
+    ) -> anyhow::Result<(serde_json::Value, HashMap<String, String>, Option<String>)> {
+        let ApiRequest {
+            api,
+            method,
+            url,
+            auth,
+            query_params,
+            headers,
+            body,
+        } = *request;
+        let RequestOptions {
+            correlation_id,
+            max_response_bytes,
+            timeout,
+            deadline,
+            format,
+        } = *options;
+        let mut resolved_auth = Vec::with_capacity(auth.len());
+        for (auth_config, credentials) in auth {
+            resolved_auth.push((
+                auth_config,
+                resolve_credentials(auth_config, credentials).await?,
+            ));
+        }
+
+        // Shared cache lookups skip the rate limit. The correlation ID is
+        // left out of the key so separate executions can share entries.
+        let cache = self
+            .response_cache
+            .as_ref()
+            .filter(|_| method.eq_ignore_ascii_case("GET") && format == ResponseFormat::Json);
+        let cache_key = cache.map(|_| {
+            let auth_pairs: Vec<(String, String)> = resolved_auth
+                .iter()
+                .flat_map(|(auth_config, credentials)| {
+                    let mut pairs = auth_header_pairs(auth_config, credentials);
+                    pairs.extend(auth_query_pairs(auth_config, credentials));
+                    pairs
+                })
+                .collect();
+            SharedResponseCache::key(url, query_params, &auth_pairs)
+        });
+        if let (Some(cache), Some(key)) = (cache, &cache_key)
+            && let Some(hit) = cache.get(key, headers)
+        {
+            return Ok(hit);
+        }
+
+        if let Some(bucket) = self.rate_limiters.get(api) {
+            bucket.acquire(deadline).await?;
+        }
+        let _slot = match &self.in_flight {
+            Some(semaphore) => Some(acquire_slot(semaphore, deadline).await?),
+            None => None,
+        };
+
+        let request_headers = headers;
+        let headers = with_correlation_header(headers, correlation_id);
+        let headers = headers.as_ref();
+
+        // Mocks see query-located API keys alongside the other parameters.
+        let mock_query_params = || -> Vec<(String, String)> {
+            let mut params = query_params.to_vec();
+            for (auth_config, credentials) in &resolved_auth {
+                params.extend(auth_query_pairs(auth_config, credentials));
+            }
+            params
+        };
+
+        let (exchange, policy) = match &self.inner {
+            HttpHandlerInner::Mock(f) => {
+                let body = body.map(RequestBody::to_json);
+                let json = f(method, url, &mock_query_params(), body.as_ref())?;
+                (
+                    (
+                        mock_body(check_mock_response_size(json, max_response_bytes)?, format),
+                        HashMap::new(),
+                        None,
+                    ),
+                    CachePolicy::default(),
+                )
+            }
+            HttpHandlerInner::MockWithHeaders(f) => {
+                let mut all_headers: Vec<(String, String)> = resolved_auth
+                    .iter()
+                    .flat_map(|(auth_config, credentials)| {
+                        auth_header_pairs(auth_config, credentials)
+                    })
+                    .collect();
+                all_headers.extend_from_slice(headers);
+                if let Some(RequestBody::Raw { content_type, .. }) = body {
+                    all_headers.push(("Content-Type".to_string(), content_type.clone()));
+                }
+                let body = body.map(RequestBody::to_json);
+                let json = f(
+                    method,
+                    url,
+                    &mock_query_params(),
+                    &all_headers,
+                    body.as_ref(),
+                )?;
+                (
+                    (
+                        mock_body(check_mock_response_size(json, max_response_bytes)?, format),
+                        HashMap::new(),
+                        None,
+                    ),
+                    CachePolicy::default(),
+                )
+            }
             HttpHandlerInner::Real(client) => {
                 let req_method = method
                     .parse::<reqwest::Method>()
@@ -131,8 +923,10 @@ impl HttpHandler {
                     builder = builder.query(query_params);
                 }
 
-                // Inject authentication
-                builder = inject_auth(builder, auth_config, credentials);
+                // Inject authentication for every applied scheme
+                for (auth_config, credentials) in &resolved_auth {
+                    builder = inject_auth(builder, auth_config, credentials);
+                }
 
                 // Add custom headers from parameters
                 for (key, value) in headers {
@@ -140,47 +934,417 @@ impl HttpHandler {
                 }
 
                 // Add request body
-                if let Some(body) = body {
-                    builder = builder
-                        .header("Content-Type", "application/json")
-                        .json(body);
+                match body {
+                    Some(RequestBody::Json(body)) => {
+                        builder = builder
+                            .header("Content-Type", "application/json")
+                            .json(body);
+                    }
+                    Some(RequestBody::Raw { content_type, text }) => {
+                        builder = builder
+                            .header("Content-Type", content_type.as_str())
+                            .body(text.clone());
+                    }
+                    None => {}
                 }
 
-                let response = builder.send().await?;
-                let status = response.status();
+                let exchange = async {
+                    let response = builder.send().await?;
+                    let status = response.status();
+                    let links = response
+                        .headers()
+                        .get_all(reqwest::header::LINK)
+                        .iter()
+                        .filter_map(|value| value.to_str().ok())
+                        .flat_map(|value| parse_link_header(value, response.url()))
+                        .collect::<HashMap<_, _>>();
+                    let deprecation = deprecation_notice(response.headers());
+                    let retry_after = retry_after_secs(response.headers());
+                    let policy = cache_policy(response.headers());
+                    let json_body = response
+                        .headers()
+                        .get(reqwest::header::CONTENT_TYPE)
+                        .and_then(|v| v.to_str().ok())
+                        .is_none_or(|ct| ct.to_ascii_lowercase().contains("json"));
+
+                    // HEAD and OPTIONS describe the resource through status and headers
+                    let head_only = method.eq_ignore_ascii_case("HEAD");
+                    let metadata = (head_only || method.eq_ignore_ascii_case("OPTIONS"))
+                        .then(|| response_metadata(&response));
+                    let bytes = if head_only {
+                        Vec::new()
+                    } else {
+                        read_body_limited(response, max_response_bytes).await?
+                    };
+                    anyhow::Ok((
+                        status,
+                        links,
+                        deprecation,
+                        retry_after,
+                        policy,
+                        metadata,
+                        json_body,
+                        bytes,
+                    ))
+                };
+                let (status, links, deprecation, retry_after, policy, metadata, json_body, bytes) =
+                    match timeout {
+                        Some(limit) => {
+                            tokio::time::timeout(limit, exchange).await.map_err(|_| {
+                                anyhow::anyhow!("request timed out after {}ms", limit.as_millis())
+                            })??
+                        }
+                        None => exchange.await?,
+                    };
 
                 if !status.is_success() {
-                    let body_text = response.text().await.unwrap_or_default();
-                    return Err(anyhow::anyhow!(
-                        "HTTP {} {}: {}",
-                        status.as_u16(),
-                        status.canonical_reason().unwrap_or(""),
-                        body_text
-                    ));
+                    return Err(HttpError {
+                        status: status.as_u16(),
+                        reason: status.canonical_reason().unwrap_or("").to_string(),
+                        body: String::from_utf8_lossy(&bytes).into_owned(),
+                        retry_after,
+                    }
+                    .into());
                 }
 
-                let json: serde_json::Value = response.json().await?;
-                Ok(json)
+                let body = match format {
+                    ResponseFormat::Json => success_body(metadata, &bytes)?,
+                    ResponseFormat::Auto if json_body => success_body(metadata, &bytes)?,
+                    ResponseFormat::Text | ResponseFormat::Auto => {
+                        serde_json::Value::String(String::from_utf8_lossy(&bytes).into_owned())
+                    }
+                };
+                ((body, links, deprecation), policy)
+            }
+        };
+
+        if let (Some(cache), Some(key)) = (cache, &cache_key) {
+            cache.insert(key, request_headers, &policy, &exchange);
+        }
+        Ok(exchange)
+    }
+}
+
+/// Parse a `Retry-After` header: delay seconds, or an HTTP date converted to
+/// the seconds remaining until then (zero once it has passed).
+fn retry_after_secs(headers: &reqwest::header::HeaderMap) -> Option<u64> {
+    let value = headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(secs);
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let remaining = date.signed_duration_since(chrono::Utc::now()).num_seconds();
+    Some(u64::try_from(remaining).unwrap_or(0))
+}
+
+/// Describe a response's RFC 9745 `Deprecation` and RFC 8594 `Sunset`
+/// headers, or `None` when it has neither.
+fn deprecation_notice(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+    };
+    let deprecation = header("deprecation").filter(|v| !v.eq_ignore_ascii_case("false"));
+    let sunset = header("sunset");
+    if deprecation.is_none() && sunset.is_none() {
+        return None;
+    }
+    let mut notice = "endpoint is deprecated".to_string();
+    if let Some(since) = deprecation.filter(|v| !v.eq_ignore_ascii_case("true")) {
+        let _ = write!(notice, " (since {since})");
+    }
+    if let Some(sunset) = sunset {
+        let _ = write!(notice, " and sunsets {sunset}");
+    }
+    Some(notice)
+}
+
+/// Decode a successful response body as JSON. HEAD and OPTIONS calls return
+/// their `metadata` instead, with any OPTIONS body (JSON or text) under `body`.
+fn success_body(
+    metadata: Option<serde_json::Value>,
+    bytes: &[u8],
+) -> anyhow::Result<serde_json::Value> {
+    let Some(mut metadata) = metadata else {
+        return Ok(serde_json::from_slice(bytes)?);
+    };
+    if !bytes.is_empty() {
+        metadata["body"] = serde_json::from_slice(bytes).unwrap_or_else(|_| {
+            serde_json::Value::String(String::from_utf8_lossy(bytes).into_owned())
+        });
+    }
+    Ok(metadata)
+}
+
+/// A mock response in the requested format. Text mode passes strings through
+/// and serializes anything else, standing in for the raw body.
+fn mock_body(json: serde_json::Value, format: ResponseFormat) -> serde_json::Value {
+    match (format, json) {
+        (ResponseFormat::Text, serde_json::Value::String(text)) => serde_json::Value::String(text),
+        (ResponseFormat::Text, json) => serde_json::Value::String(json.to_string()),
+        (ResponseFormat::Json | ResponseFormat::Auto, json) => json,
+    }
+}
+
+/// `{ status, headers }` for a response, with lowercase header names. Repeated
+/// headers are joined with `, `.
+fn response_metadata(response: &reqwest::Response) -> serde_json::Value {
+    let mut headers = serde_json::Map::new();
+    for (name, value) in response.headers() {
+        let Ok(value) = value.to_str() else {
+            continue;
+        };
+        match headers.get_mut(name.as_str()) {
+            Some(serde_json::Value::String(existing)) => {
+                existing.push_str(", ");
+                existing.push_str(value);
+            }
+            _ => {
+                headers.insert(name.as_str().to_string(), value.into());
             }
         }
     }
+    serde_json::json!({
+        "status": response.status().as_u16(),
+        "headers": headers,
+    })
+}
+
+/// Parse an RFC 8288 `Link` header into `(rel, url)` pairs, resolving
+/// relative URLs against `base`.
+///
+/// `<https://api.example.com/items?page=2>; rel="next", <...>; rel="last"`
+/// yields `("next", ".../items?page=2")` and `("last", ...)`. A link with
+/// several space-separated relations yields one pair per relation.
+pub fn parse_link_header(value: &str, base: &reqwest::Url) -> Vec<(String, String)> {
+    // Split on commas outside `<...>`, since URLs may contain commas
+    let mut segments = Vec::new();
+    let (mut start, mut in_target) = (0, false);
+    for (i, c) in value.char_indices() {
+        match c {
+            '<' => in_target = true,
+            '>' => in_target = false,
+            ',' if !in_target => {
+                segments.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    segments.push(&value[start..]);
+
+    let mut links = Vec::new();
+    for link in segments {
+        let mut parts = link.split(';');
+        let Some(target) = parts.next().map(str::trim) else {
+            continue;
+        };
+        let Some(target) = target.strip_prefix('<').and_then(|t| t.strip_suffix('>')) else {
+            continue;
+        };
+        let Ok(url) = base.join(target) else {
+            continue;
+        };
+        for param in parts {
+            let Some((key, rel)) = param.split_once('=') else {
+                continue;
+            };
+            if key.trim().eq_ignore_ascii_case("rel") {
+                for rel in rel.trim().trim_matches('"').split_whitespace() {
+                    links.push((rel.to_ascii_lowercase(), url.to_string()));
+                }
+            }
+        }
+    }
+    links
+}
+
+/// Read a response body, failing as soon as it grows past `max_bytes`.
+async fn read_body_limited(
+    mut response: reqwest::Response,
+    max_bytes: Option<usize>,
+) -> anyhow::Result<Vec<u8>> {
+    let Some(max) = max_bytes else {
+        return Ok(response.bytes().await?.to_vec());
+    };
+
+    if response
+        .content_length()
+        .is_some_and(|len| len > max as u64)
+    {
+        return Err(anyhow::anyhow!("response exceeded {max} bytes"));
+    }
+
+    let mut buf = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if buf.len() + chunk.len() > max {
+            return Err(anyhow::anyhow!("response exceeded {max} bytes"));
+        }
+        buf.extend_from_slice(&chunk);
+    }
+    Ok(buf)
+}
+
+/// Apply the response size limit to a mock response, measured as serialized JSON.
+fn check_mock_response_size(
+    json: serde_json::Value,
+    max_bytes: Option<usize>,
+) -> anyhow::Result<serde_json::Value> {
+    if let Some(max) = max_bytes
+        && serde_json::to_vec(&json)?.len() > max
+    {
+        return Err(anyhow::anyhow!("response exceeded {max} bytes"));
+    }
+    Ok(json)
+}
+
+/// Append the correlation ID header unless a same-named header is already present.
+fn with_correlation_header<'a>(
+    headers: &'a [(String, String)],
+    correlation_id: Option<&CorrelationId>,
+) -> Cow<'a, [(String, String)]> {
+    match correlation_id {
+        Some(id)
+            if !headers
+                .iter()
+                .any(|(k, _)| k.eq_ignore_ascii_case(&id.header)) =>
+        {
+            let mut all = headers.to_vec();
+            all.push((id.header.clone(), id.value.clone()));
+            Cow::Owned(all)
+        }
+        _ => Cow::Borrowed(headers),
+    }
+}
+
+/// Turn file-backed credentials into concrete ones by reading the file now.
+///
+/// Other credential kinds are returned unchanged.
+async fn resolve_credentials<'a>(
+    auth_config: &AuthConfig,
+    credentials: &'a AuthCredentials,
+) -> anyhow::Result<Cow<'a, AuthCredentials>> {
+    let AuthCredentials::FromFile { path } = credentials else {
+        return Ok(Cow::Borrowed(credentials));
+    };
+
+    let contents = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to read {credentials}: {e}"))?;
+    let secret = contents.trim();
+    if secret.is_empty() {
+        return Err(anyhow::anyhow!("{credentials} is empty"));
+    }
+
+    let resolved = match auth_config {
+        AuthConfig::ApiKey { .. } => AuthCredentials::ApiKey(secret.to_string()),
+        AuthConfig::Basic => {
+            let (username, password) = secret.split_once(':').ok_or_else(|| {
+                anyhow::anyhow!("{credentials} must contain 'username:password' for basic auth")
+            })?;
+            AuthCredentials::Basic {
+                username: username.to_string(),
+                password: password.to_string(),
+            }
+        }
+        AuthConfig::Bearer { .. } => AuthCredentials::BearerToken(secret.to_string()),
+    };
+    Ok(Cow::Owned(resolved))
+}
+
+/// Wait for an in-flight slot, giving up at `deadline`.
+async fn acquire_slot(
+    semaphore: &tokio::sync::Semaphore,
+    deadline: Option<std::time::Instant>,
+) -> anyhow::Result<tokio::sync::SemaphorePermit<'_>> {
+    let acquire = semaphore.acquire();
+    let permit = match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline.into(), acquire)
+            .await
+            .map_err(|_| anyhow::anyhow!("no request slot freed up before the script deadline"))?,
+        None => acquire.await,
+    };
+    // The semaphore is never closed
+    permit.map_err(|e| anyhow::anyhow!("request slot unavailable: {e}"))
+}
+
+/// The headers `inject_auth` adds, with lowercased names, for handlers that
+/// don't build a real request and for shared cache keys.
+fn auth_header_pairs(
+    auth_config: &AuthConfig,
+    credentials: &AuthCredentials,
+) -> Vec<(String, String)> {
+    match (auth_config, credentials) {
+        (AuthConfig::Bearer { header, prefix }, AuthCredentials::BearerToken(token)) => {
+            vec![(header.to_ascii_lowercase(), format!("{prefix}{token}"))]
+        }
+        (
+            AuthConfig::ApiKey {
+                header,
+                location: ApiKeyLocation::Header,
+            },
+            AuthCredentials::ApiKey(key),
+        ) => vec![(header.to_ascii_lowercase(), key.clone())],
+        (
+            AuthConfig::ApiKey {
+                header,
+                location: ApiKeyLocation::Cookie,
+            },
+            AuthCredentials::ApiKey(key),
+        ) => vec![("cookie".to_string(), format!("{header}={key}"))],
+        (AuthConfig::Basic, AuthCredentials::Basic { username, password }) => {
+            let encoded =
+                base64::engine::general_purpose::STANDARD.encode(format!("{username}:{password}"));
+            vec![("authorization".to_string(), format!("Basic {encoded}"))]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// The query pairs `inject_auth` adds, for API keys sent as query parameters.
+fn auth_query_pairs(
+    auth_config: &AuthConfig,
+    credentials: &AuthCredentials,
+) -> Vec<(String, String)> {
+    match (auth_config, credentials) {
+        (
+            AuthConfig::ApiKey {
+                header,
+                location: ApiKeyLocation::Query,
+            },
+            AuthCredentials::ApiKey(key),
+        ) => vec![(header.clone(), key.clone())],
+        _ => Vec::new(),
+    }
 }
 
 /// Inject authentication into the request builder based on config + credentials.
 fn inject_auth(
     mut builder: reqwest::RequestBuilder,
-    auth_config: Option<&AuthConfig>,
+    auth_config: &AuthConfig,
     credentials: &AuthCredentials,
 ) -> reqwest::RequestBuilder {
     match (auth_config, credentials) {
-        (Some(AuthConfig::Bearer { header, prefix }), AuthCredentials::BearerToken(token)) => {
+        (AuthConfig::Bearer { header, prefix }, AuthCredentials::BearerToken(token)) => {
             let value = format!("{prefix}{token}");
             builder = builder.header(header.as_str(), value);
         }
-        (Some(AuthConfig::ApiKey { header }), AuthCredentials::ApiKey(key)) => {
-            builder = builder.header(header.as_str(), key.as_str());
+        (AuthConfig::ApiKey { header, location }, AuthCredentials::ApiKey(key)) => {
+            builder = match location {
+                ApiKeyLocation::Header => builder.header(header.as_str(), key.as_str()),
+                ApiKeyLocation::Query => builder.query(&[(header, key)]),
+                ApiKeyLocation::Cookie => {
+                    builder.header(reqwest::header::COOKIE, format!("{header}={key}"))
+                }
+            };
         }
-        (Some(AuthConfig::Basic), AuthCredentials::Basic { username, password }) => {
+        (AuthConfig::Basic, AuthCredentials::Basic { username, password }) => {
             builder = builder.basic_auth(username, Some(password));
         }
         _ => {}
@@ -193,6 +1357,126 @@ mod tests {
     #![allow(clippy::unwrap_used, clippy::expect_used)]
     use super::*;
 
+    #[test]
+    fn test_deprecation_notice_from_headers() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        assert_eq!(deprecation_notice(&headers), None);
+        headers.insert("deprecation", "true".parse().unwrap());
+        assert_eq!(
+            deprecation_notice(&headers).as_deref(),
+            Some("endpoint is deprecated")
+        );
+        headers.insert("deprecation", "@1688169599".parse().unwrap());
+        headers.insert("sunset", "Sat, 01 Jan 2028 00:00:00 GMT".parse().unwrap());
+        assert_eq!(
+            deprecation_notice(&headers).as_deref(),
+            Some(
+                "endpoint is deprecated (since @1688169599) and sunsets Sat, 01 Jan 2028 00:00:00 GMT"
+            )
+        );
+    }
+
+    #[test]
+    fn test_auth_header_pairs_match_injected_headers() {
+        let cases = [
+            (
+                AuthConfig::Bearer {
+                    header: "Authorization".to_string(),
+                    prefix: "Bearer ".to_string(),
+                },
+                AuthCredentials::BearerToken("tok".to_string()),
+            ),
+            (
+                AuthConfig::ApiKey {
+                    header: "X-API-Key".to_string(),
+                    location: ApiKeyLocation::Header,
+                },
+                AuthCredentials::ApiKey("key".to_string()),
+            ),
+            (
+                AuthConfig::ApiKey {
+                    header: "api_key".to_string(),
+                    location: ApiKeyLocation::Query,
+                },
+                AuthCredentials::ApiKey("key".to_string()),
+            ),
+            (
+                AuthConfig::ApiKey {
+                    header: "session".to_string(),
+                    location: ApiKeyLocation::Cookie,
+                },
+                AuthCredentials::ApiKey("key".to_string()),
+            ),
+            (
+                AuthConfig::Basic,
+                AuthCredentials::Basic {
+                    username: "alice".to_string(),
+                    password: "s3cret".to_string(),
+                },
+            ),
+        ];
+        for (auth_config, credentials) in &cases {
+            let builder = reqwest::Client::new().get("http://localhost/");
+            let request = inject_auth(builder, auth_config, credentials)
+                .build()
+                .unwrap();
+            let injected: Vec<(String, String)> = request
+                .headers()
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_str().unwrap().to_string()))
+                .collect();
+            assert_eq!(auth_header_pairs(auth_config, credentials), injected);
+            let query: Vec<(String, String)> = request
+                .url()
+                .query_pairs()
+                .map(|(k, v)| (k.into_owned(), v.into_owned()))
+                .collect();
+            assert_eq!(auth_query_pairs(auth_config, credentials), query);
+        }
+    }
+
+    #[test]
+    fn test_retry_after_seconds_or_date() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        assert_eq!(retry_after_secs(&headers), None);
+        headers.insert("retry-after", "30".parse().unwrap());
+        assert_eq!(retry_after_secs(&headers), Some(30));
+        headers.insert(
+            "retry-after",
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(retry_after_secs(&headers), Some(0));
+        let soon = (chrono::Utc::now() + chrono::Duration::seconds(120))
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string();
+        headers.insert("retry-after", soon.parse().unwrap());
+        assert!(retry_after_secs(&headers).is_some_and(|secs| (115..=120).contains(&secs)));
+        headers.insert("retry-after", "soon".parse().unwrap());
+        assert_eq!(retry_after_secs(&headers), None);
+    }
+
+    #[test]
+    fn test_credentials_formatting_redacts_secrets() {
+        let bearer = AuthCredentials::BearerToken("sk-live-abc123".to_string());
+        assert_eq!(bearer.to_string(), "Bearer ***");
+        assert_eq!(format!("{bearer:?}"), r#"BearerToken("***")"#);
+
+        let creds = [
+            bearer,
+            AuthCredentials::ApiKey("sk-live-abc123".to_string()),
+            AuthCredentials::Basic {
+                username: "alice".to_string(),
+                password: "sk-live-abc123".to_string(),
+            },
+        ];
+        for cred in &creds {
+            for formatted in [cred.to_string(), format!("{cred:?}"), format!("{cred:#?}")] {
+                assert!(!formatted.contains("sk-live-abc123"), "leaked: {formatted}");
+            }
+        }
+        assert_eq!(creds[2].to_string(), "Basic alice:***");
+    }
+
     #[tokio::test]
     async fn test_mock_handler_returns_response() {
         let handler = HttpHandler::mock(|_method, _url, _query, _body| {
@@ -201,13 +1485,13 @@ mod tests {
 
         let result = handler
             .request(
-                "GET",
-                "http://example.com/pets/123",
-                None,
-                &AuthCredentials::None,
-                &[],
-                &[],
-                None,
+                &ApiRequest {
+                    api: "test",
+                    method: "GET",
+                    url: "http://example.com/pets/123",
+                    ..ApiRequest::default()
+                },
+                &RequestOptions::default(),
             )
             .await
             .unwrap();
@@ -234,7 +1518,7 @@ mod tests {
         // Test the inject_auth function directly
         let client = reqwest::Client::new();
         let builder = client.get("http://example.com/test");
-        let builder = inject_auth(builder, Some(&auth_config), &creds);
+        let builder = inject_auth(builder, &auth_config, &creds);
         let request = builder.build().unwrap();
         assert_eq!(
             request
@@ -249,13 +1533,14 @@ mod tests {
         // Also verify mock handler works
         let result = handler
             .request(
-                "GET",
-                "http://example.com/test",
-                Some(&auth_config),
-                &creds,
-                &[],
-                &[],
-                None,
+                &ApiRequest {
+                    api: "test",
+                    method: "GET",
+                    url: "http://example.com/test",
+                    auth: &[(auth_config.clone(), creds.clone())],
+                    ..ApiRequest::default()
+                },
+                &RequestOptions::default(),
             )
             .await
             .unwrap();
@@ -266,12 +1551,13 @@ mod tests {
     async fn test_build_request_api_key_auth() {
         let auth_config = AuthConfig::ApiKey {
             header: "X-API-Key".to_string(),
+            location: ApiKeyLocation::Header,
         };
         let creds = AuthCredentials::ApiKey("my-secret-key".to_string());
 
         let client = reqwest::Client::new();
         let builder = client.get("http://example.com/test");
-        let builder = inject_auth(builder, Some(&auth_config), &creds);
+        let builder = inject_auth(builder, &auth_config, &creds);
         let request = builder.build().unwrap();
         assert_eq!(
             request
@@ -283,4 +1569,658 @@ mod tests {
             "my-secret-key"
         );
     }
+
+    #[tokio::test]
+    async fn test_file_credentials_reread_on_each_request() {
+        let dir = tempfile::tempdir().unwrap();
+        let token_path = dir.path().join("token");
+        std::fs::write(&token_path, "first-token\n").unwrap();
+
+        let captured = Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+        let captured_clone = Arc::clone(&captured);
+        let handler =
+            HttpHandler::mock_with_headers(move |_method, _url, _query, headers, _body| {
+                let auth = headers
+                    .iter()
+                    .find(|(k, _)| k.eq_ignore_ascii_case("authorization"))
+                    .map(|(_, v)| v.clone())
+                    .unwrap_or_default();
+                captured_clone.lock().unwrap().push(auth);
+                Ok(serde_json::json!({"ok": true}))
+            });
+
+        let auth_config = AuthConfig::Bearer {
+            header: "Authorization".to_string(),
+            prefix: "Bearer ".to_string(),
+        };
+        let creds = AuthCredentials::FromFile {
+            path: token_path.clone(),
+        };
+
+        for _ in 0..2 {
+            handler
+                .request(
+                    &ApiRequest {
+                        api: "test",
+                        method: "GET",
+                        url: "http://example.com/test",
+                        auth: &[(auth_config.clone(), creds.clone())],
+                        ..ApiRequest::default()
+                    },
+                    &RequestOptions::default(),
+                )
+                .await
+                .unwrap();
+            std::fs::write(&token_path, "rotated-token").unwrap();
+        }
+
+        assert_eq!(
+            *captured.lock().unwrap(),
+            vec!["Bearer first-token", "Bearer rotated-token"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_credentials_missing_file_errors() {
+        let handler = HttpHandler::mock(|_method, _url, _query, _body| Ok(serde_json::json!({})));
+        let creds = AuthCredentials::FromFile {
+            path: PathBuf::from("/nonexistent/toolscript-token"),
+        };
+        let err = handler
+            .request(
+                &ApiRequest {
+                    api: "test",
+                    method: "GET",
+                    url: "http://example.com",
+                    auth: &[(
+                        AuthConfig::Bearer {
+                            header: "Authorization".to_string(),
+                            prefix: "Bearer ".to_string(),
+                        },
+                        creds,
+                    )],
+                    ..ApiRequest::default()
+                },
+                &RequestOptions::default(),
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("failed to read credential file"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn test_correlation_header_added_unless_explicit() {
+        let id = CorrelationId {
+            header: "X-Request-Id".to_string(),
+            value: "abc".to_string(),
+        };
+
+        let headers = vec![("X-Other".to_string(), "1".to_string())];
+        let merged = with_correlation_header(&headers, Some(&id));
+        assert_eq!(
+            merged.as_ref(),
+            &[
+                ("X-Other".to_string(), "1".to_string()),
+                ("X-Request-Id".to_string(), "abc".to_string()),
+            ]
+        );
+
+        let explicit = vec![("x-request-id".to_string(), "mine".to_string())];
+        let merged = with_correlation_header(&explicit, Some(&id));
+        assert_eq!(merged.as_ref(), explicit.as_slice());
+
+        assert!(with_correlation_header(&headers, None).len() == 1);
+    }
+
+    #[tokio::test]
+    async fn test_mock_response_over_limit_errors() {
+        let handler = HttpHandler::mock(|_method, _url, _query, _body| {
+            Ok(serde_json::json!({"data": "x".repeat(1000)}))
+        });
+
+        let err = handler
+            .request(
+                &ApiRequest {
+                    api: "test",
+                    method: "GET",
+                    url: "http://example.com/big",
+                    ..ApiRequest::default()
+                },
+                &RequestOptions {
+                    max_response_bytes: Some(100),
+                    ..RequestOptions::default()
+                },
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "response exceeded 100 bytes");
+
+        let ok = handler
+            .request(
+                &ApiRequest {
+                    api: "test",
+                    method: "GET",
+                    url: "http://example.com/big",
+                    ..ApiRequest::default()
+                },
+                &RequestOptions {
+                    max_response_bytes: Some(10_000),
+                    ..RequestOptions::default()
+                },
+            )
+            .await;
+        assert!(ok.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_real_response_over_limit_errors() {
+        let app = axum::Router::new().route(
+            "/big",
+            axum::routing::get(|| async { format!("[{}1]", "1,".repeat(10_000)) }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let handler = HttpHandler::new(&HttpClientConfig::default()).unwrap();
+        let url = format!("http://{addr}/big");
+        let err = handler
+            .request(
+                &ApiRequest {
+                    api: "test",
+                    method: "GET",
+                    url: &url,
+                    ..ApiRequest::default()
+                },
+                &RequestOptions {
+                    max_response_bytes: Some(1024),
+                    ..RequestOptions::default()
+                },
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "response exceeded 1024 bytes");
+
+        let json = handler
+            .request(
+                &ApiRequest {
+                    api: "test",
+                    method: "GET",
+                    url: &url,
+                    ..ApiRequest::default()
+                },
+                &RequestOptions::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 10_001);
+    }
+
+    #[tokio::test]
+    async fn test_custom_pool_config_performs_requests() {
+        let app = axum::Router::new().route(
+            "/pets",
+            axum::routing::get(|| async { axum::Json(serde_json::json!([{ "id": 1 }])) }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        let url = format!("http://{addr}/pets");
+
+        for config in [
+            HttpClientConfig {
+                pool_max_idle_per_host: Some(2),
+                pool_idle_timeout: Some(Duration::from_secs(5)),
+                ..HttpClientConfig::default()
+            },
+            HttpClientConfig {
+                reuse_connections: false,
+                ..HttpClientConfig::default()
+            },
+        ] {
+            let handler = HttpHandler::new(&config).unwrap();
+            for _ in 0..2 {
+                let json = handler
+                    .request(
+                        &ApiRequest {
+                            api: "test",
+                            method: "GET",
+                            url: &url,
+                            ..ApiRequest::default()
+                        },
+                        &RequestOptions::default(),
+                    )
+                    .await
+                    .unwrap();
+                assert_eq!(json, serde_json::json!([{ "id": 1 }]));
+            }
+        }
+    }
+
+    /// Serve `/echo`, which responds with the request's headers as a JSON object.
+    async fn spawn_header_echo_server() -> String {
+        let app = axum::Router::new().route(
+            "/echo",
+            axum::routing::get(|headers: axum::http::HeaderMap| async move {
+                let headers: serde_json::Map<String, serde_json::Value> = headers
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_str().unwrap().into()))
+                    .collect();
+                axum::Json(headers)
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        format!("http://{addr}/echo")
+    }
+
+    #[tokio::test]
+    async fn test_user_agent_and_default_headers_sent() {
+        let url = spawn_header_echo_server().await;
+        let handler = HttpHandler::new(&HttpClientConfig {
+            user_agent: Some("acme-bot/1.0".to_string()),
+            default_headers: vec![("X-Partner".to_string(), "acme".to_string())],
+            ..HttpClientConfig::default()
+        })
+        .unwrap();
+
+        let json = handler
+            .request(
+                &ApiRequest {
+                    api: "test",
+                    method: "GET",
+                    url: &url,
+                    ..ApiRequest::default()
+                },
+                &RequestOptions::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(json["user-agent"], "acme-bot/1.0");
+        assert_eq!(json["x-partner"], "acme");
+    }
+
+    #[tokio::test]
+    async fn test_header_param_overrides_default_header() {
+        let url = spawn_header_echo_server().await;
+        let handler = HttpHandler::new(&HttpClientConfig {
+            default_headers: vec![("X-Partner".to_string(), "acme".to_string())],
+            ..HttpClientConfig::default()
+        })
+        .unwrap();
+
+        let headers = [("x-partner".to_string(), "globex".to_string())];
+        let json = handler
+            .request(
+                &ApiRequest {
+                    api: "test",
+                    method: "GET",
+                    url: &url,
+                    headers: &headers,
+                    ..ApiRequest::default()
+                },
+                &RequestOptions::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(json["x-partner"], "globex");
+    }
+
+    #[tokio::test]
+    async fn test_response_cache_respects_no_store_and_vary() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let hits = Arc::new(AtomicUsize::new(0));
+        let count =
+            |hits: Arc<AtomicUsize>| move || async move { hits.fetch_add(1, Ordering::SeqCst) + 1 };
+        let no_store = count(Arc::clone(&hits));
+        let vary = count(Arc::clone(&hits));
+        let app = axum::Router::new()
+            .route(
+                "/no-store",
+                axum::routing::get(move || {
+                    let hit = no_store();
+                    async move { ([("Cache-Control", "no-store")], axum::Json(hit.await)) }
+                }),
+            )
+            .route(
+                "/vary",
+                axum::routing::get(move || {
+                    let hit = vary();
+                    async move { ([("Vary", "X-Tenant")], axum::Json(hit.await)) }
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let handler = HttpHandler::new(&HttpClientConfig::default())
+            .unwrap()
+            .with_response_cache(Duration::from_mins(1), 100);
+        let get = |path: &'static str, headers: Vec<(&'static str, &'static str)>| {
+            let handler = handler.clone();
+            let url = format!("http://{addr}{path}");
+            async move {
+                let headers: Vec<(String, String)> = headers
+                    .into_iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect();
+                handler
+                    .request(
+                        &ApiRequest {
+                            api: "test",
+                            method: "GET",
+                            url: &url,
+                            headers: &headers,
+                            ..ApiRequest::default()
+                        },
+                        &RequestOptions::default(),
+                    )
+                    .await
+                    .unwrap()
+            }
+        };
+
+        // no-store responses are always fetched
+        assert_eq!(get("/no-store", vec![]).await, 1);
+        assert_eq!(get("/no-store", vec![]).await, 2);
+
+        // Only the headers named by Vary tell cached responses apart
+        let tenant_a = get("/vary", vec![("X-Tenant", "a"), ("X-Trace", "1")]).await;
+        assert_eq!(
+            get("/vary", vec![("X-Tenant", "a"), ("X-Trace", "2")]).await,
+            tenant_a
+        );
+        let tenant_b = get("/vary", vec![("X-Tenant", "b")]).await;
+        assert_ne!(tenant_b, tenant_a);
+        assert_eq!(hits.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn test_invalid_default_header_rejected() {
+        let err = HttpHandler::new(&HttpClientConfig {
+            default_headers: vec![("Bad Header".to_string(), "x".to_string())],
+            ..HttpClientConfig::default()
+        })
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("Bad Header"), "got: {err}");
+    }
+
+    #[test]
+    fn test_custom_ca_bundle_and_insecure_build() {
+        let handler = HttpHandler::new(&HttpClientConfig {
+            ca_bundle: Some(PathBuf::from("testdata/test-ca.pem")),
+            ..HttpClientConfig::default()
+        });
+        assert!(handler.is_ok(), "got: {:?}", handler.err());
+
+        let handler = HttpHandler::new(&HttpClientConfig {
+            accept_invalid_certs: true,
+            ..HttpClientConfig::default()
+        });
+        assert!(handler.is_ok(), "got: {:?}", handler.err());
+    }
+
+    #[test]
+    fn test_ca_bundle_errors_name_the_file() {
+        let err = HttpHandler::new(&HttpClientConfig {
+            ca_bundle: Some(PathBuf::from("testdata/missing-ca.pem")),
+            ..HttpClientConfig::default()
+        })
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("missing-ca.pem"), "got: {err}");
+
+        let err = HttpHandler::new(&HttpClientConfig {
+            ca_bundle: Some(PathBuf::from("testdata/petstore.yaml")),
+            ..HttpClientConfig::default()
+        })
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("no certificates"), "got: {err}");
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_paces_calls_per_api() {
+        let limit = RateLimit {
+            requests_per_second: std::num::NonZeroU32::new(20).unwrap(),
+            burst: std::num::NonZeroU32::new(2).unwrap(),
+        };
+        let handler = HttpHandler::mock(|_, _, _, _| Ok(serde_json::json!({})))
+            .with_rate_limits([("limited".to_string(), limit)]);
+
+        // Burst of 2 is immediate, then 4 more calls at 20/s need >= 200ms
+        let start = std::time::Instant::now();
+        for _ in 0..6 {
+            handler
+                .request(
+                    &ApiRequest {
+                        api: "limited",
+                        method: "GET",
+                        url: "http://x",
+                        ..ApiRequest::default()
+                    },
+                    &RequestOptions::default(),
+                )
+                .await
+                .unwrap();
+        }
+        let elapsed = start.elapsed();
+        assert!(
+            elapsed >= Duration::from_millis(190),
+            "calls finished too fast: {elapsed:?}"
+        );
+
+        // Other APIs are not paced
+        let start = std::time::Instant::now();
+        for _ in 0..6 {
+            handler
+                .request(
+                    &ApiRequest {
+                        api: "other",
+                        method: "GET",
+                        url: "http://x",
+                        ..ApiRequest::default()
+                    },
+                    &RequestOptions::default(),
+                )
+                .await
+                .unwrap();
+        }
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_wait_past_deadline_fails_fast() {
+        let limit = RateLimit {
+            requests_per_second: std::num::NonZeroU32::new(1).unwrap(),
+            burst: std::num::NonZeroU32::new(1).unwrap(),
+        };
+        let handler = HttpHandler::mock(|_, _, _, _| Ok(serde_json::json!({})))
+            .with_rate_limits([("limited".to_string(), limit)]);
+        let request = ApiRequest {
+            api: "limited",
+            method: "GET",
+            url: "http://x",
+            ..ApiRequest::default()
+        };
+        let options = RequestOptions {
+            deadline: Some(std::time::Instant::now() + Duration::from_millis(100)),
+            ..RequestOptions::default()
+        };
+
+        handler.request(&request, &options).await.unwrap();
+        // The next token is a second away, past the deadline
+        let start = std::time::Instant::now();
+        let err = handler.request(&request, &options).await.unwrap_err();
+        assert!(
+            err.to_string().contains("would pass the script deadline"),
+            "got: {err}"
+        );
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn test_max_concurrent_requests_caps_in_flight_calls() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (in_flight_clone, peak_clone) = (Arc::clone(&in_flight), Arc::clone(&peak));
+        let app = axum::Router::new().route(
+            "/slow",
+            axum::routing::get(move || {
+                let in_flight = Arc::clone(&in_flight_clone);
+                let peak = Arc::clone(&peak_clone);
+                async move {
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(30)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    axum::Json(serde_json::json!({}))
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        // Clones stand in for the handler shared by concurrent executions
+        let handler = HttpHandler::new(&HttpClientConfig::default())
+            .unwrap()
+            .with_max_concurrent_requests(3);
+        let url = format!("http://{addr}/slow");
+        let calls = (0..12).map(|_| {
+            let handler = handler.clone();
+            let url = url.clone();
+            tokio::spawn(async move {
+                handler
+                    .request(
+                        &ApiRequest {
+                            api: "test",
+                            method: "GET",
+                            url: &url,
+                            ..ApiRequest::default()
+                        },
+                        &RequestOptions::default(),
+                    )
+                    .await
+            })
+        });
+        let results = futures::future::join_all(calls).await;
+
+        // Every call queued and completed rather than erroring
+        assert!(results.into_iter().all(|r| r.unwrap().is_ok()));
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_parse_link_header() {
+        let base = reqwest::Url::parse("https://api.example.com/v1/items?page=1").unwrap();
+        let links = parse_link_header(
+            r#"<https://api.example.com/v1/items?page=2&ids=1,2>; rel="next", </v1/items?page=9>; rel="last end""#,
+            &base,
+        );
+        assert_eq!(
+            links,
+            vec![
+                (
+                    "next".to_string(),
+                    "https://api.example.com/v1/items?page=2&ids=1,2".to_string()
+                ),
+                (
+                    "last".to_string(),
+                    "https://api.example.com/v1/items?page=9".to_string()
+                ),
+                (
+                    "end".to_string(),
+                    "https://api.example.com/v1/items?page=9".to_string()
+                ),
+            ]
+        );
+        assert!(parse_link_header("garbage", &base).is_empty());
+    }
+
+    #[test]
+    fn test_body_trace_redacts_sensitive_keys() {
+        let config = BodyTraceConfig::default();
+        let body = serde_json::json!({
+            "username": "fido",
+            "password": "hunter2",
+            "nested": [{ "Access_Token": "abc", "count": 3 }],
+        });
+        let traced: serde_json::Value = serde_json::from_str(&config.trace_body(&body)).unwrap();
+        assert_eq!(
+            traced,
+            serde_json::json!({
+                "username": "fido",
+                "password": "***",
+                "nested": [{ "Access_Token": "***", "count": 3 }],
+            })
+        );
+
+        let headers = config.redact_headers(&[
+            ("X-Api-Key".to_string(), "k".to_string()),
+            ("X-Page".to_string(), "2".to_string()),
+        ]);
+        assert_eq!(headers[0].1, "***");
+        assert_eq!(headers[1].1, "2");
+    }
+
+    #[test]
+    fn test_body_trace_truncates_at_limit() {
+        let config = BodyTraceConfig {
+            max_bytes: 16,
+            redact: vec![],
+        };
+        let traced = config.trace_body(&serde_json::json!({ "data": "x".repeat(100) }));
+        assert_eq!(traced, r#"{"data":"xxxxxxx...[truncated, 111 bytes total]"#);
+    }
+
+    #[tokio::test]
+    async fn test_request_applies_all_auth_schemes() {
+        let handler = HttpHandler::mock_with_headers(|_method, _url, _query, headers, _body| {
+            Ok(serde_json::json!({ "headers": headers }))
+        });
+        let auth = [
+            (
+                AuthConfig::ApiKey {
+                    header: "X-Api-Key".to_string(),
+                    location: ApiKeyLocation::Header,
+                },
+                AuthCredentials::ApiKey("key-123".to_string()),
+            ),
+            (
+                AuthConfig::Bearer {
+                    header: "Authorization".to_string(),
+                    prefix: "Bearer ".to_string(),
+                },
+                AuthCredentials::BearerToken("tok-456".to_string()),
+            ),
+        ];
+
+        let result = handler
+            .request(
+                &ApiRequest {
+                    api: "test",
+                    method: "GET",
+                    url: "http://example.com/invoices",
+                    auth: &auth,
+                    ..ApiRequest::default()
+                },
+                &RequestOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        let headers: Vec<(String, String)> =
+            serde_json::from_value(result["headers"].clone()).unwrap();
+        assert!(headers.contains(&("x-api-key".to_string(), "key-123".to_string())));
+        assert!(headers.contains(&("authorization".to_string(), "Bearer tok-456".to_string())));
+    }
 }
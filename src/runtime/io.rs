@@ -14,24 +14,28 @@ const MAX_OPEN_HANDLES: usize = 64;
 // ---------------------------------------------------------------------------
 
 /// Shared state that tracks every file operation within a single script
-/// execution, enforcing sandbox limits (write budget, handle count, path
-/// validation).
+/// execution, enforcing sandbox limits (write budget, handle count, files
+/// created, path validation).
 #[derive(Clone)]
 pub struct IoContext {
     root: PathBuf,
     max_bytes: u64,
+    max_files: usize,
     bytes_written: Arc<AtomicU64>,
     open_handles: Arc<AtomicUsize>,
+    files_created: Arc<AtomicUsize>,
     files_touched: Arc<Mutex<HashMap<String, PathBuf>>>,
 }
 
 impl IoContext {
-    pub fn new(root: PathBuf, max_bytes: u64) -> Self {
+    pub fn new(root: PathBuf, max_bytes: u64, max_files: usize) -> Self {
         Self {
             root,
             max_bytes,
+            max_files,
             bytes_written: Arc::new(AtomicU64::new(0)),
             open_handles: Arc::new(AtomicUsize::new(0)),
+            files_created: Arc::new(AtomicUsize::new(0)),
             files_touched: Arc::new(Mutex::new(HashMap::new())),
         }
     }
@@ -79,6 +83,23 @@ impl IoContext {
         Ok(())
     }
 
+    /// Count a file about to be created at `abs_path`, enforcing the
+    /// max-files limit.  Writing to a file that already exists is free.
+    fn track_create(&self, abs_path: &Path) -> Result<(), mlua::Error> {
+        if abs_path.exists() {
+            return Ok(());
+        }
+        let prev = self.files_created.fetch_add(1, Ordering::SeqCst);
+        if prev >= self.max_files {
+            self.files_created.fetch_sub(1, Ordering::SeqCst);
+            return Err(mlua::Error::external(format!(
+                "file count limit exceeded ({} files)",
+                self.max_files
+            )));
+        }
+        Ok(())
+    }
+
     /// Acquire a handle slot, enforcing the max-open limit.
     fn acquire_handle(&self) -> Result<(), mlua::Error> {
         let prev = self.open_handles.fetch_add(1, Ordering::SeqCst);
@@ -117,7 +138,7 @@ impl IoContext {
         for (name, abs_path) in &*map {
             let path_str = abs_path.to_string_lossy().to_string();
             if abs_path.exists() {
-                let bytes = abs_path.metadata().map(|m| m.len()).unwrap_or(0);
+                let bytes = abs_path.metadata().map_or(0, |m| m.len());
                 result.push(FileTouched {
                     name: name.clone(),
                     path: path_str,
@@ -200,7 +221,7 @@ impl SandboxedFileHandle {
     }
 
     fn is_closed(&self) -> bool {
-        self.inner.lock().map(|g| g.file.is_none()).unwrap_or(true)
+        self.inner.lock().map_or(true, |g| g.file.is_none())
     }
 }
 
@@ -411,6 +432,63 @@ impl UserData for SandboxedFileHandle {
     }
 }
 
+/// Collect the entries of `dir` into `out` as paths relative to the listed
+/// directory, prefixed by `prefix`. With `recursive`, subdirectories are
+/// descended into (symlinks are not followed). With `pattern`, only entries
+/// whose file name matches the glob are kept.
+fn list_dir(
+    dir: &Path,
+    prefix: &str,
+    recursive: bool,
+    pattern: Option<&str>,
+    out: &mut Vec<String>,
+) -> Result<(), mlua::Error> {
+    for entry in std::fs::read_dir(dir).map_err(mlua::Error::external)? {
+        let entry = entry.map_err(mlua::Error::external)?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let rel = format!("{prefix}{name}");
+        if pattern.is_none_or(|p| glob_match(p, &name)) {
+            out.push(rel.clone());
+        }
+        if recursive && entry.file_type().map_err(mlua::Error::external)?.is_dir() {
+            list_dir(&entry.path(), &format!("{rel}/"), recursive, pattern, out)?;
+        }
+    }
+    Ok(())
+}
+
+/// Match `name` against a glob where `*` matches any run of characters and
+/// `?` matches exactly one.
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let (pattern, name): (Vec<char>, Vec<char>) =
+        (pattern.chars().collect(), name.chars().collect());
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*` and the name index it was tried at
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                // Let the last `*` absorb one more character and retry
+                Some((star_p, star_n)) => {
+                    star = Some((star_p, star_n + 1));
+                    p = star_p + 1;
+                    n = star_n + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
 // ---------------------------------------------------------------------------
 // register_io() — wire everything into Lua globals
 // ---------------------------------------------------------------------------
@@ -432,6 +510,7 @@ pub fn register_io(lua: &Lua, ctx: IoContext) -> Result<(), mlua::Error> {
             let file = match mode_str.as_str() {
                 "r" | "rb" => std::fs::File::open(&abs_path).map_err(mlua::Error::external)?,
                 "w" | "wb" => {
+                    ctx.track_create(&abs_path)?;
                     if let Some(parent) = abs_path.parent() {
                         std::fs::create_dir_all(parent).map_err(mlua::Error::external)?;
                     }
@@ -439,6 +518,7 @@ pub fn register_io(lua: &Lua, ctx: IoContext) -> Result<(), mlua::Error> {
                     std::fs::File::create(&abs_path).map_err(mlua::Error::external)?
                 }
                 "a" | "ab" => {
+                    ctx.track_create(&abs_path)?;
                     if let Some(parent) = abs_path.parent() {
                         std::fs::create_dir_all(parent).map_err(mlua::Error::external)?;
                     }
@@ -510,6 +590,60 @@ pub fn register_io(lua: &Lua, ctx: IoContext) -> Result<(), mlua::Error> {
         io_table.set("lines", lines_fn)?;
     }
 
+    // -- io.read_file(path) -------------------------------------------------
+    {
+        let ctx = ctx.clone();
+        let read_file_fn = lua.create_function(move |lua, path: String| {
+            let abs_path = ctx.resolve(&path)?;
+            let contents = std::fs::read(&abs_path).map_err(mlua::Error::external)?;
+            lua.create_string(&contents)
+        })?;
+        io_table.set("read_file", read_file_fn)?;
+    }
+
+    // -- io.write_file(path, contents) --------------------------------------
+    {
+        let ctx = ctx.clone();
+        let write_file_fn =
+            lua.create_function(move |_lua, (path, contents): (String, mlua::String)| {
+                let abs_path = ctx.resolve(&path)?;
+                let bytes = contents.as_bytes();
+                ctx.track_write(bytes.len() as u64)?;
+                ctx.track_create(&abs_path)?;
+                if let Some(parent) = abs_path.parent() {
+                    std::fs::create_dir_all(parent).map_err(mlua::Error::external)?;
+                }
+                ctx.record_touch(&path, &abs_path);
+                std::fs::write(&abs_path, &*bytes).map_err(mlua::Error::external)?;
+                Ok(true)
+            })?;
+        io_table.set("write_file", write_file_fn)?;
+    }
+
+    // -- io.append(path, contents) ------------------------------------------
+    {
+        let ctx = ctx.clone();
+        let append_fn =
+            lua.create_function(move |_lua, (path, contents): (String, mlua::String)| {
+                let abs_path = ctx.resolve(&path)?;
+                let bytes = contents.as_bytes();
+                ctx.track_write(bytes.len() as u64)?;
+                ctx.track_create(&abs_path)?;
+                if let Some(parent) = abs_path.parent() {
+                    std::fs::create_dir_all(parent).map_err(mlua::Error::external)?;
+                }
+                ctx.record_touch(&path, &abs_path);
+                let mut file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&abs_path)
+                    .map_err(mlua::Error::external)?;
+                file.write_all(&bytes).map_err(mlua::Error::external)?;
+                Ok(true)
+            })?;
+        io_table.set("append", append_fn)?;
+    }
+
     // -- io.type(obj) -------------------------------------------------------
     {
         let type_fn = lua.create_function(|lua_inner, val: Value| {
@@ -533,31 +667,33 @@ pub fn register_io(lua: &Lua, ctx: IoContext) -> Result<(), mlua::Error> {
         io_table.set("type", type_fn)?;
     }
 
-    // -- io.list(path?) -----------------------------------------------------
+    // -- io.list(path?, opts?) ----------------------------------------------
     {
         let ctx = ctx.clone();
-        let list_fn = lua.create_function(move |lua, path: Option<String>| {
-            let abs_dir = match &path {
-                Some(p) => ctx.resolve(p)?,
-                None => ctx.root.clone(),
-            };
-            if !abs_dir.is_dir() {
-                let display_path = path.as_deref().unwrap_or(".");
-                return Err(mlua::Error::external(format!(
-                    "'{display_path}' is not a directory"
-                )));
-            }
-            let entries = std::fs::read_dir(&abs_dir).map_err(mlua::Error::external)?;
-            let result = lua.create_table()?;
-            let mut idx = 1i64;
-            for entry in entries {
-                let entry = entry.map_err(mlua::Error::external)?;
-                let name = entry.file_name().to_string_lossy().to_string();
-                result.set(idx, name)?;
-                idx += 1;
-            }
-            Ok(result)
-        })?;
+        let list_fn = lua.create_function(
+            move |lua, (path, opts): (Option<String>, Option<mlua::Table>)| {
+                let abs_dir = match &path {
+                    Some(p) => ctx.resolve(p)?,
+                    None => ctx.root.clone(),
+                };
+                if !abs_dir.is_dir() {
+                    let display_path = path.as_deref().unwrap_or(".");
+                    return Err(mlua::Error::external(format!(
+                        "'{display_path}' is not a directory"
+                    )));
+                }
+                let (recursive, pattern) = match &opts {
+                    Some(t) => (
+                        t.get::<Option<bool>>("recursive")?.unwrap_or(false),
+                        t.get::<Option<String>>("pattern")?,
+                    ),
+                    None => (false, None),
+                };
+                let mut names = Vec::new();
+                list_dir(&abs_dir, "", recursive, pattern.as_deref(), &mut names)?;
+                lua.create_sequence_from(names)
+            },
+        )?;
         io_table.set("list", list_fn)?;
     }
 
@@ -603,7 +739,7 @@ mod tests {
     fn setup() -> (tempfile::TempDir, Lua) {
         let dir = tempfile::tempdir().unwrap();
         let lua = Lua::new();
-        let ctx = IoContext::new(dir.path().to_path_buf(), 50 * 1024 * 1024);
+        let ctx = IoContext::new(dir.path().to_path_buf(), 50 * 1024 * 1024, 1000);
         register_io(&lua, ctx).unwrap();
         lua.sandbox(true).unwrap();
         (dir, lua)
@@ -612,12 +748,21 @@ mod tests {
     fn setup_with_limit(max_bytes: u64) -> (tempfile::TempDir, Lua, IoContext) {
         let dir = tempfile::tempdir().unwrap();
         let lua = Lua::new();
-        let ctx = IoContext::new(dir.path().to_path_buf(), max_bytes);
+        let ctx = IoContext::new(dir.path().to_path_buf(), max_bytes, 1000);
         register_io(&lua, ctx.clone()).unwrap();
         lua.sandbox(true).unwrap();
         (dir, lua, ctx)
     }
 
+    fn setup_with_file_limit(max_files: usize) -> (tempfile::TempDir, Lua) {
+        let dir = tempfile::tempdir().unwrap();
+        let lua = Lua::new();
+        let ctx = IoContext::new(dir.path().to_path_buf(), 50 * 1024 * 1024, max_files);
+        register_io(&lua, ctx).unwrap();
+        lua.sandbox(true).unwrap();
+        (dir, lua)
+    }
+
     // Basic write + read roundtrip
     #[test]
     fn test_write_and_read_all() {
@@ -798,11 +943,11 @@ mod tests {
         std::fs::write(dir.path().join("b.txt"), "").unwrap();
         let result: Vec<String> = lua
             .load(
-                r#"
+                r"
                 local entries = io.list()
                 table.sort(entries)
                 return entries
-                "#,
+                ",
             )
             .eval::<mlua::Table>()
             .unwrap()
@@ -835,6 +980,54 @@ mod tests {
         assert_eq!(result, vec!["c.txt", "d.txt"]);
     }
 
+    // io.list() with { recursive = true }
+    #[test]
+    fn test_io_list_recursive() {
+        let (dir, lua) = setup();
+        std::fs::create_dir_all(dir.path().join("out/nested")).unwrap();
+        std::fs::write(dir.path().join("out/a.json"), "").unwrap();
+        std::fs::write(dir.path().join("out/nested/b.json"), "").unwrap();
+        let result: Vec<String> = lua
+            .load(
+                r#"
+                local entries = io.list("out", { recursive = true })
+                table.sort(entries)
+                return entries
+                "#,
+            )
+            .eval::<mlua::Table>()
+            .unwrap()
+            .sequence_values::<String>()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(result, vec!["a.json", "nested", "nested/b.json"]);
+    }
+
+    // io.list() with a glob pattern, flat and recursive
+    #[test]
+    fn test_io_list_pattern() {
+        let (dir, lua) = setup();
+        std::fs::create_dir_all(dir.path().join("reports/2024")).unwrap();
+        std::fs::write(dir.path().join("top.json"), "").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "").unwrap();
+        std::fs::write(dir.path().join("reports/q1.json"), "").unwrap();
+        std::fs::write(dir.path().join("reports/2024/q2.json"), "").unwrap();
+        std::fs::write(dir.path().join("reports/2024/q2.csv"), "").unwrap();
+        let (flat, nested): (Vec<String>, Vec<String>) = lua
+            .load(
+                r#"
+                local flat = io.list(nil, { pattern = "*.json" })
+                local nested = io.list(nil, { recursive = true, pattern = "q?.json" })
+                table.sort(nested)
+                return flat, nested
+                "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(flat, vec!["top.json"]);
+        assert_eq!(nested, vec!["reports/2024/q2.json", "reports/q1.json"]);
+    }
+
     // os.remove()
     #[test]
     fn test_os_remove() {
@@ -947,6 +1140,96 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_write_file_then_read_file() {
+        let (dir, lua) = setup();
+        let result: String = lua
+            .load(
+                r#"
+                io.write_file("out/report.txt", "line 1\nline 2")
+                return io.read_file("out/report.txt")
+                "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(result, "line 1\nline 2");
+        assert!(dir.path().join("out/report.txt").exists());
+    }
+
+    #[test]
+    fn test_write_file_enforces_write_limit() {
+        let (dir, lua, ctx) = setup_with_limit(10);
+        lua.load(r#"io.write_file("a.txt", "hello")"#)
+            .exec()
+            .unwrap();
+        let err = lua
+            .load(r#"io.write_file("b.txt", "world!")"#)
+            .exec()
+            .unwrap_err();
+        assert!(err.to_string().contains("output size limit exceeded"));
+        assert!(!dir.path().join("b.txt").exists());
+
+        let touched = ctx.collect_final_state();
+        assert_eq!(touched.len(), 1);
+        assert_eq!(touched[0].name, "a.txt");
+    }
+
+    #[test]
+    fn test_append_twice_counts_toward_write_budget() {
+        let (dir, lua, ctx) = setup_with_limit(100);
+        lua.load(
+            r#"
+            io.append("logs/run.log", "first\n")
+            io.append("logs/run.log", "second\n")
+            "#,
+        )
+        .exec()
+        .unwrap();
+
+        let content = std::fs::read_to_string(dir.path().join("logs/run.log")).unwrap();
+        assert_eq!(content, "first\nsecond\n");
+        assert_eq!(ctx.bytes_written.load(Ordering::SeqCst), 13);
+        assert_eq!(ctx.open_handles.load(Ordering::SeqCst), 0);
+
+        let touched = ctx.collect_final_state();
+        assert_eq!(touched.len(), 1);
+        assert_eq!(touched[0].name, "logs/run.log");
+    }
+
+    #[test]
+    fn test_append_enforces_write_limit() {
+        let (dir, lua, _ctx) = setup_with_limit(8);
+        lua.load(r#"io.append("a.txt", "hello")"#).exec().unwrap();
+        let err = lua
+            .load(r#"io.append("a.txt", "world")"#)
+            .exec()
+            .unwrap_err();
+        assert!(err.to_string().contains("output size limit exceeded"));
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("a.txt")).unwrap(),
+            "hello"
+        );
+    }
+
+    #[test]
+    fn test_read_file_does_not_hold_handles() {
+        let (_dir, lua) = setup();
+        let count: i64 = lua
+            .load(
+                r#"
+                io.write_file("x.txt", "x")
+                local n = 0
+                for _ = 1, 100 do
+                    n = n + #io.read_file("x.txt")
+                end
+                return n
+                "#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(count, 100);
+    }
+
     // Security: write limit
     #[test]
     fn test_enforces_write_limit() {
@@ -974,12 +1257,45 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_enforces_file_count_limit() {
+        let (dir, lua) = setup_with_file_limit(3);
+        lua.load(
+            r#"
+            io.write_file("a.txt", "a")
+            local f = io.open("b.txt", "w")
+            f:write("b")
+            f:close()
+            io.append("c.txt", "c")
+            -- Rewriting or appending to an existing file creates nothing new
+            io.write_file("a.txt", "aa")
+            io.append("b.txt", "b")
+            "#,
+        )
+        .exec()
+        .unwrap();
+
+        for script in [
+            r#"io.write_file("d.txt", "d")"#,
+            r#"io.open("d.txt", "w")"#,
+            r#"io.append("d.txt", "d")"#,
+        ] {
+            let err = lua.load(script).exec().unwrap_err();
+            assert!(
+                err.to_string()
+                    .contains("file count limit exceeded (3 files)"),
+                "unexpected error for {script}: {err}"
+            );
+        }
+        assert!(!dir.path().join("d.txt").exists());
+    }
+
     // Security: handle limit
     #[test]
     fn test_enforces_handle_limit() {
         let dir = tempfile::tempdir().unwrap();
         let lua = Lua::new();
-        let ctx = IoContext::new(dir.path().to_path_buf(), 50 * 1024 * 1024);
+        let ctx = IoContext::new(dir.path().to_path_buf(), 50 * 1024 * 1024, 1000);
         // Pre-set the handle counter to MAX_OPEN_HANDLES - 1
         ctx.open_handles
             .store(MAX_OPEN_HANDLES - 1, Ordering::SeqCst);
@@ -1064,7 +1380,7 @@ mod tests {
     fn test_collect_final_state() {
         let dir = tempfile::tempdir().unwrap();
         let lua = Lua::new();
-        let ctx = IoContext::new(dir.path().to_path_buf(), 50 * 1024 * 1024);
+        let ctx = IoContext::new(dir.path().to_path_buf(), 50 * 1024 * 1024, 1000);
         let ctx_ref = ctx.clone();
         register_io(&lua, ctx).unwrap();
         lua.sandbox(true).unwrap();
@@ -1091,7 +1407,7 @@ mod tests {
     fn test_final_state_write_then_delete() {
         let dir = tempfile::tempdir().unwrap();
         let lua = Lua::new();
-        let ctx = IoContext::new(dir.path().to_path_buf(), 50 * 1024 * 1024);
+        let ctx = IoContext::new(dir.path().to_path_buf(), 50 * 1024 * 1024, 1000);
         let ctx_ref = ctx.clone();
         register_io(&lua, ctx).unwrap();
         lua.sandbox(true).unwrap();
@@ -1119,7 +1435,7 @@ mod tests {
     fn test_handle_cleanup_on_drop() {
         let dir = tempfile::tempdir().unwrap();
         let lua = Lua::new();
-        let ctx = IoContext::new(dir.path().to_path_buf(), 50 * 1024 * 1024);
+        let ctx = IoContext::new(dir.path().to_path_buf(), 50 * 1024 * 1024, 1000);
         let ctx_ref = ctx.clone();
         register_io(&lua, ctx).unwrap();
         lua.sandbox(true).unwrap();
@@ -1,11 +1,16 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::sync::Arc;
+use std::time::Duration;
 
 use rmcp::ServiceExt;
-use rmcp::model::{CallToolRequestParams, CallToolResult, Tool};
+use rmcp::model::{
+    CallToolRequestParams, CallToolResult, ClientRequest, GetPromptRequestParams, GetPromptResult,
+    PingRequest, Prompt, Tool,
+};
 use rmcp::service::{Peer, RoleClient, RunningService, ServiceError};
 use rmcp::transport::{ConfigureCommandExt, StreamableHttpClientTransport, TokioChildProcess};
 use tokio::sync::Mutex;
+use tracing::Instrument;
 
 use crate::config::McpServerConfigEntry;
 
@@ -44,6 +49,51 @@ impl McpServerResolvedConfig {
     }
 }
 
+/// Per-execution cache of successful MCP tool results, keyed by server, tool,
+/// and arguments.
+#[derive(Clone, Debug, Default)]
+pub struct McpCallCache {
+    entries: Arc<std::sync::Mutex<HashMap<String, CallToolResult>>>,
+}
+
+impl McpCallCache {
+    /// Look up the cached result of an identical earlier call.
+    pub fn get(
+        &self,
+        server: &str,
+        tool_name: &str,
+        arguments: Option<&serde_json::Map<String, serde_json::Value>>,
+    ) -> Option<CallToolResult> {
+        let key = Self::key(server, tool_name, arguments);
+        self.entries.lock().ok()?.get(&key).cloned()
+    }
+
+    /// Remember the result of a call. Error results are not cached.
+    pub fn insert(
+        &self,
+        server: &str,
+        tool_name: &str,
+        arguments: Option<&serde_json::Map<String, serde_json::Value>>,
+        result: &CallToolResult,
+    ) {
+        if result.is_error == Some(true) {
+            return;
+        }
+        let key = Self::key(server, tool_name, arguments);
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(key, result.clone());
+        }
+    }
+
+    fn key(
+        server: &str,
+        tool_name: &str,
+        arguments: Option<&serde_json::Map<String, serde_json::Value>>,
+    ) -> String {
+        serde_json::json!([server, tool_name, arguments]).to_string()
+    }
+}
+
 /// Type-erased wrapper around the transport-specific `RunningService`.
 ///
 /// rmcp's `RunningService` is generic over the transport, so a stdio service
@@ -87,6 +137,10 @@ struct McpClientHandle {
     config: McpServerResolvedConfig,
 }
 
+/// Longest a keepalive ping may go unanswered before the server is
+/// reconnected, however long its ping interval.
+pub const KEEPALIVE_PING_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Manages connections to upstream MCP servers.
 ///
 /// Each server is identified by its config-file name (e.g. `"filesystem"`, `"remote"`).
@@ -94,12 +148,24 @@ struct McpClientHandle {
 /// appropriate server, and `call_tool` includes automatic single-retry reconnect logic.
 pub struct McpClientManager {
     clients: HashMap<String, Arc<Mutex<McpClientHandle>>>,
+    /// Names of configured servers that failed to connect (sorted).
+    failed: Vec<String>,
+    /// Per-server limit on a single tool call.
+    call_timeouts: HashMap<String, Duration>,
+    /// Per-server keepalive ping interval.
+    ping_intervals: HashMap<String, Duration>,
+    /// Connected servers whose last ping failed and that could not be reconnected.
+    unhealthy: std::sync::Mutex<BTreeSet<String>>,
 }
 
 impl std::fmt::Debug for McpClientManager {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("McpClientManager")
             .field("servers", &self.clients.keys().collect::<Vec<_>>())
+            .field("failed", &self.failed)
+            .field("call_timeouts", &self.call_timeouts)
+            .field("ping_intervals", &self.ping_intervals)
+            .field("unhealthy", &self.unhealthy)
             .finish()
     }
 }
@@ -147,32 +213,59 @@ impl McpClientManager {
     ) -> anyhow::Result<Self> {
         let futures: Vec<_> = configs
             .into_iter()
-            .map(|(name, config)| async move {
-                match connect_one(&config).await {
-                    Ok(handle) => Some((
-                        name,
-                        Arc::new(Mutex::new(McpClientHandle {
-                            service: handle,
-                            config,
-                        })),
-                    )),
-                    Err(e) => {
-                        eprintln!("MCP: failed to connect to '{name}', skipping: {e}");
-                        None
+            .map(|(name, config)| {
+                let span = tracing::info_span!("mcp_connect", server = %name);
+                async move {
+                    match connect_one(&config).await {
+                        Ok(handle) => {
+                            tracing::info!("connected");
+                            Ok((
+                                name,
+                                Arc::new(Mutex::new(McpClientHandle {
+                                    service: handle,
+                                    config,
+                                })),
+                            ))
+                        }
+                        Err(e) => {
+                            tracing::warn!("failed to connect, skipping: {e}");
+                            Err(name)
+                        }
                     }
                 }
+                .instrument(span)
             })
             .collect();
 
         let results = futures::future::join_all(futures).await;
-        let clients: HashMap<_, _> = results.into_iter().flatten().collect();
-        Ok(Self { clients })
+        let mut clients = HashMap::new();
+        let mut failed = Vec::new();
+        for result in results {
+            match result {
+                Ok((name, handle)) => {
+                    clients.insert(name, handle);
+                }
+                Err(name) => failed.push(name),
+            }
+        }
+        failed.sort();
+        Ok(Self {
+            clients,
+            failed,
+            call_timeouts: HashMap::new(),
+            ping_intervals: HashMap::new(),
+            unhealthy: std::sync::Mutex::default(),
+        })
     }
 
     /// Create an empty manager (no upstream servers).
     pub fn empty() -> Self {
         Self {
             clients: HashMap::new(),
+            failed: Vec::new(),
+            call_timeouts: HashMap::new(),
+            ping_intervals: HashMap::new(),
+            unhealthy: std::sync::Mutex::default(),
         }
     }
 
@@ -191,7 +284,38 @@ impl McpClientManager {
         };
         let mut clients = HashMap::new();
         clients.insert(name.to_string(), Arc::new(Mutex::new(handle)));
-        Self { clients }
+        Self {
+            clients,
+            failed: Vec::new(),
+            call_timeouts: HashMap::new(),
+            ping_intervals: HashMap::new(),
+            unhealthy: std::sync::Mutex::default(),
+        }
+    }
+
+    /// Limit how long a single tool call to each named server may take.
+    #[must_use]
+    pub fn with_call_timeouts(
+        mut self,
+        call_timeouts: impl IntoIterator<Item = (String, Duration)>,
+    ) -> Self {
+        self.call_timeouts = call_timeouts.into_iter().collect();
+        self
+    }
+
+    /// The configured limit on a single tool call to `server`, if any.
+    pub fn call_timeout(&self, server: &str) -> Option<Duration> {
+        self.call_timeouts.get(server).copied()
+    }
+
+    /// Ping each named server on its interval; see [`Self::spawn_keepalive`].
+    #[must_use]
+    pub fn with_ping_intervals(
+        mut self,
+        ping_intervals: impl IntoIterator<Item = (String, Duration)>,
+    ) -> Self {
+        self.ping_intervals = ping_intervals.into_iter().collect();
+        self
     }
 
     /// Returns the names of all connected servers.
@@ -199,6 +323,101 @@ impl McpClientManager {
         self.clients.keys().cloned().collect()
     }
 
+    /// Returns the names of configured servers that failed to connect or
+    /// whose keepalive ping failed without a successful reconnect, sorted.
+    pub fn failed_servers(&self) -> Vec<String> {
+        let mut failed = self.failed.clone();
+        failed.extend(
+            self.unhealthy
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .iter()
+                .cloned(),
+        );
+        failed.sort();
+        failed
+    }
+
+    fn set_healthy(&self, server: &str, healthy: bool) {
+        let mut unhealthy = self
+            .unhealthy
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if healthy {
+            unhealthy.remove(server);
+        } else {
+            unhealthy.insert(server.to_string());
+        }
+    }
+
+    /// Replace a server's connection with a fresh one from its config.
+    async fn reconnect(&self, server: &str, handle: &mut McpClientHandle) -> anyhow::Result<()> {
+        handle.service.close().await;
+        handle.service = connect_one(&handle.config).await?;
+        tracing::info!(server, "reconnected");
+        self.set_healthy(server, true);
+        Ok(())
+    }
+
+    /// Ping `server`, reconnecting if the ping fails or takes longer than
+    /// `timeout`. Returns whether the server is healthy afterwards.
+    ///
+    /// A server that cannot be reconnected is listed by
+    /// [`Self::failed_servers`] until a later check or call reconnects it.
+    pub async fn check_health(&self, server: &str, timeout: Duration) -> bool {
+        let Some(handle) = self.clients.get(server) else {
+            return false;
+        };
+        // Ping on a cloned peer so tool calls are not blocked while it waits
+        let peer = handle.lock().await.service.peer().clone();
+        let ping = peer.send_request(ClientRequest::PingRequest(PingRequest::default()));
+        let error = match tokio::time::timeout(timeout, ping).await {
+            Ok(Ok(_)) => {
+                self.set_healthy(server, true);
+                return true;
+            }
+            Ok(Err(e)) => e.to_string(),
+            Err(_) => format!("no response after {}ms", timeout.as_millis()),
+        };
+
+        tracing::warn!(server, "ping failed, reconnecting: {error}");
+        self.set_healthy(server, false);
+        let mut guard = handle.lock().await;
+        let reconnected = self.reconnect(server, &mut guard).await;
+        drop(guard);
+        reconnected
+            .inspect_err(|e| tracing::warn!(server, "reconnect failed: {e}"))
+            .is_ok()
+    }
+
+    /// Start a background task per server with a ping interval that runs
+    /// [`Self::check_health`] on that interval. The ping timeout is the
+    /// interval, capped at [`KEEPALIVE_PING_TIMEOUT`]. The tasks stop once the
+    /// manager is dropped.
+    pub fn spawn_keepalive(self: &Arc<Self>) {
+        for (server, &interval) in &self.ping_intervals {
+            if !self.clients.contains_key(server) {
+                continue;
+            }
+            let manager = Arc::downgrade(self);
+            let server = server.clone();
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(interval);
+                // The first tick fires immediately; the connection was just made.
+                ticker.tick().await;
+                loop {
+                    ticker.tick().await;
+                    let Some(manager) = manager.upgrade() else {
+                        break;
+                    };
+                    manager
+                        .check_health(&server, interval.min(KEEPALIVE_PING_TIMEOUT))
+                        .await;
+                }
+            });
+        }
+    }
+
     /// Returns true if no upstream servers are configured.
     pub fn is_empty(&self) -> bool {
         self.clients.is_empty()
@@ -238,6 +457,77 @@ impl McpClientManager {
         results.into_iter().collect()
     }
 
+    /// List prompts from a specific upstream MCP server.
+    ///
+    /// Servers that do not advertise the prompts capability have none, so
+    /// they yield an empty list without a request.
+    pub async fn list_prompts(&self, server: &str) -> anyhow::Result<Vec<Prompt>> {
+        let handle = self
+            .clients
+            .get(server)
+            .ok_or_else(|| anyhow::anyhow!("unknown MCP server: '{server}'"))?;
+        let peer = handle.lock().await.service.peer().clone();
+        let supports_prompts = peer
+            .peer_info()
+            .is_some_and(|info| info.capabilities.prompts.is_some());
+        if !supports_prompts {
+            return Ok(Vec::new());
+        }
+        peer.list_all_prompts()
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to list prompts from '{server}': {e}"))
+    }
+
+    /// List prompts from all connected upstream MCP servers concurrently.
+    ///
+    /// Returns a map from server name to its prompts. A server whose listing
+    /// fails logs a warning and is skipped, so one broken upstream does not
+    /// hide the others' prompts.
+    pub async fn list_all_prompts(&self) -> HashMap<String, Vec<Prompt>> {
+        let futures: Vec<_> = self
+            .clients
+            .keys()
+            .map(|name| async move {
+                match self.list_prompts(name).await {
+                    Ok(prompts) => Some((name.clone(), prompts)),
+                    Err(e) => {
+                        tracing::warn!(server = %name, "failed to list prompts, skipping: {e}");
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        let results = futures::future::join_all(futures).await;
+        results.into_iter().flatten().collect()
+    }
+
+    /// Fetch a prompt from a specific upstream MCP server.
+    pub async fn get_prompt(
+        &self,
+        server: &str,
+        prompt_name: &str,
+        arguments: Option<serde_json::Map<String, serde_json::Value>>,
+    ) -> anyhow::Result<GetPromptResult> {
+        let handle = self
+            .clients
+            .get(server)
+            .ok_or_else(|| anyhow::anyhow!("unknown MCP server: '{server}'"))?;
+        let params = GetPromptRequestParams {
+            meta: None,
+            name: prompt_name.to_string(),
+            arguments,
+        };
+        handle
+            .lock()
+            .await
+            .service
+            .peer()
+            .get_prompt(params)
+            .await
+            .map_err(|e| anyhow::anyhow!("get_prompt to '{server}' failed: {e}"))
+    }
+
     /// Call a tool on a specific upstream MCP server.
     ///
     /// On transport failure, attempts one reconnect then retries the call.
@@ -268,14 +558,9 @@ impl McpClientManager {
             Ok(result) => Ok(result),
             Err(e) if is_transport_error(&e) || guard.service.is_closed() => {
                 // Transport failure: attempt reconnect
-                eprintln!("MCP: reconnecting to '{server}' after transport error...");
-                let config = guard.config.clone();
-                guard.service.close().await;
-
-                match connect_one(&config).await {
-                    Ok(new_handle) => {
-                        eprintln!("MCP: reconnected to '{server}'");
-                        guard.service = new_handle;
+                tracing::warn!(server, "reconnecting after transport error");
+                match self.reconnect(server, &mut guard).await {
+                    Ok(()) => {
                         // Retry the call
                         let retry_params = CallToolRequestParams {
                             meta: None,
@@ -295,7 +580,7 @@ impl McpClientManager {
                             })
                     }
                     Err(reconnect_err) => {
-                        eprintln!("MCP: reconnect to '{server}' failed: {reconnect_err}");
+                        tracing::warn!(server, "reconnect failed: {reconnect_err}");
                         Err(anyhow::anyhow!(
                             "reconnect to '{server}' failed (original error: {e}): {reconnect_err}"
                         ))
@@ -335,6 +620,8 @@ mod tests {
             args: Some(vec!["-y".to_string(), "server-fs".to_string()]),
             env: Some(HashMap::from([("FOO".to_string(), "bar".to_string())])),
             url: None,
+            call_timeout_secs: None,
+            ping_interval_secs: None,
         };
         let resolved = McpServerResolvedConfig::from_entry(&entry).unwrap();
         match resolved {
@@ -354,6 +641,8 @@ mod tests {
             args: None,
             env: None,
             url: Some("https://mcp.example.com/mcp".to_string()),
+            call_timeout_secs: None,
+            ping_interval_secs: None,
         };
         let resolved = McpServerResolvedConfig::from_entry(&entry).unwrap();
         match resolved {
@@ -371,6 +660,8 @@ mod tests {
             args: None,
             env: None,
             url: None,
+            call_timeout_secs: None,
+            ping_interval_secs: None,
         };
         let resolved = McpServerResolvedConfig::from_entry(&entry).unwrap();
         match resolved {
@@ -389,6 +680,8 @@ mod tests {
             args: None,
             env: None,
             url: None,
+            call_timeout_secs: None,
+            ping_interval_secs: None,
         };
         assert!(McpServerResolvedConfig::from_entry(&entry).is_err());
     }
@@ -418,6 +711,20 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn get_prompt_unknown_server_errors() {
+        let manager = McpClientManager::empty();
+        let msg = manager
+            .get_prompt("nonexistent", "some_prompt", None)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(
+            msg.contains("nonexistent"),
+            "error should name the server: {msg}"
+        );
+    }
+
     #[tokio::test]
     async fn call_tool_unknown_server_errors() {
         let manager = McpClientManager::empty();
@@ -451,6 +758,7 @@ mod tests {
         // Should succeed (with warning to stderr) but have no clients
         let manager = McpClientManager::connect_all(configs).await.unwrap();
         assert!(manager.is_empty());
+        assert_eq!(manager.failed_servers(), ["bad".to_string()]);
     }
 
     #[tokio::test]
@@ -1,14 +1,62 @@
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
+use base64::Engine as _;
 use mlua::{LuaSerdeExt, MultiValue, Value};
-
-use crate::codegen::manifest::{Manifest, ParamLocation, ParamType};
-use crate::runtime::http::{AuthCredentials, AuthCredentialsMap, HttpHandler};
-use crate::runtime::mcp_client::McpClientManager;
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+
+use crate::codegen::manifest::{
+    AuthConfig, AuthScheme, FunctionDef, Manifest, ParamLocation, ParamType,
+};
+use crate::runtime::http::{
+    ApiRequest, AuthCredentials, AuthCredentialsMap, CallTrace, CallWarnings, CorrelationId,
+    HttpError, HttpHandler, RequestBody, RequestOptions, ResponseCache, ResponseFormat,
+};
+use crate::runtime::mcp_client::{McpCallCache, McpClientManager};
 use crate::runtime::sandbox::Sandbox;
 use crate::runtime::validate;
 
+/// Per-execution settings for [`register_functions`].
+#[derive(Clone, Debug, Default)]
+pub struct RegistryOptions {
+    /// Calls beyond this many fail.
+    pub max_api_calls: Option<usize>,
+    /// Sent as a header on every call unless the script passes an explicit
+    /// header param of the same name.
+    pub correlation_id: Option<CorrelationId>,
+    /// Responses larger than this fail the call.
+    pub max_response_bytes: Option<usize>,
+    /// Records each call with redacted, truncated bodies.
+    pub trace: Option<CallTrace>,
+    /// Passing a parameter the spec marks deprecated fails the call.
+    pub reject_deprecated_params: bool,
+    /// A GET identical to an earlier successful one returns the earlier
+    /// response without a network call and without counting toward
+    /// `max_api_calls`.
+    pub cache: Option<ResponseCache>,
+    /// A call (or page of an `_all` call) slower than this raises a catchable
+    /// error.
+    pub per_call_timeout: Option<Duration>,
+    /// A call whose rate limit wait would run past this fails instead of
+    /// waiting.
+    pub deadline: Option<Instant>,
+    /// A response carrying `Deprecation` or `Sunset` headers adds a warning
+    /// naming the function.
+    pub warnings: Option<CallWarnings>,
+}
+
+/// Per-execution settings for [`register_mcp_tools`].
+#[derive(Clone, Debug, Default)]
+pub struct McpToolOptions {
+    /// Calls beyond this many (counted together with API calls) fail.
+    pub max_api_calls: Option<usize>,
+    /// Calls still running at this instant are abandoned.
+    pub deadline: Option<Instant>,
+    /// Memoizes calls to read-only or idempotent tools.
+    pub cache: Option<McpCallCache>,
+}
+
 /// Register all manifest functions into the sandbox's `sdk` table.
 ///
 /// Each `FunctionDef` becomes a Lua function under `sdk.<name>` that:
@@ -18,6 +66,17 @@ use crate::runtime::validate;
 /// 4. Serializes request body
 /// 5. Makes the HTTP call
 /// 6. Returns the response as a Lua table
+///
+/// A non-2xx response raises a `{ status, body, message }` table, so scripts
+/// can `pcall` a call and branch on the status. `body` is decoded JSON when
+/// the response body parses as JSON, else the raw text.
+///
+/// See [`RegistryOptions`] for the per-execution limits and hooks applied to
+/// every call. String values for body fields declared `format: byte` are
+/// base64-encoded before sending. Each function's `_text` variant returns the
+/// response body as an undecoded string. A trailing options table may set
+/// `accept`, which sends that `Accept` header and returns a non-JSON response
+/// as text.
 #[allow(clippy::needless_pass_by_value, clippy::too_many_lines)]
 pub fn register_functions(
     sandbox: &Sandbox,
@@ -25,24 +84,34 @@ pub fn register_functions(
     handler: Arc<HttpHandler>,
     credentials: Arc<AuthCredentialsMap>,
     api_call_counter: Arc<AtomicUsize>,
-    max_api_calls: Option<usize>,
+    options: RegistryOptions,
 ) -> anyhow::Result<()> {
+    let RegistryOptions {
+        max_api_calls,
+        correlation_id,
+        max_response_bytes,
+        trace,
+        reject_deprecated_params,
+        cache,
+        per_call_timeout,
+        deadline,
+        warnings,
+    } = options;
     let lua = sandbox.lua();
     let sdk: mlua::Table = lua.globals().get("sdk")?;
+    let raise_http_errors: mlua::Function = lua.load(RAISE_HTTP_ERRORS).set_name("=sdk").eval()?;
 
-    // Build a lookup from API name -> (base_url, auth_config)
-    let api_lookup: std::collections::HashMap<
-        String,
-        (&str, Option<&crate::codegen::manifest::AuthConfig>),
-    > = manifest
-        .apis
-        .iter()
-        .map(|api| (api.name.clone(), (api.base_url.as_str(), api.auth.as_ref())))
-        .collect();
+    // Build a lookup from API name -> API config
+    let api_lookup: std::collections::HashMap<&str, &crate::codegen::manifest::ApiConfig> =
+        manifest
+            .apis
+            .iter()
+            .map(|api| (api.name.as_str(), api))
+            .collect();
 
     for func_def in &manifest.functions {
         let func_name = func_def.name.clone();
-        let (base_url, auth_config) = api_lookup.get(&func_def.api).ok_or_else(|| {
+        let api = api_lookup.get(func_def.api.as_str()).ok_or_else(|| {
             anyhow::anyhow!(
                 "function '{}' references unknown API '{}'",
                 func_name,
@@ -50,182 +119,564 @@ pub fn register_functions(
             )
         })?;
 
-        let base_url = base_url.to_string();
-        let auth_config_owned = auth_config.cloned();
-        let func_def_clone = func_def.clone();
-        let handler_clone = Arc::clone(&handler);
-        let credentials_clone = Arc::clone(&credentials);
-        let counter_clone = Arc::clone(&api_call_counter);
-        let max_calls = max_api_calls;
-
-        let lua_fn = lua.create_function(move |lua, args: MultiValue| {
-            let func_def = &func_def_clone;
-            let handler = &handler_clone;
-            let credentials = &credentials_clone;
-            let counter = &counter_clone;
-
-            // Check API call limit
-            let current_count = counter.load(Ordering::SeqCst);
-            if let Some(max) = max_calls
-                && current_count >= max
-            {
-                return Err(mlua::Error::external(anyhow::anyhow!(
-                    "API call limit exceeded (max {max} calls)",
-                )));
+        // Link-paginated operations also get an `_all` variant that follows
+        // `rel="next"` links and concatenates the pages. Every operation gets
+        // a `_text` variant returning the body undecoded, unless the spec
+        // already has a function of that name.
+        let text_name = format!("{func_name}_text");
+        let text_taken = manifest.functions.iter().any(|f| f.name == text_name);
+        for (follow_links, format) in [
+            (false, ResponseFormat::Json),
+            (true, ResponseFormat::Json),
+            (false, ResponseFormat::Text),
+        ] {
+            if follow_links && !func_def.link_paginated {
+                continue;
+            }
+            if format == ResponseFormat::Text && text_taken {
+                continue;
             }
 
-            let arg_values: Vec<Value> = args.into_iter().collect();
-
-            // Determine calling convention
-            let has_visible_params = func_def.parameters.iter().any(|p| p.frozen_value.is_none());
-            let has_body = func_def.request_body.is_some();
+            let base_url = api.base_url.clone();
+            // An operation-level override (empty for public endpoints) replaces the API's schemes
+            let auth_schemes = func_def.auth.clone().unwrap_or_else(|| api.auth.clone());
+            let constant_headers = api.constant_headers.clone();
+            let default_query = api.default_query.clone();
+            let boolean_style = api.boolean_style;
+            let byte_fields = byte_body_fields(manifest, func_def);
+            let func_def_clone = func_def.clone();
+            let handler_clone = Arc::clone(&handler);
+            let credentials_clone = Arc::clone(&credentials);
+            let counter_clone = Arc::clone(&api_call_counter);
+            let max_calls = max_api_calls;
+            let correlation_id_clone = correlation_id.clone();
+            let trace_clone = trace.clone();
+            let cache_clone = cache.clone();
+            let warnings_clone = warnings.clone();
 
-            // Extract params table based on calling convention
-            let params_table: Option<mlua::Table> = if has_visible_params {
-                match arg_values.first().cloned().unwrap_or(Value::Nil) {
-                    Value::Table(t) => Some(t),
-                    Value::Nil => None,
-                    other => {
-                        return Err(mlua::Error::external(anyhow::anyhow!(
-                            "expected table as first argument to '{}', got {}",
-                            func_def.name,
-                            other.type_name()
-                        )));
+            let lua_fn = lua.create_function(move |lua, args: MultiValue| {
+                let func_def = &func_def_clone;
+                let handler = &handler_clone;
+                let credentials = &credentials_clone;
+                let counter = &counter_clone;
+
+                let arg_values: Vec<Value> = args.into_iter().collect();
+
+                // Determine calling convention
+                let has_visible_params =
+                    func_def.parameters.iter().any(|p| p.frozen_value.is_none());
+
+                // Extract params table based on calling convention
+                let params_table: Option<mlua::Table> = if has_visible_params {
+                    match arg_values.first().cloned().unwrap_or(Value::Nil) {
+                        Value::Table(t) => Some(t),
+                        Value::Nil => None,
+                        other => {
+                            return Err(mlua::Error::external(anyhow::anyhow!(
+                                "expected table as first argument to '{}', got {}",
+                                func_def.name,
+                                other.type_name()
+                            )));
+                        }
                     }
-                }
-            } else {
-                None
-            };
+                } else {
+                    None
+                };
 
-            let body_arg_idx = usize::from(has_visible_params);
+                let body_arg_idx = usize::from(has_visible_params);
 
-            // Build path, query, and header params
-            let mut url = base_url.clone();
-            let mut path = func_def.path.clone();
-            let mut query_params: Vec<(String, String)> = Vec::new();
-            let mut header_params: Vec<(String, String)> = Vec::new();
+                // Build path, query, and header params
+                let mut url = base_url.clone();
+                let mut path = func_def.path.clone();
+                let mut query_params: Vec<(String, String)> = Vec::new();
+                let mut header_params: Vec<(String, String)> = Vec::new();
 
-            for param in &func_def.parameters {
-                let str_value = if let Some(ref frozen) = param.frozen_value {
-                    // Frozen param — use configured value directly, skip validation
-                    frozen.clone()
-                } else {
-                    // Non-frozen — extract from table by name
-                    let value: Value = params_table
-                        .as_ref()
-                        .map(|t| t.get::<Value>(param.name.as_str()))
-                        .transpose()?
-                        .unwrap_or(Value::Nil);
-
-                    if param.required && matches!(value, Value::Nil) {
-                        return Err(mlua::Error::external(anyhow::anyhow!(
-                            "missing required parameter '{}' for function '{}'",
-                            param.name,
-                            func_def.name
-                        )));
-                    }
+                for param in &func_def.parameters {
+                    let str_value = if let Some(ref frozen) = param.frozen_value {
+                        // Frozen param — use configured value directly, skip validation
+                        frozen.clone()
+                    } else {
+                        // Non-frozen — extract from table by name
+                        let value: Value = params_table
+                            .as_ref()
+                            .map(|t| t.get::<Value>(param.name.as_str()))
+                            .transpose()?
+                            .unwrap_or(Value::Nil);
+
+                        if param.required && matches!(value, Value::Nil) {
+                            return Err(mlua::Error::external(anyhow::anyhow!(
+                                "missing required parameter '{}' for function '{}'",
+                                param.name,
+                                func_def.name
+                            )));
+                        }
 
-                    if matches!(value, Value::Nil) {
-                        continue;
-                    }
+                        if matches!(value, Value::Nil) {
+                            continue;
+                        }
 
-                    let str_val = match (&param.param_type, &value) {
-                        #[allow(clippy::cast_possible_truncation)]
-                        (ParamType::Integer, Value::Number(n)) => {
-                            format!("{}", n.round() as i64)
+                        if reject_deprecated_params && param.deprecated {
+                            return Err(mlua::Error::external(anyhow::anyhow!(
+                                "parameter '{}' of function '{}' is deprecated",
+                                param.name,
+                                func_def.name
+                            )));
+                        }
+
+                        let str_val = match (&param.param_type, &value) {
+                            #[allow(clippy::cast_possible_truncation)]
+                            (ParamType::Integer, Value::Number(n)) => {
+                                format!("{}", n.round() as i64)
+                            }
+                            _ => lua_value_to_string(&value),
+                        };
+
+                        // Validate enum and format constraints
+                        validate::validate_param_value(&func_def.name, param, &str_val)?;
+
+                        // Booleans are validated as `true`/`false`, then written in the
+                        // param's or API's style
+                        match (value, param.boolean_style.or(boolean_style)) {
+                            (Value::Boolean(b), Some(style)) => style.render(b).to_string(),
+                            _ => str_val,
                         }
-                        _ => lua_value_to_string(&value),
                     };
 
-                    // Validate enum and format constraints
-                    validate::validate_param_value(&func_def.name, param, &str_val)?;
+                    match param.location {
+                        ParamLocation::Path => {
+                            let segment = utf8_percent_encode(&str_value, PATH_SEGMENT);
+                            path =
+                                path.replace(&format!("{{{}}}", param.name), &segment.to_string());
+                        }
+                        ParamLocation::Query => {
+                            query_params.push((param.name.clone(), str_value));
+                        }
+                        ParamLocation::Header => {
+                            header_params.push((param.name.clone(), str_value));
+                        }
+                    }
+                }
+
+                url.push_str(&path);
 
-                    str_val
-                };
+                // Default query params from config, unless an explicit param already set them
+                for (name, value) in &default_query {
+                    if !query_params.iter().any(|(k, _)| k == name) {
+                        query_params.push((name.clone(), value.clone()));
+                    }
+                }
 
-                match param.location {
-                    ParamLocation::Path => {
-                        path = path.replace(&format!("{{{}}}", param.name), &str_value);
+                // Constant headers from config, unless an explicit param already set them
+                for (name, value) in &constant_headers {
+                    if !header_params
+                        .iter()
+                        .any(|(k, _)| k.eq_ignore_ascii_case(name))
+                    {
+                        header_params.push((name.clone(), value.clone()));
                     }
-                    ParamLocation::Query => {
-                        query_params.push((param.name.clone(), str_value));
+                }
+
+                // Extract request body. A string passed for a non-structured
+                // content type (e.g. `text/plain`) is sent verbatim.
+                let body: Option<RequestBody> =
+                    match (&func_def.request_body, arg_values.get(body_arg_idx)) {
+                        (Some(body_def), Some(Value::String(text)))
+                            if !is_structured_content_type(&body_def.content_type) =>
+                        {
+                            Some(RequestBody::Raw {
+                                content_type: body_def.content_type.clone(),
+                                text: text.to_str()?.to_string(),
+                            })
+                        }
+                        (Some(_), Some(body_val)) if !matches!(body_val, Value::Nil) => {
+                            let body_val = encode_byte_fields(lua, body_val, &byte_fields)?;
+                            let json_body: serde_json::Value =
+                                lua.from_value(body_val).map_err(|e| {
+                                    mlua::Error::external(anyhow::anyhow!(
+                                        "failed to serialize request body: {e}",
+                                    ))
+                                })?;
+                            Some(RequestBody::Json(json_body))
+                        }
+                        // An optional body may be omitted or nil; either sends no body
+                        _ => None,
+                    };
+                let traced_body = body.as_ref().map(RequestBody::to_json);
+
+                // A trailing options table: `accept` sets the Accept header,
+                // and a non-JSON response then comes back as text
+                let options_idx = body_arg_idx + usize::from(func_def.request_body.is_some());
+                let accept: Option<String> = match arg_values.get(options_idx) {
+                    Some(Value::Table(options)) => options.get("accept")?,
+                    Some(Value::Nil) | None => None,
+                    Some(other) => {
+                        return Err(mlua::Error::external(anyhow::anyhow!(
+                            "expected options table as argument {} to '{}', got {}",
+                            options_idx + 1,
+                            func_def.name,
+                            other.type_name()
+                        )));
                     }
-                    ParamLocation::Header => {
-                        header_params.push((param.name.clone(), str_value));
+                };
+                let mut format = format;
+                if let Some(accept) = accept {
+                    header_params.retain(|(k, _)| !k.eq_ignore_ascii_case("accept"));
+                    header_params.push(("Accept".to_string(), accept));
+                    if format == ResponseFormat::Json {
+                        format = ResponseFormat::Auto;
                     }
                 }
-            }
 
-            url.push_str(&path);
+                // Get method string
+                let method = match func_def.method {
+                    crate::codegen::manifest::HttpMethod::Get => "GET",
+                    crate::codegen::manifest::HttpMethod::Post => "POST",
+                    crate::codegen::manifest::HttpMethod::Put => "PUT",
+                    crate::codegen::manifest::HttpMethod::Patch => "PATCH",
+                    crate::codegen::manifest::HttpMethod::Delete => "DELETE",
+                    crate::codegen::manifest::HttpMethod::Head => "HEAD",
+                    crate::codegen::manifest::HttpMethod::Options => "OPTIONS",
+                };
 
-            // Extract request body
-            let body: Option<serde_json::Value> = if has_body {
-                if body_arg_idx < arg_values.len() {
-                    let body_val = arg_values[body_arg_idx].clone();
-                    if matches!(body_val, Value::Nil) {
-                        None
-                    } else {
-                        let json_body: serde_json::Value =
-                            lua.from_value(body_val).map_err(|e| {
-                                mlua::Error::external(anyhow::anyhow!(
-                                    "failed to serialize request body: {e}",
-                                ))
-                            })?;
-                        Some(json_body)
-                    }
-                } else {
-                    None
+                // A repeated GET is answered from the cache without counting as a call
+                let cache = cache_clone
+                    .as_ref()
+                    .filter(|_| method == "GET" && !follow_links && format == ResponseFormat::Json);
+                if let Some(cached) = cache.and_then(|c| c.get(&url, &query_params, &header_params))
+                {
+                    let lua_value = lua.to_value(&cached).map_err(|e| {
+                        mlua::Error::external(anyhow::anyhow!(
+                            "failed to convert response to Lua: {e}"
+                        ))
+                    })?;
+                    return Ok((true, lua_value));
                 }
-            } else {
-                None
-            };
 
-            // Get method string
-            let method = match func_def.method {
-                crate::codegen::manifest::HttpMethod::Get => "GET",
-                crate::codegen::manifest::HttpMethod::Post => "POST",
-                crate::codegen::manifest::HttpMethod::Put => "PUT",
-                crate::codegen::manifest::HttpMethod::Patch => "PATCH",
-                crate::codegen::manifest::HttpMethod::Delete => "DELETE",
-            };
+                // Check API call limit
+                let current_count = counter.load(Ordering::SeqCst);
+                if let Some(max) = max_calls
+                    && current_count >= max
+                {
+                    return Err(mlua::Error::external(anyhow::anyhow!(
+                        "API call limit exceeded (max {max} calls)",
+                    )));
+                }
 
-            // Get credentials for this API
-            let api_creds = credentials
-                .get(&func_def.api)
-                .cloned()
-                .unwrap_or(AuthCredentials::None);
-
-            // Increment API call counter
-            counter.fetch_add(1, Ordering::SeqCst);
-
-            // Make the HTTP call (blocking from Lua's perspective)
-            let response = tokio::task::block_in_place(|| {
-                tokio::runtime::Handle::current().block_on(handler.request(
-                    method,
-                    &url,
-                    auth_config_owned.as_ref(),
-                    &api_creds,
-                    &query_params,
-                    &header_params,
-                    body.as_ref(),
-                ))
-            })
-            .map_err(mlua::Error::external)?;
+                // Pair each of the API's auth schemes with its credentials
+                let auth = credentials_for_schemes(credentials, &func_def.api, &auth_schemes);
 
-            // Convert JSON response to Lua value
-            let lua_value = lua.to_value(&response).map_err(|e| {
-                mlua::Error::external(anyhow::anyhow!("failed to convert response to Lua: {e}"))
-            })?;
+                // Increment API call counter
+                counter.fetch_add(1, Ordering::SeqCst);
 
-            Ok(lua_value)
-        })?;
+                // Make the HTTP call (blocking from Lua's perspective)
+                let response = tokio::task::block_in_place(|| {
+                    tokio::runtime::Handle::current().block_on(async {
+                        let mut pages = Vec::new();
+                        let (mut page_url, mut page_query) = (url.clone(), query_params.as_slice());
+                        loop {
+                            let result = handler
+                                .request_with_links(
+                                    &ApiRequest {
+                                        api: &func_def.api,
+                                        method,
+                                        url: &page_url,
+                                        auth: &auth,
+                                        query_params: page_query,
+                                        headers: &header_params,
+                                        body: body.as_ref(),
+                                    },
+                                    &RequestOptions {
+                                        correlation_id: correlation_id_clone.as_ref(),
+                                        max_response_bytes,
+                                        timeout: per_call_timeout,
+                                        deadline,
+                                        format,
+                                    },
+                                )
+                                .await;
+                            if let Some(trace) = &trace_clone {
+                                trace.record(
+                                    method,
+                                    &page_url,
+                                    &header_params,
+                                    traced_body.as_ref(),
+                                    result
+                                        .as_ref()
+                                        .map(|(json, _, _)| json)
+                                        .map_err(ToString::to_string),
+                                );
+                            }
+                            let (json, links, deprecation) = result?;
+                            if let Some(notice) = deprecation {
+                                tracing::warn!(function = %func_def.name, "{notice}");
+                                if let Some(warnings) = &warnings_clone {
+                                    warnings.push(format!("sdk.{}: {notice}", func_def.name));
+                                }
+                            }
+                            if !follow_links {
+                                return Ok(json);
+                            }
+                            pages.push(json);
+                            let Some((_, next)) = links.into_iter().find(|(rel, _)| rel == "next")
+                            else {
+                                return Ok(concat_pages(pages));
+                            };
+                            // The auth headers go to every page, so only follow
+                            // links that stay on the API's own origin
+                            if !same_origin(&next, &base_url) {
+                                anyhow::bail!(
+                                    "refusing to follow Link rel=\"next\" to {next}: \
+                                     origin differs from {base_url}"
+                                );
+                            }
+                            // Every further page counts as an API call. The next
+                            // URL already carries the query string.
+                            if let Some(max) = max_calls
+                                && counter.load(Ordering::SeqCst) >= max
+                            {
+                                anyhow::bail!("API call limit exceeded (max {max} calls)");
+                            }
+                            counter.fetch_add(1, Ordering::SeqCst);
+                            (page_url, page_query) = (next, &[]);
+                        }
+                    })
+                });
+
+                let response = match response {
+                    Ok(response) => response,
+                    Err(e) => match e.downcast_ref::<HttpError>() {
+                        Some(http_error) => {
+                            return Ok((
+                                false,
+                                http_error_table(
+                                    lua,
+                                    http_error,
+                                    func_def.error_schema.as_deref(),
+                                )?,
+                            ));
+                        }
+                        None => return Err(mlua::Error::external(e)),
+                    },
+                };
+                if let Some(cache) = cache {
+                    cache.insert(&url, &query_params, &header_params, response.clone());
+                }
 
-        sdk.set(func_def.name.as_str(), lua_fn)?;
+                // Convert JSON response to Lua value
+                let lua_value = lua.to_value(&response).map_err(|e| {
+                    mlua::Error::external(anyhow::anyhow!("failed to convert response to Lua: {e}"))
+                })?;
+
+                Ok((true, lua_value))
+            })?;
+
+            let lua_fn: mlua::Function = raise_http_errors.call(lua_fn)?;
+            let (table, name) = function_slot(lua, &sdk, &func_def.name)?;
+            let name = match (follow_links, format) {
+                (true, _) => format!("{name}_all"),
+                (false, ResponseFormat::Text) => format!("{name}_text"),
+                (false, ResponseFormat::Json | ResponseFormat::Auto) => name.to_string(),
+            };
+            table.set(name, lua_fn)?;
+        }
     }
 
     Ok(())
 }
 
+/// The table a function is registered in, and its key there. A nested name
+/// such as `petstore.list_pets` lives in `sdk.petstore`, created on first use.
+fn function_slot<'a>(
+    lua: &mlua::Lua,
+    sdk: &mlua::Table,
+    name: &'a str,
+) -> mlua::Result<(mlua::Table, &'a str)> {
+    let Some((namespace, func)) = name.split_once('.') else {
+        return Ok((sdk.clone(), name));
+    };
+    if let Some(table) = sdk.get::<Option<mlua::Table>>(namespace)? {
+        return Ok((table, func));
+    }
+    let table = lua.create_table()?;
+    sdk.set(namespace, table.clone())?;
+    Ok((table, func))
+}
+
+/// Characters percent-encoded in a path param value: everything except the
+/// RFC 3986 unreserved set, so `/`, `?`, `#` and spaces cannot alter the URL.
+/// Query params are encoded separately by the HTTP client.
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Whether `url` has the same scheme, host and port as `base_url`.
+fn same_origin(url: &str, base_url: &str) -> bool {
+    match (reqwest::Url::parse(url), reqwest::Url::parse(base_url)) {
+        (Ok(url), Ok(base)) => url.origin() == base.origin(),
+        _ => false,
+    }
+}
+
+/// Join the pages fetched by an `_all` call: array pages are concatenated,
+/// anything else is returned as an array of page bodies.
+fn concat_pages(pages: Vec<serde_json::Value>) -> serde_json::Value {
+    if pages.iter().all(serde_json::Value::is_array) {
+        pages
+            .into_iter()
+            .flat_map(|page| match page {
+                serde_json::Value::Array(items) => items,
+                _ => Vec::new(),
+            })
+            .collect()
+    } else {
+        serde_json::Value::Array(pages)
+    }
+}
+
+/// Names of the request body schema's fields declared `format: byte`.
+fn byte_body_fields(manifest: &Manifest, func_def: &FunctionDef) -> Vec<String> {
+    let Some(body) = &func_def.request_body else {
+        return Vec::new();
+    };
+    manifest
+        .schemas
+        .iter()
+        .find(|s| s.name == body.schema)
+        .map(|schema| {
+            schema
+                .fields
+                .iter()
+                .filter(|f| f.format.as_deref() == Some("byte"))
+                .map(|f| f.name.clone())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Copy a body table with the string values of `byte_fields` base64-encoded.
+/// The raw Lua string bytes are encoded, so binary data need not be UTF-8.
+fn encode_byte_fields(
+    lua: &mlua::Lua,
+    body: &Value,
+    byte_fields: &[String],
+) -> mlua::Result<Value> {
+    let Value::Table(table) = body else {
+        return Ok(body.clone());
+    };
+    if byte_fields.is_empty() {
+        return Ok(body.clone());
+    }
+    let encoded = lua.create_table()?;
+    for pair in table.pairs::<Value, Value>() {
+        let (key, value) = pair?;
+        let value = match (&key, &value) {
+            (Value::String(k), Value::String(v))
+                if byte_fields.iter().any(|f| k.as_bytes() == f.as_bytes()) =>
+            {
+                let b64 = base64::engine::general_purpose::STANDARD.encode(v.as_bytes());
+                Value::String(lua.create_string(b64)?)
+            }
+            _ => value,
+        };
+        encoded.raw_set(key, value)?;
+    }
+    Ok(Value::Table(encoded))
+}
+
+/// Wraps a Rust SDK function returning `(ok, value)` so that a failed call
+/// raises `value` as the error. Rust callbacks cannot raise a table themselves.
+const RAISE_HTTP_ERRORS: &str = r"
+local meta = { __tostring = function(err) return err.message end }
+return function(call)
+    return function(...)
+        local ok, result = call(...)
+        if not ok then
+            error(setmetatable(result, meta), 0)
+        end
+        return result
+    end
+end
+";
+
+/// Build the `{ status, body, message, retry_after?, schema? }` table raised
+/// for a non-2xx response. `schema` names the operation's documented error
+/// body schema, so a script can `validate(err.body, err.schema)`.
+fn http_error_table(
+    lua: &mlua::Lua,
+    error: &HttpError,
+    error_schema: Option<&str>,
+) -> mlua::Result<Value> {
+    let table = lua.create_table()?;
+    table.set("status", error.status)?;
+    let body = match serde_json::from_str::<serde_json::Value>(&error.body) {
+        Ok(json) => lua.to_value(&json)?,
+        Err(_) => Value::String(lua.create_string(&error.body)?),
+    };
+    table.set("body", body)?;
+    table.set("message", error.to_string())?;
+    table.set("retry_after", error.retry_after)?;
+    table.set("schema", error_schema)?;
+    Ok(Value::Table(table))
+}
+
+/// Whether a request content type carries a structured (JSON-serialized) body.
+/// JSON, form, and multipart bodies are built from a Lua table; anything else
+/// may also be given as a raw string.
+fn is_structured_content_type(content_type: &str) -> bool {
+    let content_type = content_type.to_ascii_lowercase();
+    content_type.contains("json")
+        || content_type.starts_with("application/x-www-form-urlencoded")
+        || content_type.starts_with("multipart/")
+}
+
+/// Pair each auth scheme with the credentials to send for it.
+///
+/// A scheme-specific entry (`<api>.<scheme>`) wins and is read as that
+/// scheme's secret, so a plain token works for an API key scheme too. Otherwise the API-wide
+/// entry (`<api>`) is used for the first scheme whose type it fits, so one
+/// credential never ends up in two headers.
+fn credentials_for_schemes(
+    credentials: &AuthCredentialsMap,
+    api: &str,
+    schemes: &[AuthScheme],
+) -> Vec<(AuthConfig, AuthCredentials)> {
+    let mut api_wide = credentials.get(api);
+    schemes
+        .iter()
+        .filter_map(|scheme| {
+            if let Some(creds) = credentials.get(&format!("{api}.{}", scheme.name)) {
+                let creds = match (&scheme.config, creds) {
+                    (AuthConfig::ApiKey { .. }, AuthCredentials::BearerToken(secret)) => {
+                        AuthCredentials::ApiKey(secret.clone())
+                    }
+                    (AuthConfig::Bearer { .. }, AuthCredentials::ApiKey(secret)) => {
+                        AuthCredentials::BearerToken(secret.clone())
+                    }
+                    _ => creds.clone(),
+                };
+                return Some((scheme.config.clone(), creds));
+            }
+            let creds = api_wide.filter(|creds| credentials_fit(&scheme.config, creds))?;
+            api_wide = None;
+            Some((scheme.config.clone(), creds.clone()))
+        })
+        .collect()
+}
+
+/// Whether credentials can be used for a scheme. File-backed credentials fit
+/// any scheme, since they are interpreted according to it when read.
+const fn credentials_fit(config: &AuthConfig, credentials: &AuthCredentials) -> bool {
+    matches!(
+        (config, credentials),
+        (AuthConfig::Bearer { .. }, AuthCredentials::BearerToken(_))
+            | (AuthConfig::ApiKey { .. }, AuthCredentials::ApiKey(_))
+            | (AuthConfig::Basic, AuthCredentials::Basic { .. })
+            | (_, AuthCredentials::FromFile { .. })
+    )
+}
+
 /// Convert a Lua value to a string for URL parameter encoding.
 fn lua_value_to_string(value: &Value) -> String {
     match value {
@@ -251,14 +702,25 @@ fn lua_value_to_string(value: &Value) -> String {
 /// 2. Converts Lua table to JSON object
 /// 3. Calls `McpClientManager::call_tool` via `block_in_place`
 /// 4. Converts the result content back to a Lua value
+///
+/// A call is abandoned with a catchable error once the server's configured
+/// call timeout passes or the script `deadline` is reached, whichever is first.
+/// When `cache` is set, a call to a read-only or idempotent tool identical to
+/// an earlier successful one returns that result without reaching the
+/// upstream or counting as an API call.
 #[allow(clippy::needless_pass_by_value)]
 pub fn register_mcp_tools(
     sandbox: &Sandbox,
     manifest: &Manifest,
     mcp_client: Arc<McpClientManager>,
     api_call_counter: Arc<AtomicUsize>,
-    max_api_calls: Option<usize>,
+    options: McpToolOptions,
 ) -> anyhow::Result<()> {
+    let McpToolOptions {
+        max_api_calls,
+        deadline,
+        cache,
+    } = options;
     let lua = sandbox.lua();
     let sdk: mlua::Table = lua.globals().get("sdk")?;
 
@@ -271,18 +733,10 @@ pub fn register_mcp_tools(
             let client = Arc::clone(&mcp_client);
             let counter = Arc::clone(&api_call_counter);
             let max = max_api_calls;
+            // Only calls that are safe to repeat may be answered from the cache
+            let cache = cache.clone().filter(|_| tool.idempotent);
 
             let lua_fn = lua.create_function(move |lua, args: MultiValue| {
-                // Check API call limit
-                let current = counter.load(Ordering::SeqCst);
-                if let Some(max) = max
-                    && current >= max
-                {
-                    return Err(mlua::Error::external(anyhow::anyhow!(
-                        "API call limit exceeded (max {max} calls)",
-                    )));
-                }
-
                 // Extract params table -> JSON
                 let arguments: Option<serde_json::Map<String, serde_json::Value>> =
                     match args.into_iter().next() {
@@ -306,19 +760,54 @@ pub fn register_mcp_tools(
                         }
                     };
 
+                // A repeated call is answered from the cache without counting as a call
+                if let Some(cached) = cache
+                    .as_ref()
+                    .and_then(|c| c.get(&server_name, &tool_name, arguments.as_ref()))
+                {
+                    return convert_call_tool_result(lua, &cached);
+                }
+
+                // Check API call limit
+                let current = counter.load(Ordering::SeqCst);
+                if let Some(max) = max
+                    && current >= max
+                {
+                    return Err(mlua::Error::external(anyhow::anyhow!(
+                        "API call limit exceeded (max {max} calls)",
+                    )));
+                }
+
                 // Increment counter
                 counter.fetch_add(1, Ordering::SeqCst);
 
-                // Call the MCP tool
+                // Call the MCP tool, bounded by the call timeout and the
+                // time left before the script deadline
+                let remaining = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+                let limit = match (client.call_timeout(&server_name), remaining) {
+                    (Some(timeout), Some(remaining)) => Some(timeout.min(remaining)),
+                    (timeout, remaining) => timeout.or(remaining),
+                };
+                let call = client.call_tool(&server_name, &tool_name, arguments.clone());
                 let result = tokio::task::block_in_place(|| {
-                    tokio::runtime::Handle::current().block_on(client.call_tool(
-                        &server_name,
-                        &tool_name,
-                        arguments,
-                    ))
+                    tokio::runtime::Handle::current().block_on(async {
+                        let Some(limit) = limit else {
+                            return call.await;
+                        };
+                        tokio::time::timeout(limit, call).await.unwrap_or_else(|_| {
+                            Err(anyhow::anyhow!(
+                                "MCP tool '{server_name}.{tool_name}' timed out after {}ms",
+                                limit.as_millis()
+                            ))
+                        })
+                    })
                 })
                 .map_err(mlua::Error::external)?;
 
+                if let Some(cache) = &cache {
+                    cache.insert(&server_name, &tool_name, arguments.as_ref(), &result);
+                }
+
                 // Convert result content to Lua
                 convert_call_tool_result(lua, &result)
             })?;
@@ -366,49 +855,111 @@ fn convert_call_tool_result(
         });
     }
 
-    // Extract text content from the result
-    // Content = Annotated<RawContent>, which Derefs to RawContent.
-    // RawContent::Text(RawTextContent { text, .. })
-    let texts: Vec<&str> = result
+    // One content block is returned as-is; several become an array.
+    let mut blocks = result
         .content
         .iter()
-        .filter_map(|c| c.as_text().map(|t| t.text.as_str()))
-        .collect();
-
-    if texts.len() == 1 {
-        // Return as string — user can json.decode() if needed
-        Ok(Value::String(lua.create_string(texts[0])?))
-    } else if texts.is_empty() {
-        Ok(Value::Nil)
-    } else {
-        // Multiple text items — return as array of strings
-        let table = lua.create_table()?;
-        for (i, text) in texts.iter().enumerate() {
-            table.set(i + 1, *text)?;
+        .map(|c| convert_content_block(lua, &c.raw))
+        .collect::<Result<Vec<Value>, mlua::Error>>()?;
+
+    match blocks.len() {
+        0 => Ok(Value::Nil),
+        // Text is returned as a string — user can json.decode() if needed
+        1 => Ok(blocks.remove(0)),
+        _ => Ok(Value::Table(lua.create_sequence_from(blocks)?)),
+    }
+}
+
+/// Convert one MCP content block to Lua. Text becomes a plain string; other
+/// blocks become tables tagged with `type`, e.g.
+/// `{ type = "image", mime = "image/png", data = "<base64>" }`.
+fn convert_content_block(
+    lua: &mlua::Lua,
+    content: &rmcp::model::RawContent,
+) -> Result<Value, mlua::Error> {
+    use rmcp::model::{RawContent, ResourceContents};
+
+    let table = lua.create_table()?;
+    match content {
+        RawContent::Text(text) => return Ok(Value::String(lua.create_string(&text.text)?)),
+        RawContent::Image(image) => {
+            table.set("type", "image")?;
+            table.set("mime", image.mime_type.as_str())?;
+            table.set("data", image.data.as_str())?;
+        }
+        RawContent::Audio(audio) => {
+            table.set("type", "audio")?;
+            table.set("mime", audio.mime_type.as_str())?;
+            table.set("data", audio.data.as_str())?;
+        }
+        RawContent::Resource(embedded) => {
+            table.set("type", "resource")?;
+            match &embedded.resource {
+                ResourceContents::TextResourceContents {
+                    uri,
+                    mime_type,
+                    text,
+                    ..
+                } => {
+                    table.set("uri", uri.as_str())?;
+                    table.set("mime", mime_type.as_deref())?;
+                    table.set("text", text.as_str())?;
+                }
+                ResourceContents::BlobResourceContents {
+                    uri,
+                    mime_type,
+                    blob,
+                    ..
+                } => {
+                    table.set("uri", uri.as_str())?;
+                    table.set("mime", mime_type.as_deref())?;
+                    table.set("data", blob.as_str())?;
+                }
+            }
+        }
+        RawContent::ResourceLink(link) => {
+            table.set("type", "resource_link")?;
+            table.set("uri", link.uri.as_str())?;
+            table.set("name", link.name.as_str())?;
+            table.set("mime", link.mime_type.as_deref())?;
         }
-        Ok(Value::Table(table))
     }
+    Ok(Value::Table(table))
 }
 
 #[cfg(test)]
 mod tests {
     #![allow(clippy::unwrap_used, clippy::expect_used)]
+    use std::collections::BTreeMap;
+
     use super::*;
     use crate::codegen::manifest::*;
+    use crate::runtime::http::{BodyTraceConfig, HttpClientConfig};
     use crate::runtime::sandbox::SandboxConfig;
     use std::sync::Mutex;
 
+    #[allow(clippy::too_many_lines)]
     fn test_manifest() -> Manifest {
         Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
             apis: vec![ApiConfig {
                 name: "petstore".to_string(),
                 base_url: "https://petstore.example.com/v1".to_string(),
                 description: None,
                 version: None,
-                auth: Some(AuthConfig::Bearer {
-                    header: "Authorization".to_string(),
-                    prefix: "Bearer ".to_string(),
-                }),
+                auth: vec![AuthScheme {
+                    name: "bearerAuth".to_string(),
+                    config: AuthConfig::Bearer {
+                        header: "Authorization".to_string(),
+                        prefix: "Bearer ".to_string(),
+                    },
+                    oauth2: false,
+                }],
+                constant_headers: BTreeMap::new(),
+                rate_limit: None,
+                default_query: BTreeMap::new(),
+                deprecated: false,
+                boolean_style: None,
             }],
             functions: vec![
                 FunctionDef {
@@ -430,9 +981,17 @@ mod tests {
                         enum_values: None,
                         format: None,
                         frozen_value: None,
+                        deprecated: false,
+                        example: None,
+                        enum_descriptions: None,
+                        boolean_style: None,
+                        multiple_of: None,
                     }],
                     request_body: None,
                     response_schema: None,
+                    error_schema: None,
+                    auth: None,
+                    link_paginated: false,
                 },
                 FunctionDef {
                     name: "list_pets".to_string(),
@@ -454,6 +1013,11 @@ mod tests {
                             enum_values: None,
                             format: None,
                             frozen_value: None,
+                            deprecated: false,
+                            example: None,
+                            enum_descriptions: None,
+                            boolean_style: None,
+                            multiple_of: None,
                         },
                         ParamDef {
                             name: "limit".to_string(),
@@ -465,10 +1029,18 @@ mod tests {
                             enum_values: None,
                             format: None,
                             frozen_value: None,
+                            deprecated: false,
+                            example: None,
+                            enum_descriptions: None,
+                            boolean_style: None,
+                            multiple_of: None,
                         },
                     ],
                     request_body: None,
                     response_schema: None,
+                    error_schema: None,
+                    auth: None,
+                    link_paginated: false,
                 },
                 FunctionDef {
                     name: "create_pet".to_string(),
@@ -487,10 +1059,14 @@ mod tests {
                         description: None,
                     }),
                     response_schema: None,
+                    error_schema: None,
+                    auth: None,
+                    link_paginated: false,
                 },
             ],
             schemas: vec![],
             mcp_servers: vec![],
+            generated: None,
         }
     }
 
@@ -504,7 +1080,15 @@ mod tests {
         let creds = Arc::new(AuthCredentialsMap::new());
         let counter = Arc::new(AtomicUsize::new(0));
 
-        register_functions(&sb, &manifest, handler, creds, counter, None).unwrap();
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            creds,
+            counter,
+            RegistryOptions::default(),
+        )
+        .unwrap();
 
         let result: String = sb
             .eval(
@@ -517,6 +1101,295 @@ mod tests {
         assert_eq!(result, "Fido");
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_register_nested_and_prefixed_functions() {
+        let sb = Sandbox::new(SandboxConfig::default()).unwrap();
+        let mut manifest = test_manifest();
+        for func in &mut manifest.functions {
+            func.name = match func.name.as_str() {
+                "list_pets" => "petstore.list_pets".to_string(),
+                other => format!("petstore_{other}"),
+            };
+        }
+        let handler = Arc::new(HttpHandler::mock(|_method, _url, _query, _body| {
+            Ok(serde_json::json!({"id": "123", "name": "Fido", "status": "available"}))
+        }));
+        let creds = Arc::new(AuthCredentialsMap::new());
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            creds,
+            counter,
+            RegistryOptions::default(),
+        )
+        .unwrap();
+
+        let result: String = sb
+            .eval(
+                r#"
+            sdk.petstore.list_pets()
+            local pet = sdk.petstore_get_pet({ pet_id = "123" })
+            return pet.name
+        "#,
+            )
+            .unwrap();
+        assert_eq!(result, "Fido");
+        assert!(sb.eval::<Value>("return sdk.list_pets").unwrap().is_nil());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_cached_get_hits_network_once() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let hits_clone = Arc::clone(&hits);
+
+        let sb = Sandbox::new(SandboxConfig::default()).unwrap();
+        let manifest = test_manifest();
+        let handler = Arc::new(HttpHandler::mock(move |_method, url, _query, _body| {
+            hits_clone.fetch_add(1, Ordering::SeqCst);
+            Ok(serde_json::json!({ "url": url }))
+        }));
+        let creds = Arc::new(AuthCredentialsMap::new());
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            creds,
+            Arc::clone(&counter),
+            RegistryOptions {
+                max_api_calls: Some(2),
+                cache: Some(ResponseCache::default()),
+                ..RegistryOptions::default()
+            },
+        )
+        .unwrap();
+
+        let same: bool = sb
+            .eval(
+                r#"
+            local first = sdk.get_pet({ pet_id = "1" })
+            local second = sdk.get_pet({ pet_id = "1" })
+            return first.url == second.url
+        "#,
+            )
+            .unwrap();
+        assert!(same);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+
+        // A different URL is a new call; repeats stay free even at the limit
+        sb.eval::<Value>(r#"sdk.get_pet({ pet_id = "2" })"#)
+            .unwrap();
+        sb.eval::<Value>(r#"sdk.get_pet({ pet_id = "2" })"#)
+            .unwrap();
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_link_paginated_all_follows_next() {
+        use axum::response::IntoResponse;
+
+        let app = axum::Router::new().route(
+            "/v1/pets",
+            axum::routing::get(|uri: axum::http::Uri| async move {
+                match uri.query() {
+                    Some("page=2") => axum::Json(serde_json::json!([{ "id": 3 }])).into_response(),
+                    _ => (
+                        [(
+                            "link",
+                            r#"</v1/pets?page=2>; rel="next", </v1/pets?page=2>; rel="last""#,
+                        )],
+                        axum::Json(serde_json::json!([{ "id": 1 }, { "id": 2 }])),
+                    )
+                        .into_response(),
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let mut manifest = test_manifest();
+        manifest.apis[0].base_url = format!("http://{addr}/v1");
+        for func in &mut manifest.functions {
+            func.link_paginated = func.name == "list_pets";
+        }
+        let sb = Sandbox::new(SandboxConfig::default()).unwrap();
+        let handler = Arc::new(HttpHandler::new(&HttpClientConfig::default()).unwrap());
+        let counter = Arc::new(AtomicUsize::new(0));
+        let trace = CallTrace::new(BodyTraceConfig::default());
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            Arc::new(AuthCredentialsMap::new()),
+            Arc::clone(&counter),
+            RegistryOptions {
+                trace: Some(trace.clone()),
+                ..RegistryOptions::default()
+            },
+        )
+        .unwrap();
+
+        let ids: Vec<i64> = sb
+            .eval(
+                r"
+            local ids = {}
+            for _, pet in sdk.list_pets_all() do
+                table.insert(ids, pet.id)
+            end
+            return ids
+        ",
+            )
+            .unwrap();
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+        let urls: Vec<String> = trace
+            .take()
+            .iter()
+            .map(|entry| entry["url"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            urls,
+            vec![
+                format!("http://{addr}/v1/pets"),
+                format!("http://{addr}/v1/pets?page=2"),
+            ]
+        );
+
+        // The plain function still returns a single page
+        let first: i64 = sb.eval("return #sdk.list_pets()").unwrap();
+        assert_eq!(first, 2);
+        assert!(sb.eval::<Value>("return sdk.get_pet_all").unwrap().is_nil());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_link_paginated_all_refuses_other_origin() {
+        let app = axum::Router::new().route(
+            "/v1/pets",
+            axum::routing::get(|| async {
+                (
+                    [(
+                        "link",
+                        r#"<https://collector.example.com/pets?page=2>; rel="next""#,
+                    )],
+                    axum::Json(serde_json::json!([{ "id": 1 }])),
+                )
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let mut manifest = test_manifest();
+        manifest.apis[0].base_url = format!("http://{addr}/v1");
+        for func in &mut manifest.functions {
+            func.link_paginated = func.name == "list_pets";
+        }
+        let sb = Sandbox::new(SandboxConfig::default()).unwrap();
+        let handler = Arc::new(HttpHandler::new(&HttpClientConfig::default()).unwrap());
+        let counter = Arc::new(AtomicUsize::new(0));
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            Arc::new(AuthCredentialsMap::new()),
+            Arc::clone(&counter),
+            RegistryOptions::default(),
+        )
+        .unwrap();
+
+        let err = sb
+            .eval::<Value>("return sdk.list_pets_all()")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("refusing to follow"), "{err}");
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_path_and_query_params_percent_encoded() {
+        // Echo the raw request target so the test sees the encoded URL
+        let app = axum::Router::new().fallback(|uri: axum::http::Uri| async move {
+            axum::Json(serde_json::json!(uri.to_string()))
+        });
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let mut manifest = test_manifest();
+        manifest.apis[0].base_url = format!("http://{addr}/v1");
+        let sb = Sandbox::new(SandboxConfig::default()).unwrap();
+        let handler = Arc::new(HttpHandler::new(&HttpClientConfig::default()).unwrap());
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            Arc::new(AuthCredentialsMap::new()),
+            Arc::new(AtomicUsize::new(0)),
+            RegistryOptions::default(),
+        )
+        .unwrap();
+
+        let uri: String = sb
+            .eval(r#"return sdk.get_pet({ pet_id = "a b/c" })"#)
+            .unwrap();
+        assert_eq!(uri, "/v1/pets/a%20b%2Fc");
+
+        let uri: String = sb
+            .eval(r#"return sdk.list_pets({ status = "a&b=c" })"#)
+            .unwrap();
+        assert_eq!(uri, "/v1/pets?status=a%26b%3Dc");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_head_returns_status_and_headers_without_body() {
+        let app = axum::Router::new().route(
+            "/v1/pets",
+            axum::routing::head(|| async { ([("x-total-count", "42")], "ignored body") }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let mut manifest = test_manifest();
+        manifest.apis[0].base_url = format!("http://{addr}/v1");
+        let list_pets = manifest
+            .functions
+            .iter_mut()
+            .find(|f| f.name == "list_pets")
+            .unwrap();
+        list_pets.name = "count_pets".to_string();
+        list_pets.method = HttpMethod::Head;
+
+        let sb = Sandbox::new(SandboxConfig::default()).unwrap();
+        let handler = Arc::new(HttpHandler::new(&HttpClientConfig::default()).unwrap());
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            Arc::new(AuthCredentialsMap::new()),
+            Arc::new(AtomicUsize::new(0)),
+            RegistryOptions::default(),
+        )
+        .unwrap();
+
+        let summary: String = sb
+            .eval(
+                r#"
+            local res = sdk.count_pets()
+            return res.status .. " " .. res.headers["x-total-count"] .. " " .. tostring(res.body)
+        "#,
+            )
+            .unwrap();
+        assert_eq!(summary, "200 42 nil");
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_path_param_substitution() {
         let captured_url = Arc::new(Mutex::new(String::new()));
@@ -531,7 +1404,15 @@ mod tests {
         let creds = Arc::new(AuthCredentialsMap::new());
         let counter = Arc::new(AtomicUsize::new(0));
 
-        register_functions(&sb, &manifest, handler, creds, counter, None).unwrap();
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            creds,
+            counter,
+            RegistryOptions::default(),
+        )
+        .unwrap();
 
         sb.eval::<Value>(r#"sdk.get_pet({ pet_id = "456" })"#)
             .unwrap();
@@ -554,7 +1435,15 @@ mod tests {
         let creds = Arc::new(AuthCredentialsMap::new());
         let counter = Arc::new(AtomicUsize::new(0));
 
-        register_functions(&sb, &manifest, handler, creds, counter, None).unwrap();
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            creds,
+            counter,
+            RegistryOptions::default(),
+        )
+        .unwrap();
 
         sb.eval::<Value>(r#"sdk.list_pets({ status = "available", limit = 10 })"#)
             .unwrap();
@@ -565,6 +1454,133 @@ mod tests {
         assert!(query.iter().any(|(k, v)| k == "limit" && v == "10"));
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_boolean_param_style() {
+        let captured_query = Arc::new(Mutex::new(Vec::<(String, String)>::new()));
+        let captured_query_clone = Arc::clone(&captured_query);
+        let handler = Arc::new(HttpHandler::mock(move |_method, _url, query, _body| {
+            *captured_query_clone.lock().unwrap() = query.to_vec();
+            Ok(serde_json::json!([]))
+        }));
+
+        let mut manifest = test_manifest();
+        manifest.functions[1].parameters.push(ParamDef {
+            name: "archived".to_string(),
+            location: ParamLocation::Query,
+            param_type: ParamType::Boolean,
+            required: false,
+            description: None,
+            default: None,
+            enum_values: None,
+            format: None,
+            frozen_value: None,
+            deprecated: false,
+            example: None,
+            enum_descriptions: None,
+            boolean_style: None,
+            multiple_of: None,
+        });
+
+        let archived_query = |manifest: &Manifest, script: &str| {
+            let sb = Sandbox::new(SandboxConfig::default()).unwrap();
+            register_functions(
+                &sb,
+                manifest,
+                Arc::clone(&handler) as Arc<HttpHandler>,
+                Arc::new(AuthCredentialsMap::new()),
+                Arc::new(AtomicUsize::new(0)),
+                RegistryOptions::default(),
+            )
+            .unwrap();
+            sb.eval::<Value>(script).unwrap();
+            captured_query
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|(k, _)| k == "archived")
+                .map(|(_, v)| v.clone())
+                .unwrap()
+        };
+
+        assert_eq!(
+            archived_query(&manifest, "sdk.list_pets({ archived = true })"),
+            "true"
+        );
+
+        manifest.apis[0].boolean_style = Some(BooleanStyle::Integer);
+        assert_eq!(
+            archived_query(&manifest, "sdk.list_pets({ archived = true })"),
+            "1"
+        );
+        assert_eq!(
+            archived_query(&manifest, "sdk.list_pets({ archived = false })"),
+            "0"
+        );
+
+        // A per-param style from the spec beats the API's
+        manifest.functions[1]
+            .parameters
+            .last_mut()
+            .unwrap()
+            .boolean_style = Some(BooleanStyle::YesNo);
+        assert_eq!(
+            archived_query(&manifest, "sdk.list_pets({ archived = true })"),
+            "yes"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_default_query_sent_and_explicit_param_wins() {
+        let captured_query = Arc::new(Mutex::new(Vec::<(String, String)>::new()));
+        let captured_query_clone = Arc::clone(&captured_query);
+
+        let sb = Sandbox::new(SandboxConfig::default()).unwrap();
+        let mut manifest = test_manifest();
+        manifest.apis[0].default_query = BTreeMap::from([
+            ("api-version".to_string(), "2023-01-01".to_string()),
+            ("status".to_string(), "available".to_string()),
+        ]);
+        let handler = Arc::new(HttpHandler::mock(move |_method, _url, query, _body| {
+            *captured_query_clone.lock().unwrap() = query.to_vec();
+            Ok(serde_json::json!([]))
+        }));
+        let creds = Arc::new(AuthCredentialsMap::new());
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            creds,
+            counter,
+            RegistryOptions::default(),
+        )
+        .unwrap();
+
+        sb.eval::<Value>("sdk.list_pets()").unwrap();
+        let mut query = captured_query.lock().unwrap().clone();
+        query.sort();
+        assert_eq!(
+            query,
+            vec![
+                ("api-version".to_string(), "2023-01-01".to_string()),
+                ("status".to_string(), "available".to_string()),
+            ]
+        );
+
+        sb.eval::<Value>(r#"sdk.list_pets({ status = "sold" })"#)
+            .unwrap();
+        let mut query = captured_query.lock().unwrap().clone();
+        query.sort();
+        assert_eq!(
+            query,
+            vec![
+                ("api-version".to_string(), "2023-01-01".to_string()),
+                ("status".to_string(), "sold".to_string()),
+            ]
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_missing_required_param_errors() {
         let sb = Sandbox::new(SandboxConfig::default()).unwrap();
@@ -575,7 +1591,15 @@ mod tests {
         let creds = Arc::new(AuthCredentialsMap::new());
         let counter = Arc::new(AtomicUsize::new(0));
 
-        register_functions(&sb, &manifest, handler, creds, counter, None).unwrap();
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            creds,
+            counter,
+            RegistryOptions::default(),
+        )
+        .unwrap();
 
         let result = sb.eval::<Value>("sdk.get_pet()");
         assert!(result.is_err());
@@ -586,6 +1610,183 @@ mod tests {
         );
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_deprecated_param_rejected_in_strict_mode() {
+        let mut manifest = test_manifest();
+        manifest.functions[1].parameters[0].deprecated = true;
+        let handler = Arc::new(HttpHandler::mock(|_method, _url, _query, _body| {
+            Ok(serde_json::json!([]))
+        }));
+
+        // Lenient (default): deprecated params are still sent
+        let sb = Sandbox::new(SandboxConfig::default()).unwrap();
+        register_functions(
+            &sb,
+            &manifest,
+            Arc::clone(&handler),
+            Arc::new(AuthCredentialsMap::new()),
+            Arc::new(AtomicUsize::new(0)),
+            RegistryOptions::default(),
+        )
+        .unwrap();
+        sb.eval::<Value>(r#"sdk.list_pets({ status = "sold" })"#)
+            .unwrap();
+
+        // Strict: passing the deprecated param fails, omitting it is fine
+        let sb = Sandbox::new(SandboxConfig::default()).unwrap();
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            Arc::new(AuthCredentialsMap::new()),
+            Arc::new(AtomicUsize::new(0)),
+            RegistryOptions {
+                reject_deprecated_params: true,
+                ..RegistryOptions::default()
+            },
+        )
+        .unwrap();
+        let err = sb
+            .eval::<Value>(r#"sdk.list_pets({ status = "sold" })"#)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("parameter 'status' of function 'list_pets' is deprecated"),
+            "error was: {err}"
+        );
+        sb.eval::<Value>("sdk.list_pets({ limit = 5 })").unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_text_plain_body_sent_verbatim() {
+        let captured = Arc::new(Mutex::new(
+            None::<(Vec<(String, String)>, serde_json::Value)>,
+        ));
+        let captured_clone = Arc::clone(&captured);
+
+        let mut manifest = test_manifest();
+        manifest.functions.push(FunctionDef {
+            name: "run_query".to_string(),
+            api: "petstore".to_string(),
+            tag: None,
+            method: HttpMethod::Post,
+            path: "/graphql".to_string(),
+            summary: None,
+            description: None,
+            deprecated: false,
+            parameters: vec![],
+            request_body: Some(RequestBodyDef {
+                content_type: "text/plain".to_string(),
+                schema: "unknown".to_string(),
+                required: true,
+                description: None,
+            }),
+            response_schema: None,
+            error_schema: None,
+            auth: None,
+            link_paginated: false,
+        });
+
+        let sb = Sandbox::new(SandboxConfig::default()).unwrap();
+        let handler = Arc::new(HttpHandler::mock_with_headers(
+            move |_method, _url, _query, headers, body| {
+                *captured_clone.lock().unwrap() = Some((headers.to_vec(), body.unwrap().clone()));
+                Ok(serde_json::json!({"ok": true}))
+            },
+        ));
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            Arc::new(AuthCredentialsMap::new()),
+            Arc::new(AtomicUsize::new(0)),
+            RegistryOptions::default(),
+        )
+        .unwrap();
+
+        sb.eval::<Value>(r#"sdk.run_query("{ pets { name } }")"#)
+            .unwrap();
+        let (headers, body) = captured.lock().unwrap().take().unwrap();
+        assert_eq!(body, serde_json::json!("{ pets { name } }"));
+        assert!(
+            headers.contains(&("Content-Type".to_string(), "text/plain".to_string())),
+            "headers were: {headers:?}"
+        );
+
+        // JSON operations still serialize a string argument as JSON
+        sb.eval::<Value>(r#"sdk.create_pet("rex")"#).unwrap();
+        let (headers, body) = captured.lock().unwrap().take().unwrap();
+        assert_eq!(body, serde_json::json!("rex"));
+        assert!(!headers.iter().any(|(k, _)| k == "Content-Type"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_public_operation_sends_no_auth() {
+        let captured = Arc::new(Mutex::new(Vec::<(String, Vec<(String, String)>)>::new()));
+        let captured_clone = Arc::clone(&captured);
+
+        let mut manifest = test_manifest();
+        manifest.functions.push(FunctionDef {
+            name: "health".to_string(),
+            api: "petstore".to_string(),
+            tag: None,
+            method: HttpMethod::Get,
+            path: "/health".to_string(),
+            summary: None,
+            description: None,
+            deprecated: false,
+            parameters: vec![],
+            request_body: None,
+            response_schema: None,
+            error_schema: None,
+            auth: Some(vec![]),
+            link_paginated: false,
+        });
+
+        let sb = Sandbox::new(SandboxConfig::default()).unwrap();
+        let handler = Arc::new(HttpHandler::mock_with_headers(
+            move |_method, url, _query, headers, _body| {
+                captured_clone
+                    .lock()
+                    .unwrap()
+                    .push((url.to_string(), headers.to_vec()));
+                Ok(serde_json::json!({}))
+            },
+        ));
+        let mut creds = AuthCredentialsMap::new();
+        creds.insert(
+            "petstore".to_string(),
+            AuthCredentials::BearerToken("secret".to_string()),
+        );
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            Arc::new(creds),
+            Arc::new(AtomicUsize::new(0)),
+            RegistryOptions::default(),
+        )
+        .unwrap();
+
+        sb.eval::<Value>("sdk.health()").unwrap();
+        sb.eval::<Value>(r#"sdk.get_pet({ pet_id = "1" })"#)
+            .unwrap();
+
+        let calls = captured.lock().unwrap().clone();
+        let has_auth = |headers: &[(String, String)]| {
+            headers
+                .iter()
+                .any(|(k, _)| k.eq_ignore_ascii_case("authorization"))
+        };
+        assert!(calls[0].0.ends_with("/health"));
+        assert!(
+            !has_auth(&calls[0].1),
+            "public call sent auth: {:?}",
+            calls[0].1
+        );
+        assert!(has_auth(&calls[1].1), "authenticated call lost auth");
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_optional_param_can_be_nil() {
         let sb = Sandbox::new(SandboxConfig::default()).unwrap();
@@ -596,7 +1797,15 @@ mod tests {
         let creds = Arc::new(AuthCredentialsMap::new());
         let counter = Arc::new(AtomicUsize::new(0));
 
-        register_functions(&sb, &manifest, handler, creds, counter, None).unwrap();
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            creds,
+            counter,
+            RegistryOptions::default(),
+        )
+        .unwrap();
 
         // Call with no arguments — both params are optional
         let result = sb.eval::<Value>("sdk.list_pets()");
@@ -617,7 +1826,15 @@ mod tests {
         let creds = Arc::new(AuthCredentialsMap::new());
         let counter = Arc::new(AtomicUsize::new(0));
 
-        register_functions(&sb, &manifest, handler, creds, counter, None).unwrap();
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            creds,
+            counter,
+            RegistryOptions::default(),
+        )
+        .unwrap();
 
         sb.eval::<Value>(
             r#"
@@ -633,18 +1850,116 @@ mod tests {
         assert_eq!(body["status"], "available");
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_optional_request_body_may_be_omitted() {
+        let calls = Arc::new(Mutex::new(Vec::<Option<serde_json::Value>>::new()));
+        let calls_clone = Arc::clone(&calls);
+
+        let sb = Sandbox::new(SandboxConfig::default()).unwrap();
+        let mut manifest = test_manifest();
+        let mut touch_pet = manifest.functions[2].clone();
+        touch_pet.name = "touch_pet".to_string();
+        if let Some(body) = touch_pet.request_body.as_mut() {
+            body.required = false;
+        }
+        manifest.functions.push(touch_pet);
+        let handler = Arc::new(HttpHandler::mock(move |_method, _url, _query, body| {
+            calls_clone.lock().unwrap().push(body.cloned());
+            Ok(serde_json::json!({}))
+        }));
+        let creds = Arc::new(AuthCredentialsMap::new());
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            creds,
+            counter,
+            RegistryOptions::default(),
+        )
+        .unwrap();
+
+        sb.eval::<Value>("sdk.touch_pet()").unwrap();
+        sb.eval::<Value>("sdk.touch_pet(nil)").unwrap();
+        assert_eq!(*calls.lock().unwrap(), vec![None, None]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_byte_format_body_field_base64_encoded() {
+        let captured_body = Arc::new(Mutex::new(None::<serde_json::Value>));
+        let captured_body_clone = Arc::clone(&captured_body);
+
+        let mut manifest = test_manifest();
+        manifest.schemas.push(SchemaDef {
+            name: "Pet".to_string(),
+            description: None,
+            fields: vec![
+                FieldDef {
+                    name: "name".to_string(),
+                    field_type: FieldType::String,
+                    required: true,
+                    description: None,
+                    enum_values: None,
+                    nullable: false,
+                    format: None,
+                    example: None,
+                    enum_descriptions: None,
+                },
+                FieldDef {
+                    name: "photo".to_string(),
+                    field_type: FieldType::String,
+                    required: false,
+                    description: None,
+                    enum_values: None,
+                    nullable: false,
+                    format: Some("byte".to_string()),
+                    example: None,
+                    enum_descriptions: None,
+                },
+            ],
+            discriminator: None,
+        });
+
+        let sb = Sandbox::new(SandboxConfig::default()).unwrap();
+        let handler = Arc::new(HttpHandler::mock(move |_method, _url, _query, body| {
+            *captured_body_clone.lock().unwrap() = body.cloned();
+            Ok(serde_json::json!({}))
+        }));
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            Arc::new(AuthCredentialsMap::new()),
+            Arc::new(AtomicUsize::new(0)),
+            RegistryOptions::default(),
+        )
+        .unwrap();
+
+        sb.eval::<Value>(r#"sdk.create_pet({ name = "Buddy", photo = "hi\255" })"#)
+            .unwrap();
+        let body = captured_body.lock().unwrap().take().unwrap();
+        assert_eq!(body, serde_json::json!({"name": "Buddy", "photo": "aGn/"}));
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_optional_header_param_omitted() {
         let captured_headers = Arc::new(Mutex::new(Vec::<(String, String)>::new()));
         let captured_headers_clone = Arc::clone(&captured_headers);
 
         let manifest = Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
             apis: vec![ApiConfig {
                 name: "testapi".to_string(),
                 base_url: "https://api.example.com".to_string(),
                 description: None,
                 version: None,
-                auth: None,
+                auth: vec![],
+                constant_headers: BTreeMap::new(),
+                rate_limit: None,
+                default_query: BTreeMap::new(),
+                deprecated: false,
+                boolean_style: None,
             }],
             functions: vec![FunctionDef {
                 name: "get_thing".to_string(),
@@ -666,6 +1981,11 @@ mod tests {
                         enum_values: None,
                         format: None,
                         frozen_value: None,
+                        deprecated: false,
+                        example: None,
+                        enum_descriptions: None,
+                        boolean_style: None,
+                        multiple_of: None,
                     },
                     ParamDef {
                         name: "X-Trace-ID".to_string(),
@@ -677,13 +1997,22 @@ mod tests {
                         enum_values: None,
                         format: None,
                         frozen_value: None,
+                        deprecated: false,
+                        example: None,
+                        enum_descriptions: None,
+                        boolean_style: None,
+                        multiple_of: None,
                     },
                 ],
                 request_body: None,
                 response_schema: None,
+                error_schema: None,
+                auth: None,
+                link_paginated: false,
             }],
             schemas: vec![],
             mcp_servers: vec![],
+            generated: None,
         };
 
         let sb = Sandbox::new(SandboxConfig::default()).unwrap();
@@ -696,7 +2025,15 @@ mod tests {
         let creds = Arc::new(AuthCredentialsMap::new());
         let counter = Arc::new(AtomicUsize::new(0));
 
-        register_functions(&sb, &manifest, handler, creds, counter, None).unwrap();
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            creds,
+            counter,
+            RegistryOptions::default(),
+        )
+        .unwrap();
 
         // Call with only the required path param, omit the optional header
         let result = sb.eval::<Value>(r#"sdk.get_thing({ id = "abc-123" })"#);
@@ -718,12 +2055,18 @@ mod tests {
         let captured_headers_clone = Arc::clone(&captured_headers);
 
         let manifest = Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
             apis: vec![ApiConfig {
                 name: "testapi".to_string(),
                 base_url: "https://api.example.com".to_string(),
                 description: None,
                 version: None,
-                auth: None,
+                auth: vec![],
+                constant_headers: BTreeMap::new(),
+                rate_limit: None,
+                default_query: BTreeMap::new(),
+                deprecated: false,
+                boolean_style: None,
             }],
             functions: vec![FunctionDef {
                 name: "do_thing".to_string(),
@@ -744,12 +2087,21 @@ mod tests {
                     enum_values: None,
                     format: None,
                     frozen_value: None,
+                    deprecated: false,
+                    example: None,
+                    enum_descriptions: None,
+                    boolean_style: None,
+                    multiple_of: None,
                 }],
                 request_body: None,
                 response_schema: None,
+                error_schema: None,
+                auth: None,
+                link_paginated: false,
             }],
             schemas: vec![],
             mcp_servers: vec![],
+            generated: None,
         };
 
         let sb = Sandbox::new(SandboxConfig::default()).unwrap();
@@ -762,7 +2114,15 @@ mod tests {
         let creds = Arc::new(AuthCredentialsMap::new());
         let counter = Arc::new(AtomicUsize::new(0));
 
-        register_functions(&sb, &manifest, handler, creds, counter, None).unwrap();
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            creds,
+            counter,
+            RegistryOptions::default(),
+        )
+        .unwrap();
 
         // Pass a number from Lua
         sb.eval::<Value>(r#"sdk.do_thing({ ["X-Page-Size"] = 50 })"#)
@@ -786,12 +2146,18 @@ mod tests {
         let captured_headers_clone = Arc::clone(&captured_headers);
 
         let manifest = Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
             apis: vec![ApiConfig {
                 name: "testapi".to_string(),
                 base_url: "https://api.example.com".to_string(),
                 description: None,
                 version: None,
-                auth: None,
+                auth: vec![],
+                constant_headers: BTreeMap::new(),
+                rate_limit: None,
+                default_query: BTreeMap::new(),
+                deprecated: false,
+                boolean_style: None,
             }],
             functions: vec![FunctionDef {
                 name: "do_thing".to_string(),
@@ -813,6 +2179,11 @@ mod tests {
                         enum_values: None,
                         format: None,
                         frozen_value: None,
+                        deprecated: false,
+                        example: None,
+                        enum_descriptions: None,
+                        boolean_style: None,
+                        multiple_of: None,
                     },
                     ParamDef {
                         name: "limit".to_string(),
@@ -824,13 +2195,22 @@ mod tests {
                         enum_values: None,
                         format: None,
                         frozen_value: None,
+                        deprecated: false,
+                        example: None,
+                        enum_descriptions: None,
+                        boolean_style: None,
+                        multiple_of: None,
                     },
                 ],
                 request_body: None,
                 response_schema: None,
+                error_schema: None,
+                auth: None,
+                link_paginated: false,
             }],
             schemas: vec![],
             mcp_servers: vec![],
+            generated: None,
         };
 
         let sb = Sandbox::new(SandboxConfig::default()).unwrap();
@@ -843,7 +2223,15 @@ mod tests {
         let creds = Arc::new(AuthCredentialsMap::new());
         let counter = Arc::new(AtomicUsize::new(0));
 
-        register_functions(&sb, &manifest, handler, creds, counter, None).unwrap();
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            creds,
+            counter,
+            RegistryOptions::default(),
+        )
+        .unwrap();
 
         sb.eval::<Value>(r#"sdk.do_thing({ ["X-Request-ID"] = "trace-123", limit = 10 })"#)
             .unwrap();
@@ -860,12 +2248,18 @@ mod tests {
     #[tokio::test(flavor = "multi_thread")]
     async fn test_enum_validation_rejects_invalid() {
         let manifest = Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
             apis: vec![ApiConfig {
                 name: "testapi".to_string(),
                 base_url: "https://api.example.com".to_string(),
                 description: None,
                 version: None,
-                auth: None,
+                auth: vec![],
+                constant_headers: BTreeMap::new(),
+                rate_limit: None,
+                default_query: BTreeMap::new(),
+                deprecated: false,
+                boolean_style: None,
             }],
             functions: vec![FunctionDef {
                 name: "list_items".to_string(),
@@ -886,12 +2280,21 @@ mod tests {
                     enum_values: Some(vec!["active".into(), "inactive".into()]),
                     format: None,
                     frozen_value: None,
+                    deprecated: false,
+                    example: None,
+                    enum_descriptions: None,
+                    boolean_style: None,
+                    multiple_of: None,
                 }],
                 request_body: None,
                 response_schema: None,
+                error_schema: None,
+                auth: None,
+                link_paginated: false,
             }],
             schemas: vec![],
             mcp_servers: vec![],
+            generated: None,
         };
 
         let sb = Sandbox::new(SandboxConfig::default()).unwrap();
@@ -901,7 +2304,15 @@ mod tests {
         let creds = Arc::new(AuthCredentialsMap::new());
         let counter = Arc::new(AtomicUsize::new(0));
 
-        register_functions(&sb, &manifest, handler, creds, counter, None).unwrap();
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            creds,
+            counter,
+            RegistryOptions::default(),
+        )
+        .unwrap();
 
         let result = sb.eval::<Value>(r#"sdk.list_items({ status = "deleted" })"#);
         assert!(result.is_err());
@@ -913,12 +2324,18 @@ mod tests {
     #[tokio::test(flavor = "multi_thread")]
     async fn test_format_validation_rejects_invalid_uuid() {
         let manifest = Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
             apis: vec![ApiConfig {
                 name: "testapi".to_string(),
                 base_url: "https://api.example.com".to_string(),
                 description: None,
                 version: None,
-                auth: None,
+                auth: vec![],
+                constant_headers: BTreeMap::new(),
+                rate_limit: None,
+                default_query: BTreeMap::new(),
+                deprecated: false,
+                boolean_style: None,
             }],
             functions: vec![FunctionDef {
                 name: "get_item".to_string(),
@@ -939,12 +2356,21 @@ mod tests {
                     enum_values: None,
                     format: Some("uuid".into()),
                     frozen_value: None,
+                    deprecated: false,
+                    example: None,
+                    enum_descriptions: None,
+                    boolean_style: None,
+                    multiple_of: None,
                 }],
                 request_body: None,
                 response_schema: None,
+                error_schema: None,
+                auth: None,
+                link_paginated: false,
             }],
             schemas: vec![],
             mcp_servers: vec![],
+            generated: None,
         };
 
         let sb = Sandbox::new(SandboxConfig::default()).unwrap();
@@ -954,7 +2380,15 @@ mod tests {
         let creds = Arc::new(AuthCredentialsMap::new());
         let counter = Arc::new(AtomicUsize::new(0));
 
-        register_functions(&sb, &manifest, handler, creds, counter, None).unwrap();
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            creds,
+            counter,
+            RegistryOptions::default(),
+        )
+        .unwrap();
 
         let result = sb.eval::<Value>(r#"sdk.get_item({ id = "not-a-uuid" })"#);
         assert!(result.is_err());
@@ -968,12 +2402,18 @@ mod tests {
         let captured_query_clone = Arc::clone(&captured_query);
 
         let manifest = Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
             apis: vec![ApiConfig {
                 name: "testapi".to_string(),
                 base_url: "https://api.example.com".to_string(),
                 description: None,
                 version: None,
-                auth: None,
+                auth: vec![],
+                constant_headers: BTreeMap::new(),
+                rate_limit: None,
+                default_query: BTreeMap::new(),
+                deprecated: false,
+                boolean_style: None,
             }],
             functions: vec![FunctionDef {
                 name: "list_items".to_string(),
@@ -995,6 +2435,11 @@ mod tests {
                         enum_values: None,
                         format: None,
                         frozen_value: Some("v2".to_string()),
+                        deprecated: false,
+                        example: None,
+                        enum_descriptions: None,
+                        boolean_style: None,
+                        multiple_of: None,
                     },
                     ParamDef {
                         name: "limit".to_string(),
@@ -1006,13 +2451,22 @@ mod tests {
                         enum_values: None,
                         format: None,
                         frozen_value: None,
+                        deprecated: false,
+                        example: None,
+                        enum_descriptions: None,
+                        boolean_style: None,
+                        multiple_of: None,
                     },
                 ],
                 request_body: None,
                 response_schema: None,
+                error_schema: None,
+                auth: None,
+                link_paginated: false,
             }],
             schemas: vec![],
             mcp_servers: vec![],
+            generated: None,
         };
 
         let sb = Sandbox::new(SandboxConfig::default()).unwrap();
@@ -1023,7 +2477,15 @@ mod tests {
         let creds = Arc::new(AuthCredentialsMap::new());
         let counter = Arc::new(AtomicUsize::new(0));
 
-        register_functions(&sb, &manifest, handler, creds, counter, None).unwrap();
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            creds,
+            counter,
+            RegistryOptions::default(),
+        )
+        .unwrap();
 
         // Only pass limit — api_version is frozen
         sb.eval::<Value>(r"sdk.list_items({ limit = 5 })").unwrap();
@@ -1045,12 +2507,18 @@ mod tests {
         let captured_query_clone = Arc::clone(&captured_query);
 
         let manifest = Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
             apis: vec![ApiConfig {
                 name: "testapi".to_string(),
                 base_url: "https://api.example.com".to_string(),
                 description: None,
                 version: None,
-                auth: None,
+                auth: vec![],
+                constant_headers: BTreeMap::new(),
+                rate_limit: None,
+                default_query: BTreeMap::new(),
+                deprecated: false,
+                boolean_style: None,
             }],
             functions: vec![FunctionDef {
                 name: "get_status".to_string(),
@@ -1071,12 +2539,21 @@ mod tests {
                     enum_values: None,
                     format: None,
                     frozen_value: Some("v2".to_string()),
+                    deprecated: false,
+                    example: None,
+                    enum_descriptions: None,
+                    boolean_style: None,
+                    multiple_of: None,
                 }],
                 request_body: None,
                 response_schema: None,
+                error_schema: None,
+                auth: None,
+                link_paginated: false,
             }],
             schemas: vec![],
             mcp_servers: vec![],
+            generated: None,
         };
 
         let sb = Sandbox::new(SandboxConfig::default()).unwrap();
@@ -1087,7 +2564,15 @@ mod tests {
         let creds = Arc::new(AuthCredentialsMap::new());
         let counter = Arc::new(AtomicUsize::new(0));
 
-        register_functions(&sb, &manifest, handler, creds, counter, None).unwrap();
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            creds,
+            counter,
+            RegistryOptions::default(),
+        )
+        .unwrap();
 
         // No args at all
         sb.eval::<Value>("sdk.get_status()").unwrap();
@@ -1107,12 +2592,18 @@ mod tests {
         let captured_query_clone = Arc::clone(&captured_query);
 
         let manifest = Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
             apis: vec![ApiConfig {
                 name: "testapi".to_string(),
                 base_url: "https://api.example.com".to_string(),
                 description: None,
                 version: None,
-                auth: None,
+                auth: vec![],
+                constant_headers: BTreeMap::new(),
+                rate_limit: None,
+                default_query: BTreeMap::new(),
+                deprecated: false,
+                boolean_style: None,
             }],
             functions: vec![FunctionDef {
                 name: "create_thing".to_string(),
@@ -1133,6 +2624,11 @@ mod tests {
                     enum_values: None,
                     format: None,
                     frozen_value: Some("v2".to_string()),
+                    deprecated: false,
+                    example: None,
+                    enum_descriptions: None,
+                    boolean_style: None,
+                    multiple_of: None,
                 }],
                 request_body: Some(RequestBodyDef {
                     content_type: "application/json".to_string(),
@@ -1141,9 +2637,13 @@ mod tests {
                     description: None,
                 }),
                 response_schema: None,
+                error_schema: None,
+                auth: None,
+                link_paginated: false,
             }],
             schemas: vec![],
             mcp_servers: vec![],
+            generated: None,
         };
 
         let sb = Sandbox::new(SandboxConfig::default()).unwrap();
@@ -1155,7 +2655,15 @@ mod tests {
         let creds = Arc::new(AuthCredentialsMap::new());
         let counter = Arc::new(AtomicUsize::new(0));
 
-        register_functions(&sb, &manifest, handler, creds, counter, None).unwrap();
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            creds,
+            counter,
+            RegistryOptions::default(),
+        )
+        .unwrap();
 
         // Body is the sole arg (no params table since all frozen)
         sb.eval::<Value>(r#"sdk.create_thing({ name = "Widget" })"#)
@@ -1173,6 +2681,7 @@ mod tests {
 
     fn mcp_manifest() -> Manifest {
         Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
             apis: vec![],
             functions: vec![],
             schemas: vec![],
@@ -1193,6 +2702,8 @@ mod tests {
                         }],
                         schemas: vec![],
                         output_schemas: vec![],
+                        output_type: None,
+                        idempotent: false,
                     },
                     McpToolDef {
                         name: "list_dir".to_string(),
@@ -1207,9 +2718,12 @@ mod tests {
                         }],
                         schemas: vec![],
                         output_schemas: vec![],
+                        output_type: None,
+                        idempotent: false,
                     },
                 ],
             }],
+            generated: None,
         }
     }
 
@@ -1220,7 +2734,7 @@ mod tests {
         let client = Arc::new(McpClientManager::empty());
         let counter = Arc::new(AtomicUsize::new(0));
 
-        register_mcp_tools(&sb, &manifest, client, counter, None).unwrap();
+        register_mcp_tools(&sb, &manifest, client, counter, McpToolOptions::default()).unwrap();
 
         // Verify sdk.filesystem exists and is a table
         let is_table: bool = sb
@@ -1248,7 +2762,7 @@ mod tests {
         let client = Arc::new(McpClientManager::empty());
         let counter = Arc::new(AtomicUsize::new(0));
 
-        register_mcp_tools(&sb, &manifest, client, counter, None).unwrap();
+        register_mcp_tools(&sb, &manifest, client, counter, McpToolOptions::default()).unwrap();
 
         // Calling the tool should fail since McpClientManager::empty() has no servers
         let result =
@@ -1268,7 +2782,7 @@ mod tests {
         let client = Arc::new(McpClientManager::empty());
         let counter = Arc::new(AtomicUsize::new(0));
 
-        register_mcp_tools(&sb, &manifest, client, counter, None).unwrap();
+        register_mcp_tools(&sb, &manifest, client, counter, McpToolOptions::default()).unwrap();
 
         let result = sb.eval::<Value>(r#"return sdk.filesystem.read_file("bad")"#);
         assert!(result.is_err());
@@ -1287,7 +2801,17 @@ mod tests {
         let counter = Arc::new(AtomicUsize::new(5));
 
         // Set max to 5, counter already at 5
-        register_mcp_tools(&sb, &manifest, client, counter, Some(5)).unwrap();
+        register_mcp_tools(
+            &sb,
+            &manifest,
+            client,
+            counter,
+            McpToolOptions {
+                max_api_calls: Some(5),
+                ..McpToolOptions::default()
+            },
+        )
+        .unwrap();
 
         let result =
             sb.eval::<Value>(r#"return sdk.filesystem.read_file({ path = "/tmp/test.txt" })"#);
@@ -1306,7 +2830,7 @@ mod tests {
         let client = Arc::new(McpClientManager::empty());
         let counter = Arc::new(AtomicUsize::new(0));
 
-        register_mcp_tools(&sb, &manifest, client, counter, None).unwrap();
+        register_mcp_tools(&sb, &manifest, client, counter, McpToolOptions::default()).unwrap();
 
         // nil argument should be allowed (arguments=None), but the call will fail
         // because the empty manager has no servers — that's fine, it should fail
@@ -1400,6 +2924,62 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_convert_call_tool_result_image() {
+        let lua = mlua::Lua::new();
+        let result = rmcp::model::CallToolResult {
+            content: vec![rmcp::model::Content::image(
+                "iVBORw0KGgo=".to_string(),
+                "image/png".to_string(),
+            )],
+            structured_content: None,
+            is_error: None,
+            meta: None,
+        };
+
+        let value = convert_call_tool_result(&lua, &result).unwrap();
+        match value {
+            Value::Table(t) => {
+                assert_eq!(t.get::<String>("type").unwrap(), "image");
+                assert_eq!(t.get::<String>("mime").unwrap(), "image/png");
+                assert_eq!(t.get::<String>("data").unwrap(), "iVBORw0KGgo=");
+            }
+            other => panic!("expected Table, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_convert_call_tool_result_text_with_image_and_resource() {
+        let lua = mlua::Lua::new();
+        let result = rmcp::model::CallToolResult {
+            content: vec![
+                rmcp::model::Content::text("chart attached".to_string()),
+                rmcp::model::Content::image("R0lGODlh".to_string(), "image/gif".to_string()),
+                rmcp::model::Content::resource(rmcp::model::ResourceContents::text(
+                    "a,b\n1,2",
+                    "file:///data.csv",
+                )),
+            ],
+            structured_content: None,
+            is_error: None,
+            meta: None,
+        };
+
+        let value = convert_call_tool_result(&lua, &result).unwrap();
+        let Value::Table(t) = value else {
+            panic!("expected Table, got {value:?}");
+        };
+        assert_eq!(t.raw_len(), 3);
+        assert_eq!(t.get::<String>(1).unwrap(), "chart attached");
+        let image: mlua::Table = t.get(2).unwrap();
+        assert_eq!(image.get::<String>("mime").unwrap(), "image/gif");
+        assert_eq!(image.get::<String>("data").unwrap(), "R0lGODlh");
+        let resource: mlua::Table = t.get(3).unwrap();
+        assert_eq!(resource.get::<String>("type").unwrap(), "resource");
+        assert_eq!(resource.get::<String>("uri").unwrap(), "file:///data.csv");
+        assert_eq!(resource.get::<String>("text").unwrap(), "a,b\n1,2");
+    }
+
     #[test]
     fn test_convert_call_tool_result_structured_content() {
         let lua = mlua::Lua::new();
@@ -1440,4 +3020,408 @@ mod tests {
             "Error should mention MCP tool error. Got: {err_msg}"
         );
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_constant_headers_sent_and_explicit_param_wins() {
+        let captured_headers = Arc::new(Mutex::new(Vec::<(String, String)>::new()));
+        let captured_headers_clone = Arc::clone(&captured_headers);
+
+        let manifest = Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
+            apis: vec![ApiConfig {
+                name: "testapi".to_string(),
+                base_url: "https://api.example.com".to_string(),
+                description: None,
+                version: None,
+                auth: vec![],
+                constant_headers: BTreeMap::from([
+                    ("X-Tenant-ID".to_string(), "acme".to_string()),
+                    ("X-Region".to_string(), "eu".to_string()),
+                ]),
+                rate_limit: None,
+                default_query: BTreeMap::new(),
+                deprecated: false,
+                boolean_style: None,
+            }],
+            functions: vec![FunctionDef {
+                name: "do_thing".to_string(),
+                api: "testapi".to_string(),
+                tag: None,
+                method: HttpMethod::Get,
+                path: "/things".to_string(),
+                summary: None,
+                description: None,
+                deprecated: false,
+                parameters: vec![ParamDef {
+                    name: "X-Region".to_string(),
+                    location: ParamLocation::Header,
+                    param_type: ParamType::String,
+                    required: false,
+                    description: None,
+                    default: None,
+                    enum_values: None,
+                    format: None,
+                    frozen_value: None,
+                    deprecated: false,
+                    example: None,
+                    enum_descriptions: None,
+                    boolean_style: None,
+                    multiple_of: None,
+                }],
+                request_body: None,
+                response_schema: None,
+                error_schema: None,
+                auth: None,
+                link_paginated: false,
+            }],
+            schemas: vec![],
+            mcp_servers: vec![],
+            generated: None,
+        };
+
+        let sb = Sandbox::new(SandboxConfig::default()).unwrap();
+        let handler = Arc::new(HttpHandler::mock_with_headers(
+            move |_method, _url, _query, headers, _body| {
+                *captured_headers_clone.lock().unwrap() = headers.to_vec();
+                Ok(serde_json::json!({"ok": true}))
+            },
+        ));
+        let creds = Arc::new(AuthCredentialsMap::new());
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            creds,
+            counter,
+            RegistryOptions::default(),
+        )
+        .unwrap();
+
+        sb.eval::<Value>("sdk.do_thing()").unwrap();
+        let mut headers = captured_headers.lock().unwrap().clone();
+        headers.sort();
+        assert_eq!(
+            headers,
+            vec![
+                ("X-Region".to_string(), "eu".to_string()),
+                ("X-Tenant-ID".to_string(), "acme".to_string()),
+            ]
+        );
+
+        sb.eval::<Value>(r#"sdk.do_thing({ ["X-Region"] = "us" })"#)
+            .unwrap();
+        let mut headers = captured_headers.lock().unwrap().clone();
+        headers.sort();
+        assert_eq!(
+            headers,
+            vec![
+                ("X-Region".to_string(), "us".to_string()),
+                ("X-Tenant-ID".to_string(), "acme".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dual_security_sends_both_auth_headers() {
+        let captured_headers = Arc::new(Mutex::new(Vec::<(String, String)>::new()));
+        let captured_headers_clone = Arc::clone(&captured_headers);
+
+        let manifest = Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
+            apis: vec![ApiConfig {
+                name: "billing".to_string(),
+                base_url: "https://billing.example.com".to_string(),
+                description: None,
+                version: None,
+                auth: vec![
+                    AuthScheme {
+                        name: "apiKey".to_string(),
+                        config: AuthConfig::ApiKey {
+                            header: "X-Api-Key".to_string(),
+                            location: ApiKeyLocation::Header,
+                        },
+                        oauth2: false,
+                    },
+                    AuthScheme {
+                        name: "bearer".to_string(),
+                        config: AuthConfig::Bearer {
+                            header: "Authorization".to_string(),
+                            prefix: "Bearer ".to_string(),
+                        },
+                        oauth2: false,
+                    },
+                ],
+                constant_headers: BTreeMap::new(),
+                rate_limit: None,
+                default_query: BTreeMap::new(),
+                deprecated: false,
+                boolean_style: None,
+            }],
+            functions: vec![FunctionDef {
+                name: "list_invoices".to_string(),
+                api: "billing".to_string(),
+                tag: None,
+                method: HttpMethod::Get,
+                path: "/invoices".to_string(),
+                summary: None,
+                description: None,
+                deprecated: false,
+                parameters: vec![],
+                request_body: None,
+                response_schema: None,
+                error_schema: None,
+                auth: None,
+                link_paginated: false,
+            }],
+            schemas: vec![],
+            mcp_servers: vec![],
+            generated: None,
+        };
+
+        let sb = Sandbox::new(SandboxConfig::default()).unwrap();
+        let handler = Arc::new(HttpHandler::mock_with_headers(
+            move |_method, _url, _query, headers, _body| {
+                *captured_headers_clone.lock().unwrap() = headers.to_vec();
+                Ok(serde_json::json!({"ok": true}))
+            },
+        ));
+        let mut creds = AuthCredentialsMap::new();
+        creds.insert(
+            "billing.apiKey".to_string(),
+            AuthCredentials::BearerToken("key-123".to_string()),
+        );
+        creds.insert(
+            "billing".to_string(),
+            AuthCredentials::BearerToken("tok-456".to_string()),
+        );
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            Arc::new(creds),
+            counter,
+            RegistryOptions::default(),
+        )
+        .unwrap();
+
+        sb.eval::<Value>("sdk.list_invoices()").unwrap();
+
+        let headers = captured_headers.lock().unwrap().clone();
+        assert!(
+            headers.contains(&("x-api-key".to_string(), "key-123".to_string())),
+            "API key header missing. Got: {headers:?}"
+        );
+        assert!(
+            headers.contains(&("authorization".to_string(), "Bearer tok-456".to_string())),
+            "Bearer header missing. Got: {headers:?}"
+        );
+    }
+
+    fn dual_security_manifest(first: AuthConfig, second: AuthConfig) -> Manifest {
+        let scheme = |name: &str, config| AuthScheme {
+            name: name.to_string(),
+            config,
+            oauth2: false,
+        };
+        Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
+            apis: vec![ApiConfig {
+                name: "billing".to_string(),
+                base_url: "https://billing.example.com".to_string(),
+                description: None,
+                version: None,
+                auth: vec![scheme("first", first), scheme("second", second)],
+                constant_headers: BTreeMap::new(),
+                rate_limit: None,
+                default_query: BTreeMap::new(),
+                deprecated: false,
+                boolean_style: None,
+            }],
+            functions: vec![FunctionDef {
+                name: "list_invoices".to_string(),
+                api: "billing".to_string(),
+                tag: None,
+                method: HttpMethod::Get,
+                path: "/invoices".to_string(),
+                summary: None,
+                description: None,
+                deprecated: false,
+                parameters: vec![],
+                request_body: None,
+                response_schema: None,
+                error_schema: None,
+                auth: None,
+                link_paginated: false,
+            }],
+            schemas: vec![],
+            mcp_servers: vec![],
+            generated: None,
+        }
+    }
+
+    /// Query pairs and headers a mock handler received.
+    type CapturedRequest = (Vec<(String, String)>, Vec<(String, String)>);
+
+    /// Run `sdk.list_invoices()` against `manifest` and return what the mock
+    /// received.
+    fn capture_dual_security_request(
+        manifest: &Manifest,
+        first: AuthCredentials,
+        second: AuthCredentials,
+    ) -> CapturedRequest {
+        let captured = Arc::new(Mutex::new(CapturedRequest::default()));
+        let captured_clone = Arc::clone(&captured);
+
+        let sb = Sandbox::new(SandboxConfig::default()).unwrap();
+        let handler = Arc::new(HttpHandler::mock_with_headers(
+            move |_method, _url, query, headers, _body| {
+                *captured_clone.lock().unwrap() = (query.to_vec(), headers.to_vec());
+                Ok(serde_json::json!({"ok": true}))
+            },
+        ));
+        let mut creds = AuthCredentialsMap::new();
+        creds.insert("billing.first".to_string(), first);
+        creds.insert("billing.second".to_string(), second);
+
+        register_functions(
+            &sb,
+            manifest,
+            handler,
+            Arc::new(creds),
+            Arc::new(AtomicUsize::new(0)),
+            RegistryOptions::default(),
+        )
+        .unwrap();
+        sb.eval::<Value>("sdk.list_invoices()").unwrap();
+
+        captured.lock().unwrap().clone()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dual_security_sends_query_key_and_bearer() {
+        let manifest = dual_security_manifest(
+            AuthConfig::ApiKey {
+                header: "api_key".to_string(),
+                location: ApiKeyLocation::Query,
+            },
+            AuthConfig::Bearer {
+                header: "Authorization".to_string(),
+                prefix: "Bearer ".to_string(),
+            },
+        );
+        let (query, headers) = capture_dual_security_request(
+            &manifest,
+            AuthCredentials::ApiKey("key-123".to_string()),
+            AuthCredentials::BearerToken("tok-456".to_string()),
+        );
+
+        assert_eq!(query, vec![("api_key".to_string(), "key-123".to_string())]);
+        assert!(
+            headers.contains(&("authorization".to_string(), "Bearer tok-456".to_string())),
+            "Bearer header missing. Got: {headers:?}"
+        );
+        assert!(
+            !headers.iter().any(|(name, _)| name == "api_key"),
+            "query key leaked into headers. Got: {headers:?}"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dual_security_sends_cookie_key_and_header_key() {
+        let manifest = dual_security_manifest(
+            AuthConfig::ApiKey {
+                header: "session".to_string(),
+                location: ApiKeyLocation::Cookie,
+            },
+            AuthConfig::ApiKey {
+                header: "X-Api-Key".to_string(),
+                location: ApiKeyLocation::Header,
+            },
+        );
+        let (query, headers) = capture_dual_security_request(
+            &manifest,
+            AuthCredentials::ApiKey("sess-123".to_string()),
+            AuthCredentials::ApiKey("key-456".to_string()),
+        );
+
+        assert!(query.is_empty(), "unexpected query pairs: {query:?}");
+        assert!(
+            headers.contains(&("cookie".to_string(), "session=sess-123".to_string())),
+            "Cookie header missing. Got: {headers:?}"
+        );
+        assert!(
+            headers.contains(&("x-api-key".to_string(), "key-456".to_string())),
+            "API key header missing. Got: {headers:?}"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_oauth2_spec_accepts_bearer_credential() {
+        let yaml = r#"
+openapi: "3.0.3"
+info:
+  title: Secure
+  version: "1.0.0"
+servers:
+  - url: https://secure.example.com
+security:
+  - oauth: []
+paths:
+  /me:
+    get:
+      operationId: getMe
+      responses:
+        "200":
+          description: OK
+components:
+  securitySchemes:
+    oauth:
+      type: oauth2
+      flows:
+        authorizationCode:
+          authorizationUrl: https://auth.example.com/authorize
+          tokenUrl: https://auth.example.com/token
+          scopes: {}
+"#;
+        let spec: openapiv3::OpenAPI = serde_yaml::from_str(yaml).unwrap();
+        let manifest = crate::codegen::parser::spec_to_manifest(&spec, "secure").unwrap();
+
+        let captured_headers = Arc::new(Mutex::new(Vec::<(String, String)>::new()));
+        let captured_headers_clone = Arc::clone(&captured_headers);
+        let sb = Sandbox::new(SandboxConfig::default()).unwrap();
+        let handler = Arc::new(HttpHandler::mock_with_headers(
+            move |_method, _url, _query, headers, _body| {
+                *captured_headers_clone.lock().unwrap() = headers.to_vec();
+                Ok(serde_json::json!({"ok": true}))
+            },
+        ));
+        let mut creds = AuthCredentialsMap::new();
+        creds.insert(
+            "secure".to_string(),
+            AuthCredentials::BearerToken("access-789".to_string()),
+        );
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        register_functions(
+            &sb,
+            &manifest,
+            handler,
+            Arc::new(creds),
+            counter,
+            RegistryOptions::default(),
+        )
+        .unwrap();
+
+        sb.eval::<Value>("sdk.get_me()").unwrap();
+
+        let headers = captured_headers.lock().unwrap().clone();
+        assert!(
+            headers.contains(&("authorization".to_string(), "Bearer access-789".to_string())),
+            "Bearer header missing. Got: {headers:?}"
+        );
+    }
 }
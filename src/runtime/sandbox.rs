@@ -1,26 +1,81 @@
+use std::cell::Cell;
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use mlua::{FromLua, Lua, MultiValue, Value};
+use mlua::{FromLua, Lua, MultiValue, Table, Value, VmState};
 
 /// Configuration for the Lua sandbox.
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct SandboxConfig {
     /// Maximum memory the Lua VM may allocate (in bytes). Default: 64 MB.
     pub memory_limit: Option<usize>,
+    /// Maximum total bytes of captured log output. Default: 1 MB.
+    pub max_log_bytes: Option<usize>,
+    /// Environment variables `os.getenv` may read. Every other name returns nil.
+    /// Default: none.
+    pub env_allowlist: Vec<String>,
 }
 
 impl Default for SandboxConfig {
     fn default() -> Self {
         Self {
             memory_limit: Some(64 * 1024 * 1024),
+            max_log_bytes: Some(1024 * 1024),
+            env_allowlist: Vec::new(),
         }
     }
 }
 
+/// Captured `print()` and `log.*()` output, capped at a total byte size.
+struct LogBuffer {
+    lines: Vec<String>,
+    bytes: usize,
+    max_bytes: Option<usize>,
+    truncated: bool,
+}
+
+impl LogBuffer {
+    const fn new(max_bytes: Option<usize>) -> Self {
+        Self {
+            lines: Vec::new(),
+            bytes: 0,
+            max_bytes,
+            truncated: false,
+        }
+    }
+
+    /// Append a line, or drop it once the byte cap has been reached. The first
+    /// dropped line is replaced by a single truncation marker.
+    fn push(&mut self, line: String) {
+        if self.truncated {
+            return;
+        }
+        if let Some(max) = self.max_bytes
+            && self.bytes + line.len() > max
+        {
+            self.truncated = true;
+            self.lines
+                .push(format!("[log output truncated: exceeded {max} bytes]"));
+            return;
+        }
+        self.bytes += line.len();
+        self.lines.push(line);
+    }
+
+    fn take(&mut self) -> Vec<String> {
+        self.bytes = 0;
+        self.truncated = false;
+        std::mem::take(&mut self.lines)
+    }
+}
+
 /// A locked-down Luau environment using native sandbox mode.
 pub struct Sandbox {
     lua: Lua,
-    logs: Arc<Mutex<Vec<String>>>,
+    logs: Arc<Mutex<LogBuffer>>,
+    /// Wall-clock deadline from `set_limits`; `retry` will not sleep past it.
+    deadline: Arc<Mutex<Option<Instant>>>,
 }
 
 impl Sandbox {
@@ -28,8 +83,8 @@ impl Sandbox {
     ///
     /// Uses Luau's native sandbox mode which makes all globals and metatables
     /// read-only, creates isolated per-script environments, and restricts
-    /// `collectgarbage`. Custom `print()`, `json`, and `sdk` globals are
-    /// injected before sandboxing activates.
+    /// `collectgarbage`. Custom `print()`, `json`, `time`, `str`, `tbl`, `retry`, and
+    /// `sdk` globals are injected before sandboxing activates.
     pub fn new(config: SandboxConfig) -> anyhow::Result<Self> {
         let lua = Lua::new();
 
@@ -38,28 +93,41 @@ impl Sandbox {
             lua.set_memory_limit(limit)?;
         }
 
-        // Shared log buffer for captured print output
-        let logs: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+        // Shared log buffer for captured print/log output
+        let logs = Arc::new(Mutex::new(LogBuffer::new(config.max_log_bytes)));
 
         // Override print() to capture output
-        let logs_clone = Arc::clone(&logs);
-        let print_fn = lua.create_function(move |_, args: MultiValue| {
-            let parts: Vec<String> = args.iter().map(format_lua_value).collect();
-            let line = parts.join("\t");
-            if let Ok(mut logs) = logs_clone.lock() {
-                logs.push(line);
-            }
-            Ok(())
-        })?;
-        lua.globals().set("print", print_fn)?;
+        lua.globals()
+            .set("print", create_log_fn(&lua, Arc::clone(&logs), None)?)?;
+
+        // Add log.info(), log.warn(), log.error() — captured like print() with a level prefix
+        let log_table = lua.create_table()?;
+        for level in ["info", "warn", "error"] {
+            log_table.set(level, create_log_fn(&lua, Arc::clone(&logs), Some(level))?)?;
+        }
+        lua.globals().set("log", log_table)?;
 
         // Add json.encode() and json.decode() — Rust-backed via serde
         let json_table = lua.create_table()?;
 
-        let encode_fn = lua.create_function(|lua, value: Value| {
+        let encode_fn = lua.create_function(|lua, (value, opts): (Value, Option<Table>)| {
             use mlua::LuaSerdeExt;
-            let json_value: serde_json::Value = lua.from_value(value)?;
-            serde_json::to_string(&json_value).map_err(mlua::Error::external)
+            let mut json_value: serde_json::Value = lua.from_value(value)?;
+            let (pretty, sort_keys) = match opts {
+                Some(opts) => (
+                    opts.get::<Option<bool>>("pretty")?.unwrap_or(false),
+                    opts.get::<Option<bool>>("sort_keys")?.unwrap_or(false),
+                ),
+                None => (false, false),
+            };
+            if sort_keys {
+                json_value = sort_json_keys(json_value);
+            }
+            if pretty {
+                serde_json::to_string_pretty(&json_value).map_err(mlua::Error::external)
+            } else {
+                serde_json::to_string(&json_value).map_err(mlua::Error::external)
+            }
         })?;
         json_table.set("encode", encode_fn)?;
 
@@ -73,6 +141,27 @@ impl Sandbox {
 
         lua.globals().set("json", json_table)?;
 
+        // Add time.* — pure date helpers over UTC unix timestamps
+        lua.globals().set("time", create_time_table(&lua)?)?;
+
+        // Add str.* — string helpers kept apart from the standard `string` library
+        lua.globals().set("str", create_str_table(&lua)?)?;
+
+        // Add tbl.* — deep merge/clone and key/value listing for plain tables
+        lua.globals().set("tbl", create_tbl_table(&lua)?)?;
+
+        // Add url.* — percent-encoding plus parsing and building of URLs
+        lua.globals().set("url", create_url_table(&lua)?)?;
+
+        // Add retry() — re-run a function until it succeeds, sleeping between attempts
+        let deadline = Arc::new(Mutex::new(None));
+        lua.globals()
+            .set("retry", create_retry_fn(&lua, Arc::clone(&deadline))?)?;
+
+        // Add os.getenv() — reads only allowlisted variables, so secrets stay hidden
+        let os_table: Table = lua.globals().get("os")?;
+        os_table.set("getenv", create_getenv_fn(&lua, config.env_allowlist)?)?;
+
         // Create empty sdk table (will be populated by registry)
         let sdk_table = lua.create_table()?;
         lua.globals().set("sdk", sdk_table)?;
@@ -81,7 +170,11 @@ impl Sandbox {
         // do not want scripts loading external modules.
         lua.globals().set("require", Value::Nil)?;
 
-        Ok(Self { lua, logs })
+        Ok(Self {
+            lua,
+            logs,
+            deadline,
+        })
     }
 
     /// Evaluate a Lua script and return the result.
@@ -108,6 +201,37 @@ impl Sandbox {
         Ok(())
     }
 
+    /// Install execution limits enforced from the Luau interrupt callback.
+    ///
+    /// Luau calls the interrupt at function calls and loop back-edges, so
+    /// `max_instructions` bounds the number of those checkpoints rather than
+    /// individual bytecode instructions. That is enough to stop a tight
+    /// `while true do end` loop without waiting for the wall-clock `deadline`.
+    pub fn set_limits(&self, deadline: Instant, max_instructions: Option<u64>) {
+        if let Ok(mut slot) = self.deadline.lock() {
+            *slot = Some(deadline);
+        }
+        let steps = Cell::new(0u64);
+        self.lua.set_interrupt(move |_lua| {
+            if let Some(max) = max_instructions {
+                let count = steps.get() + 1;
+                steps.set(count);
+                if count > max {
+                    return Err(mlua::Error::external(anyhow::anyhow!(
+                        "script exceeded the instruction limit of {max}"
+                    )));
+                }
+            }
+            if Instant::now() >= deadline {
+                Err(mlua::Error::external(anyhow::anyhow!(
+                    "script execution timed out"
+                )))
+            } else {
+                Ok(VmState::Continue)
+            }
+        });
+    }
+
     /// Access the raw Lua state (for registry to add functions).
     pub const fn lua(&self) -> &Lua {
         &self.lua
@@ -118,7 +242,469 @@ impl Sandbox {
         let Ok(mut logs) = self.logs.lock() else {
             return Vec::new();
         };
-        std::mem::take(&mut *logs)
+        logs.take()
+    }
+}
+
+/// `retry(fn, opts?)`, written in Lua so that the final error value (e.g. an
+/// HTTP error table) propagates unchanged. Receives the Rust sleep helper.
+const RETRY_LUA: &str = r#"
+local sleep = ...
+return function(fn, opts)
+    if type(fn) ~= "function" then
+        error("retry: expected a function", 2)
+    end
+    opts = opts or {}
+    local attempts = opts.attempts or 3
+    local delay_ms = opts.delay_ms or 0
+    local backoff = opts.backoff or 1
+    if type(attempts) ~= "number" or attempts < 1 or attempts % 1 ~= 0 then
+        error("retry: attempts must be a positive integer", 2)
+    end
+    if type(delay_ms) ~= "number" or delay_ms < 0 then
+        error("retry: delay_ms must be a non-negative number", 2)
+    end
+    if type(backoff) ~= "number" or backoff < 1 then
+        error("retry: backoff must be a number >= 1", 2)
+    end
+    local attempt = 1
+    while true do
+        local results = table.pack(pcall(fn))
+        if results[1] then
+            return table.unpack(results, 2, results.n)
+        end
+        if attempt >= attempts then
+            error(results[2], 0)
+        end
+        sleep(delay_ms)
+        delay_ms *= backoff
+        attempt += 1
+    end
+end
+"#;
+
+/// Create the `retry` global. Sleeps between attempts count against the
+/// script's wall-clock deadline: a sleep that would overrun it fails the
+/// script with a timeout instead.
+fn create_retry_fn(lua: &Lua, deadline: Arc<Mutex<Option<Instant>>>) -> mlua::Result<Value> {
+    let sleep_fn = lua.create_function(move |_, delay_ms: f64| {
+        let delay = Duration::try_from_secs_f64(delay_ms / 1000.0)
+            .map_err(|e| mlua::Error::external(anyhow::anyhow!("retry: invalid delay: {e}")))?;
+        let deadline = deadline.lock().ok().and_then(|slot| *slot);
+        if deadline.is_some_and(|deadline| Instant::now() + delay >= deadline) {
+            return Err(mlua::Error::external(anyhow::anyhow!(
+                "script execution timed out"
+            )));
+        }
+        std::thread::sleep(delay);
+        Ok(())
+    })?;
+    lua.load(RETRY_LUA).set_name("=retry").call(sleep_fn)
+}
+
+/// Create a Lua function that joins its arguments with tabs and appends the
+/// line to the log buffer, prefixed with `[level]` when a level is given.
+/// Build `os.getenv(name)`. Allowlisted values are captured when the sandbox is
+/// created, so repeated reads within one script always agree.
+fn create_getenv_fn(lua: &Lua, allowlist: Vec<String>) -> mlua::Result<mlua::Function> {
+    let env: HashMap<String, String> = allowlist
+        .into_iter()
+        .filter_map(|name| std::env::var(&name).ok().map(|value| (name, value)))
+        .collect();
+    lua.create_function(move |_, name: String| Ok(env.get(&name).cloned()))
+}
+
+fn create_log_fn(
+    lua: &Lua,
+    logs: Arc<Mutex<LogBuffer>>,
+    level: Option<&'static str>,
+) -> mlua::Result<mlua::Function> {
+    lua.create_function(move |_, args: MultiValue| {
+        let parts: Vec<String> = args.iter().map(format_lua_value).collect();
+        let mut line = parts.join("\t");
+        if let Some(level) = level {
+            line = format!("[{level}] {line}");
+        }
+        if let Ok(mut logs) = logs.lock() {
+            logs.push(line);
+        }
+        Ok(())
+    })
+}
+
+/// Build the `time` global: `now`, `now_iso`, `format`, `parse_iso`, `add`.
+///
+/// Timestamps are integer unix seconds and all formatting is in UTC.
+fn create_time_table(lua: &Lua) -> mlua::Result<Table> {
+    use chrono::format::{Item, StrftimeItems};
+    use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+
+    fn to_datetime(ts: i64) -> mlua::Result<DateTime<Utc>> {
+        DateTime::from_timestamp(ts, 0)
+            .ok_or_else(|| mlua::Error::external(anyhow::anyhow!("timestamp {ts} is out of range")))
+    }
+
+    let time_table = lua.create_table()?;
+
+    time_table.set(
+        "now",
+        lua.create_function(|_, ()| Ok(Utc::now().timestamp()))?,
+    )?;
+
+    time_table.set(
+        "now_iso",
+        lua.create_function(|_, ()| Ok(Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)))?,
+    )?;
+
+    time_table.set(
+        "format",
+        lua.create_function(|_, (ts, fmt): (i64, Option<String>)| {
+            let dt = to_datetime(ts)?;
+            let Some(fmt) = fmt else {
+                return Ok(dt.to_rfc3339_opts(SecondsFormat::Secs, true));
+            };
+            let items: Vec<Item<'_>> = StrftimeItems::new(&fmt).collect();
+            if items.iter().any(|item| matches!(item, Item::Error)) {
+                return Err(mlua::Error::external(anyhow::anyhow!(
+                    "invalid time format '{fmt}'"
+                )));
+            }
+            Ok(dt.format_with_items(items.into_iter()).to_string())
+        })?,
+    )?;
+
+    time_table.set(
+        "parse_iso",
+        lua.create_function(|_, s: String| {
+            if let Ok(dt) = DateTime::parse_from_rfc3339(&s) {
+                return Ok(dt.timestamp());
+            }
+            NaiveDate::parse_from_str(&s, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map(|dt| dt.and_utc().timestamp())
+                .ok_or_else(|| {
+                    mlua::Error::external(anyhow::anyhow!(
+                        "invalid ISO 8601 timestamp '{s}' (expected RFC 3339 or YYYY-MM-DD)"
+                    ))
+                })
+        })?,
+    )?;
+
+    time_table.set(
+        "add",
+        lua.create_function(|_, (ts, seconds): (i64, i64)| {
+            ts.checked_add(seconds).ok_or_else(|| {
+                mlua::Error::external(anyhow::anyhow!("timestamp overflow in time.add"))
+            })
+        })?,
+    )?;
+
+    Ok(time_table)
+}
+
+/// Build the `str` global: `split`, `trim`, `starts_with`, `ends_with`, `replace_all`.
+///
+/// Separators and patterns are plain substrings, not Lua patterns.
+fn create_str_table(lua: &Lua) -> mlua::Result<Table> {
+    let str_table = lua.create_table()?;
+
+    str_table.set(
+        "split",
+        lua.create_function(|lua, (s, sep): (String, String)| {
+            if sep.is_empty() {
+                return Err(mlua::Error::external(anyhow::anyhow!(
+                    "str.split separator must not be empty"
+                )));
+            }
+            lua.create_sequence_from(s.split(sep.as_str()))
+        })?,
+    )?;
+
+    str_table.set(
+        "trim",
+        lua.create_function(|_, s: String| Ok(s.trim().to_string()))?,
+    )?;
+
+    str_table.set(
+        "starts_with",
+        lua.create_function(|_, (s, prefix): (String, String)| Ok(s.starts_with(&prefix)))?,
+    )?;
+
+    str_table.set(
+        "ends_with",
+        lua.create_function(|_, (s, suffix): (String, String)| Ok(s.ends_with(&suffix)))?,
+    )?;
+
+    str_table.set(
+        "replace_all",
+        lua.create_function(|_, (s, from, to): (String, String, String)| {
+            if from.is_empty() {
+                return Err(mlua::Error::external(anyhow::anyhow!(
+                    "str.replace_all pattern must not be empty"
+                )));
+            }
+            Ok(s.replace(&from, &to))
+        })?,
+    )?;
+
+    Ok(str_table)
+}
+
+/// Deepest nesting `tbl.deep_clone` and `tbl.deep_merge` follow, so a table
+/// that contains itself fails instead of recursing forever.
+const MAX_TABLE_DEPTH: usize = 100;
+
+fn create_tbl_table(lua: &Lua) -> mlua::Result<Table> {
+    let tbl_table = lua.create_table()?;
+
+    tbl_table.set(
+        "deep_merge",
+        lua.create_function(|lua, (a, b): (Table, Table)| deep_merge(lua, &a, &b, 0))?,
+    )?;
+
+    tbl_table.set(
+        "deep_clone",
+        lua.create_function(|lua, value: Value| deep_clone(lua, value, 0))?,
+    )?;
+
+    tbl_table.set(
+        "keys",
+        lua.create_function(|lua, t: Table| {
+            let keys = t
+                .pairs::<Value, Value>()
+                .map(|pair| pair.map(|(key, _)| key))
+                .collect::<mlua::Result<Vec<_>>>()?;
+            lua.create_sequence_from(keys)
+        })?,
+    )?;
+
+    tbl_table.set(
+        "values",
+        lua.create_function(|lua, t: Table| {
+            let values = t
+                .pairs::<Value, Value>()
+                .map(|pair| pair.map(|(_, value)| value))
+                .collect::<mlua::Result<Vec<_>>>()?;
+            lua.create_sequence_from(values)
+        })?,
+    )?;
+
+    Ok(tbl_table)
+}
+
+/// A non-empty table whose keys are exactly `1..=n`.
+fn is_array(t: &Table) -> mlua::Result<bool> {
+    let len = t.raw_len();
+    if len == 0 {
+        return Ok(false);
+    }
+    let mut count = 0;
+    for pair in t.pairs::<Value, Value>() {
+        pair?;
+        count += 1;
+    }
+    Ok(count == len)
+}
+
+fn check_depth(depth: usize) -> mlua::Result<()> {
+    if depth > MAX_TABLE_DEPTH {
+        return Err(mlua::Error::external(anyhow::anyhow!(
+            "table nested deeper than {MAX_TABLE_DEPTH} levels (does it contain itself?)"
+        )));
+    }
+    Ok(())
+}
+
+/// Copy `value`, recursing into table values. Keys and metatables are not copied.
+fn deep_clone(lua: &Lua, value: Value, depth: usize) -> mlua::Result<Value> {
+    let Value::Table(t) = value else {
+        return Ok(value);
+    };
+    check_depth(depth)?;
+    let copy = lua.create_table()?;
+    for pair in t.pairs::<Value, Value>() {
+        let (key, value) = pair?;
+        copy.raw_set(key, deep_clone(lua, value, depth + 1)?)?;
+    }
+    Ok(Value::Table(copy))
+}
+
+/// Merge `b` over `a` into a new table. Two arrays concatenate (`a`'s items
+/// then `b`'s). Otherwise keys merge: where both sides hold a table the two
+/// merge recursively, and any other conflict takes `b`'s value.
+fn deep_merge(lua: &Lua, a: &Table, b: &Table, depth: usize) -> mlua::Result<Table> {
+    check_depth(depth)?;
+    let merged = lua.create_table()?;
+    if is_array(a)? && is_array(b)? {
+        for value in a.sequence_values::<Value>().chain(b.sequence_values()) {
+            merged.raw_push(deep_clone(lua, value?, depth + 1)?)?;
+        }
+        return Ok(merged);
+    }
+    for pair in a.pairs::<Value, Value>() {
+        let (key, value) = pair?;
+        merged.raw_set(key, deep_clone(lua, value, depth + 1)?)?;
+    }
+    for pair in b.pairs::<Value, Value>() {
+        let (key, value) = pair?;
+        let value = match (a.raw_get::<Value>(key.clone())?, value) {
+            (Value::Table(left), Value::Table(right)) => {
+                Value::Table(deep_merge(lua, &left, &right, depth + 1)?)
+            }
+            (_, value) => deep_clone(lua, value, depth + 1)?,
+        };
+        merged.raw_set(key, value)?;
+    }
+    Ok(merged)
+}
+
+/// Characters `url.encode` leaves as-is: ASCII alphanumerics and `-._~`
+/// (the RFC 3986 unreserved set).
+const URL_ENCODE_SET: &percent_encoding::AsciiSet = &percent_encoding::NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Build the `url` global: `encode`, `decode`, `parse`, `build`.
+///
+/// `parse` returns `{ scheme, host, port?, path, query, fragment? }`, where
+/// `query` maps each key to its value, or to an array of values when the key
+/// repeats. `build` takes the same shape and emits query keys in sorted order.
+fn create_url_table(lua: &Lua) -> mlua::Result<Table> {
+    let url_table = lua.create_table()?;
+
+    url_table.set(
+        "encode",
+        lua.create_function(|_, s: String| {
+            Ok(percent_encoding::utf8_percent_encode(&s, URL_ENCODE_SET).to_string())
+        })?,
+    )?;
+
+    url_table.set(
+        "decode",
+        lua.create_function(|_, s: String| {
+            percent_encoding::percent_decode_str(&s)
+                .decode_utf8()
+                .map(std::borrow::Cow::into_owned)
+                .map_err(|e| {
+                    mlua::Error::external(anyhow::anyhow!("url.decode: invalid UTF-8: {e}"))
+                })
+        })?,
+    )?;
+
+    url_table.set(
+        "parse",
+        lua.create_function(|lua, s: String| {
+            let parsed = url::Url::parse(&s)
+                .map_err(|e| mlua::Error::external(anyhow::anyhow!("url.parse: {e}")))?;
+            let result = lua.create_table()?;
+            result.set("scheme", parsed.scheme())?;
+            result.set("host", parsed.host_str())?;
+            result.set("port", parsed.port())?;
+            result.set("path", parsed.path())?;
+            result.set("fragment", parsed.fragment())?;
+
+            let query = lua.create_table()?;
+            for (key, value) in parsed.query_pairs() {
+                match query.get::<Value>(key.as_ref())? {
+                    Value::Nil => query.set(key.as_ref(), value.as_ref())?,
+                    Value::Table(values) => values.push(value.as_ref())?,
+                    first => {
+                        query.set(key.as_ref(), lua.create_sequence_from([first])?)?;
+                        query.get::<Table>(key.as_ref())?.push(value.as_ref())?;
+                    }
+                }
+            }
+            result.set("query", query)?;
+            Ok(result)
+        })?,
+    )?;
+
+    url_table.set(
+        "build",
+        lua.create_function(|_, parts: Table| {
+            let scheme: Option<String> = parts.get("scheme")?;
+            let host: Option<String> = parts.get("host")?;
+            let scheme = scheme.unwrap_or_else(|| "https".to_string());
+            let Some(host) = host else {
+                return Err(mlua::Error::external(anyhow::anyhow!(
+                    "url.build requires a host"
+                )));
+            };
+            let mut built = url::Url::parse(&format!("{scheme}://{host}"))
+                .map_err(|e| mlua::Error::external(anyhow::anyhow!("url.build: {e}")))?;
+            let port: Option<u16> = parts.get("port")?;
+            built.set_port(port).map_err(|()| {
+                mlua::Error::external(anyhow::anyhow!("url.build: scheme cannot have a port"))
+            })?;
+            if let Some(path) = parts.get::<Option<String>>("path")? {
+                built.set_path(&path);
+            }
+
+            if let Some(query) = parts.get::<Option<Table>>("query")? {
+                let mut pairs = Vec::new();
+                for entry in query.pairs::<String, Value>() {
+                    let (key, value) = entry?;
+                    match value {
+                        Value::Table(values) => {
+                            for item in values.sequence_values::<Value>() {
+                                pairs.push((key.clone(), url_query_value(&item?)?));
+                            }
+                        }
+                        other => pairs.push((key, url_query_value(&other)?)),
+                    }
+                }
+                // Stable sort keeps repeated values in their array order.
+                pairs.sort_by(|a, b| a.0.cmp(&b.0));
+                if !pairs.is_empty() {
+                    built.query_pairs_mut().extend_pairs(pairs);
+                }
+            }
+
+            let fragment: Option<String> = parts.get("fragment")?;
+            built.set_fragment(fragment.as_deref());
+            Ok(built.to_string())
+        })?,
+    )?;
+
+    Ok(url_table)
+}
+
+/// Render a scalar query value for `url.build`.
+fn url_query_value(value: &Value) -> mlua::Result<String> {
+    match value {
+        Value::String(_) | Value::Integer(_) | Value::Number(_) | Value::Boolean(_) => {
+            Ok(format_lua_value(value))
+        }
+        other => Err(mlua::Error::external(anyhow::anyhow!(
+            "url.build query values must be strings, numbers or booleans, got {}",
+            other.type_name()
+        ))),
+    }
+}
+
+/// Recursively rebuild a JSON value with object keys in sorted order.
+///
+/// Object key order otherwise depends on the `serde_json` map implementation,
+/// so `sort_keys` guarantees diff-friendly output regardless of feature flags.
+fn sort_json_keys(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<(String, serde_json::Value)> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            serde_json::Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, sort_json_keys(v)))
+                    .collect(),
+            )
+        }
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.into_iter().map(sort_json_keys).collect())
+        }
+        other => other,
     }
 }
 
@@ -127,6 +713,7 @@ fn format_lua_value(value: &Value) -> String {
     match value {
         Value::Nil => "nil".to_string(),
         Value::Boolean(b) => b.to_string(),
+        Value::Integer(i) => i.to_string(),
         Value::Number(n) =>
         {
             #[allow(
@@ -255,6 +842,52 @@ mod tests {
         assert_eq!(logs, vec!["hello", "world"]);
     }
 
+    #[test]
+    fn test_sandbox_log_levels() {
+        let sb = sandboxed();
+        let (_, logs) = sb
+            .eval_with_logs::<Value>(
+                r#"
+                log.info("starting", 3)
+                log.warn("careful")
+                log.error("failed")
+            "#,
+            )
+            .unwrap();
+        assert_eq!(
+            logs,
+            vec!["[info] starting\t3", "[warn] careful", "[error] failed"]
+        );
+    }
+
+    #[test]
+    fn test_sandbox_log_byte_cap() {
+        let sb = Sandbox::new(SandboxConfig {
+            max_log_bytes: Some(10),
+            ..SandboxConfig::default()
+        })
+        .unwrap();
+        sb.enable_sandbox().unwrap();
+        let (_, logs) = sb
+            .eval_with_logs::<Value>(
+                r#"
+                print("12345")
+                print("67890")
+                print("overflow")
+                log.info("dropped")
+            "#,
+            )
+            .unwrap();
+        assert_eq!(
+            logs,
+            vec![
+                "12345",
+                "67890",
+                "[log output truncated: exceeded 10 bytes]"
+            ]
+        );
+    }
+
     #[test]
     fn test_sandbox_json_encode_decode() {
         let sb = sandboxed();
@@ -271,6 +904,330 @@ mod tests {
         assert_eq!(result, "test");
     }
 
+    #[test]
+    fn test_sandbox_json_encode_sort_keys_stable() {
+        let script = r"
+            local t = { zebra = 1, apple = { delta = true, bravo = false }, mango = { 3, 2, 1 } }
+            return json.encode(t, { sort_keys = true })
+        ";
+        let first: String = sandboxed().eval(script).unwrap();
+        for _ in 0..5 {
+            let again: String = sandboxed().eval(script).unwrap();
+            assert_eq!(again, first);
+        }
+        assert_eq!(
+            first,
+            r#"{"apple":{"bravo":false,"delta":true},"mango":[3,2,1],"zebra":1}"#
+        );
+    }
+
+    #[test]
+    fn test_sandbox_json_encode_pretty() {
+        let sb = sandboxed();
+        let result: String = sb
+            .eval(
+                r#"
+                local t = { name = "test", items = { { id = 1 }, { id = 2 } } }
+                return json.encode(t, { pretty = true, sort_keys = true })
+            "#,
+            )
+            .unwrap();
+        assert!(result.contains('\n'));
+        assert!(result.starts_with("{\n  \"items\": [\n    {\n      \"id\": 1"));
+
+        // Compact output is unchanged without options
+        let compact: String = sb.eval(r"return json.encode({ a = { 1, 2 } })").unwrap();
+        assert_eq!(compact, r#"{"a":[1,2]}"#);
+    }
+
+    #[test]
+    fn test_sandbox_time_iso_round_trip() {
+        let sb = sandboxed();
+        let ts: i64 = sb
+            .eval(r#"return time.parse_iso("2024-03-05T14:30:00+02:00")"#)
+            .unwrap();
+        assert_eq!(ts, 1_709_641_800);
+        let iso: String = sb.eval(&format!("return time.format({ts})")).unwrap();
+        assert_eq!(iso, "2024-03-05T12:30:00Z");
+
+        let date_only: i64 = sb.eval(r#"return time.parse_iso("2024-03-05")"#).unwrap();
+        assert_eq!(date_only, 1_709_596_800);
+
+        let err = sb.eval::<i64>(r#"return time.parse_iso("yesterday")"#);
+        assert!(err.unwrap_err().to_string().contains("invalid ISO 8601"));
+    }
+
+    #[test]
+    fn test_sandbox_time_format_and_add() {
+        let sb = sandboxed();
+        let formatted: String = sb
+            .eval(r#"return time.format(time.add(0, 86400 + 3661), "%Y-%m-%d %H:%M:%S")"#)
+            .unwrap();
+        assert_eq!(formatted, "1970-01-02 01:01:01");
+
+        let err = sb.eval::<String>(r#"return time.format(0, "%Q")"#);
+        assert!(err.unwrap_err().to_string().contains("invalid time format"));
+    }
+
+    #[test]
+    fn test_sandbox_time_now() {
+        let sb = sandboxed();
+        let now: i64 = sb.eval("return time.now()").unwrap();
+        let iso_ts: i64 = sb.eval("return time.parse_iso(time.now_iso())").unwrap();
+        assert!(now > 1_700_000_000);
+        assert!((iso_ts - now).abs() <= 1);
+    }
+
+    #[test]
+    fn test_sandbox_str_split_multi_char_separator() {
+        let sb = sandboxed();
+        let parts: Vec<String> = sb.eval(r#"return str.split("a::b::::c", "::")"#).unwrap();
+        assert_eq!(parts, ["a", "b", "", "c"]);
+
+        let single: Vec<String> = sb.eval(r#"return str.split("abc", ",")"#).unwrap();
+        assert_eq!(single, ["abc"]);
+
+        let err = sb.eval::<Value>(r#"return str.split("abc", "")"#);
+        assert!(err.unwrap_err().to_string().contains("must not be empty"));
+    }
+
+    #[test]
+    fn test_sandbox_str_trim_and_affixes() {
+        let sb = sandboxed();
+        let trimmed: String = sb
+            .eval("return str.trim(\" \\t\\n hello world \\r\\n\\t \")")
+            .unwrap();
+        assert_eq!(trimmed, "hello world");
+
+        let checks: Vec<bool> = sb
+            .eval(
+                r#"
+                return {
+                    str.starts_with("v2/pets", "v2/"),
+                    str.starts_with("v2/pets", "pets"),
+                    str.ends_with("report.csv", ".csv"),
+                    str.ends_with("report.csv", ".json"),
+                }
+            "#,
+            )
+            .unwrap();
+        assert_eq!(checks, [true, false, true, false]);
+
+        let replaced: String = sb
+            .eval(r#"return str.replace_all("a.b.c", ".", "%")"#)
+            .unwrap();
+        assert_eq!(replaced, "a%b%c");
+
+        // The standard string library is untouched
+        let upper: String = sb.eval(r#"return string.upper("ok")"#).unwrap();
+        assert_eq!(upper, "OK");
+    }
+
+    #[test]
+    fn test_sandbox_retry_succeeds_after_failures() {
+        let sb = sandboxed();
+        let result: Vec<i64> = sb
+            .eval(
+                r#"
+                local calls = 0
+                local value = retry(function()
+                    calls += 1
+                    if calls < 3 then
+                        error("not ready")
+                    end
+                    return 42
+                end, { attempts = 5, delay_ms = 1, backoff = 2 })
+                return { value, calls }
+            "#,
+            )
+            .unwrap();
+        assert_eq!(result, [42, 3]);
+    }
+
+    #[test]
+    fn test_sandbox_retry_propagates_final_error() {
+        let sb = sandboxed();
+        let err = sb
+            .eval::<Value>(
+                r#"
+                local calls = 0
+                retry(function()
+                    calls += 1
+                    error("attempt " .. calls .. " failed")
+                end, { attempts = 3 })
+            "#,
+            )
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("attempt 3 failed"), "error was: {err}");
+
+        let status: i64 = sb
+            .eval(
+                r"
+                local ok, err = pcall(retry, function() error({ status = 503 }) end, { attempts = 2 })
+                return err.status
+            ",
+            )
+            .unwrap();
+        assert_eq!(status, 503);
+    }
+
+    #[test]
+    fn test_sandbox_retry_sleep_respects_deadline() {
+        let sb = sandboxed();
+        sb.set_limits(Instant::now() + Duration::from_millis(200), None);
+        let start = Instant::now();
+        let err = sb
+            .eval::<Value>(
+                r#"retry(function() error("down") end, { attempts = 3, delay_ms = 10000 })"#,
+            )
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("timed out"), "error was: {err}");
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_sandbox_getenv_returns_allowlisted_var() {
+        let sb = Sandbox::new(SandboxConfig {
+            env_allowlist: vec!["CARGO_PKG_NAME".to_string()],
+            ..SandboxConfig::default()
+        })
+        .unwrap();
+        sb.enable_sandbox().unwrap();
+        let value: String = sb.eval("return os.getenv('CARGO_PKG_NAME')").unwrap();
+        assert_eq!(value, env!("CARGO_PKG_NAME"));
+    }
+
+    #[test]
+    fn test_sandbox_getenv_hides_unlisted_var() {
+        // Cargo sets CARGO_MANIFEST_DIR for test runs, so the variable exists
+        assert!(std::env::var("CARGO_MANIFEST_DIR").is_ok());
+        let sb = Sandbox::new(SandboxConfig {
+            env_allowlist: vec!["CARGO_PKG_NAME".to_string()],
+            ..SandboxConfig::default()
+        })
+        .unwrap();
+        sb.enable_sandbox().unwrap();
+        let is_nil: bool = sb
+            .eval("return os.getenv('CARGO_MANIFEST_DIR') == nil")
+            .unwrap();
+        assert!(is_nil);
+    }
+
+    #[test]
+    fn test_sandbox_tbl_deep_merge_precedence() {
+        let sb = sandboxed();
+        let merged: String = sb
+            .eval(
+                r#"
+                local a = { name = "a", opts = { retries = 1, verbose = true, tags = { "x" } }, keep = 1 }
+                local b = { name = "b", opts = { retries = 3, tags = { "y", "z" } } }
+                return json.encode(tbl.deep_merge(a, b), { sort_keys = true })
+            "#,
+            )
+            .unwrap();
+        assert_eq!(
+            merged,
+            r#"{"keep":1,"name":"b","opts":{"retries":3,"tags":["x","y","z"],"verbose":true}}"#
+        );
+    }
+
+    #[test]
+    fn test_sandbox_tbl_deep_merge_scalar_replaces_table() {
+        let sb = sandboxed();
+        let merged: String = sb
+            .eval(r#"return json.encode(tbl.deep_merge({ v = { 1, 2 } }, { v = "flat" }))"#)
+            .unwrap();
+        assert_eq!(merged, r#"{"v":"flat"}"#);
+    }
+
+    #[test]
+    fn test_sandbox_tbl_deep_clone_is_independent() {
+        let sb = sandboxed();
+        let result: String = sb
+            .eval(
+                r#"
+                local src = { user = { name = "ann", roles = { "admin" } } }
+                local copy = tbl.deep_clone(src)
+                copy.user.name = "bob"
+                table.insert(copy.user.roles, "dev")
+                src.user.roles[1] = "owner"
+                return src.user.name .. "," .. #src.user.roles .. "," .. copy.user.roles[1]
+            "#,
+            )
+            .unwrap();
+        assert_eq!(result, "ann,1,admin");
+    }
+
+    #[test]
+    fn test_sandbox_tbl_deep_clone_rejects_cycles() {
+        let sb = sandboxed();
+        let err = sb
+            .eval::<Value>("local t = {} t.self = t return tbl.deep_clone(t)")
+            .unwrap_err();
+        assert!(err.to_string().contains("nested deeper"), "got: {err}");
+    }
+
+    #[test]
+    fn test_sandbox_tbl_keys_and_values() {
+        let sb = sandboxed();
+        let result: String = sb
+            .eval(
+                r#"
+                local keys = tbl.keys({ b = 2, a = 1 })
+                local values = tbl.values({ b = 2, a = 1 })
+                table.sort(keys)
+                table.sort(values)
+                return table.concat(keys, ",") .. "|" .. table.concat(values, ",")
+            "#,
+            )
+            .unwrap();
+        assert_eq!(result, "a,b|1,2");
+    }
+
+    #[test]
+    fn test_sandbox_url_parse_into_table() {
+        let sb = sandboxed();
+        let result: String = sb
+            .eval(
+                r#"
+                local u = url.parse("https://api.example.com:8443/v1/pets?limit=10&tag=a&tag=b%20c#top")
+                return table.concat({
+                    u.scheme, u.host, tostring(u.port), u.path,
+                    u.query.limit, u.query.tag[1], u.query.tag[2], u.fragment,
+                }, "|")
+            "#,
+            )
+            .unwrap();
+        assert_eq!(result, "https|api.example.com|8443|/v1/pets|10|a|b c|top");
+    }
+
+    #[test]
+    fn test_sandbox_url_build_round_trip() {
+        let sb = sandboxed();
+        let built: String = sb
+            .eval(
+                r#"
+                return url.build(url.parse("https://api.example.com/v1/pets?tag=a&limit=10&tag=b+c"))
+            "#,
+            )
+            .unwrap();
+        assert_eq!(
+            built,
+            "https://api.example.com/v1/pets?limit=10&tag=a&tag=b+c"
+        );
+    }
+
+    #[test]
+    fn test_sandbox_url_encode_decode() {
+        let sb = sandboxed();
+        let result: String = sb
+            .eval(r#"local e = url.encode("a b/c~d") return e .. "|" .. url.decode(e)"#)
+            .unwrap();
+        assert_eq!(result, "a%20b%2Fc~d|a b/c~d");
+    }
+
     #[test]
     fn test_sandbox_has_sdk_table() {
         let sb = sandboxed();
@@ -282,6 +1239,7 @@ mod tests {
     fn test_sandbox_memory_limit() {
         let sb = Sandbox::new(SandboxConfig {
             memory_limit: Some(1024 * 1024), // 1 MB
+            ..SandboxConfig::default()
         })
         .unwrap();
         sb.enable_sandbox().unwrap();
@@ -1,6 +1,11 @@
-use crate::codegen::manifest::ParamDef;
+use std::collections::HashMap;
+use std::sync::Arc;
 
-/// Validate a parameter value against its enum constraints and format.
+use mlua::{Lua, Value};
+
+use crate::codegen::manifest::{FieldDef, FieldType, ParamDef, SchemaDef};
+
+/// Validate a parameter value against its enum constraints, format and `multipleOf`.
 /// Returns `Ok(())` if valid, or an `mlua::Error` with a descriptive message.
 pub fn validate_param_value(
     func_name: &str,
@@ -25,9 +30,35 @@ pub fn validate_param_value(
         validate_format(func_name, &param.name, fmt, value)?;
     }
 
+    if let Some(step) = param
+        .multiple_of
+        .as_ref()
+        .and_then(serde_json::Number::as_f64)
+        && let Ok(number) = value.parse::<f64>()
+        && !is_multiple_of(number, step)
+    {
+        return Err(mlua::Error::external(anyhow::anyhow!(
+            "parameter '{}' for '{}': expected a multiple of {}, got '{}'",
+            param.name,
+            func_name,
+            step,
+            value,
+        )));
+    }
+
     Ok(())
 }
 
+/// Whether `value / step` is a whole number, allowing for float rounding
+/// (`0.07 / 0.01` is `7.000000000000001`).
+fn is_multiple_of(value: f64, step: f64) -> bool {
+    if step <= 0.0 {
+        return true;
+    }
+    let quotient = value / step;
+    (quotient - quotient.round()).abs() <= 1e-9 * quotient.abs().max(1.0)
+}
+
 fn validate_format(
     func_name: &str,
     param_name: &str,
@@ -243,6 +274,200 @@ fn is_valid_int64(value: &str) -> bool {
     value.parse::<i64>().is_ok()
 }
 
+/// Register the `validate(value, "SchemaName", opts?)` global, which checks a
+/// Lua value against a manifest schema and raises the first violation found.
+/// Returns `true` when the value conforms.
+///
+/// Fields the schema does not list are tolerated unless `opts.strict` is set,
+/// except that an object's typed `additionalProperties` are type-checked.
+pub fn register_validate(lua: &Lua, schemas: &[SchemaDef]) -> Result<(), mlua::Error> {
+    let schemas: Arc<HashMap<String, SchemaDef>> = Arc::new(
+        schemas
+            .iter()
+            .map(|schema| (schema.name.clone(), schema.clone()))
+            .collect(),
+    );
+    let validate = lua.create_function(
+        move |_, (value, name, opts): (Value, String, Option<mlua::Table>)| {
+            let schema = schemas.get(&name).ok_or_else(|| {
+                mlua::Error::external(anyhow::anyhow!("validate: unknown schema '{name}'"))
+            })?;
+            let strict = match opts {
+                Some(opts) => opts.get::<Option<bool>>("strict")?.unwrap_or(false),
+                None => false,
+            };
+            check_fields(&schemas, &schema.fields, None, &value, "", strict, 0)
+                .map_err(|e| mlua::Error::external(anyhow::anyhow!("validate {name}: {e}")))?;
+            Ok(true)
+        },
+    )?;
+    lua.globals().set("validate", validate)
+}
+
+/// Deepest nesting `validate` follows, so a table that contains itself
+/// checked against a recursive schema fails instead of overflowing the stack.
+const MAX_VALIDATE_DEPTH: usize = 100;
+
+/// Check that `value` is a table holding `fields`. `path` prefixes field
+/// names in errors (`owner.name`). A key not among `fields` must match
+/// `additional` when the schema types its additional properties; otherwise,
+/// with `strict`, it is an error. A schema that lists no fields accepts any
+/// keys.
+fn check_fields(
+    schemas: &HashMap<String, SchemaDef>,
+    fields: &[FieldDef],
+    additional: Option<&FieldType>,
+    value: &Value,
+    path: &str,
+    strict: bool,
+    depth: usize,
+) -> Result<(), String> {
+    let Value::Table(table) = value else {
+        return Err(type_error(path, "object", value));
+    };
+    if additional.is_some() || (strict && !fields.is_empty()) {
+        for pair in table.pairs::<Value, Value>() {
+            let (key, item) = pair.map_err(|e| e.to_string())?;
+            let key = key.to_string().unwrap_or_default();
+            if fields.iter().any(|field| field.name == key) {
+                continue;
+            }
+            let key_path = if path.is_empty() {
+                key
+            } else {
+                format!("{path}.{key}")
+            };
+            match additional {
+                Some(additional) => {
+                    check_type(schemas, additional, &item, &key_path, strict, depth + 1)?;
+                }
+                None => return Err(format!("unexpected field '{key_path}'")),
+            }
+        }
+    }
+    for field in fields {
+        let field_path = if path.is_empty() {
+            field.name.clone()
+        } else {
+            format!("{path}.{}", field.name)
+        };
+        let field_value: Value = table.get(field.name.as_str()).map_err(|e| e.to_string())?;
+        // JSON null counts as absent
+        if field_value.is_nil() || field_value.is_null() {
+            if field.required && !field.nullable {
+                return Err(format!("missing required field '{field_path}'"));
+            }
+            continue;
+        }
+        check_type(
+            schemas,
+            &field.field_type,
+            &field_value,
+            &field_path,
+            strict,
+            depth + 1,
+        )?;
+        if let (Some(allowed), Value::String(s)) = (&field.enum_values, &field_value) {
+            let s = s.to_string_lossy();
+            if !allowed.contains(&s) {
+                return Err(format!(
+                    "field '{field_path}': expected one of [{}], got '{s}'",
+                    allowed.join(", ")
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_type(
+    schemas: &HashMap<String, SchemaDef>,
+    field_type: &FieldType,
+    value: &Value,
+    path: &str,
+    strict: bool,
+    depth: usize,
+) -> Result<(), String> {
+    if depth > MAX_VALIDATE_DEPTH {
+        return Err(format!(
+            "value nested deeper than {MAX_VALIDATE_DEPTH} levels (does it contain itself?)"
+        ));
+    }
+    match field_type {
+        FieldType::String if !value.is_string() => Err(type_error(path, "string", value)),
+        FieldType::Integer
+            if !(value.is_integer() || value.as_number().is_some_and(|n| n.fract() == 0.0)) =>
+        {
+            Err(type_error(path, "integer", value))
+        }
+        FieldType::Number if !value.is_number() && !value.is_integer() => {
+            Err(type_error(path, "number", value))
+        }
+        FieldType::Boolean if !value.is_boolean() => Err(type_error(path, "boolean", value)),
+        FieldType::Array { items } => {
+            let Value::Table(table) = value else {
+                return Err(type_error(path, "array", value));
+            };
+            for (i, item) in table.sequence_values::<Value>().enumerate() {
+                let item = item.map_err(|e| e.to_string())?;
+                check_type(
+                    schemas,
+                    items,
+                    &item,
+                    &format!("{path}[{}]", i + 1),
+                    strict,
+                    depth + 1,
+                )?;
+            }
+            Ok(())
+        }
+        FieldType::Object { schema } => match schemas.get(schema) {
+            Some(def) => check_fields(schemas, &def.fields, None, value, path, strict, depth),
+            None if value.is_table() => Ok(()),
+            None => Err(type_error(path, "object", value)),
+        },
+        FieldType::InlineObject { fields, additional } => check_fields(
+            schemas,
+            fields,
+            additional.as_deref(),
+            value,
+            path,
+            strict,
+            depth,
+        ),
+        FieldType::Map { value: inner } => {
+            let Value::Table(table) = value else {
+                return Err(type_error(path, "map", value));
+            };
+            for pair in table.pairs::<Value, Value>() {
+                let (key, item) = pair.map_err(|e| e.to_string())?;
+                let key = key.to_string().unwrap_or_default();
+                check_type(
+                    schemas,
+                    inner,
+                    &item,
+                    &format!("{path}.{key}"),
+                    strict,
+                    depth + 1,
+                )?;
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn type_error(path: &str, expected: &str, value: &Value) -> String {
+    if path.is_empty() {
+        format!("expected {expected}, got {}", value.type_name())
+    } else {
+        format!(
+            "field '{path}': expected {expected}, got {}",
+            value.type_name()
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::unwrap_used, clippy::expect_used)]
@@ -265,6 +490,11 @@ mod tests {
             enum_values,
             format,
             frozen_value: None,
+            deprecated: false,
+            example: None,
+            enum_descriptions: None,
+            boolean_style: None,
+            multiple_of: None,
         }
     }
 
@@ -310,6 +540,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn integer_enum_rejects_out_of_set_value() {
+        let mut param = make_param(
+            "status_code",
+            Some(vec![
+                "200".to_string(),
+                "404".to_string(),
+                "500".to_string(),
+            ]),
+            None,
+        );
+        param.param_type = ParamType::Integer;
+        assert!(validate_param_value("list_responses", &param, "404").is_ok());
+        let err = validate_param_value("list_responses", &param, "302")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("expected one of [200, 404, 500], got '302'"),
+            "unexpected error: {err}"
+        );
+    }
+
+    // -------------------------------------------------------
+    // multipleOf
+    // -------------------------------------------------------
+
+    #[test]
+    fn multiple_of_rejects_off_step_value() {
+        let mut param = make_param("amount", None, None);
+        param.param_type = ParamType::Number;
+        param.multiple_of = serde_json::Number::from_f64(0.01);
+        let err = validate_param_value("create_payment", &param, "0.005")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("expected a multiple of 0.01, got '0.005'"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn multiple_of_accepts_float_multiples() {
+        let mut param = make_param("amount", None, None);
+        param.param_type = ParamType::Number;
+        param.multiple_of = serde_json::Number::from_f64(0.01);
+        for value in ["0.02", "0.07", "19.99", "100", "-0.3"] {
+            assert!(
+                validate_param_value("create_payment", &param, value).is_ok(),
+                "{value} should pass"
+            );
+        }
+    }
+
+    #[test]
+    fn multiple_of_integer_step() {
+        let mut param = make_param("page_size", None, None);
+        param.param_type = ParamType::Integer;
+        param.multiple_of = Some(serde_json::Number::from(5));
+        assert!(validate_param_value("list_items", &param, "25").is_ok());
+        assert!(validate_param_value("list_items", &param, "12").is_err());
+    }
+
     // -------------------------------------------------------
     // No constraints
     // -------------------------------------------------------
@@ -597,4 +889,192 @@ mod tests {
         let param = make_param("ts", None, Some("date-time".to_string()));
         assert!(validate_param_value("f", &param, "2024-01-15T10:30:00z").is_ok());
     }
+
+    fn pet_schemas() -> Vec<SchemaDef> {
+        let field = |name: &str, field_type: FieldType, required: bool| FieldDef {
+            name: name.to_string(),
+            field_type,
+            required,
+            description: None,
+            enum_values: None,
+            nullable: false,
+            format: None,
+            example: None,
+            enum_descriptions: None,
+        };
+        let mut status = field("status", FieldType::String, false);
+        status.enum_values = Some(vec!["available".to_string(), "sold".to_string()]);
+        vec![SchemaDef {
+            name: "Pet".to_string(),
+            description: None,
+            fields: vec![
+                field("id", FieldType::Integer, true),
+                field("name", FieldType::String, true),
+                status,
+                field(
+                    "tags",
+                    FieldType::Array {
+                        items: Box::new(FieldType::String),
+                    },
+                    false,
+                ),
+            ],
+            discriminator: None,
+        }]
+    }
+
+    #[test]
+    fn test_validate_conforming_table() {
+        let lua = Lua::new();
+        register_validate(&lua, &pet_schemas()).unwrap();
+        let ok: bool = lua
+            .load(r#"return validate({ id = 1, name = "Fido", status = "sold", tags = { "a" } }, "Pet")"#)
+            .eval()
+            .unwrap();
+        assert!(ok);
+    }
+
+    #[test]
+    fn test_validate_missing_required_field() {
+        let lua = Lua::new();
+        register_validate(&lua, &pet_schemas()).unwrap();
+        let err = lua
+            .load(r#"return validate({ id = 1 }, "Pet")"#)
+            .eval::<bool>()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("missing required field 'name'"), "{err}");
+    }
+
+    #[test]
+    fn test_validate_wrong_type_and_enum() {
+        let lua = Lua::new();
+        register_validate(&lua, &pet_schemas()).unwrap();
+        let err = lua
+            .load(r#"return validate({ id = 1, name = "Fido", tags = { 5 } }, "Pet")"#)
+            .eval::<bool>()
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("field 'tags[1]': expected string, got integer"),
+            "{err}"
+        );
+        let err = lua
+            .load(r#"return validate({ id = 1, name = "Fido", status = "lost" }, "Pet")"#)
+            .eval::<bool>()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("expected one of [available, sold]"), "{err}");
+    }
+
+    #[test]
+    fn test_validate_extra_field_tolerated_unless_strict() {
+        let lua = Lua::new();
+        register_validate(&lua, &pet_schemas()).unwrap();
+        let ok: bool = lua
+            .load(r#"return validate({ id = 1, name = "Fido", color = "brown" }, "Pet")"#)
+            .eval()
+            .unwrap();
+        assert!(ok);
+
+        let err = lua
+            .load(
+                r#"return validate({ id = 1, name = "Fido", color = "brown" }, "Pet", { strict = true })"#,
+            )
+            .eval::<bool>()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("unexpected field 'color'"), "{err}");
+
+        let ok: bool = lua
+            .load(r#"return validate({ id = 1, name = "Fido" }, "Pet", { strict = true })"#)
+            .eval()
+            .unwrap();
+        assert!(ok);
+    }
+
+    #[test]
+    fn test_validate_cyclic_table_errors_instead_of_overflowing() {
+        let field = |name: &str, field_type: FieldType| FieldDef {
+            name: name.to_string(),
+            field_type,
+            required: false,
+            description: None,
+            enum_values: None,
+            nullable: false,
+            format: None,
+            example: None,
+            enum_descriptions: None,
+        };
+        let node = SchemaDef {
+            name: "Node".to_string(),
+            description: None,
+            fields: vec![
+                field("value", FieldType::Integer),
+                field(
+                    "next",
+                    FieldType::Object {
+                        schema: "Node".to_string(),
+                    },
+                ),
+            ],
+            discriminator: None,
+        };
+        let lua = Lua::new();
+        register_validate(&lua, &[node]).unwrap();
+        let err = lua
+            .load(r#"local n = { value = 1 } n.next = n return validate(n, "Node")"#)
+            .eval::<bool>()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("nested deeper than"), "{err}");
+    }
+
+    #[test]
+    fn test_validate_strict_checks_typed_additional_properties() {
+        let field = |name: &str, field_type: FieldType| FieldDef {
+            name: name.to_string(),
+            field_type,
+            required: false,
+            description: None,
+            enum_values: None,
+            nullable: false,
+            format: None,
+            example: None,
+            enum_descriptions: None,
+        };
+        let labels = SchemaDef {
+            name: "Labeled".to_string(),
+            description: None,
+            fields: vec![field(
+                "labels",
+                FieldType::InlineObject {
+                    fields: vec![field("owner", FieldType::String)],
+                    additional: Some(Box::new(FieldType::Integer)),
+                },
+            )],
+            discriminator: None,
+        };
+        let lua = Lua::new();
+        register_validate(&lua, &[labels]).unwrap();
+        let ok: bool = lua
+            .load(
+                r#"return validate({ labels = { owner = "ops", priority = 2 } }, "Labeled", { strict = true })"#,
+            )
+            .eval()
+            .unwrap();
+        assert!(ok);
+
+        let err = lua
+            .load(
+                r#"return validate({ labels = { owner = "ops", priority = "high" } }, "Labeled", { strict = true })"#,
+            )
+            .eval::<bool>()
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("field 'labels.priority': expected integer, got string"),
+            "{err}"
+        );
+    }
 }
@@ -1,9 +1,11 @@
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use jsonwebtoken::jwk::JwkSet;
 use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header};
 use tokio::sync::RwLock;
 
+use crate::runtime::executor::CallerContext;
 use crate::runtime::http::{AuthCredentials, AuthCredentialsMap};
 
 /// Configuration for MCP-level JWT authentication on the HTTP transport.
@@ -12,6 +14,11 @@ pub struct McpAuthConfig {
     pub authority: String,
     pub audience: String,
     pub jwks_uri_override: Option<String>,
+    /// Scopes a token must carry (in `scope` or `scp`); otherwise 403.
+    pub required_scopes: Vec<String>,
+    /// `(claim, value)` pairs a token must carry; an array claim must contain
+    /// the value. Otherwise 403.
+    pub required_claims: Vec<(String, String)>,
 }
 
 impl McpAuthConfig {
@@ -19,10 +26,22 @@ impl McpAuthConfig {
         let authority = std::env::var("MCP_AUTH_AUTHORITY").ok()?;
         let audience = std::env::var("MCP_AUTH_AUDIENCE").ok()?;
         let jwks_uri_override = std::env::var("MCP_AUTH_JWKS_URI").ok();
+        let required_scopes = std::env::var("MCP_AUTH_REQUIRED_SCOPES")
+            .map(|scopes| {
+                scopes
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|scope| !scope.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
         Some(Self {
             authority,
             audience,
             jwks_uri_override,
+            required_scopes,
+            required_claims: Vec::new(),
         })
     }
 }
@@ -37,11 +56,24 @@ pub enum AuthError {
     InvalidToken(String),
     #[error("JWKS fetch error: {0}")]
     JwksFetchError(String),
+    #[error("forbidden: {0}")]
+    Forbidden(String),
 }
 
 #[derive(Clone, Debug)]
 pub struct AuthContext {
     pub subject: String,
+    /// Every claim in the validated token, including `sub`, `iss`, `aud`, and `exp`.
+    pub claims: serde_json::Map<String, serde_json::Value>,
+}
+
+impl From<AuthContext> for CallerContext {
+    fn from(ctx: AuthContext) -> Self {
+        Self {
+            subject: ctx.subject,
+            claims: ctx.claims,
+        }
+    }
 }
 
 #[derive(Debug, Clone, serde::Deserialize)]
@@ -119,13 +151,27 @@ pub fn merge_credentials(
 #[derive(Debug, serde::Deserialize)]
 struct JwtClaims {
     sub: String,
-    #[allow(dead_code)]
     iss: String,
-    #[allow(dead_code)]
     #[serde(default)]
     aud: serde_json::Value,
-    #[allow(dead_code)]
     exp: u64,
+    /// Every other claim, checked against the configured requirements.
+    #[serde(flatten)]
+    extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl From<JwtClaims> for AuthContext {
+    fn from(claims: JwtClaims) -> Self {
+        let mut all = claims.extra;
+        all.insert("sub".to_string(), claims.sub.clone().into());
+        all.insert("iss".to_string(), claims.iss.into());
+        all.insert("aud".to_string(), claims.aud);
+        all.insert("exp".to_string(), claims.exp.into());
+        Self {
+            subject: claims.sub,
+            claims: all,
+        }
+    }
 }
 
 /// Validate a JWT against a known key, algorithm, issuer, and audience.
@@ -139,6 +185,17 @@ pub fn validate_jwt_with_key(
     expected_issuer: &str,
     expected_audience: &str,
 ) -> Result<AuthContext, AuthError> {
+    let claims = decode_claims(token, key, algorithm, expected_issuer, expected_audience)?;
+    Ok(claims.into())
+}
+
+fn decode_claims(
+    token: &str,
+    key: &DecodingKey,
+    algorithm: Algorithm,
+    expected_issuer: &str,
+    expected_audience: &str,
+) -> Result<JwtClaims, AuthError> {
     let mut validation = Validation::new(algorithm);
     validation.set_audience(&[expected_audience]);
     validation.set_issuer(&[expected_issuer]);
@@ -146,10 +203,60 @@ pub fn validate_jwt_with_key(
 
     let token_data = decode::<JwtClaims>(token, key, &validation)
         .map_err(|e| AuthError::InvalidToken(e.to_string()))?;
+    Ok(token_data.claims)
+}
+
+/// Check a validated token's claims against the configured scope and claim
+/// requirements.
+///
+/// Scopes are read from `scope` (space-separated, per RFC 8693) or `scp`
+/// (string or array). A required claim matches a string claim equal to the
+/// value, an array claim containing it, or a number/boolean claim equal to
+/// the value parsed as JSON.
+fn check_requirements(
+    claims: &serde_json::Map<String, serde_json::Value>,
+    config: &McpAuthConfig,
+) -> Result<(), AuthError> {
+    let granted: Vec<&str> = ["scope", "scp"]
+        .iter()
+        .filter_map(|name| claims.get(*name))
+        .flat_map(|value| match value {
+            serde_json::Value::String(scopes) => scopes.split_whitespace().collect(),
+            serde_json::Value::Array(items) => {
+                items.iter().filter_map(serde_json::Value::as_str).collect()
+            }
+            _ => Vec::new(),
+        })
+        .collect();
+    if let Some(missing) = config
+        .required_scopes
+        .iter()
+        .find(|scope| !granted.contains(&scope.as_str()))
+    {
+        return Err(AuthError::Forbidden(format!(
+            "token is missing required scope '{missing}'"
+        )));
+    }
 
-    Ok(AuthContext {
-        subject: token_data.claims.sub,
-    })
+    for (name, expected) in &config.required_claims {
+        let matches = |value: &serde_json::Value| match value {
+            serde_json::Value::String(actual) => actual == expected,
+            serde_json::Value::Null
+            | serde_json::Value::Array(_)
+            | serde_json::Value::Object(_) => false,
+            other => serde_json::from_str::<serde_json::Value>(expected).is_ok_and(|v| v == *other),
+        };
+        let satisfied = claims.get(name).is_some_and(|value| match value {
+            serde_json::Value::Array(items) => items.iter().any(matches),
+            other => matches(other),
+        });
+        if !satisfied {
+            return Err(AuthError::Forbidden(format!(
+                "token claim '{name}' does not include '{expected}'"
+            )));
+        }
+    }
+    Ok(())
 }
 
 // ---------------------------------------------------------------------------
@@ -162,11 +269,29 @@ struct OidcDiscovery {
     jwks_uri: String,
 }
 
+/// How long a fetched JWKS is trusted before it is fetched again.
+const DEFAULT_JWKS_TTL: Duration = Duration::from_hours(1);
+
+/// Minimum time between fetches triggered by an unknown `kid`.
+const DEFAULT_JWKS_MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A fetched JWKS and when it was fetched.
+struct CachedJwks {
+    keys: JwkSet,
+    fetched_at: Instant,
+}
+
 /// Validates JWTs by fetching and caching the issuer's JWKS.
+///
+/// The cache expires after a TTL. A token whose `kid` is not cached forces
+/// a refresh (key rotation), at most once per minimum refresh interval so
+/// tokens with bogus `kid`s cannot hammer the JWKS endpoint.
 pub struct JwtValidator {
     config: McpAuthConfig,
-    jwks: Arc<RwLock<Option<JwkSet>>>,
+    jwks: Arc<RwLock<Option<CachedJwks>>>,
     http_client: reqwest::Client,
+    ttl: Duration,
+    min_refresh_interval: Duration,
 }
 
 impl JwtValidator {
@@ -175,9 +300,24 @@ impl JwtValidator {
             config,
             jwks: Arc::new(RwLock::new(None)),
             http_client: reqwest::Client::new(),
+            ttl: DEFAULT_JWKS_TTL,
+            min_refresh_interval: DEFAULT_JWKS_MIN_REFRESH_INTERVAL,
         }
     }
 
+    /// Override the JWKS cache TTL and the minimum interval between
+    /// refreshes forced by an unknown `kid`.
+    #[must_use]
+    pub const fn with_cache_policy(
+        mut self,
+        ttl: Duration,
+        min_refresh_interval: Duration,
+    ) -> Self {
+        self.ttl = ttl;
+        self.min_refresh_interval = min_refresh_interval;
+        self
+    }
+
     /// Resolve the JWKS URI, either from config override or OIDC discovery.
     async fn resolve_jwks_uri(&self) -> Result<String, AuthError> {
         if let Some(ref uri) = self.config.jwks_uri_override {
@@ -212,17 +352,29 @@ impl JwtValidator {
         Ok(doc.jwks_uri)
     }
 
-    /// Return the cached JWKS, fetching it first if the cache is empty.
+    /// Return the cached JWKS, fetching it first if the cache is empty or
+    /// older than the TTL.
     async fn get_jwks(&self) -> Result<JwkSet, AuthError> {
         {
             let cache = self.jwks.read().await;
-            if let Some(ref jwks) = *cache {
-                return Ok(jwks.clone());
+            if let Some(ref cached) = *cache
+                && cached.fetched_at.elapsed() < self.ttl
+            {
+                return Ok(cached.keys.clone());
             }
         }
         self.refresh_jwks().await
     }
 
+    /// Whether an unknown `kid` may force a refresh now, given when the
+    /// cached JWKS was last fetched.
+    async fn may_force_refresh(&self) -> bool {
+        let cache = self.jwks.read().await;
+        cache
+            .as_ref()
+            .is_none_or(|cached| cached.fetched_at.elapsed() >= self.min_refresh_interval)
+    }
+
     /// Fetch the JWKS from the authority and update the cache.
     async fn refresh_jwks(&self) -> Result<JwkSet, AuthError> {
         let uri = self.resolve_jwks_uri().await?;
@@ -248,7 +400,10 @@ impl JwtValidator {
 
         {
             let mut cache = self.jwks.write().await;
-            *cache = Some(jwks.clone());
+            *cache = Some(CachedJwks {
+                keys: jwks.clone(),
+                fetched_at: Instant::now(),
+            });
         }
 
         Ok(jwks)
@@ -257,7 +412,9 @@ impl JwtValidator {
     /// Validate a JWT token using the cached (or freshly fetched) JWKS.
     ///
     /// If the `kid` in the token header is not found in the cached JWKS, the
-    /// JWKS is refreshed once (key rotation support).
+    /// JWKS is refreshed once (key rotation support), unless the cache was
+    /// fetched within the minimum refresh interval. A valid token lacking a
+    /// required scope or claim yields [`AuthError::Forbidden`].
     pub async fn validate(&self, token: &str) -> Result<AuthContext, AuthError> {
         let header = decode_header(token)
             .map_err(|e| AuthError::InvalidToken(format!("bad header: {e}")))?;
@@ -272,26 +429,29 @@ impl JwtValidator {
         // Try cached JWKS first
         let jwks = self.get_jwks().await?;
 
+        let no_match = || AuthError::InvalidToken(format!("no matching JWK for kid '{kid}'"));
         let jwk = if let Some(jwk) = jwks.find(kid) {
             jwk.clone()
-        } else {
+        } else if self.may_force_refresh().await {
             // Key rotation: refresh and try once more
             let refreshed = self.refresh_jwks().await?;
-            refreshed.find(kid).cloned().ok_or_else(|| {
-                AuthError::InvalidToken(format!("no matching JWK for kid '{kid}'"))
-            })?
+            refreshed.find(kid).cloned().ok_or_else(no_match)?
+        } else {
+            return Err(no_match());
         };
 
         let decoding_key = DecodingKey::from_jwk(&jwk)
             .map_err(|e| AuthError::InvalidToken(format!("invalid JWK: {e}")))?;
 
-        validate_jwt_with_key(
+        let claims = decode_claims(
             token,
             &decoding_key,
             algorithm,
             &self.config.authority,
             &self.config.audience,
-        )
+        )?;
+        check_requirements(&claims.extra, &self.config)?;
+        Ok(claims.into())
     }
 }
 
@@ -334,10 +494,20 @@ pub fn unauthorized_response(config: &McpAuthConfig) -> axum::response::Response
         .unwrap_or_else(|_| axum::response::Response::new(axum::body::Body::from("Unauthorized")))
 }
 
+/// Build a 403 Forbidden response for a valid token lacking a required scope or claim.
+pub fn forbidden_response(reason: &str) -> axum::response::Response<axum::body::Body> {
+    axum::response::Response::builder()
+        .status(axum::http::StatusCode::FORBIDDEN)
+        .body(axum::body::Body::from(format!("Forbidden: {reason}")))
+        .unwrap_or_else(|_| axum::response::Response::new(axum::body::Body::from("Forbidden")))
+}
+
 /// Auth middleware for `axum::middleware::from_fn_with_state`.
 ///
 /// State is `(Arc<JwtValidator>, McpAuthConfig)`. On success the [`AuthContext`]
 /// is inserted into the request extensions so downstream handlers can access it.
+/// Tokens that fail validation get 401; valid tokens missing a required scope
+/// or claim get 403.
 pub async fn auth_middleware(
     axum::extract::State((validator, config)): axum::extract::State<(
         Arc<JwtValidator>,
@@ -363,6 +533,7 @@ pub async fn auth_middleware(
             request.extensions_mut().insert(auth_context);
             next.run(request).await
         }
+        Err(AuthError::Forbidden(reason)) => forbidden_response(&reason),
         Err(_) => unauthorized_response(&config),
     }
 }
@@ -566,6 +737,9 @@ mod tests {
         assert!(result.is_ok());
         let ctx = result.unwrap();
         assert_eq!(ctx.subject, "user-123");
+        assert_eq!(ctx.claims["sub"], "user-123");
+        assert_eq!(ctx.claims["iss"], "https://auth.example.com");
+        assert_eq!(ctx.claims["aud"], "https://mcp.example.com");
     }
 
     #[test]
@@ -654,6 +828,170 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_check_requirements_scopes_and_claims() {
+        let config = McpAuthConfig {
+            authority: "https://auth.example.com".to_string(),
+            audience: "https://mcp.example.com".to_string(),
+            jwks_uri_override: None,
+            required_scopes: vec!["toolscript.execute".to_string()],
+            required_claims: vec![
+                ("tenant".to_string(), "acme".to_string()),
+                ("roles".to_string(), "admin".to_string()),
+            ],
+        };
+        let claims = |value: serde_json::Value| value.as_object().unwrap().clone();
+
+        let ok = claims(serde_json::json!({
+            "scp": ["openid", "toolscript.execute"],
+            "tenant": "acme",
+            "roles": ["reader", "admin"],
+        }));
+        assert!(check_requirements(&ok, &config).is_ok());
+
+        let wrong_tenant = claims(serde_json::json!({
+            "scope": "toolscript.execute",
+            "tenant": "globex",
+            "roles": ["admin"],
+        }));
+        let err = check_requirements(&wrong_tenant, &config).unwrap_err();
+        assert!(matches!(err, AuthError::Forbidden(_)));
+        assert!(err.to_string().contains("'tenant'"), "error was: {err}");
+
+        let no_scope = claims(serde_json::json!({ "tenant": "acme", "roles": "admin" }));
+        let err = check_requirements(&no_scope, &config).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("missing required scope 'toolscript.execute'"),
+            "error was: {err}"
+        );
+    }
+
+    // ----- JWKS caching tests -----
+
+    const JWKS_SECRET: &[u8] = b"test-secret-key-that-is-long-enough-for-hs256";
+    const JWKS_SECRET_B64URL: &str = "dGVzdC1zZWNyZXQta2V5LXRoYXQtaXMtbG9uZy1lbm91Z2gtZm9yLWhzMjU2";
+
+    /// A JWKS endpoint whose key IDs can be changed, counting fetches.
+    struct MockJwks {
+        uri: String,
+        kids: Arc<std::sync::Mutex<Vec<&'static str>>>,
+        fetches: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    async fn spawn_mock_jwks(kids: &[&'static str]) -> MockJwks {
+        let kids = Arc::new(std::sync::Mutex::new(kids.to_vec()));
+        let fetches = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let (kids_clone, fetches_clone) = (Arc::clone(&kids), Arc::clone(&fetches));
+        let app = axum::Router::new().route(
+            "/jwks",
+            axum::routing::get(move || {
+                fetches_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                let keys: Vec<serde_json::Value> = kids_clone
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .map(|kid| {
+                        serde_json::json!({
+                            "kty": "oct", "kid": kid, "alg": "HS256", "k": JWKS_SECRET_B64URL
+                        })
+                    })
+                    .collect();
+                async move { axum::Json(serde_json::json!({ "keys": keys })) }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        MockJwks {
+            uri: format!("http://{addr}/jwks"),
+            kids,
+            fetches,
+        }
+    }
+
+    impl MockJwks {
+        fn validator(&self, min_refresh_interval: Duration) -> JwtValidator {
+            JwtValidator::new(McpAuthConfig {
+                authority: "https://auth.example.com".to_string(),
+                audience: "https://mcp.example.com".to_string(),
+                jwks_uri_override: Some(self.uri.clone()),
+                required_scopes: Vec::new(),
+                required_claims: Vec::new(),
+            })
+            .with_cache_policy(DEFAULT_JWKS_TTL, min_refresh_interval)
+        }
+
+        fn fetches(&self) -> usize {
+            self.fetches.load(std::sync::atomic::Ordering::SeqCst)
+        }
+    }
+
+    fn token_for_kid(kid: &str) -> String {
+        let mut header = Header::new(JwtAlgorithm::HS256);
+        header.kid = Some(kid.to_string());
+        let claims = TestClaims {
+            sub: "user-123".to_string(),
+            iss: "https://auth.example.com".to_string(),
+            aud: "https://mcp.example.com".to_string(),
+            exp: (std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs())
+                + 3600,
+        };
+        encode(&header, &claims, &EncodingKey::from_secret(JWKS_SECRET)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_jwks_cached_across_validations() {
+        let jwks = spawn_mock_jwks(&["key-1"]).await;
+        let validator = jwks.validator(Duration::ZERO);
+
+        for _ in 0..3 {
+            validator.validate(&token_for_kid("key-1")).await.unwrap();
+        }
+        assert_eq!(jwks.fetches(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_jwks_unknown_kid_forces_refresh() {
+        let jwks = spawn_mock_jwks(&["key-1"]).await;
+        let validator = jwks.validator(Duration::ZERO);
+        validator.validate(&token_for_kid("key-1")).await.unwrap();
+
+        // The issuer rotates to a new key
+        jwks.kids.lock().unwrap().push("key-2");
+        let ctx = validator.validate(&token_for_kid("key-2")).await.unwrap();
+        assert_eq!(ctx.subject, "user-123");
+        assert_eq!(jwks.fetches(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_jwks_forced_refresh_rate_limited() {
+        let jwks = spawn_mock_jwks(&["key-1"]).await;
+        let validator = jwks.validator(Duration::from_hours(1));
+        validator.validate(&token_for_kid("key-1")).await.unwrap();
+
+        for _ in 0..3 {
+            let err = validator
+                .validate(&token_for_kid("bogus"))
+                .await
+                .unwrap_err();
+            assert!(
+                err.to_string().contains("no matching JWK"),
+                "error was: {err}"
+            );
+        }
+        assert_eq!(
+            jwks.fetches(),
+            1,
+            "unknown kids must not refetch within the interval"
+        );
+    }
+
     // ----- Task 5: Tower auth middleware tests -----
 
     #[test]
@@ -681,6 +1019,8 @@ mod tests {
             authority: "https://auth.example.com".to_string(),
             audience: "https://mcp.example.com".to_string(),
             jwks_uri_override: None,
+            required_scopes: Vec::new(),
+            required_claims: Vec::new(),
         };
         let header = www_authenticate_value(&config);
         assert!(header.contains("Bearer"));
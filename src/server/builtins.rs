@@ -7,7 +7,7 @@ pub struct BuiltinFunction {
 }
 
 /// Description for the luau API entry in `list_apis`.
-pub const LUAU_DESCRIPTION: &str = "Built-in Luau runtime globals: I/O, JSON, logging. Standard Lua libraries (string, table, math) are also available.";
+pub const LUAU_DESCRIPTION: &str = "Built-in Luau runtime globals: I/O, JSON, logging, time, string and table helpers, retry, schema validation. Standard Lua libraries (string, table, math) are also available.";
 
 static BUILTINS: &[BuiltinFunction] = &[
     BuiltinFunction {
@@ -40,14 +40,43 @@ function io.open(path: string, mode: string?): file_handle end",
 function io.lines(path: string): () -> string? end",
         io_only: true,
     },
+    BuiltinFunction {
+        name: "io.read_file",
+        summary: "Read a whole file into a string",
+        annotation: "\
+-- Read a whole file into a string. Paths are relative to the I/O directory.
+-- Opens and closes the file in one call, without holding a file handle.
+function io.read_file(path: string): string end",
+        io_only: true,
+    },
+    BuiltinFunction {
+        name: "io.write_file",
+        summary: "Write a string to a file, replacing it",
+        annotation: "\
+-- Write a string to a file, creating or replacing it. Parent directories are
+-- created as needed. Counts toward the I/O write budget. Returns true.
+function io.write_file(path: string, contents: string): boolean end",
+        io_only: true,
+    },
+    BuiltinFunction {
+        name: "io.append",
+        summary: "Append a string to a file",
+        annotation: "\
+-- Append a string to the end of a file, creating it (and parent directories)
+-- if needed. Counts toward the I/O write budget. Returns true.
+function io.append(path: string, contents: string): boolean end",
+        io_only: true,
+    },
     BuiltinFunction {
         name: "io.list",
         summary: "List directory entries",
         annotation: "\
 -- List file and directory names in a directory.
 -- Paths are relative to the I/O directory. Defaults to the root I/O directory.
--- Returns an array of entry names (not full paths). Does not recurse.
-function io.list(path: string?): {string} end",
+-- Returns an array of entry names relative to the listed directory.
+-- opts.recursive also lists subdirectories' entries as \"sub/name\".
+-- opts.pattern keeps only entries whose name matches a glob (`*`, `?`), e.g. \"*.json\".
+function io.list(path: string?, opts: { recursive: boolean?, pattern: string? }?): {string} end",
         io_only: true,
     },
     BuiltinFunction {
@@ -64,7 +93,10 @@ function io.type(obj: any): string? end",
         summary: "Serialize a Lua value to a JSON string",
         annotation: "\
 -- Serialize a Lua value (table, string, number, boolean, nil) to a JSON string.
-function json.encode(value: any): string end",
+-- Options:
+--   pretty: boolean? -- 2-space indented output (default false)
+--   sort_keys: boolean? -- object keys in sorted order (default false)
+function json.encode(value: any, options: { pretty: boolean?, sort_keys: boolean? }?): string end",
         io_only: false,
     },
     BuiltinFunction {
@@ -76,6 +108,194 @@ function json.encode(value: any): string end",
 function json.decode(str: string): any end",
         io_only: false,
     },
+    BuiltinFunction {
+        name: "time.now",
+        summary: "Current time as unix seconds",
+        annotation: "\
+-- Returns the current time as integer unix seconds (UTC).
+function time.now(): number end",
+        io_only: false,
+    },
+    BuiltinFunction {
+        name: "time.now_iso",
+        summary: "Current time as an RFC 3339 string",
+        annotation: "\
+-- Returns the current UTC time as an RFC 3339 string, e.g. \"2024-03-05T12:30:00Z\".
+function time.now_iso(): string end",
+        io_only: false,
+    },
+    BuiltinFunction {
+        name: "time.format",
+        summary: "Format a unix timestamp with a strftime pattern",
+        annotation: "\
+-- Format integer unix seconds in UTC using a strftime pattern
+-- (e.g. \"%Y-%m-%d\"). Without a pattern, returns RFC 3339.
+-- Raises an error on an invalid pattern.
+function time.format(ts: number, fmt: string?): string end",
+        io_only: false,
+    },
+    BuiltinFunction {
+        name: "time.parse_iso",
+        summary: "Parse an ISO 8601 timestamp into unix seconds",
+        annotation: "\
+-- Parse an RFC 3339 timestamp (\"2024-03-05T14:30:00+02:00\") or a date
+-- (\"2024-03-05\", midnight UTC) into integer unix seconds.
+-- Raises an error if the string cannot be parsed.
+function time.parse_iso(s: string): number end",
+        io_only: false,
+    },
+    BuiltinFunction {
+        name: "time.add",
+        summary: "Add seconds to a unix timestamp",
+        annotation: "\
+-- Returns ts + seconds. Use negative seconds to go back in time,
+-- e.g. time.add(time.now(), -7 * 86400) for one week ago.
+function time.add(ts: number, seconds: number): number end",
+        io_only: false,
+    },
+    BuiltinFunction {
+        name: "str.split",
+        summary: "Split a string on a plain separator",
+        annotation: "\
+-- Split a string on a plain (non-pattern) separator, which may be several
+-- characters. Empty pieces are kept. Raises an error if sep is empty.
+--
+-- Usage: str.split(\"a,b,,c\", \",\") --> {\"a\", \"b\", \"\", \"c\"}
+function str.split(s: string, sep: string): {string} end",
+        io_only: false,
+    },
+    BuiltinFunction {
+        name: "str.trim",
+        summary: "Strip leading and trailing whitespace",
+        annotation: "\
+-- Returns s without leading and trailing whitespace (spaces, tabs, newlines).
+function str.trim(s: string): string end",
+        io_only: false,
+    },
+    BuiltinFunction {
+        name: "str.starts_with",
+        summary: "Check whether a string starts with a prefix",
+        annotation: "\
+-- Returns true if s begins with prefix (plain comparison, no patterns).
+function str.starts_with(s: string, prefix: string): boolean end",
+        io_only: false,
+    },
+    BuiltinFunction {
+        name: "str.ends_with",
+        summary: "Check whether a string ends with a suffix",
+        annotation: "\
+-- Returns true if s ends with suffix (plain comparison, no patterns).
+function str.ends_with(s: string, suffix: string): boolean end",
+        io_only: false,
+    },
+    BuiltinFunction {
+        name: "str.replace_all",
+        summary: "Replace every occurrence of a substring",
+        annotation: "\
+-- Replace every occurrence of the plain substring from with to.
+-- Unlike string.gsub, no characters are special. Raises an error if from is empty.
+function str.replace_all(s: string, from: string, to: string): string end",
+        io_only: false,
+    },
+    BuiltinFunction {
+        name: "tbl.deep_merge",
+        summary: "Recursively merge two tables into a new one",
+        annotation: "\
+-- Merge b over a into a new table; neither input is modified.
+-- Two arrays (keys exactly 1..n) concatenate: a's items, then b's.
+-- Otherwise keys merge: nested tables on both sides merge recursively,
+-- and any other conflict takes b's value.
+--
+-- Usage: tbl.deep_merge({ opts = { a = 1 } }, { opts = { b = 2 } }) --> { opts = { a = 1, b = 2 } }
+function tbl.deep_merge(a: {[any]: any}, b: {[any]: any}): {[any]: any} end",
+        io_only: false,
+    },
+    BuiltinFunction {
+        name: "tbl.deep_clone",
+        summary: "Recursively copy a table",
+        annotation: "\
+-- Copy a table and every nested table value, so changes to the copy never
+-- reach the original. Non-table values are returned as-is.
+function tbl.deep_clone<T>(t: T): T end",
+        io_only: false,
+    },
+    BuiltinFunction {
+        name: "tbl.keys",
+        summary: "List a table's keys",
+        annotation: "\
+-- Returns the table's keys as an array, in `pairs` order.
+function tbl.keys(t: {[any]: any}): {any} end",
+        io_only: false,
+    },
+    BuiltinFunction {
+        name: "tbl.values",
+        summary: "List a table's values",
+        annotation: "\
+-- Returns the table's values as an array, in `pairs` order.
+function tbl.values(t: {[any]: any}): {any} end",
+        io_only: false,
+    },
+    BuiltinFunction {
+        name: "url.encode",
+        summary: "Percent-encode a string",
+        annotation: "\
+-- Percent-encodes everything except ASCII letters, digits and `-._~`.
+-- Usage: url.encode(\"a b/c\") --> \"a%20b%2Fc\"
+function url.encode(s: string): string end",
+        io_only: false,
+    },
+    BuiltinFunction {
+        name: "url.decode",
+        summary: "Decode a percent-encoded string",
+        annotation: "\
+-- Errors if the decoded bytes are not valid UTF-8.
+function url.decode(s: string): string end",
+        io_only: false,
+    },
+    BuiltinFunction {
+        name: "url.parse",
+        summary: "Split a URL into its parts",
+        annotation: "\
+-- Query values are strings, or arrays of strings when a key repeats.
+-- Usage: url.parse(\"https://h/p?a=1\").query.a --> \"1\"
+function url.parse(s: string): { scheme: string, host: string?, port: number?, path: string, query: {[string]: string | {string}}, fragment: string? } end",
+        io_only: false,
+    },
+    BuiltinFunction {
+        name: "url.build",
+        summary: "Assemble a URL from its parts",
+        annotation: "\
+-- Accepts the table returned by url.parse; scheme defaults to \"https\".
+-- Query keys are emitted in sorted order; array values repeat the key.
+function url.build(parts: { scheme: string?, host: string, port: number?, path: string?, query: {[string]: any}?, fragment: string? }): string end",
+        io_only: false,
+    },
+    BuiltinFunction {
+        name: "retry",
+        summary: "Re-run a function until it succeeds",
+        annotation: "\
+-- Call fn until it returns without raising, up to opts.attempts times (default 3).
+-- Waits opts.delay_ms (default 0) between attempts, multiplied by opts.backoff
+-- (default 1) after each one. Returns fn's results; re-raises the last error.
+-- Waits count against the script timeout.
+--
+-- Usage: local job = retry(function() return sdk.get_job({ id = id }) end, { attempts = 5, delay_ms = 500 })
+function retry(fn: () -> ...any, opts: { attempts: number?, delay_ms: number?, backoff: number? }?): ...any end",
+        io_only: false,
+    },
+    BuiltinFunction {
+        name: "validate",
+        summary: "Check a value against an SDK schema",
+        annotation: "\
+-- Check value against the named SDK schema: required fields present, field
+-- types and enum values match. Raises the first violation; returns true otherwise.
+-- Fields the schema does not list are allowed unless opts.strict is true;
+-- typed additionalProperties are checked against their type instead.
+--
+-- Usage: local pet = sdk.get_pet({ pet_id = id }); validate(pet, \"Pet\", { strict = true })
+function validate(value: any, schema: string, opts: { strict: boolean? }?): boolean end",
+        io_only: false,
+    },
     BuiltinFunction {
         name: "print",
         summary: "Log output (captured in response, not written to stdout)",
@@ -86,6 +306,34 @@ function json.decode(str: string): any end",
 function print(...: any) end",
         io_only: false,
     },
+    BuiltinFunction {
+        name: "log.info",
+        summary: "Log an informational message (captured in response)",
+        annotation: "\
+-- Log an informational message, captured like print() with an \"[info]\" prefix.
+-- Arguments are converted to strings and joined with tabs.
+function log.info(...: any) end",
+        io_only: false,
+    },
+    BuiltinFunction {
+        name: "log.warn",
+        summary: "Log a warning message (captured in response)",
+        annotation: "\
+-- Log a warning message, captured like print() with a \"[warn]\" prefix.
+-- Arguments are converted to strings and joined with tabs.
+function log.warn(...: any) end",
+        io_only: false,
+    },
+    BuiltinFunction {
+        name: "log.error",
+        summary: "Log an error message (captured in response)",
+        annotation: "\
+-- Log an error message, captured like print() with an \"[error]\" prefix.
+-- Arguments are converted to strings and joined with tabs.
+-- Does not raise; use error() to abort the script.
+function log.error(...: any) end",
+        io_only: false,
+    },
     BuiltinFunction {
         name: "os.remove",
         summary: "Delete a file in the I/O directory",
@@ -104,6 +352,15 @@ function os.remove(path: string): true end",
 function os.clock(): number end",
         io_only: false,
     },
+    BuiltinFunction {
+        name: "os.getenv",
+        summary: "Read an allowlisted environment variable",
+        annotation: "\
+-- Returns the value of an environment variable the server allows scripts
+-- to read, or nil for any other name (even if it is set).
+function os.getenv(name: string): string? end",
+        io_only: false,
+    },
 ];
 
 /// Returns all built-in functions. Call with `io_enabled` to filter.
@@ -119,7 +376,7 @@ mod tests {
     #[test]
     fn test_builtin_functions_with_io() {
         let funcs: Vec<_> = builtin_functions(true).collect();
-        assert_eq!(funcs.len(), 9);
+        assert_eq!(funcs.len(), 36);
         assert!(funcs.iter().any(|f| f.name == "io.open"));
         assert!(funcs.iter().any(|f| f.name == "json.encode"));
     }
@@ -127,7 +384,7 @@ mod tests {
     #[test]
     fn test_builtin_functions_without_io() {
         let funcs: Vec<_> = builtin_functions(false).collect();
-        assert_eq!(funcs.len(), 4); // json.encode, json.decode, print, os.clock
+        assert_eq!(funcs.len(), 28); // json.*, time.*, str.*, tbl.*, url.*, retry, validate, print, log.*, os.clock, os.getenv
         assert!(funcs.iter().all(|f| !f.io_only));
         assert!(!funcs.iter().any(|f| f.name == "io.open"));
     }
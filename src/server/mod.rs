@@ -1,18 +1,25 @@
 pub mod auth;
 pub mod builtins;
+pub mod prompts;
+pub mod reload;
 pub mod resources;
 pub mod tools;
 
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use anyhow::Context;
+use arc_swap::ArcSwap;
+
 use rmcp::ServerHandler;
 use rmcp::handler::server::router::Router;
 use rmcp::model::{
-    Implementation, ListResourcesResult, PaginatedRequestParams, ReadResourceRequestParams,
+    GetPromptRequestParams, GetPromptResult, Implementation, ListPromptsResult,
+    ListResourcesResult, PaginatedRequestParams, Prompt, ReadResourceRequestParams,
     ReadResourceResult, ServerCapabilities, ServerInfo,
 };
 use rmcp::service::{RequestContext, RoleServer};
+use rmcp::transport::streamable_http_server::{StreamableHttpServerConfig, StreamableHttpService};
 
 use crate::codegen::annotations::{render_function_docs, render_mcp_tool_docs};
 use crate::codegen::manifest::Manifest;
@@ -33,6 +40,10 @@ pub struct ToolScriptServer {
     pub auth: AuthCredentialsMap,
     /// Whether I/O operations are enabled (sandboxed file access).
     pub io_enabled: bool,
+    /// Connections to upstream MCP servers, used to proxy prompts.
+    pub mcp_client: Arc<McpClientManager>,
+    /// Upstream MCP prompts, renamed `{server}.{prompt}`.
+    pub prompts: Vec<Prompt>,
 }
 
 impl ToolScriptServer {
@@ -66,8 +77,13 @@ impl ToolScriptServer {
             annotation_cache.insert(builtin.name.to_string(), builtin.annotation.to_string());
         }
 
-        let executor =
-            ScriptExecutor::new(manifest.clone(), handler, config, io_config, mcp_client);
+        let executor = ScriptExecutor::new(
+            manifest.clone(),
+            handler,
+            config,
+            io_config,
+            Arc::clone(&mcp_client),
+        );
 
         Self {
             manifest,
@@ -75,9 +91,22 @@ impl ToolScriptServer {
             annotation_cache,
             auth,
             io_enabled,
+            mcp_client,
+            prompts: Vec::new(),
         }
     }
 
+    /// Expose upstream MCP prompts, keyed by server name, as
+    /// `{server}.{prompt}` prompts of this server.
+    #[must_use]
+    pub fn with_prompts<S: std::hash::BuildHasher>(
+        mut self,
+        upstream: HashMap<String, Vec<Prompt>, S>,
+    ) -> Self {
+        self.prompts = prompts::prefixed_prompts(upstream);
+        self
+    }
+
     /// Build the server info for the MCP protocol initialize response.
     /// Description and instructions are derived from the loaded manifest so
     /// the LLM knows which APIs this server exposes.
@@ -112,42 +141,14 @@ impl ToolScriptServer {
             api_summaries.join("; ")
         );
 
-        // Build instructions listing both APIs and MCP servers
-        let api_names: Vec<&str> = self.manifest.apis.iter().map(|a| a.name.as_str()).collect();
-        let mcp_names: Vec<&str> = self
-            .manifest
-            .mcp_servers
-            .iter()
-            .map(|s| s.name.as_str())
-            .collect();
-
-        let sources = if api_names.is_empty() && mcp_names.is_empty() {
-            "no APIs or MCP servers".to_string()
-        } else {
-            let mut parts = Vec::new();
-            if !api_names.is_empty() {
-                parts.push(format!("APIs: {}", api_names.join(", ")));
-            }
-            if !mcp_names.is_empty() {
-                parts.push(format!("MCP servers: {}", mcp_names.join(", ")));
-            }
-            parts.join("; and ")
-        };
-
-        let instructions = format!(
-            "This server provides a Luau SDK for the following {sources}. \
-             Use list_apis to see available APIs and MCP servers, \
-             list_functions to browse SDK functions (optionally filtered by API or server name), \
-             get_function_docs for detailed type signatures and parameter docs, \
-             search_docs to find functions by keyword, \
-             and execute_script to run Luau scripts that chain multiple API and MCP tool calls together.",
-        );
+        let instructions = server_instructions(&self.manifest);
 
         ServerInfo {
             protocol_version: rmcp::model::ProtocolVersion::default(),
             capabilities: ServerCapabilities::builder()
                 .enable_tools()
                 .enable_resources()
+                .enable_prompts()
                 .build(),
             server_info: Implementation {
                 name: "toolscript".to_string(),
@@ -161,14 +162,19 @@ impl ToolScriptServer {
         }
     }
 
-    /// Build a Router that wires tools + the server handler together.
+    /// Build a Router that wires tools, upstream prompts, and the server
+    /// handler together.
     pub fn into_router(self) -> Router<Self> {
+        let prompt_routes = prompts::prompt_routes(&self);
+        let execute_script = tools::execute_script_tool(&self.manifest);
         Router::new(self)
+            .with_prompts(prompt_routes)
             .with_tool(tools::list_apis_tool())
+            .with_tool(tools::list_mcp_servers_tool())
             .with_tool(tools::list_functions_tool())
             .with_tool(tools::get_function_docs_tool())
             .with_tool(tools::search_docs_tool())
-            .with_tool(tools::execute_script_tool())
+            .with_tool(execute_script)
     }
 }
 
@@ -203,11 +209,229 @@ impl ServerHandler for ToolScriptServer {
         );
         std::future::ready(result)
     }
+
+    fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> impl std::future::Future<Output = Result<ListPromptsResult, rmcp::ErrorData>> + Send + '_
+    {
+        std::future::ready(Ok(ListPromptsResult {
+            prompts: self.prompts.clone(),
+            ..Default::default()
+        }))
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, rmcp::ErrorData> {
+        prompts::get_prompt_impl(self, &request.name, request.arguments).await
+    }
+}
+
+/// Bind the HTTP transport listener on all interfaces.
+///
+/// Port 0 asks the OS for an ephemeral port. The returned address carries the
+/// port that was actually bound, so callers can report the real `/mcp` URL.
+pub async fn bind_http_listener(
+    port: u16,
+) -> anyhow::Result<(tokio::net::TcpListener, std::net::SocketAddr)> {
+    let listener = tokio::net::TcpListener::bind(("0.0.0.0", port))
+        .await
+        .with_context(|| format!("failed to bind HTTP listener on port {port}"))?;
+    let addr = listener.local_addr()?;
+    Ok((listener, addr))
+}
+
+/// Build the streamable HTTP MCP service for the `/mcp` route.
+///
+/// Every request is served by whichever server is in `server` when it
+/// arrives (see [`reload::ReloadingRouter`]), so a reload swapped in with
+/// [`ArcSwap::store`] reaches open sessions too, while requests already in
+/// flight finish on the old manifest.
+pub fn http_service(
+    server: Arc<ArcSwap<ToolScriptServer>>,
+    config: StreamableHttpServerConfig,
+) -> StreamableHttpService<reload::ReloadingRouter> {
+    StreamableHttpService::new(
+        move || Ok(reload::ReloadingRouter::new(server.clone())),
+        Arc::default(),
+        config,
+    )
+}
+
+/// Build the `/healthz` and `/readyz` probe routes for the HTTP transport.
+///
+/// `/healthz` always answers 200 once the process is serving. `/readyz` answers
+/// 200 when every configured upstream MCP server is connected and answering its
+/// keepalive pings, otherwise 503 with the list of failed servers. Merge these
+/// after any auth `route_layer` so the probes stay unauthenticated.
+pub fn health_router(mcp_client: Arc<McpClientManager>) -> axum::Router {
+    use axum::http::StatusCode;
+    use axum::routing::get;
+
+    axum::Router::new()
+        .route(
+            "/healthz",
+            get(|| async { axum::Json(serde_json::json!({ "status": "ok" })) }),
+        )
+        .route(
+            "/readyz",
+            get(move || async move {
+                let failed = mcp_client.failed_servers();
+                if failed.is_empty() {
+                    (
+                        StatusCode::OK,
+                        axum::Json(serde_json::json!({ "status": "ready" })),
+                    )
+                } else {
+                    (
+                        StatusCode::SERVICE_UNAVAILABLE,
+                        axum::Json(serde_json::json!({
+                            "status": "not_ready",
+                            "failed_servers": failed,
+                        })),
+                    )
+                }
+            }),
+        )
+}
+
+/// The MCP `instructions` text: the APIs and MCP servers this server exposes
+/// and how to use the discovery and execution tools.
+fn server_instructions(manifest: &Manifest) -> String {
+    // Build instructions listing both APIs and MCP servers
+    let api_names: Vec<&str> = manifest.apis.iter().map(|a| a.name.as_str()).collect();
+    let mcp_names: Vec<&str> = manifest
+        .mcp_servers
+        .iter()
+        .map(|s| s.name.as_str())
+        .collect();
+
+    let sources = if api_names.is_empty() && mcp_names.is_empty() {
+        "no APIs or MCP servers".to_string()
+    } else {
+        let mut parts = Vec::new();
+        if !api_names.is_empty() {
+            parts.push(format!("APIs: {}", api_names.join(", ")));
+        }
+        if !mcp_names.is_empty() {
+            parts.push(format!("MCP servers: {}", mcp_names.join(", ")));
+        }
+        parts.join("; and ")
+    };
+
+    format!(
+        "This server provides a Luau SDK for the following {sources}. \
+         Use list_apis to see available APIs and MCP servers, \
+         list_mcp_servers to see only the upstream MCP servers, \
+         list_functions to browse SDK functions (optionally filtered by API or server name), \
+         get_function_docs for detailed type signatures and parameter docs, \
+         search_docs to find functions by keyword, \
+         and execute_script to run Luau scripts that chain multiple API and MCP tool calls together.",
+    )
+}
+
+/// Everything an agent sees about the SDK in one document: the server
+/// instructions, then the docs for every function and MCP tool, as served by
+/// `get_function_docs`.
+pub fn render_script_context(manifest: &Manifest) -> String {
+    let mut sections = vec![server_instructions(manifest)];
+    sections.extend(
+        manifest
+            .functions
+            .iter()
+            .map(|func| render_function_docs(func, &manifest.schemas)),
+    );
+    sections.extend(
+        manifest
+            .mcp_servers
+            .iter()
+            .flat_map(|server| &server.tools)
+            .map(render_mcp_tool_docs),
+    );
+    let mut context = sections.join("\n\n");
+    context.push('\n');
+    context
+}
+
+/// One entry per function and MCP tool: where it comes from, the API or
+/// server, its SDK name, the endpoint it calls, and a one-line summary.
+pub fn function_list(manifest: &Manifest) -> Vec<serde_json::Value> {
+    let first_line = |text: Option<&String>| {
+        text.and_then(|t| t.lines().next())
+            .map(|line| line.trim().to_string())
+    };
+    let functions = manifest.functions.iter().map(|func| {
+        serde_json::json!({
+            "source": "api",
+            "api": func.api,
+            "name": func.name,
+            "endpoint": format!("{} {}", format!("{:?}", func.method).to_uppercase(), func.path),
+            "summary": first_line(func.summary.as_ref().or(func.description.as_ref())),
+        })
+    });
+    let tools = manifest.mcp_servers.iter().flat_map(|server| {
+        server.tools.iter().map(move |tool| {
+            serde_json::json!({
+                "source": "mcp",
+                "api": server.name,
+                "name": format!("{}.{}", server.name, tool.name),
+                "endpoint": null,
+                "summary": first_line(tool.description.as_ref()),
+            })
+        })
+    });
+    functions.chain(tools).collect()
+}
+
+/// Render [`function_list`] as an aligned `SOURCE NAME ENDPOINT SUMMARY` table.
+pub fn render_function_list(manifest: &Manifest) -> String {
+    let cell =
+        |entry: &serde_json::Value, key: &str| entry[key].as_str().unwrap_or("-").to_string();
+    let mut rows = vec![[
+        "SOURCE".to_string(),
+        "NAME".to_string(),
+        "ENDPOINT".to_string(),
+        "SUMMARY".to_string(),
+    ]];
+    rows.extend(function_list(manifest).iter().map(|entry| {
+        [
+            cell(entry, "source"),
+            cell(entry, "name"),
+            cell(entry, "endpoint"),
+            cell(entry, "summary"),
+        ]
+    }));
+    let widths: Vec<usize> = (0..3)
+        .map(|col| rows.iter().map(|row| row[col].len()).max().unwrap_or(0))
+        .collect();
+
+    let mut table = String::new();
+    for row in &rows {
+        let line = format!(
+            "{:<w0$}  {:<w1$}  {:<w2$}  {}",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+        );
+        table.push_str(line.trim_end());
+        table.push('\n');
+    }
+    table
 }
 
 #[cfg(test)]
 mod tests {
     #![allow(clippy::unwrap_used, clippy::expect_used)]
+    use std::collections::BTreeMap;
+
     use super::*;
     use crate::codegen::manifest::*;
     use crate::runtime::http::HttpHandler;
@@ -216,15 +440,25 @@ mod tests {
     #[allow(clippy::too_many_lines)]
     pub fn test_manifest() -> Manifest {
         Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
             apis: vec![ApiConfig {
                 name: "petstore".to_string(),
                 base_url: "https://petstore.example.com/v1".to_string(),
                 description: Some("A sample petstore API".to_string()),
                 version: Some("1.0.0".to_string()),
-                auth: Some(AuthConfig::Bearer {
-                    header: "Authorization".to_string(),
-                    prefix: "Bearer ".to_string(),
-                }),
+                auth: vec![AuthScheme {
+                    name: "bearerAuth".to_string(),
+                    config: AuthConfig::Bearer {
+                        header: "Authorization".to_string(),
+                        prefix: "Bearer ".to_string(),
+                    },
+                    oauth2: false,
+                }],
+                constant_headers: BTreeMap::new(),
+                rate_limit: None,
+                default_query: BTreeMap::new(),
+                deprecated: false,
+                boolean_style: None,
             }],
             functions: vec![
                 FunctionDef {
@@ -246,9 +480,17 @@ mod tests {
                         enum_values: None,
                         format: None,
                         frozen_value: None,
+                        deprecated: false,
+                        example: None,
+                        enum_descriptions: None,
+                        boolean_style: None,
+                        multiple_of: None,
                     }],
                     request_body: None,
                     response_schema: Some("Pet".to_string()),
+                    error_schema: None,
+                    auth: None,
+                    link_paginated: false,
                 },
                 FunctionDef {
                     name: "get_pet".to_string(),
@@ -269,9 +511,17 @@ mod tests {
                         enum_values: None,
                         format: None,
                         frozen_value: None,
+                        deprecated: false,
+                        example: None,
+                        enum_descriptions: None,
+                        boolean_style: None,
+                        multiple_of: None,
                     }],
                     request_body: None,
                     response_schema: Some("Pet".to_string()),
+                    error_schema: None,
+                    auth: None,
+                    link_paginated: false,
                 },
                 FunctionDef {
                     name: "create_pet".to_string(),
@@ -290,6 +540,9 @@ mod tests {
                         description: None,
                     }),
                     response_schema: Some("Pet".to_string()),
+                    error_schema: None,
+                    auth: None,
+                    link_paginated: false,
                 },
             ],
             schemas: vec![
@@ -305,6 +558,8 @@ mod tests {
                             enum_values: None,
                             nullable: false,
                             format: None,
+                            example: None,
+                            enum_descriptions: None,
                         },
                         FieldDef {
                             name: "name".to_string(),
@@ -314,8 +569,11 @@ mod tests {
                             enum_values: None,
                             nullable: false,
                             format: None,
+                            example: None,
+                            enum_descriptions: None,
                         },
                     ],
+                    discriminator: None,
                 },
                 SchemaDef {
                     name: "NewPet".to_string(),
@@ -328,7 +586,10 @@ mod tests {
                         enum_values: None,
                         nullable: false,
                         format: None,
+                        example: None,
+                        enum_descriptions: None,
                     }],
+                    discriminator: None,
                 },
             ],
             mcp_servers: vec![McpServerEntry {
@@ -347,8 +608,11 @@ mod tests {
                     }],
                     schemas: vec![],
                     output_schemas: vec![],
+                    output_type: None,
+                    idempotent: false,
                 }],
             }],
+            generated: None,
         }
     }
 
@@ -363,6 +627,13 @@ mod tests {
         )
     }
 
+    #[tokio::test]
+    async fn test_bind_http_listener_ephemeral_port() {
+        let (listener, addr) = bind_http_listener(0).await.unwrap();
+        assert_ne!(addr.port(), 0);
+        assert_eq!(listener.local_addr().unwrap().port(), addr.port());
+    }
+
     #[test]
     fn test_list_apis() {
         let server = test_server();
@@ -374,15 +645,62 @@ mod tests {
         assert_eq!(apis[0]["base_url"], "https://petstore.example.com/v1");
         assert_eq!(apis[0]["version"], "1.0.0");
         assert_eq!(apis[0]["function_count"], 3);
+        assert_eq!(apis[0]["deprecated"], false);
+    }
+
+    #[test]
+    fn test_list_apis_reports_deprecated_api() {
+        let mut manifest = test_manifest();
+        for func in &mut manifest.functions {
+            func.deprecated = true;
+        }
+        manifest.apis[0].deprecated = true;
+        let server = ToolScriptServer::new(
+            manifest,
+            Arc::new(HttpHandler::mock(|_, _, _, _| Ok(serde_json::json!({})))),
+            AuthCredentialsMap::new(),
+            ExecutorConfig::default(),
+            None,
+            Arc::new(McpClientManager::empty()),
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&tools::list_apis_impl(&server)).unwrap();
+        assert_eq!(json[0]["name"], "petstore");
+        assert_eq!(json[0]["deprecated"], true);
+    }
+
+    #[test]
+    fn test_list_apis_notes_oauth2() {
+        let mut manifest = test_manifest();
+        manifest.apis[0].auth[0].oauth2 = true;
+        let server = ToolScriptServer::new(
+            manifest,
+            Arc::new(HttpHandler::mock(|_, _, _, _| Ok(serde_json::json!({})))),
+            AuthCredentialsMap::new(),
+            ExecutorConfig::default(),
+            None,
+            Arc::new(McpClientManager::empty()),
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&tools::list_apis_impl(&server)).unwrap();
+        assert!(
+            json[0]["auth_note"].as_str().unwrap().contains("OAuth2"),
+            "got: {}",
+            json[0]
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&tools::list_apis_impl(&test_server())).unwrap();
+        assert!(json[0].get("auth_note").is_none());
     }
 
     #[test]
     fn test_list_functions_all() {
         let server = test_server();
-        let result = tools::list_functions_impl(&server, None, None);
+        let result = tools::list_functions_impl(&server, None, None, None, None);
         let json: serde_json::Value = serde_json::from_str(&result).unwrap();
-        let funcs = json.as_array().unwrap();
-        assert_eq!(funcs.len(), 8); // 3 OpenAPI + 1 MCP + 4 builtins (no io)
+        let funcs = json["functions"].as_array().unwrap();
+        assert_eq!(funcs.len(), 32); // 3 OpenAPI + 1 MCP + 28 builtins (no io)
         // Check that create_pet has deprecated=true
         let create = funcs.iter().find(|f| f["name"] == "create_pet").unwrap();
         assert_eq!(create["deprecated"], true);
@@ -391,18 +709,70 @@ mod tests {
     #[test]
     fn test_list_functions_filtered_by_tag() {
         let server = test_server();
-        let result = tools::list_functions_impl(&server, None, Some("pets"));
+        let result = tools::list_functions_impl(&server, None, Some("pets"), None, None);
         let json: serde_json::Value = serde_json::from_str(&result).unwrap();
-        let funcs = json.as_array().unwrap();
+        let funcs = json["functions"].as_array().unwrap();
         assert_eq!(funcs.len(), 3); // all are tagged "pets"
 
         // Filter by non-existent tag
-        let result = tools::list_functions_impl(&server, None, Some("users"));
+        let result = tools::list_functions_impl(&server, None, Some("users"), None, None);
         let json: serde_json::Value = serde_json::from_str(&result).unwrap();
-        let funcs = json.as_array().unwrap();
+        let funcs = json["functions"].as_array().unwrap();
         assert_eq!(funcs.len(), 0);
     }
 
+    #[test]
+    fn test_list_functions_paginated() {
+        let server = test_server();
+        let result = tools::list_functions_impl(&server, None, None, None, Some(3));
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        let funcs = json["functions"].as_array().unwrap();
+        assert_eq!(funcs.len(), 3);
+        assert_eq!(funcs[0]["name"], "list_pets");
+        assert_eq!(json["total"], 32);
+        assert_eq!(json["next_offset"], 3);
+
+        let result = tools::list_functions_impl(&server, None, None, Some(3), Some(3));
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        let funcs = json["functions"].as_array().unwrap();
+        assert_eq!(funcs.len(), 3);
+        assert_eq!(funcs[0]["name"], "read_file");
+        assert_eq!(json["next_offset"], 6);
+
+        // Last page is short and has no next_offset
+        let result = tools::list_functions_impl(&server, None, None, Some(30), Some(3));
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(json["functions"].as_array().unwrap().len(), 2);
+        assert!(json["next_offset"].is_null());
+
+        // Offset past the end yields an empty page
+        let result = tools::list_functions_impl(&server, None, None, Some(100), None);
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert!(json["functions"].as_array().unwrap().is_empty());
+        assert_eq!(json["total"], 32);
+        assert!(json["next_offset"].is_null());
+    }
+
+    #[test]
+    fn test_list_functions_pagination_composes_with_filters() {
+        let server = test_server();
+        let result = tools::list_functions_impl(&server, Some("petstore"), None, None, Some(2));
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        let funcs = json["functions"].as_array().unwrap();
+        assert_eq!(funcs.len(), 2);
+        assert!(funcs.iter().all(|f| f["api"] == "petstore"));
+        assert_eq!(json["total"], 3);
+        assert_eq!(json["next_offset"], 2);
+
+        let result = tools::list_functions_impl(&server, None, Some("pets"), Some(2), Some(2));
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        let funcs = json["functions"].as_array().unwrap();
+        assert_eq!(funcs.len(), 1);
+        assert_eq!(funcs[0]["name"], "create_pet");
+        assert_eq!(json["total"], 3);
+        assert!(json["next_offset"].is_null());
+    }
+
     #[test]
     fn test_get_function_docs_found() {
         let server = test_server();
@@ -432,6 +802,58 @@ mod tests {
         assert!(!items.is_empty());
     }
 
+    #[test]
+    fn test_search_docs_ranks_name_match_first() {
+        let mut manifest = test_manifest();
+        for func in &mut manifest.functions {
+            if func.name == "get_pet" {
+                func.description = Some("Use create_pet to add new pets".to_string());
+            }
+        }
+        let server = ToolScriptServer::new(
+            manifest,
+            Arc::new(HttpHandler::mock(|_, _, _, _| Ok(serde_json::json!({})))),
+            AuthCredentialsMap::new(),
+            ExecutorConfig::default(),
+            None,
+            Arc::new(McpClientManager::empty()),
+        );
+        // get_pet comes first in the manifest but only matches "create" in its description
+        let results = tools::search_docs_impl(&server, "create");
+        let json: serde_json::Value = serde_json::from_str(&results).unwrap();
+        let items = json.as_array().unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0]["name"], "create_pet");
+        assert_eq!(items[1]["name"], "get_pet");
+        assert!(items[0]["score"].as_u64().unwrap() > items[1]["score"].as_u64().unwrap());
+    }
+
+    #[test]
+    fn test_search_docs_multi_word_requires_all_terms() {
+        let server = test_server();
+        let results = tools::search_docs_impl(&server, "Create PET");
+        let json: serde_json::Value = serde_json::from_str(&results).unwrap();
+        let items = json.as_array().unwrap();
+        let names: Vec<&str> = items.iter().map(|i| i["name"].as_str().unwrap()).collect();
+        // list_pets and get_pet match "pet" but not "create"
+        assert_eq!(names, vec!["create_pet"]);
+
+        // Terms may match in different fields of the same entry
+        let results = tools::search_docs_impl(&server, "pet store");
+        let json: serde_json::Value = serde_json::from_str(&results).unwrap();
+        let items = json.as_array().unwrap();
+        let names: Vec<&str> = items.iter().map(|i| i["name"].as_str().unwrap()).collect();
+        assert_eq!(names, vec!["list_pets", "Pet"]);
+
+        let results = tools::search_docs_impl(&server, "create nonexistent");
+        let json: serde_json::Value = serde_json::from_str(&results).unwrap();
+        assert!(json.as_array().unwrap().is_empty());
+
+        let results = tools::search_docs_impl(&server, "   ");
+        let json: serde_json::Value = serde_json::from_str(&results).unwrap();
+        assert!(json.as_array().unwrap().is_empty());
+    }
+
     #[test]
     fn test_frozen_params_hidden_from_docs() {
         let mut manifest = test_manifest();
@@ -462,6 +884,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_constant_headers_hidden_from_docs() {
+        let mut manifest = test_manifest();
+        manifest.apis[0]
+            .constant_headers
+            .insert("X-Tenant-ID".to_string(), "acme".to_string());
+        let server = ToolScriptServer::new(
+            manifest,
+            Arc::new(HttpHandler::mock(|_, _, _, _| Ok(serde_json::json!({})))),
+            AuthCredentialsMap::new(),
+            ExecutorConfig::default(),
+            None,
+            Arc::new(McpClientManager::empty()),
+        );
+
+        let docs = tools::get_function_docs_impl(&server, "list_pets").unwrap();
+        assert!(
+            !docs.contains("X-Tenant-ID"),
+            "Constant header should not appear in docs. Got:\n{docs}"
+        );
+    }
+
     #[test]
     fn test_frozen_params_hidden_from_search() {
         let mut manifest = test_manifest();
@@ -533,6 +977,7 @@ mod tests {
             Some(crate::runtime::executor::IoConfig {
                 dir: output_dir.path().to_path_buf(),
                 max_bytes: 50 * 1024 * 1024,
+                max_files: 1000,
             }),
             Arc::new(McpClientManager::empty()),
         );
@@ -557,6 +1002,37 @@ mod tests {
         assert_eq!(result.files_touched[0].name, "test.txt");
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_execute_script_structured_content() {
+        let server = test_server();
+        let meta_auth = AuthCredentialsMap::new();
+
+        let result = tools::execute_script_impl(
+            &server,
+            r#"return { name = "Fido", tags = { "good", "dog" } }"#,
+            None,
+            None,
+            &meta_auth,
+            None,
+        )
+        .await;
+        assert_eq!(result.is_error, Some(false));
+        assert_eq!(
+            result.structured_content,
+            Some(serde_json::json!({ "name": "Fido", "tags": ["good", "dog"] }))
+        );
+        // Text content still carries the full response envelope
+        let text = &result.content[0].as_text().unwrap().text;
+        let json: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(json["result"]["name"], "Fido");
+
+        // Non-table results have no structured content
+        let result =
+            tools::execute_script_impl(&server, "return 42", None, None, &meta_auth, None).await;
+        assert_eq!(result.is_error, Some(false));
+        assert!(result.structured_content.is_none());
+    }
+
     #[test]
     fn test_list_apis_includes_mcp() {
         let server = test_server();
@@ -569,12 +1045,24 @@ mod tests {
         assert_eq!(mcp_entry["description"], "File system access");
     }
 
+    #[test]
+    fn test_list_mcp_servers() {
+        let server = test_server();
+        let result = tools::list_mcp_servers_impl(&server);
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        let servers = json.as_array().unwrap();
+        assert_eq!(servers.len(), 1); // OpenAPI APIs and builtins are excluded
+        assert_eq!(servers[0]["name"], "filesystem");
+        assert_eq!(servers[0]["tool_count"], 1);
+        assert_eq!(servers[0]["description"], "File system access");
+    }
+
     #[test]
     fn test_list_functions_includes_mcp() {
         let server = test_server();
-        let result = tools::list_functions_impl(&server, None, None);
+        let result = tools::list_functions_impl(&server, None, None, None, None);
         let json: serde_json::Value = serde_json::from_str(&result).unwrap();
-        let funcs = json.as_array().unwrap();
+        let funcs = json["functions"].as_array().unwrap();
         let mcp_tool = funcs.iter().find(|f| f["name"] == "read_file").unwrap();
         assert_eq!(mcp_tool["source"], "mcp");
         assert_eq!(mcp_tool["api"], "filesystem");
@@ -586,17 +1074,17 @@ mod tests {
     fn test_list_functions_filtered_by_mcp_server() {
         let server = test_server();
         // Filter by MCP server name
-        let result = tools::list_functions_impl(&server, Some("filesystem"), None);
+        let result = tools::list_functions_impl(&server, Some("filesystem"), None, None, None);
         let json: serde_json::Value = serde_json::from_str(&result).unwrap();
-        let funcs = json.as_array().unwrap();
+        let funcs = json["functions"].as_array().unwrap();
         assert_eq!(funcs.len(), 1);
         assert_eq!(funcs[0]["name"], "read_file");
         assert_eq!(funcs[0]["source"], "mcp");
 
         // Filter by OpenAPI API name should not include MCP tools
-        let result = tools::list_functions_impl(&server, Some("petstore"), None);
+        let result = tools::list_functions_impl(&server, Some("petstore"), None, None, None);
         let json: serde_json::Value = serde_json::from_str(&result).unwrap();
-        let funcs = json.as_array().unwrap();
+        let funcs = json["functions"].as_array().unwrap();
         assert_eq!(funcs.len(), 3);
         assert!(funcs.iter().all(|f| f["source"] != "mcp"));
     }
@@ -633,6 +1121,79 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_execute_script_description_shows_manifest_example() {
+        let router = test_server().into_router();
+        let tool = router
+            .tool_router
+            .list_all()
+            .into_iter()
+            .find(|t| t.name == "execute_script")
+            .unwrap();
+        let desc = tool.description.unwrap();
+        assert!(
+            desc.contains("local r1 = sdk.list_pets()\nlocal r2 = sdk.get_pet({ pet_id = "),
+            "Description should call a real function. Got:\n{desc}"
+        );
+    }
+
+    #[test]
+    fn test_render_function_list_for_petstore() {
+        let spec = crate::codegen::parser::load_spec_from_file(std::path::Path::new(
+            "testdata/petstore.yaml",
+        ))
+        .unwrap();
+        let mut manifest = crate::codegen::parser::spec_to_manifest(&spec, "petstore").unwrap();
+        manifest.mcp_servers = test_manifest().mcp_servers;
+
+        let table = render_function_list(&manifest);
+        let rows: Vec<Vec<&str>> = table
+            .lines()
+            .map(|line| line.split_whitespace().collect())
+            .collect();
+        assert_eq!(rows[0], ["SOURCE", "NAME", "ENDPOINT", "SUMMARY"]);
+        assert!(
+            rows.iter()
+                .any(|row| row[..4] == ["api", "list_pets", "GET", "/pets"]),
+            "Got:\n{table}"
+        );
+        assert!(
+            rows.iter()
+                .any(|row| row[..3] == ["mcp", "filesystem.read_file", "-"]),
+            "Got:\n{table}"
+        );
+
+        let list = function_list(&manifest);
+        let list_pets = list.iter().find(|e| e["name"] == "list_pets").unwrap();
+        assert_eq!(list_pets["source"], "api");
+        assert_eq!(list_pets["endpoint"], "GET /pets");
+    }
+
+    #[test]
+    fn test_render_script_context_for_petstore() {
+        let spec = crate::codegen::parser::load_spec_from_file(std::path::Path::new(
+            "testdata/petstore.yaml",
+        ))
+        .unwrap();
+        let mut manifest = crate::codegen::parser::spec_to_manifest(&spec, "petstore").unwrap();
+        manifest.mcp_servers = test_manifest().mcp_servers;
+
+        let context = render_script_context(&manifest);
+        assert!(
+            context.starts_with("This server provides a Luau SDK for the following APIs: petstore; and MCP servers: filesystem."),
+            "Got:\n{context}"
+        );
+        assert!(
+            context.contains("function sdk.list_pets"),
+            "Got:\n{context}"
+        );
+        assert!(context.contains("export type Pet = {"), "Got:\n{context}");
+        assert!(
+            context.contains("function sdk.filesystem.read_file"),
+            "Got:\n{context}"
+        );
+    }
+
     #[test]
     fn test_search_docs_finds_mcp_tool() {
         let server = test_server();
@@ -652,16 +1213,16 @@ mod tests {
         let apis = json.as_array().unwrap();
         let luau_entry = apis.iter().find(|a| a["name"] == "luau").unwrap();
         assert_eq!(luau_entry["source"], "builtin");
-        assert_eq!(luau_entry["function_count"], 4); // no io in test_server
+        assert_eq!(luau_entry["function_count"], 28); // no io in test_server
     }
 
     #[test]
     fn test_list_functions_filtered_by_luau() {
         let server = test_server();
-        let result = tools::list_functions_impl(&server, Some("luau"), None);
+        let result = tools::list_functions_impl(&server, Some("luau"), None, None, None);
         let json: serde_json::Value = serde_json::from_str(&result).unwrap();
-        let funcs = json.as_array().unwrap();
-        assert_eq!(funcs.len(), 4); // json.encode, json.decode, print, os.clock
+        let funcs = json["functions"].as_array().unwrap();
+        assert_eq!(funcs.len(), 28); // json.*, time.*, str.*, tbl.*, url.*, retry, validate, print, log.*, os.clock, os.getenv
         assert!(funcs.iter().all(|f| f["source"] == "builtin"));
         assert!(funcs.iter().all(|f| f["api"] == "luau"));
     }
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use futures::FutureExt;
+use rmcp::handler::server::prompt::PromptContext;
+use rmcp::handler::server::router::prompt::PromptRoute;
+use rmcp::model::{GetPromptResult, Prompt};
+
+use super::ToolScriptServer;
+
+/// Rename upstream prompts to `{server}.{prompt}` and flatten them into one
+/// list, sorted by the prefixed name.
+///
+/// The prefix keeps prompts from different upstream servers apart and tells
+/// [`get_prompt_impl`] which server to forward a request to.
+pub fn prefixed_prompts<S: std::hash::BuildHasher>(
+    upstream: HashMap<String, Vec<Prompt>, S>,
+) -> Vec<Prompt> {
+    let mut prompts: Vec<Prompt> = upstream
+        .into_iter()
+        .flat_map(|(server, prompts)| {
+            prompts.into_iter().map(move |mut prompt| {
+                prompt.name = format!("{server}.{}", prompt.name);
+                prompt
+            })
+        })
+        .collect();
+    prompts.sort_by(|a, b| a.name.cmp(&b.name));
+    prompts
+}
+
+/// Fetch a prefixed prompt by forwarding it to the upstream server that owns it.
+pub async fn get_prompt_impl(
+    server: &ToolScriptServer,
+    name: &str,
+    arguments: Option<serde_json::Map<String, serde_json::Value>>,
+) -> Result<GetPromptResult, rmcp::ErrorData> {
+    let known = server.prompts.iter().any(|p| p.name == name);
+    let Some((upstream, prompt_name)) = name.split_once('.').filter(|_| known) else {
+        return Err(rmcp::ErrorData::invalid_params(
+            format!("Prompt '{name}' not found"),
+            None,
+        ));
+    };
+    server
+        .mcp_client
+        .get_prompt(upstream, prompt_name, arguments)
+        .await
+        .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))
+}
+
+/// Prompt routes for every upstream prompt known to `server`.
+///
+/// The rmcp router answers `prompts/list` from its registered routes, so each
+/// upstream prompt needs a route that proxies `prompts/get`.
+pub fn prompt_routes(server: &ToolScriptServer) -> Vec<PromptRoute<ToolScriptServer>> {
+    server
+        .prompts
+        .iter()
+        .map(|prompt| {
+            PromptRoute::new_dyn(
+                prompt.clone(),
+                |context: PromptContext<'_, ToolScriptServer>| {
+                    async move {
+                        get_prompt_impl(context.server, &context.name, context.arguments).await
+                    }
+                    .boxed()
+                },
+            )
+        })
+        .collect()
+}
+
+// ---- Arc<ToolScriptServer> prompt variants for HTTP transport ----
+
+pub fn prompt_routes_arc(server: &ToolScriptServer) -> Vec<PromptRoute<Arc<ToolScriptServer>>> {
+    server
+        .prompts
+        .iter()
+        .map(|prompt| {
+            PromptRoute::new_dyn(
+                prompt.clone(),
+                |context: PromptContext<'_, Arc<ToolScriptServer>>| {
+                    async move {
+                        get_prompt_impl(context.server, &context.name, context.arguments).await
+                    }
+                    .boxed()
+                },
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+    use super::*;
+
+    #[test]
+    fn test_prefixed_prompts_sorted_by_server() {
+        let mut upstream = HashMap::new();
+        upstream.insert(
+            "github".to_string(),
+            vec![Prompt::new("review_pr", Some("Review a PR"), None)],
+        );
+        upstream.insert(
+            "docs".to_string(),
+            vec![
+                Prompt::new("summarize", None::<String>, None),
+                Prompt::new("explain", None::<String>, None),
+            ],
+        );
+        let names: Vec<String> = prefixed_prompts(upstream)
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(
+            names,
+            ["docs.explain", "docs.summarize", "github.review_pr"]
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! Hot reload of the served manifest for the HTTP transport.
+
+use std::future::Future;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use arc_swap::ArcSwap;
+use rmcp::handler::server::router::Router;
+use rmcp::service::{NotificationContext, RequestContext, RoleServer, ServiceRole};
+use rmcp::{ErrorData, Service};
+use tokio::sync::mpsc;
+
+use super::{ToolScriptServer, prompts, tools};
+
+/// MCP service over whichever server is current in an [`ArcSwap`].
+///
+/// Each request loads the current server once and finishes on it, even if a
+/// reload lands midway; the next request, on this session or any other, sees
+/// the reloaded server. The tool and prompt router is rebuilt only when the
+/// server has changed.
+pub struct ReloadingRouter {
+    server: Arc<ArcSwap<ToolScriptServer>>,
+    current: Mutex<Option<Arc<Router<Arc<ToolScriptServer>>>>>,
+}
+
+impl ReloadingRouter {
+    /// Serve the server currently stored in `server`.
+    pub const fn new(server: Arc<ArcSwap<ToolScriptServer>>) -> Self {
+        Self {
+            server,
+            current: Mutex::new(None),
+        }
+    }
+
+    /// The router for the current server, reusing the last one if the server
+    /// has not been swapped since.
+    fn router(&self) -> Arc<Router<Arc<ToolScriptServer>>> {
+        let server = self.server.load_full();
+        let mut current = self.current.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(router) = current.as_ref()
+            && Arc::ptr_eq(&*router.service, &server)
+        {
+            return Arc::clone(router);
+        }
+        let router = Arc::new(
+            Router::new(Arc::clone(&server))
+                .with_tool(tools::list_apis_tool_arc())
+                .with_tool(tools::list_mcp_servers_tool_arc())
+                .with_tool(tools::list_functions_tool_arc())
+                .with_tool(tools::get_function_docs_tool_arc())
+                .with_tool(tools::search_docs_tool_arc())
+                .with_tool(tools::execute_script_tool_arc(&server.manifest))
+                .with_prompts(prompts::prompt_routes_arc(&server)),
+        );
+        *current = Some(Arc::clone(&router));
+        router
+    }
+}
+
+impl Service<RoleServer> for ReloadingRouter {
+    fn handle_request(
+        &self,
+        request: <RoleServer as ServiceRole>::PeerReq,
+        context: RequestContext<RoleServer>,
+    ) -> impl Future<Output = Result<<RoleServer as ServiceRole>::Resp, ErrorData>> + Send + '_
+    {
+        let router = self.router();
+        async move { router.handle_request(request, context).await }
+    }
+
+    fn handle_notification(
+        &self,
+        notification: <RoleServer as ServiceRole>::PeerNot,
+        context: NotificationContext<RoleServer>,
+    ) -> impl Future<Output = Result<(), ErrorData>> + Send + '_ {
+        let router = self.router();
+        async move { router.handle_notification(notification, context).await }
+    }
+
+    fn get_info(&self) -> <RoleServer as ServiceRole>::Info {
+        self.router().get_info()
+    }
+}
+
+/// Rebuild the server each time a reload is signalled and swap it into `server`.
+///
+/// `signals` is fed by SIGHUP in the binary and by tests directly. A failed
+/// rebuild is logged and the current server keeps serving. Returns once every
+/// sender is dropped.
+pub async fn reload_on<F, Fut>(
+    server: Arc<ArcSwap<ToolScriptServer>>,
+    mut signals: mpsc::Receiver<()>,
+    rebuild: F,
+) where
+    F: Fn() -> Fut,
+    Fut: Future<Output = anyhow::Result<ToolScriptServer>>,
+{
+    while signals.recv().await.is_some() {
+        match rebuild().await {
+            Ok(next) => {
+                tracing::info!(
+                    apis = next.manifest.apis.len(),
+                    functions = next.manifest.functions.len(),
+                    "reloaded manifest"
+                );
+                server.store(Arc::new(next));
+            }
+            Err(e) => tracing::warn!("reload failed, keeping the current manifest: {e:#}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+    use std::collections::HashMap;
+
+    use rmcp::transport::streamable_http_server::StreamableHttpServerConfig;
+
+    use super::*;
+    use crate::codegen::manifest::Manifest;
+    use crate::runtime::executor::ExecutorConfig;
+    use crate::runtime::http::{AuthCredentialsMap, HttpHandler};
+    use crate::runtime::mcp_client::{McpClientManager, McpServerResolvedConfig};
+    use crate::server::tests::test_manifest;
+
+    fn server_with(manifest: Manifest) -> ToolScriptServer {
+        ToolScriptServer::new(
+            manifest,
+            Arc::new(HttpHandler::mock(|_, _, _, _| {
+                Ok(serde_json::json!({"adopted": true}))
+            })),
+            AuthCredentialsMap::new(),
+            ExecutorConfig::default(),
+            None,
+            Arc::new(McpClientManager::empty()),
+        )
+    }
+
+    async fn connect(url: &str) -> McpClientManager {
+        let mut configs = HashMap::new();
+        configs.insert(
+            "toolscript".to_string(),
+            McpServerResolvedConfig::Http {
+                url: url.to_string(),
+            },
+        );
+        McpClientManager::connect_all(configs).await.unwrap()
+    }
+
+    async fn run_script(client: &McpClientManager, script: &str) -> serde_json::Value {
+        let mut args = serde_json::Map::new();
+        args.insert("script".to_string(), serde_json::json!(script));
+        let result = client
+            .call_tool("toolscript", "execute_script", Some(args))
+            .await
+            .unwrap();
+        let text = &result.content[0].as_text().unwrap().text;
+        serde_json::from_str::<serde_json::Value>(text).unwrap()["result"].clone()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_reload_reaches_new_and_open_sessions() {
+        let original = test_manifest();
+        let mut reloaded = test_manifest();
+        let mut adopt = reloaded.functions[0].clone();
+        adopt.name = "adopt_pet".to_string();
+        reloaded.functions.push(adopt);
+
+        let server = Arc::new(ArcSwap::from_pointee(server_with(original)));
+        let service =
+            crate::server::http_service(server.clone(), StreamableHttpServerConfig::default());
+        let app = axum::Router::new().nest_service("/mcp", service);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let before = connect(&url).await;
+        assert_eq!(
+            run_script(&before, "return type(sdk.adopt_pet)").await,
+            "nil"
+        );
+
+        let (tx, rx) = mpsc::channel(1);
+        let reload_task = tokio::spawn(reload_on(server, rx, move || {
+            let manifest = reloaded.clone();
+            async move { Ok(server_with(manifest)) }
+        }));
+        tx.send(()).await.unwrap();
+        drop(tx);
+        reload_task.await.unwrap();
+
+        let after = connect(&url).await;
+        assert_eq!(
+            run_script(&after, "return sdk.adopt_pet().adopted").await,
+            true
+        );
+        // The session opened before the reload sees it on its next request
+        assert_eq!(
+            run_script(&before, "return sdk.adopt_pet().adopted").await,
+            true
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_reload_keeps_current_server() {
+        let server = Arc::new(ArcSwap::from_pointee(server_with(test_manifest())));
+        let (tx, rx) = mpsc::channel(1);
+        let reload_task = tokio::spawn(reload_on(server.clone(), rx, || async {
+            anyhow::bail!("spec fetch failed")
+        }));
+        tx.send(()).await.unwrap();
+        drop(tx);
+        reload_task.await.unwrap();
+        assert_eq!(
+            server.load().manifest.functions.len(),
+            test_manifest().functions.len()
+        );
+    }
+}
@@ -493,7 +493,7 @@ mod tests {
         assert_eq!(result.contents.len(), 1);
         if let ResourceContents::TextResourceContents { text, .. } = &result.contents[0] {
             assert!(text.contains("Luau Runtime"), "Got:\n{text}");
-            assert!(text.contains("Documented functions: 4"), "Got:\n{text}");
+            assert!(text.contains("Documented functions: 28"), "Got:\n{text}");
         } else {
             panic!("Expected TextResourceContents");
         }
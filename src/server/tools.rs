@@ -10,6 +10,8 @@ use serde::Deserialize;
 use super::ToolScriptServer;
 use super::auth;
 use super::builtins;
+use crate::codegen::manifest::{FunctionDef, Manifest, ParamType};
+use crate::runtime::executor::CallerContext;
 use crate::runtime::http::AuthCredentialsMap;
 
 // ---- Tool parameter structs ----
@@ -18,6 +20,8 @@ use crate::runtime::http::AuthCredentialsMap;
 struct ListFunctionsParams {
     api: Option<String>,
     tag: Option<String>,
+    offset: Option<usize>,
+    limit: Option<usize>,
 }
 
 #[derive(Deserialize)]
@@ -34,8 +38,12 @@ struct QueryParam {
 struct ExecuteScriptParams {
     script: String,
     timeout_ms: Option<u64>,
+    correlation_id: Option<String>,
 }
 
+/// Page size used by `list_functions` when the caller does not pass a `limit`.
+pub const DEFAULT_LIST_FUNCTIONS_LIMIT: usize = 50;
+
 // ---- Tool implementations (pure logic, testable without MCP protocol) ----
 
 /// Implementation for `list_apis`: returns JSON array of API summaries.
@@ -51,13 +59,20 @@ pub fn list_apis_impl(server: &ToolScriptServer) -> String {
                 .iter()
                 .filter(|f| f.api == api.name)
                 .count();
-            serde_json::json!({
+            let mut entry = serde_json::json!({
                 "name": api.name,
                 "description": api.description,
                 "version": api.version,
                 "base_url": api.base_url,
                 "function_count": function_count,
-            })
+                "deprecated": api.deprecated,
+            });
+            if api.auth.iter().any(|scheme| scheme.oauth2) {
+                entry["auth_note"] = serde_json::json!(
+                    "uses OAuth2; calls send the configured access token as a bearer token"
+                );
+            }
+            entry
         })
         .collect();
 
@@ -83,11 +98,32 @@ pub fn list_apis_impl(server: &ToolScriptServer) -> String {
     serde_json::to_string_pretty(&apis).unwrap_or_else(|_| "[]".to_string())
 }
 
-/// Implementation for `list_functions`: returns JSON array of function summaries.
+/// Implementation for `list_mcp_servers`: returns JSON array of upstream MCP server summaries.
+pub fn list_mcp_servers_impl(server: &ToolScriptServer) -> String {
+    let servers: Vec<serde_json::Value> = server
+        .manifest
+        .mcp_servers
+        .iter()
+        .map(|mcp_server| {
+            serde_json::json!({
+                "name": mcp_server.name,
+                "description": mcp_server.description,
+                "tool_count": mcp_server.tools.len(),
+            })
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&servers).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Implementation for `list_functions`: returns a page of function summaries as
+/// `{ functions, total, next_offset }`. `next_offset` is null on the last page.
 pub fn list_functions_impl(
     server: &ToolScriptServer,
     api: Option<&str>,
     tag: Option<&str>,
+    offset: Option<usize>,
+    limit: Option<usize>,
 ) -> String {
     let mut funcs: Vec<serde_json::Value> = server
         .manifest
@@ -150,7 +186,19 @@ pub fn list_functions_impl(
         }
     }
 
-    serde_json::to_string_pretty(&funcs).unwrap_or_else(|_| "[]".to_string())
+    let total = funcs.len();
+    let offset = offset.unwrap_or(0).min(total);
+    let limit = limit.unwrap_or(DEFAULT_LIST_FUNCTIONS_LIMIT).max(1);
+    let end = offset.saturating_add(limit).min(total);
+    let next_offset = (end < total).then_some(end);
+    let page: Vec<serde_json::Value> = funcs.drain(offset..end).collect();
+
+    let response = serde_json::json!({
+        "functions": page,
+        "total": total,
+        "next_offset": next_offset,
+    });
+    serde_json::to_string_pretty(&response).unwrap_or_else(|_| "{}".to_string())
 }
 
 /// Implementation for `get_function_docs`: returns the full Luau type annotation.
@@ -162,149 +210,220 @@ pub fn get_function_docs_impl(server: &ToolScriptServer, name: &str) -> Result<S
         .ok_or_else(|| format!("Function '{name}' not found"))
 }
 
-/// Implementation for `search_docs`: case-insensitive search across all documentation.
+/// Score weight for a query term found in a name.
+const NAME_WEIGHT: u32 = 10;
+/// Score weight for a query term found in a summary.
+const SUMMARY_WEIGHT: u32 = 5;
+/// Score weight for a query term found in a description, parameter, field, or annotation.
+const DETAIL_WEIGHT: u32 = 1;
+
+/// A searchable piece of documentation with its score weight and the context
+/// line reported when it matches.
+struct SearchField {
+    weight: u32,
+    text: String,
+    context: String,
+}
+
+impl SearchField {
+    fn new(weight: u32, text: &str, context: String) -> Self {
+        Self {
+            weight,
+            text: text.to_lowercase(),
+            context,
+        }
+    }
+}
+
+/// Score a documentation entry against the query terms.
+///
+/// Every term must appear in at least one field; each term contributes the
+/// weight of the best field it appears in. Returns `None` when any term is
+/// missing, otherwise the score and the context lines of all matching fields.
+fn score_fields(terms: &[String], fields: &[SearchField]) -> Option<(u32, Vec<String>)> {
+    let mut score = 0;
+    for term in terms {
+        score += fields
+            .iter()
+            .filter(|f| f.text.contains(term.as_str()))
+            .map(|f| f.weight)
+            .max()?;
+    }
+    let context = fields
+        .iter()
+        .filter(|f| terms.iter().any(|t| f.text.contains(t.as_str())))
+        .map(|f| f.context.clone())
+        .collect();
+    Some((score, context))
+}
+
+/// Implementation for `search_docs`: case-insensitive, ranked search across all documentation.
+///
+/// The query is split on whitespace and every term must match somewhere in an
+/// entry. Results are sorted by score (name matches rank above summary matches,
+/// which rank above description/parameter matches).
 #[allow(clippy::too_many_lines)]
 pub fn search_docs_impl(server: &ToolScriptServer, query: &str) -> String {
-    let query_lower = query.to_lowercase();
-    let mut results: Vec<serde_json::Value> = Vec::new();
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    if terms.is_empty() {
+        return "[]".to_string();
+    }
+    let mut results: Vec<(u32, serde_json::Value)> = Vec::new();
 
     // Search functions
     for func in &server.manifest.functions {
-        let mut matches = false;
-        let mut context = Vec::new();
-
-        if func.name.to_lowercase().contains(&query_lower) {
-            matches = true;
-            context.push(format!("name: {}", func.name));
-        }
-        if let Some(ref summary) = func.summary
-            && summary.to_lowercase().contains(&query_lower)
-        {
-            matches = true;
-            context.push(format!("summary: {summary}"));
+        let mut fields = vec![SearchField::new(
+            NAME_WEIGHT,
+            &func.name,
+            format!("name: {}", func.name),
+        )];
+        if let Some(ref summary) = func.summary {
+            fields.push(SearchField::new(
+                SUMMARY_WEIGHT,
+                summary,
+                format!("summary: {summary}"),
+            ));
         }
-        if let Some(ref desc) = func.description
-            && desc.to_lowercase().contains(&query_lower)
-        {
-            matches = true;
-            context.push(format!("description: {desc}"));
+        if let Some(ref desc) = func.description {
+            fields.push(SearchField::new(
+                DETAIL_WEIGHT,
+                desc,
+                format!("description: {desc}"),
+            ));
         }
         for param in &func.parameters {
             if param.frozen_value.is_some() {
                 continue; // Skip frozen params from search
             }
-            if param.name.to_lowercase().contains(&query_lower) {
-                matches = true;
-                context.push(format!("parameter: {}", param.name));
-            }
+            fields.push(SearchField::new(
+                DETAIL_WEIGHT,
+                &param.name,
+                format!("parameter: {}", param.name),
+            ));
         }
 
-        if matches {
-            results.push(serde_json::json!({
-                "type": "function",
-                "name": func.name,
-                "api": func.api,
-                "context": context,
-            }));
+        if let Some((score, context)) = score_fields(&terms, &fields) {
+            results.push((
+                score,
+                serde_json::json!({
+                    "type": "function",
+                    "name": func.name,
+                    "api": func.api,
+                    "score": score,
+                    "context": context,
+                }),
+            ));
         }
     }
 
     // Search schemas
     for schema in &server.manifest.schemas {
-        let mut matches = false;
-        let mut context = Vec::new();
-
-        if schema.name.to_lowercase().contains(&query_lower) {
-            matches = true;
-            context.push(format!("name: {}", schema.name));
-        }
-        if let Some(ref desc) = schema.description
-            && desc.to_lowercase().contains(&query_lower)
-        {
-            matches = true;
-            context.push(format!("description: {desc}"));
+        let mut fields = vec![SearchField::new(
+            NAME_WEIGHT,
+            &schema.name,
+            format!("name: {}", schema.name),
+        )];
+        if let Some(ref desc) = schema.description {
+            fields.push(SearchField::new(
+                DETAIL_WEIGHT,
+                desc,
+                format!("description: {desc}"),
+            ));
         }
         for field in &schema.fields {
-            if field.name.to_lowercase().contains(&query_lower) {
-                matches = true;
-                context.push(format!("field: {}", field.name));
-            }
+            fields.push(SearchField::new(
+                DETAIL_WEIGHT,
+                &field.name,
+                format!("field: {}", field.name),
+            ));
         }
 
-        if matches {
-            results.push(serde_json::json!({
-                "type": "schema",
-                "name": schema.name,
-                "context": context,
-            }));
+        if let Some((score, context)) = score_fields(&terms, &fields) {
+            results.push((
+                score,
+                serde_json::json!({
+                    "type": "schema",
+                    "name": schema.name,
+                    "score": score,
+                    "context": context,
+                }),
+            ));
         }
     }
 
     // Search MCP tools
     for mcp_server in &server.manifest.mcp_servers {
         for tool in &mcp_server.tools {
-            let mut matches = false;
-            let mut context = Vec::new();
-
             let full_name = format!("{}.{}", mcp_server.name, tool.name);
-            if full_name.to_lowercase().contains(&query_lower)
-                || tool.name.to_lowercase().contains(&query_lower)
-            {
-                matches = true;
-                context.push(format!("name: {full_name}"));
-            }
-            if let Some(ref desc) = tool.description
-                && desc.to_lowercase().contains(&query_lower)
-            {
-                matches = true;
-                context.push(format!("description: {desc}"));
+            let mut fields = vec![SearchField::new(
+                NAME_WEIGHT,
+                &full_name,
+                format!("name: {full_name}"),
+            )];
+            if let Some(ref desc) = tool.description {
+                fields.push(SearchField::new(
+                    SUMMARY_WEIGHT,
+                    desc,
+                    format!("description: {desc}"),
+                ));
             }
             for param in &tool.params {
-                if param.name.to_lowercase().contains(&query_lower) {
-                    matches = true;
-                    context.push(format!("parameter: {}", param.name));
-                }
+                fields.push(SearchField::new(
+                    DETAIL_WEIGHT,
+                    &param.name,
+                    format!("parameter: {}", param.name),
+                ));
             }
 
-            if matches {
-                results.push(serde_json::json!({
-                    "type": "mcp_tool",
-                    "name": full_name,
-                    "server": mcp_server.name,
-                    "context": context,
-                }));
+            if let Some((score, context)) = score_fields(&terms, &fields) {
+                results.push((
+                    score,
+                    serde_json::json!({
+                        "type": "mcp_tool",
+                        "name": full_name,
+                        "server": mcp_server.name,
+                        "score": score,
+                        "context": context,
+                    }),
+                ));
             }
         }
     }
 
     // Search built-in Luau globals
     for builtin in builtins::builtin_functions(server.io_enabled) {
-        let mut matches = false;
-        let mut context = Vec::new();
-
-        if builtin.name.to_lowercase().contains(&query_lower) {
-            matches = true;
-            context.push(format!("name: {}", builtin.name));
-        }
-        if builtin.summary.to_lowercase().contains(&query_lower) {
-            matches = true;
-            context.push(format!("summary: {}", builtin.summary));
-        }
-        if builtin.annotation.to_lowercase().contains(&query_lower) {
-            matches = true;
-            context.push("annotation match".to_string());
-        }
-
-        if matches {
-            results.push(serde_json::json!({
-                "type": "builtin",
-                "name": builtin.name,
-                "api": "luau",
-                "context": context,
-            }));
+        let fields = [
+            SearchField::new(NAME_WEIGHT, builtin.name, format!("name: {}", builtin.name)),
+            SearchField::new(
+                SUMMARY_WEIGHT,
+                builtin.summary,
+                format!("summary: {}", builtin.summary),
+            ),
+            SearchField::new(
+                DETAIL_WEIGHT,
+                builtin.annotation,
+                "annotation match".to_string(),
+            ),
+        ];
+
+        if let Some((score, context)) = score_fields(&terms, &fields) {
+            results.push((
+                score,
+                serde_json::json!({
+                    "type": "builtin",
+                    "name": builtin.name,
+                    "api": "luau",
+                    "score": score,
+                    "context": context,
+                }),
+            ));
         }
     }
 
+    // Stable sort keeps the original ordering among equally-scored results
+    results.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    let results: Vec<serde_json::Value> = results.into_iter().map(|(_, r)| r).collect();
+
     serde_json::to_string_pretty(&results).unwrap_or_else(|_| "[]".to_string())
 }
 
@@ -321,7 +440,18 @@ fn make_tool(name: &str, description: &str, schema: serde_json::Value) -> Tool {
 fn list_apis_tool_def() -> Tool {
     make_tool(
         "list_apis",
-        "List available APIs. Returns a JSON array where each entry has: name, description, and function count. Use list_functions to see the functions within an API.",
+        "List available APIs. Returns a JSON array where each entry has: name, description, function count, and (for OpenAPI APIs) whether the whole API is deprecated. Use list_functions to see the functions within an API.",
+        serde_json::json!({
+            "type": "object",
+            "properties": {},
+        }),
+    )
+}
+
+fn list_mcp_servers_tool_def() -> Tool {
+    make_tool(
+        "list_mcp_servers",
+        "List upstream MCP servers. Returns a JSON array where each entry has: name, description, and tool count. Tools from a server are called as sdk.<server>.<tool>() in scripts; use list_functions with the server name as the api filter to see them.",
         serde_json::json!({
             "type": "object",
             "properties": {},
@@ -332,12 +462,14 @@ fn list_apis_tool_def() -> Tool {
 fn list_functions_tool_def() -> Tool {
     make_tool(
         "list_functions",
-        "List available SDK functions. Returns a JSON array with name, summary, api, and tag for each function. Use get_function_docs to see the full Luau type signature and calling convention.",
+        "List available SDK functions. Returns a JSON object { functions, total, next_offset } where functions has name, summary, api, and tag for each function. Results are paginated (50 per page by default); pass next_offset as offset to fetch the next page, which is null on the last page. Use get_function_docs to see the full Luau type signature and calling convention.",
         serde_json::json!({
             "type": "object",
             "properties": {
                 "api": { "type": "string", "description": "Filter by API name" },
                 "tag": { "type": "string", "description": "Filter by tag" },
+                "offset": { "type": "integer", "minimum": 0, "description": "Number of matching functions to skip (default 0)" },
+                "limit": { "type": "integer", "minimum": 1, "description": "Maximum number of functions to return (default 50)" },
             },
         }),
     )
@@ -360,7 +492,7 @@ fn get_function_docs_tool_def() -> Tool {
 fn search_docs_tool_def() -> Tool {
     make_tool(
         "search_docs",
-        "Search SDK documentation by keyword. Matches against function names, summaries, parameter names, and schema fields. Returns a JSON array of matches with context showing where the query matched.",
+        "Search SDK documentation by keyword. Matches against function names, summaries, parameter names, and schema fields. Multi-word queries require every word to match somewhere. Returns a JSON array of matches sorted by relevance (name matches first), each with a score and context showing where the query matched.",
         serde_json::json!({
             "type": "object",
             "properties": {
@@ -381,6 +513,16 @@ pub fn list_apis_tool() -> ToolRoute<ToolScriptServer> {
     )
 }
 
+pub fn list_mcp_servers_tool() -> ToolRoute<ToolScriptServer> {
+    ToolRoute::new_dyn(
+        list_mcp_servers_tool_def(),
+        |context: ToolCallContext<'_, ToolScriptServer>| {
+            let result = list_mcp_servers_impl(context.service);
+            std::future::ready(Ok(CallToolResult::success(vec![Content::text(result)]))).boxed()
+        },
+    )
+}
+
 pub fn list_functions_tool() -> ToolRoute<ToolScriptServer> {
     ToolRoute::new_dyn(
         list_functions_tool_def(),
@@ -392,6 +534,8 @@ pub fn list_functions_tool() -> ToolRoute<ToolScriptServer> {
                 context.service,
                 params.api.as_deref(),
                 params.tag.as_deref(),
+                params.offset,
+                params.limit,
             );
             std::future::ready(Ok(CallToolResult::success(vec![Content::text(result)]))).boxed()
         },
@@ -440,9 +584,9 @@ pub fn search_docs_tool() -> ToolRoute<ToolScriptServer> {
     )
 }
 
-pub fn execute_script_tool() -> ToolRoute<ToolScriptServer> {
+pub fn execute_script_tool(manifest: &Manifest) -> ToolRoute<ToolScriptServer> {
     ToolRoute::new_dyn(
-        execute_script_tool_def(),
+        execute_script_tool_def(manifest),
         |mut context: ToolCallContext<'_, ToolScriptServer>| {
             let args = context.arguments.take().unwrap_or_default();
             let params: Result<ExecuteScriptParams, _> =
@@ -454,37 +598,145 @@ pub fn execute_script_tool() -> ToolRoute<ToolScriptServer> {
                 .map_or_else(AuthCredentialsMap::new, |auth_value| {
                     auth::parse_meta_auth(auth_value)
                 });
-            execute_script_async(params, context.service, meta_auth).boxed()
+            let caller = caller_context(&context.request_context.extensions);
+            execute_script_async(params, context.service, meta_auth, caller).boxed()
         },
     )
 }
 
-fn execute_script_tool_def() -> Tool {
-    make_tool(
-        "execute_script",
-        "Execute a Luau script against the SDK. Auth comes from server-side configuration.\n\n\
+/// The `execute_script` tool, with an example script built from `manifest`
+/// appended to its description.
+fn execute_script_tool_def(manifest: &Manifest) -> Tool {
+    let mut description = "Execute a Luau script against the SDK. Auth comes from server-side configuration.\n\n\
          Returns a JSON object with:\n\
          - result: the script's return value (any JSON type)\n\
          - logs: array of strings captured from print() calls\n\
-         - files_touched: array of { name, op, bytes } for files modified via io/os\n\n\
+         - files_touched: array of { name, op, bytes } for files modified via io/os\n\
+         - correlation_id: the ID sent as a request header on every API call the script made\n\n\
+         When the script returns a table with string keys, that table is also provided as structured content.\n\n\
+         Oversized results are truncated without changing their type: shortened objects carry \
+         __truncated = true, shortened arrays end with a { __truncated, __omitted_items } entry, \
+         and an entry in warnings notes the original size.\n\n\
          On error, returns a text message prefixed with \"Script execution error:\".\n\n\
+         An SDK call that gets a non-2xx response raises a table { status, body, message }; \
+         use pcall to catch it and branch on err.status. When the response has a Retry-After \
+         header, err.retry_after holds the seconds to wait. When the function documents an error \
+         body (\"-- on error: T\"), err.schema is \"T\".\n\n\
+         Every SDK function also has a <name>_text variant taking the same arguments that returns \
+         the response body as an undecoded string, for endpoints that do not return JSON. \
+         A trailing options table { accept = \"text/csv\" } sets the Accept header for one call; \
+         a non-JSON response then comes back as a string.\n\n\
+         When the server requires a bearer token, the read-only global `context` holds the caller's \
+         identity: context.subject and context.claims (nil when unauthenticated).\n\n\
          Only a subset of Lua globals are available in the sandbox. \
-         Use list_functions(api: \"luau\") or browse sdk://luau/functions to see built-in functions and their signatures.",
+         Use list_functions(api: \"luau\") or browse sdk://luau/functions to see built-in functions and their signatures."
+        .to_string();
+    if let Some(example) = example_script(manifest) {
+        description.push_str("\n\nExample:\n");
+        description.push_str(&example);
+    }
+    make_tool(
+        "execute_script",
+        &description,
         serde_json::json!({
             "type": "object",
             "properties": {
                 "script": { "type": "string", "description": "Luau script to execute" },
                 "timeout_ms": { "type": "integer", "description": "Execution timeout in milliseconds (optional)" },
+                "correlation_id": { "type": "string", "description": "ID sent as a request header on every API call (optional; generated when omitted)" },
             },
             "required": ["script"],
         }),
     )
 }
 
+/// A short script calling the manifest's first two non-deprecated functions,
+/// so the example always names real SDK functions. `None` without functions.
+fn example_script(manifest: &Manifest) -> Option<String> {
+    let funcs: Vec<&FunctionDef> = manifest
+        .functions
+        .iter()
+        .filter(|f| !f.deprecated)
+        .take(2)
+        .collect();
+    if funcs.is_empty() {
+        return None;
+    }
+    let mut lines: Vec<String> = funcs
+        .iter()
+        .enumerate()
+        .map(|(i, f)| format!("local r{} = sdk.{}({})", i + 1, f.name, example_args(f)))
+        .collect();
+    let returned: Vec<String> = funcs
+        .iter()
+        .enumerate()
+        .map(|(i, f)| {
+            // Nested names (`api.func`) are not valid bare table keys.
+            if f.name.contains('.') {
+                format!("[\"{}\"] = r{}", f.name, i + 1)
+            } else {
+                format!("{} = r{}", f.name, i + 1)
+            }
+        })
+        .collect();
+    lines.push(format!("return {{ {} }}", returned.join(", ")));
+    Some(lines.join("\n"))
+}
+
+/// Call arguments for `func` with placeholder values for its required
+/// visible params and body, following the SDK calling convention.
+fn example_args(func: &FunctionDef) -> String {
+    let visible: Vec<_> = func
+        .parameters
+        .iter()
+        .filter(|p| p.frozen_value.is_none())
+        .collect();
+    let body_required = func.request_body.as_ref().is_some_and(|b| b.required);
+    let mut args = Vec::new();
+    if !visible.is_empty() {
+        let fields: Vec<String> = visible
+            .iter()
+            .filter(|p| p.required)
+            .map(|p| {
+                let value = match p.param_type {
+                    ParamType::String => "\"...\"",
+                    ParamType::Integer | ParamType::Number => "1",
+                    ParamType::Boolean => "true",
+                };
+                format!("{} = {value}", p.name)
+            })
+            .collect();
+        // The params table may be omitted when nothing in it is required
+        if !fields.is_empty() {
+            args.push(format!("{{ {} }}", fields.join(", ")));
+        } else if body_required {
+            args.push("{}".to_string());
+        }
+    }
+    if body_required {
+        args.push("{ ... }".to_string());
+    }
+    args.join(", ")
+}
+
+/// The authenticated caller, when the HTTP auth middleware validated a token.
+///
+/// The streamable HTTP transport stores the request's HTTP parts in the
+/// request extensions, and the middleware stores the [`auth::AuthContext`]
+/// in those parts.
+fn caller_context(extensions: &rmcp::model::Extensions) -> Option<CallerContext> {
+    extensions
+        .get::<axum::http::request::Parts>()
+        .and_then(|parts| parts.extensions.get::<auth::AuthContext>())
+        .cloned()
+        .map(CallerContext::from)
+}
+
 async fn execute_script_async(
     params: Result<ExecuteScriptParams, serde_json::Error>,
     server: &ToolScriptServer,
     meta_auth: AuthCredentialsMap,
+    caller: Option<CallerContext>,
 ) -> Result<CallToolResult, rmcp::ErrorData> {
     let params = match params {
         Ok(p) => p,
@@ -495,15 +747,40 @@ async fn execute_script_async(
         }
     };
 
-    let merged_auth = auth::merge_credentials(&server.auth, &meta_auth);
+    Ok(execute_script_impl(
+        server,
+        &params.script,
+        params.timeout_ms,
+        params.correlation_id.as_deref(),
+        &meta_auth,
+        caller.as_ref(),
+    )
+    .await)
+}
+
+/// Implementation for `execute_script`: runs the script and builds the tool result.
+///
+/// The text content is a JSON object with the result, logs, and touched files.
+/// When the script returns a table that maps to a JSON object, that value is
+/// also returned as `structured_content`. The correlation ID sent with the
+/// script's API calls is echoed back when enabled.
+pub async fn execute_script_impl(
+    server: &ToolScriptServer,
+    script: &str,
+    timeout_ms: Option<u64>,
+    correlation_id: Option<&str>,
+    meta_auth: &AuthCredentialsMap,
+    caller: Option<&CallerContext>,
+) -> CallToolResult {
+    let merged_auth = auth::merge_credentials(&server.auth, meta_auth);
     let result = server
         .executor
-        .execute(&params.script, &merged_auth, params.timeout_ms)
+        .execute_with_correlation_id(script, &merged_auth, timeout_ms, correlation_id, caller)
         .await;
 
     match result {
         Ok(exec_result) => {
-            let response = serde_json::json!({
+            let mut response = serde_json::json!({
                 "result": exec_result.result,
                 "logs": exec_result.logs,
                 "files_touched": exec_result.files_touched.iter().map(|f| {
@@ -514,13 +791,24 @@ async fn execute_script_async(
                     })
                 }).collect::<Vec<_>>(),
             });
-            Ok(CallToolResult::success(vec![Content::text(
+            if let Some(id) = exec_result.correlation_id {
+                response["correlation_id"] = serde_json::Value::String(id);
+            }
+            if let Some(trace) = exec_result.trace {
+                response["trace"] = serde_json::Value::Array(trace);
+            }
+            if !exec_result.warnings.is_empty() {
+                response["warnings"] = serde_json::json!(exec_result.warnings);
+            }
+            let mut tool_result = CallToolResult::success(vec![Content::text(
                 serde_json::to_string_pretty(&response).unwrap_or_default(),
-            )]))
+            )]);
+            tool_result.structured_content = exec_result.structured_content;
+            tool_result
+        }
+        Err(e) => {
+            CallToolResult::error(vec![Content::text(format!("Script execution error: {e}"))])
         }
-        Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
-            "Script execution error: {e}"
-        ))])),
     }
 }
 
@@ -538,6 +826,16 @@ pub fn list_apis_tool_arc() -> ToolRoute<Arc<ToolScriptServer>> {
     )
 }
 
+pub fn list_mcp_servers_tool_arc() -> ToolRoute<Arc<ToolScriptServer>> {
+    ToolRoute::new_dyn(
+        list_mcp_servers_tool_def(),
+        |context: ToolCallContext<'_, Arc<ToolScriptServer>>| {
+            let result = list_mcp_servers_impl(context.service);
+            std::future::ready(Ok(CallToolResult::success(vec![Content::text(result)]))).boxed()
+        },
+    )
+}
+
 pub fn list_functions_tool_arc() -> ToolRoute<Arc<ToolScriptServer>> {
     ToolRoute::new_dyn(
         list_functions_tool_def(),
@@ -549,6 +847,8 @@ pub fn list_functions_tool_arc() -> ToolRoute<Arc<ToolScriptServer>> {
                 context.service,
                 params.api.as_deref(),
                 params.tag.as_deref(),
+                params.offset,
+                params.limit,
             );
             std::future::ready(Ok(CallToolResult::success(vec![Content::text(result)]))).boxed()
         },
@@ -597,9 +897,9 @@ pub fn search_docs_tool_arc() -> ToolRoute<Arc<ToolScriptServer>> {
     )
 }
 
-pub fn execute_script_tool_arc() -> ToolRoute<Arc<ToolScriptServer>> {
+pub fn execute_script_tool_arc(manifest: &Manifest) -> ToolRoute<Arc<ToolScriptServer>> {
     ToolRoute::new_dyn(
-        execute_script_tool_def(),
+        execute_script_tool_def(manifest),
         |mut context: ToolCallContext<'_, Arc<ToolScriptServer>>| {
             let args = context.arguments.take().unwrap_or_default();
             let params: Result<ExecuteScriptParams, _> =
@@ -611,7 +911,8 @@ pub fn execute_script_tool_arc() -> ToolRoute<Arc<ToolScriptServer>> {
                 .map_or_else(AuthCredentialsMap::new, |auth_value| {
                     auth::parse_meta_auth(auth_value)
                 });
-            execute_script_async(params, context.service, meta_auth).boxed()
+            let caller = caller_context(&context.request_context.extensions);
+            execute_script_async(params, context.service, meta_auth, caller).boxed()
         },
     )
 }
@@ -8,15 +8,13 @@ use toolscript::config::SpecInput;
 #[tokio::test]
 async fn test_generate_from_petstore() {
     let output_dir = tempfile::tempdir().unwrap();
-    let no_frozen: HashMap<String, HashMap<String, String>> = HashMap::new();
     toolscript::codegen::generate::generate(
         &[SpecInput {
             name: None,
             source: "testdata/petstore.yaml".to_string(),
         }],
         output_dir.path(),
-        &HashMap::new(),
-        &no_frozen,
+        &toolscript::codegen::generate::GenerateOptions::default(),
     )
     .await
     .unwrap();
@@ -64,15 +62,13 @@ async fn test_generate_from_petstore() {
 #[tokio::test]
 async fn test_generate_from_advanced() {
     let output_dir = tempfile::tempdir().unwrap();
-    let no_frozen: HashMap<String, HashMap<String, String>> = HashMap::new();
     toolscript::codegen::generate::generate(
         &[SpecInput {
             name: None,
             source: "testdata/advanced.yaml".to_string(),
         }],
         output_dir.path(),
-        &HashMap::new(),
-        &no_frozen,
+        &toolscript::codegen::generate::GenerateOptions::default(),
     )
     .await
     .unwrap();
@@ -176,8 +172,10 @@ async fn test_frozen_params_end_to_end() {
             source: "testdata/petstore.yaml".to_string(),
         }],
         output_dir.path(),
-        &HashMap::new(),
-        &per_api_frozen,
+        &toolscript::codegen::generate::GenerateOptions {
+            per_api_frozen,
+            ..toolscript::codegen::generate::GenerateOptions::default()
+        },
     )
     .await
     .unwrap();
@@ -215,3 +213,178 @@ async fn test_frozen_params_end_to_end() {
         }
     }
 }
+
+#[test]
+fn test_inspect_prints_manifest() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_toolscript"))
+        .args(["inspect", "testdata/petstore.yaml"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "inspect failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("list_pets"));
+    let manifest: toolscript::codegen::manifest::Manifest = serde_json::from_str(&stdout).unwrap();
+    assert!(manifest.functions.iter().any(|f| f.name == "list_pets"));
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_toolscript"))
+        .args(["inspect", "testdata/petstore.yaml", "--functions-only"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let functions: Vec<toolscript::codegen::manifest::FunctionDef> =
+        serde_json::from_slice(&output.stdout).unwrap();
+    assert!(functions.iter().any(|f| f.name == "list_pets"));
+}
+
+#[test]
+fn test_inspect_reads_spec_from_stdin() {
+    use std::io::Write;
+
+    let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_toolscript"))
+        .args(["inspect", "-"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(&std::fs::read("testdata/petstore.yaml").unwrap())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(
+        output.status.success(),
+        "inspect failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let manifest: toolscript::codegen::manifest::Manifest =
+        serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(manifest.apis[0].name, "stdin");
+    assert!(manifest.functions.iter().any(|f| f.name == "list_pets"));
+}
+
+#[test]
+fn test_generate_continue_on_error_writes_output_and_exits_nonzero() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_toolscript"))
+        .args([
+            "generate",
+            "testdata/petstore.yaml",
+            "missing.yaml",
+            "--continue-on-error",
+            "-o",
+        ])
+        .arg(dir.path())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("missing.yaml"), "stderr: {stderr}");
+
+    let manifest: toolscript::codegen::manifest::Manifest =
+        serde_json::from_slice(&std::fs::read(dir.path().join("manifest.json")).unwrap()).unwrap();
+    assert_eq!(manifest.apis.len(), 1);
+    assert_eq!(manifest.apis[0].name, "petstore");
+}
+
+#[test]
+fn test_generate_is_reproducible() {
+    let generate_into = |dir: &std::path::Path| {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_toolscript"))
+            .args([
+                "generate",
+                "testdata/petstore.yaml",
+                "testdata/advanced.yaml",
+                "-o",
+            ])
+            .arg(dir)
+            .env("SOURCE_DATE_EPOCH", "1700000000")
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "generate failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let mut files: Vec<(String, Vec<u8>)> = ["manifest.json", "sdk"]
+            .iter()
+            .flat_map(|entry| {
+                let path = dir.join(entry);
+                if path.is_dir() {
+                    std::fs::read_dir(path)
+                        .unwrap()
+                        .map(|e| e.unwrap().path())
+                        .collect()
+                } else {
+                    vec![path]
+                }
+            })
+            .map(|path| {
+                let name = path.strip_prefix(dir).unwrap().display().to_string();
+                (name, std::fs::read(&path).unwrap())
+            })
+            .collect();
+        files.sort();
+        files
+    };
+
+    let first = tempfile::tempdir().unwrap();
+    let second = tempfile::tempdir().unwrap();
+    let first_files = generate_into(first.path());
+    assert_eq!(first_files, generate_into(second.path()));
+
+    let manifest: toolscript::codegen::manifest::Manifest =
+        serde_json::from_slice(&first_files[0].1).unwrap();
+    assert_eq!(
+        manifest.generated.unwrap().generated_at,
+        "2023-11-14T22:13:20Z"
+    );
+}
+
+#[test]
+fn test_check_valid_config_exits_zero() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("toolscript.toml");
+    let spec = concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/petstore.yaml");
+    std::fs::write(&config, format!("[apis.petstore]\nspec = \"{spec}\"\n")).unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_toolscript"))
+        .args(["check", "--config"])
+        .arg(&config)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "check failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_check_missing_spec_exits_nonzero() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("toolscript.toml");
+    std::fs::write(
+        &config,
+        "[apis.petstore]\nspec = \"missing/petstore.yaml\"\n",
+    )
+    .unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_toolscript"))
+        .args(["check", "--config"])
+        .arg(&config)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("spec file 'missing/petstore.yaml' does not exist"),
+        "stderr was: {stderr}"
+    );
+}
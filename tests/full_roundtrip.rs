@@ -1,10 +1,10 @@
 #![allow(clippy::unwrap_used, clippy::expect_used)]
 
-use std::collections::HashMap;
 use std::sync::Arc;
 
-use toolscript::codegen::generate::generate;
+use toolscript::codegen::generate::{GenerateOptions, generate};
 use toolscript::codegen::manifest::Manifest;
+use toolscript::codegen::parser::FunctionNaming;
 use toolscript::config::SpecInput;
 use toolscript::runtime::executor::{ExecutorConfig, ScriptExecutor};
 use toolscript::runtime::http::{AuthCredentialsMap, HttpHandler};
@@ -15,15 +15,13 @@ use toolscript::runtime::mcp_client::McpClientManager;
 async fn test_full_roundtrip_with_mock_api() {
     // 1. Generate from petstore spec
     let output_dir = tempfile::tempdir().unwrap();
-    let no_frozen: HashMap<String, HashMap<String, String>> = HashMap::new();
     generate(
         &[SpecInput {
             name: None,
             source: "testdata/petstore.yaml".to_string(),
         }],
         output_dir.path(),
-        &HashMap::new(),
-        &no_frozen,
+        &GenerateOptions::default(),
     )
     .await
     .unwrap();
@@ -140,19 +138,112 @@ async fn test_full_roundtrip_with_mock_api() {
     assert!(result.logs.len() >= 2);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_base_url_override_redirects_calls() {
+    let output_dir = tempfile::tempdir().unwrap();
+    generate(
+        &[SpecInput {
+            name: Some("petstore".to_string()),
+            source: "testdata/petstore.yaml".to_string(),
+        }],
+        output_dir.path(),
+        &GenerateOptions::default(),
+    )
+    .await
+    .unwrap();
+
+    let manifest_str = std::fs::read_to_string(output_dir.path().join("manifest.json")).unwrap();
+    let mut manifest = Manifest::from_json(&manifest_str).unwrap();
+    manifest
+        .override_base_url("petstore", "https://staging.example.com/api")
+        .unwrap();
+
+    let handler = HttpHandler::mock(|_method, url, _query, _body| Ok(serde_json::json!(url)));
+    let executor = ScriptExecutor::new(
+        manifest,
+        Arc::new(handler),
+        ExecutorConfig::default(),
+        None,
+        Arc::new(McpClientManager::empty()),
+    );
+
+    let result = executor
+        .execute(
+            "return sdk.get_pet_by_id({ petId = 'pet-1' })",
+            &AuthCredentialsMap::new(),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        result.result,
+        serde_json::json!("https://staging.example.com/api/pets/pet-1")
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_original_naming_keeps_operation_ids() {
+    let output_dir = tempfile::tempdir().unwrap();
+    generate(
+        &[SpecInput {
+            name: Some("petstore".to_string()),
+            source: "testdata/petstore.yaml".to_string(),
+        }],
+        output_dir.path(),
+        &GenerateOptions {
+            function_naming: FunctionNaming::Original,
+            ..GenerateOptions::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let manifest_str = std::fs::read_to_string(output_dir.path().join("manifest.json")).unwrap();
+    let manifest = Manifest::from_json(&manifest_str).unwrap();
+    let mut names: Vec<&str> = manifest.functions.iter().map(|f| f.name.as_str()).collect();
+    names.sort_unstable();
+    assert_eq!(names, ["createPet", "getPetById", "listPets"]);
+
+    let annotations = std::fs::read_to_string(output_dir.path().join("sdk/pets.luau")).unwrap();
+    assert!(
+        annotations.contains("function sdk.getPetById("),
+        "annotations should use the operationId:\n{annotations}"
+    );
+
+    let handler = HttpHandler::mock(|_method, url, _query, _body| Ok(serde_json::json!(url)));
+    let executor = ScriptExecutor::new(
+        manifest,
+        Arc::new(handler),
+        ExecutorConfig::default(),
+        None,
+        Arc::new(McpClientManager::empty()),
+    );
+    let result = executor
+        .execute(
+            "return sdk.getPetById({ petId = 'pet-1' })",
+            &AuthCredentialsMap::new(),
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(
+        result.result.as_str().unwrap().ends_with("/pets/pet-1"),
+        "unexpected result: {}",
+        result.result
+    );
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_generated_lua_annotations_are_valid() {
     // Generate and verify the Lua annotation files have proper content
     let output_dir = tempfile::tempdir().unwrap();
-    let no_frozen: HashMap<String, HashMap<String, String>> = HashMap::new();
     generate(
         &[SpecInput {
             name: None,
             source: "testdata/petstore.yaml".to_string(),
         }],
         output_dir.path(),
-        &HashMap::new(),
-        &no_frozen,
+        &GenerateOptions::default(),
     )
     .await
     .unwrap();
@@ -186,15 +277,13 @@ async fn test_generated_lua_annotations_are_valid() {
 #[tokio::test(flavor = "multi_thread")]
 async fn test_roundtrip_with_named_spec() {
     let output_dir = tempfile::tempdir().unwrap();
-    let no_frozen: HashMap<String, HashMap<String, String>> = HashMap::new();
     generate(
         &[SpecInput {
             name: Some("mystore".to_string()),
             source: "testdata/petstore.yaml".to_string(),
         }],
         output_dir.path(),
-        &HashMap::new(),
-        &no_frozen,
+        &GenerateOptions::default(),
     )
     .await
     .unwrap();
@@ -239,11 +328,59 @@ async fn test_roundtrip_with_named_spec() {
     assert_eq!(result.result, serde_json::json!("Buddy"));
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_tag_filter_limits_registered_functions() {
+    let output_dir = tempfile::tempdir().unwrap();
+    generate(
+        &[SpecInput {
+            name: None,
+            source: "testdata/petstore.yaml".to_string(),
+        }],
+        output_dir.path(),
+        &GenerateOptions::default(),
+    )
+    .await
+    .unwrap();
+
+    let manifest_str = std::fs::read_to_string(output_dir.path().join("manifest.json")).unwrap();
+    let mut manifest: Manifest = serde_json::from_str(&manifest_str).unwrap();
+    manifest.functions[0].tag = Some("admin".to_string());
+    let hidden = manifest.functions[0].name.clone();
+    manifest.retain_tags(
+        &["pets".to_string(), "admin".to_string()],
+        &["admin".to_string()],
+    );
+    assert!(
+        manifest
+            .functions
+            .iter()
+            .all(|f| f.tag.as_deref() == Some("pets"))
+    );
+
+    let executor = ScriptExecutor::new(
+        manifest,
+        Arc::new(HttpHandler::mock(|_method, _url, _query, _body| {
+            Ok(serde_json::json!({}))
+        })),
+        ExecutorConfig::default(),
+        None,
+        Arc::new(McpClientManager::empty()),
+    );
+    let result = executor
+        .execute(
+            &format!("return {{ sdk.{hidden} == nil, sdk.get_pet_by_id ~= nil }}"),
+            &AuthCredentialsMap::new(),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.result, serde_json::json!([true, true]));
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_io_roundtrip() {
     let output_dir = tempfile::tempdir().unwrap();
     let spec_output = tempfile::tempdir().unwrap();
-    let no_frozen: HashMap<String, HashMap<String, String>> = HashMap::new();
 
     generate(
         &[SpecInput {
@@ -251,8 +388,7 @@ async fn test_io_roundtrip() {
             source: "testdata/petstore.yaml".to_string(),
         }],
         spec_output.path(),
-        &HashMap::new(),
-        &no_frozen,
+        &GenerateOptions::default(),
     )
     .await
     .unwrap();
@@ -278,6 +414,7 @@ async fn test_io_roundtrip() {
         Some(toolscript::runtime::executor::IoConfig {
             dir: output_dir.path().to_path_buf(),
             max_bytes: 50 * 1024 * 1024,
+            max_files: 1000,
         }),
         Arc::new(McpClientManager::empty()),
     );
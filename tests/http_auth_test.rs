@@ -10,6 +10,8 @@ async fn test_well_known_endpoint_no_auth_required() {
         authority: "https://auth.example.com".to_string(),
         audience: "https://mcp.example.com".to_string(),
         jwks_uri_override: None,
+        required_scopes: Vec::new(),
+        required_claims: Vec::new(),
     };
 
     let well_known = serde_json::json!({
@@ -74,3 +76,180 @@ fn find_free_port() -> u16 {
         .unwrap()
         .port()
 }
+
+/// Build an app shaped like `serve_http` with auth enabled: a protected `/mcp`
+/// route plus the unauthenticated health probes.
+fn app_with_auth(
+    mcp_client: std::sync::Arc<toolscript::runtime::mcp_client::McpClientManager>,
+) -> axum::Router {
+    use std::sync::Arc;
+    use toolscript::server::auth::{JwtValidator, McpAuthConfig, auth_middleware};
+
+    let config = McpAuthConfig {
+        authority: "https://auth.example.com".to_string(),
+        audience: "https://mcp.example.com".to_string(),
+        jwks_uri_override: None,
+        required_scopes: Vec::new(),
+        required_claims: Vec::new(),
+    };
+    let validator = Arc::new(JwtValidator::new(config.clone()));
+    axum::Router::new()
+        .route("/mcp", axum::routing::post(|| async { "ok" }))
+        .route_layer(axum::middleware::from_fn_with_state(
+            (validator, config),
+            auth_middleware,
+        ))
+        .merge(toolscript::server::health_router(mcp_client))
+}
+
+async fn get_status(app: axum::Router, method: &str, uri: &str) -> (u16, serde_json::Value) {
+    use tower::ServiceExt;
+
+    let request = axum::http::Request::builder()
+        .method(method)
+        .uri(uri)
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status().as_u16();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body = serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null);
+    (status, body)
+}
+
+#[tokio::test]
+async fn test_healthz_bypasses_auth() {
+    use std::sync::Arc;
+    use toolscript::runtime::mcp_client::McpClientManager;
+
+    let app = app_with_auth(Arc::new(McpClientManager::empty()));
+
+    let (status, body) = get_status(app.clone(), "GET", "/healthz").await;
+    assert_eq!(status, 200);
+    assert_eq!(body["status"], "ok");
+
+    let (status, _) = get_status(app, "POST", "/mcp").await;
+    assert_eq!(status, 401, "/mcp must still require auth");
+}
+
+#[tokio::test]
+async fn test_readyz_reflects_upstream_state() {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use toolscript::runtime::mcp_client::{McpClientManager, McpServerResolvedConfig};
+
+    let (status, body) = get_status(
+        app_with_auth(Arc::new(McpClientManager::empty())),
+        "GET",
+        "/readyz",
+    )
+    .await;
+    assert_eq!(status, 200);
+    assert_eq!(body["status"], "ready");
+
+    let mut configs = HashMap::new();
+    configs.insert(
+        "broken".to_string(),
+        McpServerResolvedConfig::Stdio {
+            command: "/nonexistent/binary/that/does/not/exist".to_string(),
+            args: vec![],
+            env: HashMap::new(),
+        },
+    );
+    let manager = McpClientManager::connect_all(configs).await.unwrap();
+    let (status, body) = get_status(app_with_auth(Arc::new(manager)), "GET", "/readyz").await;
+    assert_eq!(status, 503);
+    assert_eq!(body["status"], "not_ready");
+    assert_eq!(body["failed_servers"], serde_json::json!(["broken"]));
+}
+
+/// Serve a JWKS holding one HS256 key and return its URL.
+async fn spawn_jwks_server(secret_b64url: &'static str) -> String {
+    let jwks = serde_json::json!({
+        "keys": [{ "kty": "oct", "kid": "test-key", "alg": "HS256", "k": secret_b64url }]
+    });
+    let app = axum::Router::new().route(
+        "/jwks",
+        axum::routing::get(move || {
+            let jwks = jwks.clone();
+            async move { axum::Json(jwks) }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}/jwks")
+}
+
+#[tokio::test]
+async fn test_required_scope_enforced() {
+    use jsonwebtoken::{Algorithm, EncodingKey, Header, encode};
+    use std::sync::Arc;
+    use toolscript::server::auth::{JwtValidator, McpAuthConfig, auth_middleware};
+    use tower::ServiceExt;
+
+    const SECRET: &[u8] = b"test-secret-key-that-is-long-enough-for-hs256";
+    const SECRET_B64URL: &str = "dGVzdC1zZWNyZXQta2V5LXRoYXQtaXMtbG9uZy1lbm91Z2gtZm9yLWhzMjU2";
+
+    let config = McpAuthConfig {
+        authority: "https://auth.example.com".to_string(),
+        audience: "https://mcp.example.com".to_string(),
+        jwks_uri_override: Some(spawn_jwks_server(SECRET_B64URL).await),
+        required_scopes: vec!["toolscript.execute".to_string()],
+        required_claims: vec![],
+    };
+    let validator = Arc::new(JwtValidator::new(config.clone()));
+    let app = axum::Router::new()
+        .route("/mcp", axum::routing::post(|| async { "ok" }))
+        .route_layer(axum::middleware::from_fn_with_state(
+            (validator, config),
+            auth_middleware,
+        ));
+
+    let token_with_scope = |scope: &str| {
+        let exp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 3600;
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = Some("test-key".to_string());
+        encode(
+            &header,
+            &serde_json::json!({
+                "sub": "user-123",
+                "iss": "https://auth.example.com",
+                "aud": "https://mcp.example.com",
+                "exp": exp,
+                "scope": scope,
+            }),
+            &EncodingKey::from_secret(SECRET),
+        )
+        .unwrap()
+    };
+    let post_mcp = |token: String| {
+        axum::http::Request::builder()
+            .method("POST")
+            .uri("/mcp")
+            .header("Authorization", format!("Bearer {token}"))
+            .body(axum::body::Body::empty())
+            .unwrap()
+    };
+
+    let response = app
+        .clone()
+        .oneshot(post_mcp(token_with_scope("openid profile")))
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 403);
+
+    let response = app
+        .oneshot(post_mcp(token_with_scope("openid toolscript.execute")))
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+}
@@ -1,5 +1,6 @@
 #![allow(clippy::unwrap_used, clippy::expect_used)]
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use toolscript::codegen::manifest::*;
@@ -11,6 +12,7 @@ use toolscript::server::tools;
 
 fn mcp_only_manifest() -> Manifest {
     Manifest {
+        schema_version: MANIFEST_SCHEMA_VERSION,
         apis: vec![],
         functions: vec![],
         schemas: vec![],
@@ -31,6 +33,8 @@ fn mcp_only_manifest() -> Manifest {
                     }],
                     schemas: vec![],
                     output_schemas: vec![],
+                    output_type: None,
+                    idempotent: false,
                 },
                 McpToolDef {
                     name: "write_file".to_string(),
@@ -54,20 +58,29 @@ fn mcp_only_manifest() -> Manifest {
                     ],
                     schemas: vec![],
                     output_schemas: vec![],
+                    output_type: None,
+                    idempotent: false,
                 },
             ],
         }],
+        generated: None,
     }
 }
 
 fn mixed_manifest() -> Manifest {
     Manifest {
+        schema_version: MANIFEST_SCHEMA_VERSION,
         apis: vec![ApiConfig {
             name: "petstore".to_string(),
             base_url: "https://petstore.example.com/v1".to_string(),
             description: Some("Pet store API".to_string()),
             version: Some("1.0.0".to_string()),
-            auth: None,
+            auth: vec![],
+            constant_headers: BTreeMap::new(),
+            rate_limit: None,
+            default_query: BTreeMap::new(),
+            deprecated: false,
+            boolean_style: None,
         }],
         functions: vec![FunctionDef {
             name: "list_pets".to_string(),
@@ -81,6 +94,9 @@ fn mixed_manifest() -> Manifest {
             parameters: vec![],
             request_body: None,
             response_schema: None,
+            error_schema: None,
+            auth: None,
+            link_paginated: false,
         }],
         schemas: vec![],
         mcp_servers: vec![McpServerEntry {
@@ -99,8 +115,11 @@ fn mixed_manifest() -> Manifest {
                 }],
                 schemas: vec![],
                 output_schemas: vec![],
+                output_type: None,
+                idempotent: false,
             }],
         }],
+        generated: None,
     }
 }
 
@@ -133,10 +152,10 @@ fn test_mcp_only_list_apis() {
 #[test]
 fn test_mcp_only_list_functions() {
     let server = make_server(mcp_only_manifest());
-    let result = tools::list_functions_impl(&server, None, None);
+    let result = tools::list_functions_impl(&server, None, None, None, None);
     let json: serde_json::Value = serde_json::from_str(&result).unwrap();
-    let funcs = json.as_array().unwrap();
-    assert_eq!(funcs.len(), 6); // 2 MCP + 4 builtins (no io)
+    let funcs = json["functions"].as_array().unwrap();
+    assert_eq!(funcs.len(), 30); // 2 MCP + 28 builtins (no io)
     // MCP tools should be from filesystem
     let mcp_funcs: Vec<_> = funcs.iter().filter(|f| f["source"] == "mcp").collect();
     assert_eq!(mcp_funcs.len(), 2);
@@ -145,7 +164,7 @@ fn test_mcp_only_list_functions() {
     }
     // Builtins should be from luau
     let builtin_funcs: Vec<_> = funcs.iter().filter(|f| f["source"] == "builtin").collect();
-    assert_eq!(builtin_funcs.len(), 4);
+    assert_eq!(builtin_funcs.len(), 28);
     for f in &builtin_funcs {
         assert_eq!(f["api"], "luau");
     }
@@ -191,9 +210,9 @@ fn test_mixed_mode_all_visible() {
 
     // All should appear in list_functions
     let funcs_json: serde_json::Value =
-        serde_json::from_str(&tools::list_functions_impl(&server, None, None)).unwrap();
-    let funcs = funcs_json.as_array().unwrap();
-    assert_eq!(funcs.len(), 6); // 1 OpenAPI + 1 MCP + 4 builtins (no io)
+        serde_json::from_str(&tools::list_functions_impl(&server, None, None, None, None)).unwrap();
+    let funcs = funcs_json["functions"].as_array().unwrap();
+    assert_eq!(funcs.len(), 30); // 1 OpenAPI + 1 MCP + 28 builtins (no io)
 
     // All should be findable via get_function_docs
     assert!(tools::get_function_docs_impl(&server, "list_pets").is_ok());
@@ -206,6 +225,7 @@ fn test_mixed_mode_all_visible() {
 #[test]
 fn test_mcp_tool_docs_with_schemas() {
     let manifest = Manifest {
+        schema_version: MANIFEST_SCHEMA_VERSION,
         apis: vec![],
         functions: vec![],
         schemas: vec![],
@@ -237,6 +257,8 @@ fn test_mcp_tool_docs_with_schemas() {
                             enum_values: None,
                             nullable: false,
                             format: None,
+                            example: None,
+                            enum_descriptions: None,
                         },
                         FieldDef {
                             name: "email".to_string(),
@@ -246,12 +268,18 @@ fn test_mcp_tool_docs_with_schemas() {
                             enum_values: None,
                             nullable: false,
                             format: None,
+                            example: None,
+                            enum_descriptions: None,
                         },
                     ],
+                    discriminator: None,
                 }],
                 output_schemas: vec![],
+                output_type: None,
+                idempotent: false,
             }],
         }],
+        generated: None,
     };
     let server = make_server(manifest);
     let docs = tools::get_function_docs_impl(&server, "users.create_user").unwrap();
@@ -7,29 +7,59 @@
 
 use std::borrow::Cow;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
 
 use futures::FutureExt;
 use rmcp::ServerHandler;
+use rmcp::handler::server::prompt::PromptContext;
 use rmcp::handler::server::router::Router;
+use rmcp::handler::server::router::prompt::PromptRoute;
 use rmcp::handler::server::router::tool::ToolRoute;
 use rmcp::handler::server::tool::ToolCallContext;
-use rmcp::model::{CallToolResult, Content, Implementation, ServerCapabilities, ServerInfo, Tool};
+use rmcp::model::{
+    CallToolResult, Content, GetPromptRequestParams, GetPromptResult, Implementation, Prompt,
+    PromptArgument, PromptMessage, PromptMessageContent, PromptMessageRole, ServerCapabilities,
+    ServerInfo, Tool, ToolAnnotations,
+};
 
-use toolscript::codegen::luau_types::{extract_schema_defs, json_schema_to_params};
-use toolscript::codegen::manifest::{McpServerEntry, McpToolDef};
+use toolscript::codegen::annotations::{render_mcp_tool_annotation, render_mcp_tool_docs};
+use toolscript::codegen::manifest::{
+    MANIFEST_SCHEMA_VERSION, Manifest, McpServerEntry, McpToolDef,
+};
+use toolscript::runtime::executor::{ExecutorConfig, ScriptExecutor};
+use toolscript::runtime::http::{AuthCredentialsMap, HttpHandler};
 use toolscript::runtime::mcp_client::McpClientManager;
+use toolscript::server::ToolScriptServer;
 
 // ---- Mock MCP Server ----
 
-/// A minimal MCP server for testing with `echo` and `get_data` tools.
+/// A minimal MCP server for testing with `echo`, `get_data`, `get_weather`,
+/// `slow`, and `count` tools and a `greet` prompt.
 #[derive(Clone)]
 struct MockMcpServer;
 
+/// When set, the mock stops answering pings.
+static PINGS_HANG: AtomicBool = AtomicBool::new(false);
+
 impl ServerHandler for MockMcpServer {
+    async fn ping(
+        &self,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<(), rmcp::ErrorData> {
+        if PINGS_HANG.load(Ordering::SeqCst) {
+            std::future::pending::<()>().await;
+        }
+        Ok(())
+    }
+
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             protocol_version: rmcp::model::ProtocolVersion::default(),
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_prompts()
+                .build(),
             server_info: Implementation {
                 name: "mock-mcp-server".to_string(),
                 title: Some("Mock MCP Server".to_string()),
@@ -93,11 +123,156 @@ fn get_data_tool() -> ToolRoute<MockMcpServer> {
     )
 }
 
+fn slow_tool() -> ToolRoute<MockMcpServer> {
+    ToolRoute::new_dyn(
+        make_tool(
+            "slow",
+            "Answer after five seconds",
+            serde_json::json!({
+                "type": "object",
+                "properties": {}
+            }),
+        ),
+        |_context: ToolCallContext<'_, MockMcpServer>| {
+            async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok(CallToolResult::success(vec![Content::text("done")]))
+            }
+            .boxed()
+        },
+    )
+}
+
+/// Number of times the `count` tool has been invoked.
+static COUNT_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+fn count_tool() -> ToolRoute<MockMcpServer> {
+    let mut tool = make_tool(
+        "count",
+        "Return how many times this tool has been called",
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string" }
+            }
+        }),
+    );
+    tool.annotations = Some(ToolAnnotations::new().read_only(true));
+    ToolRoute::new_dyn(tool, |_context: ToolCallContext<'_, MockMcpServer>| {
+        let calls = COUNT_CALLS.fetch_add(1, Ordering::SeqCst) + 1;
+        std::future::ready(Ok(CallToolResult::success(vec![Content::text(
+            calls.to_string(),
+        )])))
+        .boxed()
+    })
+}
+
+/// Calls to the `append` tool.
+static APPEND_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+/// A side-effecting tool with no read-only or idempotent annotation.
+fn append_tool() -> ToolRoute<MockMcpServer> {
+    ToolRoute::new_dyn(
+        make_tool(
+            "append",
+            "Append a line to a file",
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string" }
+                }
+            }),
+        ),
+        |_context: ToolCallContext<'_, MockMcpServer>| {
+            let calls = APPEND_CALLS.fetch_add(1, Ordering::SeqCst) + 1;
+            std::future::ready(Ok(CallToolResult::success(vec![Content::text(
+                calls.to_string(),
+            )])))
+            .boxed()
+        },
+    )
+}
+
+fn get_weather_tool() -> ToolRoute<MockMcpServer> {
+    let mut tool = make_tool(
+        "get_weather",
+        "Current weather for a city",
+        serde_json::json!({
+            "type": "object",
+            "required": ["city"],
+            "properties": {
+                "city": { "type": "string" }
+            }
+        }),
+    );
+    tool.output_schema = Some(Arc::new(rmcp::model::object(serde_json::json!({
+        "type": "object",
+        "required": ["city", "current"],
+        "properties": {
+            "city": { "type": "string" },
+            "current": { "$ref": "#/$defs/Reading" }
+        },
+        "$defs": {
+            "Reading": {
+                "type": "object",
+                "description": "A single weather reading",
+                "required": ["temp_c"],
+                "properties": {
+                    "temp_c": { "type": "number" },
+                    "conditions": { "type": "string" }
+                }
+            }
+        }
+    }))));
+    ToolRoute::new_dyn(tool, |_context: ToolCallContext<'_, MockMcpServer>| {
+        std::future::ready(Ok(CallToolResult::structured(serde_json::json!({
+            "city": "Oslo",
+            "current": { "temp_c": 4.5 }
+        }))))
+        .boxed()
+    })
+}
+
+fn greet_prompt() -> PromptRoute<MockMcpServer> {
+    let prompt = Prompt::new(
+        "greet",
+        Some("Greet someone by name"),
+        Some(vec![PromptArgument {
+            name: "name".to_string(),
+            title: None,
+            description: Some("Who to greet".to_string()),
+            required: Some(true),
+        }]),
+    );
+    PromptRoute::new_dyn(prompt, |context: PromptContext<'_, MockMcpServer>| {
+        let name = context
+            .arguments
+            .as_ref()
+            .and_then(|args| args.get("name"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("world")
+            .to_string();
+        std::future::ready(Ok(GetPromptResult {
+            description: Some("A greeting".to_string()),
+            messages: vec![PromptMessage::new_text(
+                PromptMessageRole::User,
+                format!("Say hello to {name}"),
+            )],
+        }))
+        .boxed()
+    })
+}
+
 impl MockMcpServer {
     fn into_router(self) -> Router<Self> {
         Router::new(self)
             .with_tool(echo_tool())
             .with_tool(get_data_tool())
+            .with_tool(get_weather_tool())
+            .with_tool(slow_tool())
+            .with_tool(count_tool())
+            .with_tool(append_tool())
+            .with_prompt(greet_prompt())
     }
 }
 
@@ -109,6 +284,13 @@ impl MockMcpServer {
 /// The server runs in a background task. The returned manager has a single
 /// server named `"mock"`.
 async fn spawn_mock_server() -> (Arc<McpClientManager>, tokio::task::JoinHandle<()>) {
+    let (manager, handle) = connect_mock_server().await;
+    (Arc::new(manager), handle)
+}
+
+/// Like [`spawn_mock_server`], but returns the manager unwrapped so tests can
+/// configure it further.
+async fn connect_mock_server() -> (McpClientManager, tokio::task::JoinHandle<()>) {
     let (client_stream, server_stream) = tokio::io::duplex(8192);
 
     let server = MockMcpServer;
@@ -132,7 +314,7 @@ async fn spawn_mock_server() -> (Arc<McpClientManager>, tokio::task::JoinHandle<
 
     let manager = McpClientManager::from_running_service("mock", client_service);
 
-    (Arc::new(manager), server_handle)
+    (manager, server_handle)
 }
 
 // ---- Tests ----
@@ -182,31 +364,207 @@ async fn test_mock_server_call_tool_unknown_errors() {
     assert!(result.is_err(), "Expected error for unknown tool");
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_mcp_call_timeout_raises_catchable_error() {
+    let (manager, _handle) = connect_mock_server().await;
+    let manager = manager.with_call_timeouts([("mock".to_string(), Duration::from_millis(200))]);
+    let tools = ["slow", "echo"].map(|name| McpToolDef {
+        name: name.to_string(),
+        server: "mock".to_string(),
+        description: None,
+        params: vec![],
+        schemas: vec![],
+        output_schemas: vec![],
+        output_type: None,
+        idempotent: false,
+    });
+    let manifest = Manifest {
+        schema_version: MANIFEST_SCHEMA_VERSION,
+        apis: vec![],
+        functions: vec![],
+        schemas: vec![],
+        mcp_servers: vec![McpServerEntry {
+            name: "mock".to_string(),
+            description: None,
+            tools: tools.to_vec(),
+        }],
+        generated: None,
+    };
+    let executor = ScriptExecutor::new(
+        manifest,
+        Arc::new(HttpHandler::mock(|_, _, _, _| Ok(serde_json::json!({})))),
+        ExecutorConfig::default(),
+        None,
+        Arc::new(manager),
+    );
+
+    let started = std::time::Instant::now();
+    let result = executor
+        .execute(
+            r#"
+            local ok, err = pcall(sdk.mock.slow, {})
+            local echoed = sdk.mock.echo({ text = "still running" })
+            return { ok = ok, err = tostring(err), echoed = echoed }
+        "#,
+            &AuthCredentialsMap::new(),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(result.result["ok"], false);
+    assert!(
+        result.result["err"]
+            .as_str()
+            .unwrap()
+            .contains("MCP tool 'mock.slow' timed out after 200ms"),
+        "error was: {}",
+        result.result["err"]
+    );
+    assert_eq!(result.result["echoed"], "still running");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_identical_mcp_calls_are_cached_per_execution() {
+    let (manager, _handle) = spawn_mock_server().await;
+    let manifest = Manifest {
+        schema_version: MANIFEST_SCHEMA_VERSION,
+        apis: vec![],
+        functions: vec![],
+        schemas: vec![],
+        mcp_servers: vec![McpServerEntry {
+            name: "mock".to_string(),
+            description: None,
+            tools: vec![McpToolDef {
+                name: "count".to_string(),
+                server: "mock".to_string(),
+                description: None,
+                params: vec![],
+                schemas: vec![],
+                output_schemas: vec![],
+                output_type: None,
+                idempotent: true,
+            }],
+        }],
+        generated: None,
+    };
+    let executor = ScriptExecutor::new(
+        manifest,
+        Arc::new(HttpHandler::mock(|_, _, _, _| Ok(serde_json::json!({})))),
+        ExecutorConfig {
+            max_api_calls: Some(1),
+            cache_mcp_calls: true,
+            ..ExecutorConfig::default()
+        },
+        None,
+        manager,
+    );
+
+    // The repeat is answered from the cache, so it neither reaches the
+    // mock nor counts against the limit of one call
+    let result = executor
+        .execute(
+            r#"
+            local first = sdk.mock.count({ path = "/tmp/a" })
+            local second = sdk.mock.count({ path = "/tmp/a" })
+            local ok = pcall(sdk.mock.count, { path = "/tmp/b" })
+            return { first = first, second = second, ok = ok }
+        "#,
+            &AuthCredentialsMap::new(),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(COUNT_CALLS.load(Ordering::SeqCst), 1);
+    assert_eq!(result.result["first"], "1");
+    assert_eq!(result.result["second"], "1");
+    assert_eq!(result.result["ok"], false);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_non_idempotent_mcp_calls_are_not_cached() {
+    let (manager, _handle) = spawn_mock_server().await;
+    let manifest = Manifest {
+        schema_version: MANIFEST_SCHEMA_VERSION,
+        apis: vec![],
+        functions: vec![],
+        schemas: vec![],
+        mcp_servers: vec![McpServerEntry {
+            name: "mock".to_string(),
+            description: None,
+            tools: vec![McpToolDef {
+                name: "append".to_string(),
+                server: "mock".to_string(),
+                description: None,
+                params: vec![],
+                schemas: vec![],
+                output_schemas: vec![],
+                output_type: None,
+                idempotent: false,
+            }],
+        }],
+        generated: None,
+    };
+    let executor = ScriptExecutor::new(
+        manifest,
+        Arc::new(HttpHandler::mock(|_, _, _, _| Ok(serde_json::json!({})))),
+        ExecutorConfig {
+            cache_mcp_calls: true,
+            ..ExecutorConfig::default()
+        },
+        None,
+        manager,
+    );
+
+    let result = executor
+        .execute(
+            r#"
+            local first = sdk.mock.append({ path = "/tmp/a" })
+            local second = sdk.mock.append({ path = "/tmp/a" })
+            return { first = first, second = second }
+        "#,
+            &AuthCredentialsMap::new(),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(APPEND_CALLS.load(Ordering::SeqCst), 2);
+    assert_eq!(result.result["first"], "1");
+    assert_eq!(result.result["second"], "2");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_unresponsive_server_marked_unhealthy_by_keepalive() {
+    let (manager, _handle) = connect_mock_server().await;
+    let manager =
+        Arc::new(manager.with_ping_intervals([("mock".to_string(), Duration::from_millis(100))]));
+
+    assert!(manager.check_health("mock", Duration::from_secs(1)).await);
+    assert!(manager.failed_servers().is_empty());
+
+    // The mock has no config to reconnect with, so it stays unhealthy
+    PINGS_HANG.store(true, Ordering::SeqCst);
+    manager.spawn_keepalive();
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    while manager.failed_servers().is_empty() && tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(manager.failed_servers(), ["mock".to_string()]);
+}
+
 #[tokio::test]
 async fn test_mock_server_in_discovery_pipeline() {
     let (manager, _handle) = spawn_mock_server().await;
     let all_tools = manager.list_all_tools().await.unwrap();
     let mock_tools = all_tools.get("mock").unwrap();
 
-    // Build McpServerEntry / McpToolDef shapes like discover_mcp_tools does
-    let mut tool_defs = Vec::new();
-    for tool in mock_tools {
-        let input_schema = serde_json::Value::Object(tool.input_schema.as_ref().clone());
-        let params = json_schema_to_params(&input_schema);
-        let schemas = extract_schema_defs(&input_schema);
-
-        tool_defs.push(McpToolDef {
-            name: tool.name.to_string(),
-            server: "mock".to_string(),
-            description: tool
-                .description
-                .as_ref()
-                .map(std::string::ToString::to_string),
-            params,
-            schemas,
-            output_schemas: vec![],
-        });
-    }
+    let tool_defs = mock_tools
+        .iter()
+        .map(|tool| McpToolDef::from_tool("mock", tool))
+        .collect();
 
     let server_entry = McpServerEntry {
         name: "mock".to_string(),
@@ -214,6 +572,15 @@ async fn test_mock_server_in_discovery_pipeline() {
         tools: tool_defs,
     };
 
+    // Read-only annotations make a tool's calls cacheable
+    let idempotent: Vec<&str> = server_entry
+        .tools
+        .iter()
+        .filter(|t| t.idempotent)
+        .map(|t| t.name.as_str())
+        .collect();
+    assert_eq!(idempotent, ["count"]);
+
     // Verify the echo tool was properly discovered
     let echo_tool = server_entry
         .tools
@@ -227,4 +594,113 @@ async fn test_mock_server_in_discovery_pipeline() {
         "Missing 'text' param. Got: {:?}",
         echo_tool.params
     );
+    assert_eq!(
+        render_mcp_tool_annotation(echo_tool).lines().last(),
+        Some("function sdk.mock.echo(params: { text: string }): any end")
+    );
+
+    // The advertised output schema becomes the return type, with its
+    // named definitions documented alongside
+    let weather_tool = server_entry
+        .tools
+        .iter()
+        .find(|t| t.name == "get_weather")
+        .expect("get_weather tool not found");
+    let annotation = render_mcp_tool_annotation(weather_tool);
+    assert!(
+        annotation.ends_with("): { city: string, current: Reading } end"),
+        "Unexpected return type. Got:\n{annotation}"
+    );
+    let docs = render_mcp_tool_docs(weather_tool);
+    assert!(
+        docs.contains("export type Reading = {"),
+        "Missing output schema. Got:\n{docs}"
+    );
+    assert!(
+        docs.contains("-- A single weather reading"),
+        "Missing output schema description. Got:\n{docs}"
+    );
+}
+
+#[tokio::test]
+async fn test_list_all_prompts_skips_failing_server() {
+    let (manager, handle) = spawn_mock_server().await;
+    assert_eq!(manager.list_all_prompts().await["mock"].len(), 1);
+
+    handle.abort();
+    let _ = handle.await;
+    assert!(manager.list_prompts("mock").await.is_err());
+    assert!(manager.list_all_prompts().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_upstream_prompts_proxied_through_toolscript_server() {
+    let (manager, _handle) = spawn_mock_server().await;
+    let upstream = manager.list_all_prompts().await;
+    let server = ToolScriptServer::new(
+        Manifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
+            apis: vec![],
+            functions: vec![],
+            schemas: vec![],
+            mcp_servers: vec![],
+            generated: None,
+        },
+        Arc::new(HttpHandler::mock(|_, _, _, _| Ok(serde_json::json!({})))),
+        AuthCredentialsMap::new(),
+        ExecutorConfig::default(),
+        None,
+        Arc::clone(&manager),
+    )
+    .with_prompts(upstream);
+
+    // Serve the toolscript server itself and talk to it as an MCP client
+    let (client_stream, server_stream) = tokio::io::duplex(8192);
+    let router = server.into_router();
+    tokio::spawn(async move {
+        let service = rmcp::serve_server(router, tokio::io::split(server_stream))
+            .await
+            .expect("failed to start toolscript server");
+        let _ = service.waiting().await;
+    });
+    let client = rmcp::ServiceExt::serve((), tokio::io::split(client_stream))
+        .await
+        .expect("failed to connect client");
+
+    let info = client.peer_info().expect("no server info");
+    assert!(info.capabilities.prompts.is_some());
+
+    let prompts = client.list_all_prompts().await.unwrap();
+    let names: Vec<&str> = prompts.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["mock.greet"]);
+    assert_eq!(
+        prompts[0].description.as_deref(),
+        Some("Greet someone by name")
+    );
+
+    let mut arguments = serde_json::Map::new();
+    arguments.insert("name".to_string(), serde_json::json!("Ada"));
+    let result = client
+        .get_prompt(GetPromptRequestParams {
+            meta: None,
+            name: "mock.greet".to_string(),
+            arguments: Some(arguments),
+        })
+        .await
+        .unwrap();
+    assert_eq!(result.description.as_deref(), Some("A greeting"));
+    match &result.messages[0].content {
+        PromptMessageContent::Text { text } => assert_eq!(text, "Say hello to Ada"),
+        other => panic!("expected text content, got {other:?}"),
+    }
+
+    // Unprefixed or unknown names are not forwarded
+    let err = client
+        .get_prompt(GetPromptRequestParams {
+            meta: None,
+            name: "greet".to_string(),
+            arguments: None,
+        })
+        .await;
+    assert!(err.is_err());
 }